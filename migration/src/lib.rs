@@ -1,12 +1,56 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20220101_000002_create_job_runs_table;
+mod m20220101_000003_create_webhook_tables;
+mod m20220101_000004_create_outbox_table;
+mod m20220101_000005_create_notifications_table;
+mod m20220101_000006_create_external_calendar_tables;
+mod m20220101_000007_create_room_blocks_table;
+mod m20220101_000008_add_housekeeping_to_rooms;
+mod m20220101_000009_add_room_type_to_bookings;
+mod m20220101_000010_create_reservations_table;
+mod m20220101_000011_create_waitlist_entries_table;
+mod m20220101_000012_add_location_to_hotels;
+mod m20220101_000013_add_search_vectors;
+mod m20220101_000014_create_amenities_tables;
+mod m20220101_000015_create_reviews_table;
+mod m20220101_000016_create_media_table;
+mod m20220101_000017_add_profile_to_guests;
+mod m20220101_000018_create_audit_log_table;
+mod m20220101_000019_add_merge_to_guests;
+mod m20220101_000020_create_loyalty_entries_table;
+mod m20220101_000021_create_promotions_table;
+mod m20220101_000022_add_timezone_to_hotels;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20220101_000002_create_job_runs_table::Migration),
+            Box::new(m20220101_000003_create_webhook_tables::Migration),
+            Box::new(m20220101_000004_create_outbox_table::Migration),
+            Box::new(m20220101_000005_create_notifications_table::Migration),
+            Box::new(m20220101_000006_create_external_calendar_tables::Migration),
+            Box::new(m20220101_000007_create_room_blocks_table::Migration),
+            Box::new(m20220101_000008_add_housekeeping_to_rooms::Migration),
+            Box::new(m20220101_000009_add_room_type_to_bookings::Migration),
+            Box::new(m20220101_000010_create_reservations_table::Migration),
+            Box::new(m20220101_000011_create_waitlist_entries_table::Migration),
+            Box::new(m20220101_000012_add_location_to_hotels::Migration),
+            Box::new(m20220101_000013_add_search_vectors::Migration),
+            Box::new(m20220101_000014_create_amenities_tables::Migration),
+            Box::new(m20220101_000015_create_reviews_table::Migration),
+            Box::new(m20220101_000016_create_media_table::Migration),
+            Box::new(m20220101_000017_add_profile_to_guests::Migration),
+            Box::new(m20220101_000018_create_audit_log_table::Migration),
+            Box::new(m20220101_000019_add_merge_to_guests::Migration),
+            Box::new(m20220101_000020_create_loyalty_entries_table::Migration),
+            Box::new(m20220101_000021_create_promotions_table::Migration),
+            Box::new(m20220101_000022_add_timezone_to_hotels::Migration),
+        ]
     }
 }
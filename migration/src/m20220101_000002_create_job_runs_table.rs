@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create job runs table
+        manager
+            .create_table(
+                Table::create()
+                    .table(JobRuns::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(JobRuns::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(JobRuns::JobName).string().not_null())
+                    .col(ColumnDef::new(JobRuns::Succeeded).boolean().not_null())
+                    .col(ColumnDef::new(JobRuns::AffectedRows).big_integer().not_null())
+                    .col(ColumnDef::new(JobRuns::Error).text().null())
+                    .col(ColumnDef::new(JobRuns::StartedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(JobRuns::FinishedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_job_runs_job_name_started_at")
+                    .table(JobRuns::Table)
+                    .col(JobRuns::JobName)
+                    .col(JobRuns::StartedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JobRuns::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum JobRuns {
+    Table,
+    Id,
+    JobName,
+    Succeeded,
+    AffectedRows,
+    Error,
+    StartedAt,
+    FinishedAt,
+}
@@ -0,0 +1,156 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::prelude::extension::postgres::Type;
+
+#[derive(Iden)]
+pub enum WebhookDeliveryStatus {
+    #[iden = "webhook_delivery_status"]
+    Enum,
+    #[iden = "pending"]
+    Pending,
+    #[iden = "delivered"]
+    Delivered,
+    #[iden = "failed"]
+    Failed,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create the enum type
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(WebhookDeliveryStatus::Enum)
+                    .values([
+                        WebhookDeliveryStatus::Pending,
+                        WebhookDeliveryStatus::Delivered,
+                        WebhookDeliveryStatus::Failed,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create webhook subscriptions table
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookSubscriptions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WebhookSubscriptions::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WebhookSubscriptions::Url).text().not_null())
+                    .col(ColumnDef::new(WebhookSubscriptions::Secret).string().not_null())
+                    .col(ColumnDef::new(WebhookSubscriptions::Events)
+                        .array(ColumnType::String(StringLen::None))
+                        .not_null())
+                    .col(ColumnDef::new(WebhookSubscriptions::IsActive).boolean().not_null())
+                    .col(ColumnDef::new(WebhookSubscriptions::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(WebhookSubscriptions::UpdatedAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create webhook deliveries table
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookDeliveries::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WebhookDeliveries::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WebhookDeliveries::SubscriptionId).uuid().not_null())
+                    .col(ColumnDef::new(WebhookDeliveries::EventType).string().not_null())
+                    .col(ColumnDef::new(WebhookDeliveries::Payload).json_binary().not_null())
+                    .col(ColumnDef::new(WebhookDeliveries::Status)
+                        .custom(Alias::new("webhook_delivery_status"))
+                        .not_null())
+                    .col(ColumnDef::new(WebhookDeliveries::Attempts).integer().not_null())
+                    .col(ColumnDef::new(WebhookDeliveries::ResponseStatus).integer().null())
+                    .col(ColumnDef::new(WebhookDeliveries::LastError).text().null())
+                    .col(ColumnDef::new(WebhookDeliveries::NextAttemptAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(WebhookDeliveries::DeliveredAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(WebhookDeliveries::CreatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_webhook_deliveries_subscription")
+                            .from(WebhookDeliveries::Table, WebhookDeliveries::SubscriptionId)
+                            .to(WebhookSubscriptions::Table, WebhookSubscriptions::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_deliveries_status_next_attempt_at")
+                    .table(WebhookDeliveries::Table)
+                    .col(WebhookDeliveries::Status)
+                    .col(WebhookDeliveries::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebhookDeliveries::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(WebhookSubscriptions::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .name(Alias::new("webhook_delivery_status"))
+                    .to_owned()
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum WebhookSubscriptions {
+    Table,
+    Id,
+    Url,
+    Secret,
+    Events,
+    IsActive,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum WebhookDeliveries {
+    Table,
+    Id,
+    SubscriptionId,
+    EventType,
+    Payload,
+    Status,
+    Attempts,
+    ResponseStatus,
+    LastError,
+    NextAttemptAt,
+    DeliveredAt,
+    CreatedAt,
+}
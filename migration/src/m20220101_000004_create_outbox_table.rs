@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create outbox table
+        manager
+            .create_table(
+                Table::create()
+                    .table(Outbox::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Outbox::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Outbox::EventType).string().not_null())
+                    .col(ColumnDef::new(Outbox::AggregateType).string().not_null())
+                    .col(ColumnDef::new(Outbox::AggregateId).uuid().not_null())
+                    .col(ColumnDef::new(Outbox::Payload).json_binary().not_null())
+                    .col(ColumnDef::new(Outbox::Attempts).integer().not_null())
+                    .col(ColumnDef::new(Outbox::LastError).text().null())
+                    .col(ColumnDef::new(Outbox::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(Outbox::ProcessedAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_outbox_processed_at_created_at")
+                    .table(Outbox::Table)
+                    .col(Outbox::ProcessedAt)
+                    .col(Outbox::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Outbox::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Outbox {
+    Table,
+    Id,
+    EventType,
+    AggregateType,
+    AggregateId,
+    Payload,
+    Attempts,
+    LastError,
+    CreatedAt,
+    ProcessedAt,
+}
@@ -0,0 +1,155 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::prelude::extension::postgres::Type;
+
+#[derive(Iden)]
+pub enum NotificationKind {
+    #[iden = "notification_kind"]
+    Enum,
+    #[iden = "confirmation"]
+    Confirmation,
+    #[iden = "reminder"]
+    Reminder,
+    #[iden = "cancellation"]
+    Cancellation,
+}
+
+#[derive(Iden)]
+pub enum NotificationStatus {
+    #[iden = "notification_status"]
+    Enum,
+    #[iden = "pending"]
+    Pending,
+    #[iden = "sent"]
+    Sent,
+    #[iden = "failed"]
+    Failed,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create the enum types
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(NotificationKind::Enum)
+                    .values([
+                        NotificationKind::Confirmation,
+                        NotificationKind::Reminder,
+                        NotificationKind::Cancellation,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(NotificationStatus::Enum)
+                    .values([
+                        NotificationStatus::Pending,
+                        NotificationStatus::Sent,
+                        NotificationStatus::Failed,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create notifications table
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notifications::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Notifications::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Notifications::BookingId).uuid().not_null())
+                    .col(ColumnDef::new(Notifications::Kind)
+                        .custom(Alias::new("notification_kind"))
+                        .not_null())
+                    .col(ColumnDef::new(Notifications::Recipient).string().not_null())
+                    .col(ColumnDef::new(Notifications::Status)
+                        .custom(Alias::new("notification_status"))
+                        .not_null())
+                    .col(ColumnDef::new(Notifications::Attempts).integer().not_null())
+                    .col(ColumnDef::new(Notifications::LastError).text().null())
+                    .col(ColumnDef::new(Notifications::SentAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(Notifications::CreatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_notifications_booking")
+                            .from(Notifications::Table, Notifications::BookingId)
+                            .to(Bookings::Table, Bookings::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One notification of each kind per booking
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_notifications_booking_id_kind")
+                    .table(Notifications::Table)
+                    .col(Notifications::BookingId)
+                    .col(Notifications::Kind)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Notifications::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .name(Alias::new("notification_status"))
+                    .to_owned()
+            )
+            .await?;
+        manager
+            .drop_type(
+                Type::drop()
+                    .name(Alias::new("notification_kind"))
+                    .to_owned()
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Notifications {
+    Table,
+    Id,
+    BookingId,
+    Kind,
+    Recipient,
+    Status,
+    Attempts,
+    LastError,
+    SentAt,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Bookings {
+    Table,
+    Id,
+}
@@ -0,0 +1,135 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create external calendars table
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExternalCalendars::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ExternalCalendars::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ExternalCalendars::RoomId).uuid().not_null())
+                    .col(ColumnDef::new(ExternalCalendars::Name).string().not_null())
+                    .col(ColumnDef::new(ExternalCalendars::Url).text().null())
+                    .col(ColumnDef::new(ExternalCalendars::LastSyncedAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(ExternalCalendars::LastError).text().null())
+                    .col(ColumnDef::new(ExternalCalendars::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(ExternalCalendars::UpdatedAt).timestamp_with_time_zone().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_external_calendars_room")
+                            .from(ExternalCalendars::Table, ExternalCalendars::RoomId)
+                            .to(Rooms::Table, Rooms::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create external blocks table
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExternalBlocks::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ExternalBlocks::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ExternalBlocks::CalendarId).uuid().not_null())
+                    .col(ColumnDef::new(ExternalBlocks::RoomId).uuid().not_null())
+                    .col(ColumnDef::new(ExternalBlocks::Uid).text().not_null())
+                    .col(ColumnDef::new(ExternalBlocks::Summary).text().null())
+                    .col(ColumnDef::new(ExternalBlocks::StartsAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(ExternalBlocks::EndsAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(ExternalBlocks::CreatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_external_blocks_calendar")
+                            .from(ExternalBlocks::Table, ExternalBlocks::CalendarId)
+                            .to(ExternalCalendars::Table, ExternalCalendars::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_external_blocks_room")
+                            .from(ExternalBlocks::Table, ExternalBlocks::RoomId)
+                            .to(Rooms::Table, Rooms::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_external_blocks_room_id_starts_at")
+                    .table(ExternalBlocks::Table)
+                    .col(ExternalBlocks::RoomId)
+                    .col(ExternalBlocks::StartsAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ExternalBlocks::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ExternalCalendars::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum ExternalCalendars {
+    Table,
+    Id,
+    RoomId,
+    Name,
+    Url,
+    LastSyncedAt,
+    LastError,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum ExternalBlocks {
+    Table,
+    Id,
+    CalendarId,
+    RoomId,
+    Uid,
+    Summary,
+    StartsAt,
+    EndsAt,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Rooms {
+    Table,
+    Id,
+}
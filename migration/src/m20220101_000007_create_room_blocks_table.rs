@@ -0,0 +1,117 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::prelude::extension::postgres::Type;
+
+#[derive(Iden)]
+pub enum RoomBlockKind {
+    #[iden = "room_block_kind"]
+    Enum,
+    #[iden = "maintenance"]
+    Maintenance,
+    #[iden = "out_of_order"]
+    OutOfOrder,
+    #[iden = "other"]
+    Other,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create the enum type
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(RoomBlockKind::Enum)
+                    .values([
+                        RoomBlockKind::Maintenance,
+                        RoomBlockKind::OutOfOrder,
+                        RoomBlockKind::Other,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create room blocks table, the dates are the first and last blocked night
+        manager
+            .create_table(
+                Table::create()
+                    .table(RoomBlocks::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RoomBlocks::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RoomBlocks::RoomId).uuid().not_null())
+                    .col(ColumnDef::new(RoomBlocks::Kind)
+                        .custom(Alias::new("room_block_kind"))
+                        .not_null())
+                    .col(ColumnDef::new(RoomBlocks::StartDate).date().not_null())
+                    .col(ColumnDef::new(RoomBlocks::EndDate).date().not_null())
+                    .col(ColumnDef::new(RoomBlocks::Reason).text().not_null())
+                    .col(ColumnDef::new(RoomBlocks::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(RoomBlocks::UpdatedAt).timestamp_with_time_zone().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_room_blocks_room")
+                            .from(RoomBlocks::Table, RoomBlocks::RoomId)
+                            .to(Rooms::Table, Rooms::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .check(Expr::col(RoomBlocks::EndDate).gte(Expr::col(RoomBlocks::StartDate)))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_room_blocks_room_id_start_date")
+                    .table(RoomBlocks::Table)
+                    .col(RoomBlocks::RoomId)
+                    .col(RoomBlocks::StartDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RoomBlocks::Table).to_owned())
+            .await?;
+        manager
+            .drop_type(
+                Type::drop()
+                    .name(Alias::new("room_block_kind"))
+                    .to_owned()
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum RoomBlocks {
+    Table,
+    Id,
+    RoomId,
+    Kind,
+    StartDate,
+    EndDate,
+    Reason,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Rooms {
+    Table,
+    Id,
+}
@@ -0,0 +1,88 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::prelude::extension::postgres::Type;
+
+#[derive(Iden)]
+pub enum HousekeepingStatus {
+    #[iden = "housekeeping_status"]
+    Enum,
+    #[iden = "clean"]
+    Clean,
+    #[iden = "dirty"]
+    Dirty,
+    #[iden = "inspected"]
+    Inspected,
+    #[iden = "out_of_service"]
+    OutOfService,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create the enum type
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(HousekeepingStatus::Enum)
+                    .values([
+                        HousekeepingStatus::Clean,
+                        HousekeepingStatus::Dirty,
+                        HousekeepingStatus::Inspected,
+                        HousekeepingStatus::OutOfService,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing rooms start out clean
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Rooms::Table)
+                    .add_column(
+                        ColumnDef::new(Rooms::HousekeepingStatus)
+                            .custom(Alias::new("housekeeping_status"))
+                            .not_null()
+                            .default(Expr::cust("'clean'::housekeeping_status")),
+                    )
+                    .add_column(ColumnDef::new(Rooms::HousekeepingNote).text().null())
+                    .add_column(ColumnDef::new(Rooms::HousekeepingUpdatedAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Rooms::Table)
+                    .drop_column(Rooms::HousekeepingStatus)
+                    .drop_column(Rooms::HousekeepingNote)
+                    .drop_column(Rooms::HousekeepingUpdatedAt)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_type(
+                Type::drop()
+                    .name(Alias::new("housekeeping_status"))
+                    .to_owned()
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Rooms {
+    Table,
+    HousekeepingStatus,
+    HousekeepingNote,
+    HousekeepingUpdatedAt,
+}
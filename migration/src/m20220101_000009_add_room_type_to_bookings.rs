@@ -0,0 +1,110 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Bookings are made against a hotel's room type, the room is assigned later
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bookings::Table)
+                    .add_column(ColumnDef::new(Bookings::HotelId).uuid().null())
+                    .add_column(ColumnDef::new(Bookings::RoomType).string().null())
+                    .modify_column(ColumnDef::new(Bookings::RoomId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing bookings take the hotel and type of their room
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE bookings b SET hotel_id = r.hotel_id, room_type = r.room_type \
+                 FROM rooms r WHERE r.id = b.room_id",
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bookings::Table)
+                    .modify_column(ColumnDef::new(Bookings::HotelId).uuid().not_null())
+                    .modify_column(ColumnDef::new(Bookings::RoomType).string().not_null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_bookings_hotel")
+                            .from_tbl(Bookings::Table)
+                            .from_col(Bookings::HotelId)
+                            .to_tbl(Hotels::Table)
+                            .to_col(Hotels::Id)
+                            .on_delete(ForeignKeyAction::NoAction)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_bookings_hotel_id_room_type_check_in_date")
+                    .table(Bookings::Table)
+                    .col(Bookings::HotelId)
+                    .col(Bookings::RoomType)
+                    .col(Bookings::CheckInDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Unassigned bookings cannot survive a NOT NULL room_id
+        manager
+            .get_connection()
+            .execute_unprepared("DELETE FROM bookings WHERE room_id IS NULL")
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_bookings_hotel_id_room_type_check_in_date")
+                    .table(Bookings::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bookings::Table)
+                    .drop_foreign_key(Alias::new("fk_bookings_hotel"))
+                    .drop_column(Bookings::HotelId)
+                    .drop_column(Bookings::RoomType)
+                    .modify_column(ColumnDef::new(Bookings::RoomId).uuid().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Bookings {
+    Table,
+    RoomId,
+    HotelId,
+    RoomType,
+    CheckInDate,
+}
+
+#[derive(Iden)]
+enum Hotels {
+    Table,
+    Id,
+}
@@ -0,0 +1,107 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create reservations table, the parent of a group's bookings
+        manager
+            .create_table(
+                Table::create()
+                    .table(Reservations::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Reservations::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Reservations::GuestId).uuid().not_null())
+                    .col(ColumnDef::new(Reservations::Name).string().null())
+                    .col(ColumnDef::new(Reservations::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(Reservations::UpdatedAt).timestamp_with_time_zone().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reservations_guest")
+                            .from(Reservations::Table, Reservations::GuestId)
+                            .to(Guests::Table, Guests::Id)
+                            .on_delete(ForeignKeyAction::NoAction)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bookings::Table)
+                    .add_column(ColumnDef::new(Bookings::ReservationId).uuid().null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_bookings_reservation")
+                            .from_tbl(Bookings::Table)
+                            .from_col(Bookings::ReservationId)
+                            .to_tbl(Reservations::Table)
+                            .to_col(Reservations::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_bookings_reservation_id")
+                    .table(Bookings::Table)
+                    .col(Bookings::ReservationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bookings::Table)
+                    .drop_foreign_key(Alias::new("fk_bookings_reservation"))
+                    .drop_column(Bookings::ReservationId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Reservations::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Reservations {
+    Table,
+    Id,
+    GuestId,
+    Name,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Bookings {
+    Table,
+    ReservationId,
+}
+
+#[derive(Iden)]
+enum Guests {
+    Table,
+    Id,
+}
@@ -0,0 +1,259 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::prelude::extension::postgres::Type;
+
+#[derive(Iden)]
+pub enum WaitlistStatus {
+    #[iden = "waitlist_status"]
+    Enum,
+    #[iden = "waiting"]
+    Waiting,
+    #[iden = "offered"]
+    Offered,
+}
+
+#[derive(Iden)]
+pub enum NotificationKind {
+    #[iden = "notification_kind"]
+    Enum,
+    #[iden = "waitlist_offer"]
+    WaitlistOffer,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create the enum type
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(WaitlistStatus::Enum)
+                    .values([
+                        WaitlistStatus::Waiting,
+                        WaitlistStatus::Offered,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create waitlist entries table
+        manager
+            .create_table(
+                Table::create()
+                    .table(WaitlistEntries::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WaitlistEntries::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WaitlistEntries::GuestId).uuid().not_null())
+                    .col(ColumnDef::new(WaitlistEntries::HotelId).uuid().not_null())
+                    .col(ColumnDef::new(WaitlistEntries::RoomType).string().not_null())
+                    .col(ColumnDef::new(WaitlistEntries::CheckInDate).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(WaitlistEntries::CheckOutDate).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(WaitlistEntries::Status)
+                        .custom(Alias::new("waitlist_status"))
+                        .not_null())
+                    .col(ColumnDef::new(WaitlistEntries::OfferedRoomId).uuid().null())
+                    .col(ColumnDef::new(WaitlistEntries::OfferedBookingId).uuid().null())
+                    .col(ColumnDef::new(WaitlistEntries::OfferedAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(WaitlistEntries::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(WaitlistEntries::UpdatedAt).timestamp_with_time_zone().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_waitlist_entries_guest")
+                            .from(WaitlistEntries::Table, WaitlistEntries::GuestId)
+                            .to(Guests::Table, Guests::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_waitlist_entries_hotel")
+                            .from(WaitlistEntries::Table, WaitlistEntries::HotelId)
+                            .to(Hotels::Table, Hotels::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_waitlist_entries_offered_room")
+                            .from(WaitlistEntries::Table, WaitlistEntries::OfferedRoomId)
+                            .to(Rooms::Table, Rooms::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_waitlist_entries_offered_booking")
+                            .from(WaitlistEntries::Table, WaitlistEntries::OfferedBookingId)
+                            .to(Bookings::Table, Bookings::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .check(Expr::col(WaitlistEntries::CheckOutDate).gt(Expr::col(WaitlistEntries::CheckInDate)))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Matching walks the waiting entries of a hotel and room type oldest first
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_waitlist_entries_hotel_id_room_type_status_created_at")
+                    .table(WaitlistEntries::Table)
+                    .col(WaitlistEntries::HotelId)
+                    .col(WaitlistEntries::RoomType)
+                    .col(WaitlistEntries::Status)
+                    .col(WaitlistEntries::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // A cancelled booking is offered to one entry at most
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_waitlist_entries_offered_booking_id")
+                    .table(WaitlistEntries::Table)
+                    .col(WaitlistEntries::OfferedBookingId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Offers are emailed, so notifications now belong to a booking or a waitlist entry
+        manager
+            .alter_type(
+                Type::alter()
+                    .name(NotificationKind::Enum)
+                    .add_value(NotificationKind::WaitlistOffer)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Notifications::Table)
+                    .modify_column(ColumnDef::new(Notifications::BookingId).uuid().null())
+                    .add_column(ColumnDef::new(Notifications::WaitlistEntryId).uuid().null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_notifications_waitlist_entry")
+                            .from_tbl(Notifications::Table)
+                            .from_col(Notifications::WaitlistEntryId)
+                            .to_tbl(WaitlistEntries::Table)
+                            .to_col(WaitlistEntries::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One notification of each kind per waitlist entry
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_notifications_waitlist_entry_id_kind")
+                    .table(Notifications::Table)
+                    .col(Notifications::WaitlistEntryId)
+                    .col(Notifications::Kind)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(Notifications::Table)
+                    .and_where(Expr::col(Notifications::BookingId).is_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Notifications::Table)
+                    .drop_foreign_key(Alias::new("fk_notifications_waitlist_entry"))
+                    .drop_column(Notifications::WaitlistEntryId)
+                    .modify_column(ColumnDef::new(Notifications::BookingId).uuid().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Postgres cannot drop an enum value, `waitlist_offer` stays in `notification_kind`
+        manager
+            .drop_table(Table::drop().table(WaitlistEntries::Table).to_owned())
+            .await?;
+        manager
+            .drop_type(
+                Type::drop()
+                    .name(Alias::new("waitlist_status"))
+                    .to_owned()
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum WaitlistEntries {
+    Table,
+    Id,
+    GuestId,
+    HotelId,
+    RoomType,
+    CheckInDate,
+    CheckOutDate,
+    Status,
+    OfferedRoomId,
+    OfferedBookingId,
+    OfferedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Notifications {
+    Table,
+    BookingId,
+    WaitlistEntryId,
+    Kind,
+}
+
+#[derive(Iden)]
+enum Guests {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Hotels {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Rooms {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Bookings {
+    Table,
+    Id,
+}
@@ -0,0 +1,92 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Structured address and coordinates, `address` stays as the formatted line
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Hotels::Table)
+                    .add_column(ColumnDef::new(Hotels::Street).string().null())
+                    .add_column(ColumnDef::new(Hotels::City).string().null())
+                    .add_column(ColumnDef::new(Hotels::Region).string().null())
+                    .add_column(ColumnDef::new(Hotels::PostalCode).string().null())
+                    .add_column(ColumnDef::new(Hotels::Country).string_len(2).null())
+                    .add_column(ColumnDef::new(Hotels::Latitude).double().null())
+                    .add_column(ColumnDef::new(Hotels::Longitude).double().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing free text addresses become the street until the hotel is edited
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Hotels::Table)
+                    .value(Hotels::Street, Expr::col(Hotels::Address))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_hotels_country_city")
+                    .table(Hotels::Table)
+                    .col(Hotels::Country)
+                    .col(Hotels::City)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Radius searches narrow down to a bounding box first
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_hotels_latitude_longitude")
+                    .table(Hotels::Table)
+                    .col(Hotels::Latitude)
+                    .col(Hotels::Longitude)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Hotels::Table)
+                    .drop_column(Hotels::Street)
+                    .drop_column(Hotels::City)
+                    .drop_column(Hotels::Region)
+                    .drop_column(Hotels::PostalCode)
+                    .drop_column(Hotels::Country)
+                    .drop_column(Hotels::Latitude)
+                    .drop_column(Hotels::Longitude)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Hotels {
+    Table,
+    Address,
+    Street,
+    City,
+    Region,
+    PostalCode,
+    Country,
+    Latitude,
+    Longitude,
+}
@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Rooms::Table)
+                    .add_column(ColumnDef::new(Rooms::Description).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Generated columns keep the documents in sync with every write, the
+        // weights rank a match in a name above one in a description
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE hotels ADD COLUMN search_vector tsvector GENERATED ALWAYS AS ( \
+                    setweight(to_tsvector('english', coalesce(name, '')), 'A') \
+                    || setweight(to_tsvector('english', coalesce(description, '')), 'B') \
+                    || setweight(to_tsvector('english', coalesce(address, '')), 'C') \
+                 ) STORED",
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE rooms ADD COLUMN search_vector tsvector GENERATED ALWAYS AS ( \
+                    setweight(to_tsvector('english', coalesce(room_type, '')), 'A') \
+                    || setweight(to_tsvector('english', coalesce(description, '')), 'B') \
+                 ) STORED",
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("CREATE INDEX idx_hotels_search_vector ON hotels USING GIN (search_vector)")
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("CREATE INDEX idx_rooms_search_vector ON rooms USING GIN (search_vector)")
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Hotels::Table)
+                    .drop_column(Hotels::SearchVector)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Rooms::Table)
+                    .drop_column(Rooms::SearchVector)
+                    .drop_column(Rooms::Description)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Hotels {
+    Table,
+    SearchVector,
+}
+
+#[derive(Iden)]
+enum Rooms {
+    Table,
+    Description,
+    SearchVector,
+}
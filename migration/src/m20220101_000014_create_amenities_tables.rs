@@ -0,0 +1,195 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Catalogue of amenities, `code` is the stable key used in filters
+        manager
+            .create_table(
+                Table::create()
+                    .table(Amenities::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Amenities::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Amenities::Code).string().not_null().unique_key())
+                    .col(ColumnDef::new(Amenities::Name).string().not_null())
+                    .col(ColumnDef::new(Amenities::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(Amenities::UpdatedAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(HotelAmenities::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(HotelAmenities::HotelId).uuid().not_null())
+                    .col(ColumnDef::new(HotelAmenities::AmenityId).uuid().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(HotelAmenities::HotelId)
+                            .col(HotelAmenities::AmenityId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_hotel_amenities_hotel")
+                            .from(HotelAmenities::Table, HotelAmenities::HotelId)
+                            .to(Hotels::Table, Hotels::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_hotel_amenities_amenity")
+                            .from(HotelAmenities::Table, HotelAmenities::AmenityId)
+                            .to(Amenities::Table, Amenities::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Amenity filters look hotels up by amenity
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_hotel_amenities_amenity_id")
+                    .table(HotelAmenities::Table)
+                    .col(HotelAmenities::AmenityId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Room types are not a table of their own, they are keyed by hotel and name
+        manager
+            .create_table(
+                Table::create()
+                    .table(RoomTypeAmenities::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RoomTypeAmenities::HotelId).uuid().not_null())
+                    .col(ColumnDef::new(RoomTypeAmenities::RoomType).string().not_null())
+                    .col(ColumnDef::new(RoomTypeAmenities::AmenityId).uuid().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(RoomTypeAmenities::HotelId)
+                            .col(RoomTypeAmenities::RoomType)
+                            .col(RoomTypeAmenities::AmenityId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_room_type_amenities_hotel")
+                            .from(RoomTypeAmenities::Table, RoomTypeAmenities::HotelId)
+                            .to(Hotels::Table, Hotels::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_room_type_amenities_amenity")
+                            .from(RoomTypeAmenities::Table, RoomTypeAmenities::AmenityId)
+                            .to(Amenities::Table, Amenities::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Policies, the times also default the times of date only bookings
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Hotels::Table)
+                    .add_column(
+                        ColumnDef::new(Hotels::CheckInTime)
+                            .time()
+                            .not_null()
+                            .default(Expr::cust("'15:00'")),
+                    )
+                    .add_column(
+                        ColumnDef::new(Hotels::CheckOutTime)
+                            .time()
+                            .not_null()
+                            .default(Expr::cust("'11:00'")),
+                    )
+                    .add_column(ColumnDef::new(Hotels::ChildPolicy).text().null())
+                    .add_column(ColumnDef::new(Hotels::PetPolicy).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Hotels::Table)
+                    .drop_column(Hotels::CheckInTime)
+                    .drop_column(Hotels::CheckOutTime)
+                    .drop_column(Hotels::ChildPolicy)
+                    .drop_column(Hotels::PetPolicy)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RoomTypeAmenities::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(HotelAmenities::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Amenities::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Amenities {
+    Table,
+    Id,
+    Code,
+    Name,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum HotelAmenities {
+    Table,
+    HotelId,
+    AmenityId,
+}
+
+#[derive(Iden)]
+enum RoomTypeAmenities {
+    Table,
+    HotelId,
+    RoomType,
+    AmenityId,
+}
+
+#[derive(Iden)]
+enum Hotels {
+    Table,
+    Id,
+    CheckInTime,
+    CheckOutTime,
+    ChildPolicy,
+    PetPolicy,
+}
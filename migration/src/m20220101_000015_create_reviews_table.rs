@@ -0,0 +1,206 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::prelude::extension::postgres::Type;
+
+#[derive(Iden)]
+pub enum ReviewStatus {
+    #[iden = "review_status"]
+    Enum,
+    #[iden = "pending"]
+    Pending,
+    #[iden = "published"]
+    Published,
+    #[iden = "rejected"]
+    Rejected,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create the enum type
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(ReviewStatus::Enum)
+                    .values([
+                        ReviewStatus::Pending,
+                        ReviewStatus::Published,
+                        ReviewStatus::Rejected,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create reviews table, one per completed booking
+        manager
+            .create_table(
+                Table::create()
+                    .table(Reviews::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Reviews::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Reviews::BookingId).uuid().not_null().unique_key())
+                    .col(ColumnDef::new(Reviews::HotelId).uuid().not_null())
+                    .col(ColumnDef::new(Reviews::GuestId).uuid().not_null())
+                    .col(ColumnDef::new(Reviews::Rating).small_integer().not_null())
+                    .col(ColumnDef::new(Reviews::Cleanliness).small_integer().not_null())
+                    .col(ColumnDef::new(Reviews::Location).small_integer().not_null())
+                    .col(ColumnDef::new(Reviews::Service).small_integer().not_null())
+                    .col(ColumnDef::new(Reviews::Comment).text().null())
+                    .col(ColumnDef::new(Reviews::Status)
+                        .custom(Alias::new("review_status"))
+                        .not_null())
+                    .col(ColumnDef::new(Reviews::ModerationNote).text().null())
+                    .col(ColumnDef::new(Reviews::ModeratedAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(Reviews::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(Reviews::UpdatedAt).timestamp_with_time_zone().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reviews_booking")
+                            .from(Reviews::Table, Reviews::BookingId)
+                            .to(Bookings::Table, Bookings::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reviews_hotel")
+                            .from(Reviews::Table, Reviews::HotelId)
+                            .to(Hotels::Table, Hotels::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reviews_guest")
+                            .from(Reviews::Table, Reviews::GuestId)
+                            .to(Guests::Table, Guests::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .check(Expr::col(Reviews::Rating).between(1, 5))
+                    .check(Expr::col(Reviews::Cleanliness).between(1, 5))
+                    .check(Expr::col(Reviews::Location).between(1, 5))
+                    .check(Expr::col(Reviews::Service).between(1, 5))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Hotel pages list the published reviews of a hotel newest first
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_reviews_hotel_id_status_created_at")
+                    .table(Reviews::Table)
+                    .col(Reviews::HotelId)
+                    .col(Reviews::Status)
+                    .col(Reviews::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // The rating is now the average of the published reviews, hotels
+        // without any have none
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Hotels::Table)
+                    .modify_column(ColumnDef::new(Hotels::Rating).double().null())
+                    .add_column(ColumnDef::new(Hotels::ReviewCount).integer().not_null().default(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Hotels::Table)
+                    .value(Hotels::Rating, Option::<f64>::None)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Hotels::Table)
+                    .value(Hotels::Rating, 0.0)
+                    .and_where(Expr::col(Hotels::Rating).is_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Hotels::Table)
+                    .modify_column(ColumnDef::new(Hotels::Rating).double().not_null())
+                    .drop_column(Hotels::ReviewCount)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Reviews::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .name(Alias::new("review_status"))
+                    .to_owned()
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Reviews {
+    Table,
+    Id,
+    BookingId,
+    HotelId,
+    GuestId,
+    Rating,
+    Cleanliness,
+    Location,
+    Service,
+    Comment,
+    Status,
+    ModerationNote,
+    ModeratedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Bookings {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Hotels {
+    Table,
+    Id,
+    Rating,
+    ReviewCount,
+}
+
+#[derive(Iden)]
+enum Guests {
+    Table,
+    Id,
+}
@@ -0,0 +1,130 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Photos of a hotel or of a room, the files live in the media storage
+        manager
+            .create_table(
+                Table::create()
+                    .table(Media::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Media::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Media::HotelId).uuid().null())
+                    .col(ColumnDef::new(Media::RoomId).uuid().null())
+                    .col(ColumnDef::new(Media::StorageKey).string().not_null())
+                    .col(ColumnDef::new(Media::ThumbnailKey).string().not_null())
+                    .col(ColumnDef::new(Media::ContentType).string().not_null())
+                    .col(ColumnDef::new(Media::SizeBytes).big_integer().not_null())
+                    .col(ColumnDef::new(Media::Width).integer().not_null())
+                    .col(ColumnDef::new(Media::Height).integer().not_null())
+                    .col(ColumnDef::new(Media::Caption).text().null())
+                    .col(ColumnDef::new(Media::Position).integer().not_null())
+                    .col(ColumnDef::new(Media::IsCover).boolean().not_null().default(false))
+                    .col(ColumnDef::new(Media::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(Media::UpdatedAt).timestamp_with_time_zone().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_media_hotel")
+                            .from(Media::Table, Media::HotelId)
+                            .to(Hotels::Table, Hotels::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_media_room")
+                            .from(Media::Table, Media::RoomId)
+                            .to(Rooms::Table, Rooms::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .check(Expr::col(Media::HotelId).is_null().ne(Expr::col(Media::RoomId).is_null()))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Galleries are listed in order
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_media_hotel_id_position")
+                    .table(Media::Table)
+                    .col(Media::HotelId)
+                    .col(Media::Position)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_media_room_id_position")
+                    .table(Media::Table)
+                    .col(Media::RoomId)
+                    .col(Media::Position)
+                    .to_owned(),
+            )
+            .await?;
+
+        // At most one cover per gallery
+        manager
+            .get_connection()
+            .execute_unprepared("CREATE UNIQUE INDEX idx_media_hotel_cover ON media (hotel_id) WHERE is_cover")
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("CREATE UNIQUE INDEX idx_media_room_cover ON media (room_id) WHERE is_cover")
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Media::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Media {
+    Table,
+    Id,
+    HotelId,
+    RoomId,
+    StorageKey,
+    ThumbnailKey,
+    ContentType,
+    SizeBytes,
+    Width,
+    Height,
+    Caption,
+    Position,
+    IsCover,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Hotels {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Rooms {
+    Table,
+    Id,
+}
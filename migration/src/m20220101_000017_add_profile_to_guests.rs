@@ -0,0 +1,184 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::prelude::extension::postgres::Type;
+
+#[derive(Iden)]
+pub enum IdDocumentType {
+    #[iden = "id_document_type"]
+    Enum,
+    #[iden = "passport"]
+    Passport,
+    #[iden = "national_id"]
+    NationalId,
+    #[iden = "driving_licence"]
+    DrivingLicence,
+    #[iden = "residence_permit"]
+    ResidencePermit,
+    #[iden = "other"]
+    Other,
+}
+
+#[derive(Iden)]
+pub enum FloorPreference {
+    #[iden = "floor_preference"]
+    Enum,
+    #[iden = "low"]
+    Low,
+    #[iden = "high"]
+    High,
+}
+
+#[derive(Iden)]
+pub enum BedPreference {
+    #[iden = "bed_preference"]
+    Enum,
+    #[iden = "single"]
+    Single,
+    #[iden = "double"]
+    Double,
+    #[iden = "queen"]
+    Queen,
+    #[iden = "king"]
+    King,
+    #[iden = "twin"]
+    Twin,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create the enum types
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(IdDocumentType::Enum)
+                    .values([
+                        IdDocumentType::Passport,
+                        IdDocumentType::NationalId,
+                        IdDocumentType::DrivingLicence,
+                        IdDocumentType::ResidencePermit,
+                        IdDocumentType::Other,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(FloorPreference::Enum)
+                    .values([FloorPreference::Low, FloorPreference::High])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(BedPreference::Enum)
+                    .values([
+                        BedPreference::Single,
+                        BedPreference::Double,
+                        BedPreference::Queen,
+                        BedPreference::King,
+                        BedPreference::Twin,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // Address, identity for police registration, stay preferences and
+        // marketing consent, all optional
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Guests::Table)
+                    .add_column(ColumnDef::new(Guests::Street).string().null())
+                    .add_column(ColumnDef::new(Guests::City).string().null())
+                    .add_column(ColumnDef::new(Guests::Region).string().null())
+                    .add_column(ColumnDef::new(Guests::PostalCode).string().null())
+                    .add_column(ColumnDef::new(Guests::Country).string_len(2).null())
+                    .add_column(ColumnDef::new(Guests::DateOfBirth).date().null())
+                    .add_column(ColumnDef::new(Guests::Nationality).string_len(2).null())
+                    .add_column(ColumnDef::new(Guests::IdDocumentType)
+                        .custom(Alias::new("id_document_type"))
+                        .null())
+                    .add_column(ColumnDef::new(Guests::IdDocumentNumber).string().null())
+                    .add_column(ColumnDef::new(Guests::FloorPreference)
+                        .custom(Alias::new("floor_preference"))
+                        .null())
+                    .add_column(ColumnDef::new(Guests::BedPreference)
+                        .custom(Alias::new("bed_preference"))
+                        .null())
+                    .add_column(ColumnDef::new(Guests::Allergies).text().null())
+                    .add_column(ColumnDef::new(Guests::MarketingEmailConsent).boolean().not_null().default(false))
+                    .add_column(ColumnDef::new(Guests::MarketingSmsConsent).boolean().not_null().default(false))
+                    .add_column(ColumnDef::new(Guests::MarketingConsentAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Guests::Table)
+                    .drop_column(Guests::Street)
+                    .drop_column(Guests::City)
+                    .drop_column(Guests::Region)
+                    .drop_column(Guests::PostalCode)
+                    .drop_column(Guests::Country)
+                    .drop_column(Guests::DateOfBirth)
+                    .drop_column(Guests::Nationality)
+                    .drop_column(Guests::IdDocumentType)
+                    .drop_column(Guests::IdDocumentNumber)
+                    .drop_column(Guests::FloorPreference)
+                    .drop_column(Guests::BedPreference)
+                    .drop_column(Guests::Allergies)
+                    .drop_column(Guests::MarketingEmailConsent)
+                    .drop_column(Guests::MarketingSmsConsent)
+                    .drop_column(Guests::MarketingConsentAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(IdDocumentType::Enum).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(FloorPreference::Enum).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(BedPreference::Enum).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Guests {
+    Table,
+    Street,
+    City,
+    Region,
+    PostalCode,
+    Country,
+    DateOfBirth,
+    Nationality,
+    IdDocumentType,
+    IdDocumentNumber,
+    FloorPreference,
+    BedPreference,
+    Allergies,
+    MarketingEmailConsent,
+    MarketingSmsConsent,
+    MarketingConsentAt,
+}
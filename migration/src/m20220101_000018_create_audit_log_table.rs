@@ -0,0 +1,88 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Trail of the privacy sensitive operations, such as data subject requests
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditLog::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditLog::Action).string().not_null())
+                    .col(ColumnDef::new(AuditLog::EntityType).string().not_null())
+                    .col(ColumnDef::new(AuditLog::EntityId).uuid().not_null())
+                    .col(ColumnDef::new(AuditLog::Details).json_binary().null())
+                    .col(ColumnDef::new(AuditLog::CreatedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_log_entity_type_entity_id_created_at")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::EntityType)
+                    .col(AuditLog::EntityId)
+                    .col(AuditLog::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Anonymized guests keep their row so their bookings stay accounted for
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Guests::Table)
+                    .add_column(ColumnDef::new(Guests::AnonymizedAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Guests::Table)
+                    .drop_column(Guests::AnonymizedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(AuditLog::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum AuditLog {
+    Table,
+    Id,
+    Action,
+    EntityType,
+    EntityId,
+    Details,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Guests {
+    Table,
+    AnonymizedAt,
+}
@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // A merged guest stays as a tombstone pointing at the guest it was merged into
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Guests::Table)
+                    .add_column(ColumnDef::new(Guests::MergedIntoId).uuid().null())
+                    .add_column(ColumnDef::new(Guests::MergedAt).timestamp_with_time_zone().null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_guests_merged_into")
+                            .from_tbl(Guests::Table)
+                            .from_col(Guests::MergedIntoId)
+                            .to_tbl(Guests::Table)
+                            .to_col(Guests::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Duplicate detection compares names and emails by trigram similarity
+        manager
+            .get_connection()
+            .execute_unprepared("CREATE EXTENSION IF NOT EXISTS pg_trgm")
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE INDEX idx_guests_full_name_trgm ON guests \
+                 USING GIN (lower(first_name || ' ' || last_name) gin_trgm_ops)",
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("CREATE INDEX idx_guests_email_trgm ON guests USING GIN (lower(email) gin_trgm_ops)")
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DROP INDEX IF EXISTS idx_guests_email_trgm")
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("DROP INDEX IF EXISTS idx_guests_full_name_trgm")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Guests::Table)
+                    .drop_foreign_key(Alias::new("fk_guests_merged_into"))
+                    .drop_column(Guests::MergedIntoId)
+                    .drop_column(Guests::MergedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Guests {
+    Table,
+    Id,
+    MergedIntoId,
+    MergedAt,
+}
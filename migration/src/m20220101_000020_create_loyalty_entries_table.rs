@@ -0,0 +1,161 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::prelude::extension::postgres::Type;
+
+#[derive(Iden)]
+pub enum LoyaltyEntryKind {
+    #[iden = "loyalty_entry_kind"]
+    Enum,
+    #[iden = "earned"]
+    Earned,
+    #[iden = "reversed"]
+    Reversed,
+    #[iden = "redeemed"]
+    Redeemed,
+    #[iden = "refunded"]
+    Refunded,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create the enum type
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(LoyaltyEntryKind::Enum)
+                    .values([
+                        LoyaltyEntryKind::Earned,
+                        LoyaltyEntryKind::Reversed,
+                        LoyaltyEntryKind::Redeemed,
+                        LoyaltyEntryKind::Refunded,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create loyalty_entries table, the points ledger of the guests, the
+        // balance is the sum of the signed points
+        manager
+            .create_table(
+                Table::create()
+                    .table(LoyaltyEntries::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LoyaltyEntries::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LoyaltyEntries::GuestId).uuid().not_null())
+                    .col(ColumnDef::new(LoyaltyEntries::BookingId).uuid().null())
+                    .col(ColumnDef::new(LoyaltyEntries::Kind)
+                        .custom(Alias::new("loyalty_entry_kind"))
+                        .not_null())
+                    .col(ColumnDef::new(LoyaltyEntries::Points).integer().not_null())
+                    .col(ColumnDef::new(LoyaltyEntries::CreatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_loyalty_entries_guest")
+                            .from(LoyaltyEntries::Table, LoyaltyEntries::GuestId)
+                            .to(Guests::Table, Guests::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_loyalty_entries_booking")
+                            .from(LoyaltyEntries::Table, LoyaltyEntries::BookingId)
+                            .to(Bookings::Table, Bookings::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The account lists the entries of a guest newest first
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_loyalty_entries_guest_id_created_at")
+                    .table(LoyaltyEntries::Table)
+                    .col(LoyaltyEntries::GuestId)
+                    .col(LoyaltyEntries::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_loyalty_entries_booking_id")
+                    .table(LoyaltyEntries::Table)
+                    .col(LoyaltyEntries::BookingId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Points redeemed on a booking and the discount they bought
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bookings::Table)
+                    .add_column(ColumnDef::new(Bookings::LoyaltyPointsRedeemed).integer().not_null().default(0))
+                    .add_column(ColumnDef::new(Bookings::LoyaltyDiscount).decimal_len(10, 2).not_null().default(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bookings::Table)
+                    .drop_column(Bookings::LoyaltyPointsRedeemed)
+                    .drop_column(Bookings::LoyaltyDiscount)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(LoyaltyEntries::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(LoyaltyEntryKind::Enum).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum LoyaltyEntries {
+    Table,
+    Id,
+    GuestId,
+    BookingId,
+    Kind,
+    Points,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Guests {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Bookings {
+    Table,
+    Id,
+    LoyaltyPointsRedeemed,
+    LoyaltyDiscount,
+}
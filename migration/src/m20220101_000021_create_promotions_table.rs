@@ -0,0 +1,167 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::prelude::extension::postgres::Type;
+
+#[derive(Iden)]
+pub enum DiscountKind {
+    #[iden = "discount_kind"]
+    Enum,
+    #[iden = "percent"]
+    Percent,
+    #[iden = "fixed"]
+    Fixed,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create the enum type
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(DiscountKind::Enum)
+                    .values([DiscountKind::Percent, DiscountKind::Fixed])
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create promotions table, campaign codes and the bookings they apply to
+        manager
+            .create_table(
+                Table::create()
+                    .table(Promotions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Promotions::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Promotions::Code).string().not_null().unique_key())
+                    .col(ColumnDef::new(Promotions::Name).string().not_null())
+                    .col(ColumnDef::new(Promotions::DiscountKind)
+                        .custom(Alias::new("discount_kind"))
+                        .not_null())
+                    .col(ColumnDef::new(Promotions::DiscountValue).decimal_len(10, 2).not_null())
+                    .col(ColumnDef::new(Promotions::HotelId).uuid().null())
+                    .col(ColumnDef::new(Promotions::RoomType).string().null())
+                    .col(ColumnDef::new(Promotions::MinNights).integer().null())
+                    .col(ColumnDef::new(Promotions::StartsAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(Promotions::EndsAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(Promotions::MaxRedemptions).integer().null())
+                    .col(ColumnDef::new(Promotions::MaxRedemptionsPerGuest).integer().null())
+                    .col(ColumnDef::new(Promotions::IsActive).boolean().not_null().default(true))
+                    .col(ColumnDef::new(Promotions::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(Promotions::UpdatedAt).timestamp_with_time_zone().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_promotions_hotel")
+                            .from(Promotions::Table, Promotions::HotelId)
+                            .to(Hotels::Table, Hotels::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .check(Expr::col(Promotions::DiscountValue).gt(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        // The promotion a booking was made with and the discount it gave, the
+        // code is kept once the promotion is deleted
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bookings::Table)
+                    .add_column(ColumnDef::new(Bookings::PromotionId).uuid().null())
+                    .add_column(ColumnDef::new(Bookings::PromoCode).string().null())
+                    .add_column(ColumnDef::new(Bookings::PromotionDiscount).decimal_len(10, 2).not_null().default(0))
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_bookings_promotion")
+                            .from_tbl(Bookings::Table)
+                            .from_col(Bookings::PromotionId)
+                            .to_tbl(Promotions::Table)
+                            .to_col(Promotions::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Redemptions are counted per promotion and guest
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_bookings_promotion_id_guest_id")
+                    .table(Bookings::Table)
+                    .col(Bookings::PromotionId)
+                    .col(Bookings::GuestId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bookings::Table)
+                    .drop_foreign_key(Alias::new("fk_bookings_promotion"))
+                    .drop_column(Bookings::PromotionId)
+                    .drop_column(Bookings::PromoCode)
+                    .drop_column(Bookings::PromotionDiscount)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Promotions::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(DiscountKind::Enum).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Promotions {
+    Table,
+    Id,
+    Code,
+    Name,
+    DiscountKind,
+    DiscountValue,
+    HotelId,
+    RoomType,
+    MinNights,
+    StartsAt,
+    EndsAt,
+    MaxRedemptions,
+    MaxRedemptionsPerGuest,
+    IsActive,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Hotels {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Bookings {
+    Table,
+    GuestId,
+    PromotionId,
+    PromoCode,
+    PromotionDiscount,
+}
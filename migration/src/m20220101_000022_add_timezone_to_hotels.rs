@@ -0,0 +1,46 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // IANA timezone the check-in and check-out times are in, existing
+        // hotels keep their times in UTC
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Hotels::Table)
+                    .add_column(
+                        ColumnDef::new(Hotels::Timezone)
+                            .string()
+                            .not_null()
+                            .default("UTC"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Hotels::Table)
+                    .drop_column(Hotels::Timezone)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Hotels {
+    Table,
+    Timezone,
+}
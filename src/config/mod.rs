@@ -4,6 +4,26 @@ use sea_orm::DatabaseConnection;
 pub struct AppConfig {
     pub database_url    : String
     , pub port          : u16
+    , pub scheduler_interval_secs   : u64
+    , pub pending_booking_ttl_mins  : i64
+    , pub outbox_interval_secs      : u64
+    , pub outbox_retention_days     : i64
+    , pub webhook_interval_secs     : u64
+    , pub webhook_max_attempts      : i32
+    , pub webhook_backoff_secs      : i64
+    , pub smtp_host                 : String
+    , pub smtp_port                 : u16
+    , pub smtp_username             : Option<String>
+    , pub smtp_password             : Option<String>
+    , pub smtp_from                 : String
+    , pub smtp_starttls             : bool
+    , pub notification_interval_secs: u64
+    , pub notification_max_attempts : i32
+    , pub reminder_lead_hours       : i64
+    , pub ical_sync_interval_secs   : u64
+    , pub media_dir                 : String
+    , pub media_max_upload_mib      : u64
+    , pub staff_api_key             : Option<String>
 }
 
 
@@ -32,16 +52,131 @@ impl AppConfig {
             .parse()
             .expect("PORT must be a number");
 
+        // Get scheduler vars
+        let scheduler_interval_secs = env::var("SCHEDULER_INTERVAL_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .expect("SCHEDULER_INTERVAL_SECS must be a number");
+
+        let pending_booking_ttl_mins = env::var("PENDING_BOOKING_TTL_MINS")
+            .unwrap_or_else(|_| "1440".to_string())
+            .parse()
+            .expect("PENDING_BOOKING_TTL_MINS must be a number");
+
+        let outbox_interval_secs = env::var("OUTBOX_INTERVAL_SECS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .expect("OUTBOX_INTERVAL_SECS must be a number");
+
+        let outbox_retention_days = env::var("OUTBOX_RETENTION_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .expect("OUTBOX_RETENTION_DAYS must be a number");
+
+        // Get webhook delivery vars
+        let webhook_interval_secs = env::var("WEBHOOK_INTERVAL_SECS")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .expect("WEBHOOK_INTERVAL_SECS must be a number");
+
+        let webhook_max_attempts = env::var("WEBHOOK_MAX_ATTEMPTS")
+            .unwrap_or_else(|_| "8".to_string())
+            .parse()
+            .expect("WEBHOOK_MAX_ATTEMPTS must be a number");
+
+        let webhook_backoff_secs = env::var("WEBHOOK_BACKOFF_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .expect("WEBHOOK_BACKOFF_SECS must be a number");
+
+        // Get smtp vars, defaults match a local MailHog sink
+        let smtp_host = env::var("SMTP_HOST").unwrap_or_else(|_| "localhost".to_string());
+
+        let smtp_port = env::var("SMTP_PORT")
+            .unwrap_or_else(|_| "1025".to_string())
+            .parse()
+            .expect("SMTP_PORT must be a number");
+
+        let smtp_username = env::var("SMTP_USERNAME").ok().filter(|v| !v.is_empty());
+        let smtp_password = env::var("SMTP_PASSWORD").ok().filter(|v| !v.is_empty());
+
+        let smtp_from = env::var("SMTP_FROM")
+            .unwrap_or_else(|_| "Hotel Booking <no-reply@hotel-booking.local>".to_string());
+
+        let smtp_starttls = env::var("SMTP_STARTTLS")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .expect("SMTP_STARTTLS must be true or false");
+
+        // Get notification vars
+        let notification_interval_secs = env::var("NOTIFICATION_INTERVAL_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .expect("NOTIFICATION_INTERVAL_SECS must be a number");
+
+        let notification_max_attempts = env::var("NOTIFICATION_MAX_ATTEMPTS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .expect("NOTIFICATION_MAX_ATTEMPTS must be a number");
+
+        let reminder_lead_hours = env::var("REMINDER_LEAD_HOURS")
+            .unwrap_or_else(|_| "24".to_string())
+            .parse()
+            .expect("REMINDER_LEAD_HOURS must be a number");
+
+        // Get external calendar vars
+        let ical_sync_interval_secs = env::var("ICAL_SYNC_INTERVAL_SECS")
+            .unwrap_or_else(|_| "900".to_string())
+            .parse()
+            .expect("ICAL_SYNC_INTERVAL_SECS must be a number");
+
+        // Get media vars
+        let media_dir = env::var("MEDIA_DIR").unwrap_or_else(|_| "media".to_string());
+
+        let media_max_upload_mib = env::var("MEDIA_MAX_UPLOAD_MIB")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .expect("MEDIA_MAX_UPLOAD_MIB must be a number");
+
+        // Get access vars, without a staff key sensitive guest data stays hidden
+        let staff_api_key = env::var("STAFF_API_KEY").ok().filter(|v| !v.is_empty());
+
         Self {
             database_url
             , port
+            , scheduler_interval_secs
+            , pending_booking_ttl_mins
+            , outbox_interval_secs
+            , outbox_retention_days
+            , webhook_interval_secs
+            , webhook_max_attempts
+            , webhook_backoff_secs
+            , smtp_host
+            , smtp_port
+            , smtp_username
+            , smtp_password
+            , smtp_from
+            , smtp_starttls
+            , notification_interval_secs
+            , notification_max_attempts
+            , reminder_lead_hours
+            , ical_sync_interval_secs
+            , media_dir
+            , media_max_upload_mib
+            , staff_api_key
         }
     }
-    
+
 
     pub async fn establish_connection(&self) -> DatabaseConnection {
         sea_orm::Database::connect(&self.database_url)
             .await
             .expect("Faield to connect to database")
     }
-}
\ No newline at end of file
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -18,7 +18,19 @@ pub enum ApiError {
     , #[error("Hotel not found with ID: {0}")]
     HotelNotFound(String)
     , #[error("Room not found with ID: {0}")]
-    RoomNotFound(String),
+    RoomNotFound(String)
+    , #[error("Validation error: {0}")]
+    Validation(String)
+    , #[error("Room {0} is not available for the requested dates")]
+    RoomUnavailable(String)
+    , #[error("No {0} room is left for the requested dates")]
+    RoomTypeSoldOut(String)
+    , #[error("External calendar error: {0}")]
+    ExternalCalendar(String)
+    , #[error("Media storage error: {0}")]
+    Storage(String)
+    , #[error("Forbidden: {0}")]
+    Forbidden(String),
 }
 
 impl<'r> Responder<'r, 'static> for ApiError {
@@ -30,6 +42,11 @@ impl<'r> Responder<'r, 'static> for ApiError {
                 _ => Status::InternalServerError,
             },
             ApiError::HotelNotFound(_) | ApiError::RoomNotFound(_) => Status::NotFound,
+            ApiError::Validation(_) => Status::BadRequest,
+            ApiError::RoomUnavailable(_) | ApiError::RoomTypeSoldOut(_) => Status::Conflict,
+            ApiError::ExternalCalendar(_) => Status::BadGateway,
+            ApiError::Storage(_) => Status::InternalServerError,
+            ApiError::Forbidden(_) => Status::Forbidden,
         };
 
         let error = ErrorResponse {
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+use sea_orm::*;
+use sea_orm::sea_query::{LockBehavior, LockType};
+use chrono::{Utc, FixedOffset};
+use crate::{
+    events::DomainEvent,
+    jobs::Job,
+    models::outbox,
+};
+
+const BATCH_SIZE: u64 = 100;
+
+/// Entries failing this many times are left in the outbox for inspection
+const MAX_ATTEMPTS: i32 = 10;
+
+#[async_trait]
+pub trait EventHandler: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Handles one event. Database writes should go through `txn` so they
+    /// commit together with the outbox entry being marked as processed.
+    async fn handle(&self, txn: &DatabaseTransaction, event: &DomainEvent) -> Result<(), DbErr>;
+}
+
+/// Drains the outbox in creation order and hands every event to all
+/// registered handlers. An entry is only marked processed once every handler
+/// succeeded, so handlers see each event at least once and must be idempotent.
+#[derive(Default)]
+pub struct OutboxDispatcher {
+    handlers    : Vec<Arc<dyn EventHandler>>
+}
+
+impl OutboxDispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(mut self, handler: impl EventHandler + 'static) -> Self {
+        self.handlers.push(Arc::new(handler));
+        self
+    }
+
+    async fn dispatch(&self, txn: &DatabaseTransaction, entry: &outbox::Model) -> Result<(), DbErr> {
+        let event: DomainEvent = serde_json::from_value(entry.payload.clone())
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        // Run handlers in a savepoint so a failing handler leaves no partial writes
+        let savepoint = txn.begin().await?;
+
+        for handler in &self.handlers {
+            if let Err(err) = handler.handle(&savepoint, &event).await {
+                savepoint.rollback().await?;
+                return Err(DbErr::Custom(format!("{}: {}", handler.name(), err)));
+            }
+        }
+
+        savepoint.commit().await
+    }
+}
+
+#[async_trait]
+impl Job for OutboxDispatcher {
+    fn name(&self) -> &'static str {
+        "dispatch_outbox"
+    }
+
+    async fn run(&self, db: &DatabaseConnection) -> Result<u64, DbErr> {
+        let txn = db.begin().await?;
+
+        // Skip rows locked by another dispatcher instance
+        let entries = outbox::Entity::find()
+            .filter(outbox::Column::ProcessedAt.is_null())
+            .filter(outbox::Column::Attempts.lt(MAX_ATTEMPTS))
+            .order_by_asc(outbox::Column::CreatedAt)
+            .limit(BATCH_SIZE)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .all(&txn)
+            .await?;
+
+        let mut processed = 0;
+
+        for entry in entries {
+            let result = self.dispatch(&txn, &entry).await;
+            let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+            let attempts = entry.attempts + 1;
+
+            let mut entry: outbox::ActiveModel = entry.into();
+            entry.attempts = Set(attempts);
+
+            match result {
+                Ok(()) => {
+                    entry.processed_at  = Set(Some(now));
+                    entry.last_error    = Set(None);
+                    processed += 1;
+                }
+                Err(err) => {
+                    println!("⚠️ Outbox event failed (attempt {}): {}", attempts, err);
+                    entry.last_error    = Set(Some(err.to_string()));
+                }
+            }
+
+            entry.update(&txn).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(processed)
+    }
+}
@@ -0,0 +1,379 @@
+use rust_decimal::Decimal;
+use sea_orm::*;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc, FixedOffset};
+use crate::{
+    models::outbox,
+    schemas::{amenities::{AmenitySchemaOut, RoomTypeAmenitiesSchemaOut}, booking::BookingSchemaOut, guests::GuestSchemaOut, loyalty::LoyaltyEntrySchemaOut, promotions::PromotionSchemaOut, hotels::HotelSchemaOut, rooms::RoomSchemaOut, room_blocks::RoomBlockSchemaOut, housekeeping::HousekeepingSchemaOut, reservations::ReservationSchemaOut, waitlist::WaitlistEntrySchemaOut, reviews::ReviewSchemaOut, media::{MediaOwner, MediaSchemaOut}},
+};
+
+pub mod dispatcher;
+
+pub use dispatcher::{EventHandler, OutboxDispatcher};
+
+/// Something that happened to an aggregate, recorded in the `outbox` table by
+/// the service mutation that caused it
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum DomainEvent {
+    HotelCreated(HotelSchemaOut)
+    , HotelUpdated(HotelSchemaOut)
+    , HotelDeleted { id: Uuid }
+    , RoomTypeAmenitiesUpdated(RoomTypeAmenitiesSchemaOut)
+
+    , AmenityCreated(AmenitySchemaOut)
+    , AmenityUpdated(AmenitySchemaOut)
+    , AmenityDeleted { id: Uuid }
+
+    , RoomCreated(RoomSchemaOut)
+    , RoomUpdated(RoomSchemaOut)
+    , RoomPriceChanged { room_id: Uuid, old_price: Decimal, new_price: Decimal }
+    , RoomDeleted { id: Uuid }
+    , RoomHousekeepingChanged(HousekeepingSchemaOut)
+
+    , RoomBlockCreated(RoomBlockSchemaOut)
+    , RoomBlockUpdated(RoomBlockSchemaOut)
+    , RoomBlockDeleted { id: Uuid, room_id: Uuid }
+
+    , GuestCreated(GuestSchemaOut)
+    , GuestUpdated(GuestSchemaOut)
+    , GuestDeleted { id: Uuid }
+    , GuestAnonymized { id: Uuid }
+    , GuestMerged { id: Uuid, merged_ids: Vec<Uuid> }
+
+    , BookingCreated(BookingSchemaOut)
+    , BookingUpdated(BookingSchemaOut)
+    , BookingConfirmed(BookingSchemaOut)
+    , BookingCancelled(BookingSchemaOut)
+    , BookingCompleted(BookingSchemaOut)
+    , BookingRoomAssigned(BookingSchemaOut)
+    , BookingDeleted { id: Uuid }
+
+    , LoyaltyPointsChanged(LoyaltyEntrySchemaOut)
+
+    , PromotionCreated(PromotionSchemaOut)
+    , PromotionUpdated(PromotionSchemaOut)
+    , PromotionDeleted { id: Uuid }
+
+    , ReservationCreated(ReservationSchemaOut)
+    , ReservationUpdated(ReservationSchemaOut)
+    , ReservationCancelled(ReservationSchemaOut)
+
+    , WaitlistEntryCreated(WaitlistEntrySchemaOut)
+    , WaitlistEntryDeleted { id: Uuid }
+    , WaitlistOffered(WaitlistEntrySchemaOut)
+
+    , ReviewCreated(ReviewSchemaOut)
+    , ReviewUpdated(ReviewSchemaOut)
+    , ReviewModerated(ReviewSchemaOut)
+    , ReviewDeleted { id: Uuid }
+
+    , MediaCreated(MediaSchemaOut)
+    , MediaUpdated(MediaSchemaOut)
+    , MediaReordered { owner: MediaOwner, media_ids: Vec<Uuid> }
+    , MediaDeleted { id: Uuid }
+}
+
+impl DomainEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomainEvent::HotelCreated(_)            => "HotelCreated",
+            DomainEvent::HotelUpdated(_)            => "HotelUpdated",
+            DomainEvent::HotelDeleted { .. }        => "HotelDeleted",
+            DomainEvent::RoomTypeAmenitiesUpdated(_) => "RoomTypeAmenitiesUpdated",
+            DomainEvent::AmenityCreated(_)          => "AmenityCreated",
+            DomainEvent::AmenityUpdated(_)          => "AmenityUpdated",
+            DomainEvent::AmenityDeleted { .. }      => "AmenityDeleted",
+            DomainEvent::RoomCreated(_)             => "RoomCreated",
+            DomainEvent::RoomUpdated(_)             => "RoomUpdated",
+            DomainEvent::RoomPriceChanged { .. }    => "RoomPriceChanged",
+            DomainEvent::RoomDeleted { .. }         => "RoomDeleted",
+            DomainEvent::RoomHousekeepingChanged(_) => "RoomHousekeepingChanged",
+            DomainEvent::RoomBlockCreated(_)        => "RoomBlockCreated",
+            DomainEvent::RoomBlockUpdated(_)        => "RoomBlockUpdated",
+            DomainEvent::RoomBlockDeleted { .. }    => "RoomBlockDeleted",
+            DomainEvent::GuestCreated(_)            => "GuestCreated",
+            DomainEvent::GuestUpdated(_)            => "GuestUpdated",
+            DomainEvent::GuestDeleted { .. }        => "GuestDeleted",
+            DomainEvent::GuestAnonymized { .. }     => "GuestAnonymized",
+            DomainEvent::GuestMerged { .. }         => "GuestMerged",
+            DomainEvent::BookingCreated(_)          => "BookingCreated",
+            DomainEvent::BookingUpdated(_)          => "BookingUpdated",
+            DomainEvent::BookingConfirmed(_)        => "BookingConfirmed",
+            DomainEvent::BookingCancelled(_)        => "BookingCancelled",
+            DomainEvent::BookingCompleted(_)        => "BookingCompleted",
+            DomainEvent::BookingRoomAssigned(_)     => "BookingRoomAssigned",
+            DomainEvent::BookingDeleted { .. }      => "BookingDeleted",
+            DomainEvent::LoyaltyPointsChanged(_)    => "LoyaltyPointsChanged",
+            DomainEvent::PromotionCreated(_)        => "PromotionCreated",
+            DomainEvent::PromotionUpdated(_)        => "PromotionUpdated",
+            DomainEvent::PromotionDeleted { .. }    => "PromotionDeleted",
+            DomainEvent::ReservationCreated(_)      => "ReservationCreated",
+            DomainEvent::ReservationUpdated(_)      => "ReservationUpdated",
+            DomainEvent::ReservationCancelled(_)    => "ReservationCancelled",
+            DomainEvent::WaitlistEntryCreated(_)    => "WaitlistEntryCreated",
+            DomainEvent::WaitlistEntryDeleted { .. } => "WaitlistEntryDeleted",
+            DomainEvent::WaitlistOffered(_)         => "WaitlistOffered",
+            DomainEvent::ReviewCreated(_)           => "ReviewCreated",
+            DomainEvent::ReviewUpdated(_)           => "ReviewUpdated",
+            DomainEvent::ReviewModerated(_)         => "ReviewModerated",
+            DomainEvent::ReviewDeleted { .. }       => "ReviewDeleted",
+            DomainEvent::MediaCreated(_)            => "MediaCreated",
+            DomainEvent::MediaUpdated(_)            => "MediaUpdated",
+            DomainEvent::MediaReordered { .. }      => "MediaReordered",
+            DomainEvent::MediaDeleted { .. }        => "MediaDeleted",
+        }
+    }
+
+    /// Type and id of the aggregate the event belongs to
+    pub fn aggregate(&self) -> (&'static str, Uuid) {
+        match self {
+            DomainEvent::HotelCreated(h)
+            | DomainEvent::HotelUpdated(h)              => ("hotel", h.id),
+            DomainEvent::HotelDeleted { id }            => ("hotel", *id),
+            DomainEvent::RoomTypeAmenitiesUpdated(r)    => ("hotel", r.hotel_id),
+            DomainEvent::AmenityCreated(a)
+            | DomainEvent::AmenityUpdated(a)            => ("amenity", a.id),
+            DomainEvent::AmenityDeleted { id }          => ("amenity", *id),
+            DomainEvent::RoomCreated(r)
+            | DomainEvent::RoomUpdated(r)               => ("room", r.id),
+            DomainEvent::RoomPriceChanged { room_id, .. } => ("room", *room_id),
+            DomainEvent::RoomDeleted { id }             => ("room", *id),
+            DomainEvent::RoomHousekeepingChanged(h)     => ("room", h.room_id),
+            DomainEvent::RoomBlockCreated(b)
+            | DomainEvent::RoomBlockUpdated(b)          => ("room_block", b.id),
+            DomainEvent::RoomBlockDeleted { id, .. }    => ("room_block", *id),
+            DomainEvent::GuestCreated(g)
+            | DomainEvent::GuestUpdated(g)              => ("guest", g.id),
+            DomainEvent::GuestDeleted { id }
+            | DomainEvent::GuestAnonymized { id }
+            | DomainEvent::GuestMerged { id, .. }       => ("guest", *id),
+            DomainEvent::BookingCreated(b)
+            | DomainEvent::BookingUpdated(b)
+            | DomainEvent::BookingConfirmed(b)
+            | DomainEvent::BookingCancelled(b)
+            | DomainEvent::BookingCompleted(b)
+            | DomainEvent::BookingRoomAssigned(b)       => ("booking", b.id),
+            DomainEvent::BookingDeleted { id }          => ("booking", *id),
+            DomainEvent::LoyaltyPointsChanged(e)        => ("guest", e.guest_id),
+            DomainEvent::PromotionCreated(p)
+            | DomainEvent::PromotionUpdated(p)          => ("promotion", p.id),
+            DomainEvent::PromotionDeleted { id }        => ("promotion", *id),
+            DomainEvent::ReservationCreated(r)
+            | DomainEvent::ReservationUpdated(r)
+            | DomainEvent::ReservationCancelled(r)      => ("reservation", r.id),
+            DomainEvent::WaitlistEntryCreated(w)
+            | DomainEvent::WaitlistOffered(w)           => ("waitlist_entry", w.id),
+            DomainEvent::WaitlistEntryDeleted { id }    => ("waitlist_entry", *id),
+            DomainEvent::ReviewCreated(r)
+            | DomainEvent::ReviewUpdated(r)
+            | DomainEvent::ReviewModerated(r)           => ("review", r.id),
+            DomainEvent::ReviewDeleted { id }           => ("review", *id),
+            DomainEvent::MediaCreated(m)
+            | DomainEvent::MediaUpdated(m)              => ("media", m.id),
+            DomainEvent::MediaReordered { owner: MediaOwner::Hotel(id), .. } => ("hotel", *id),
+            DomainEvent::MediaReordered { owner: MediaOwner::Room(id), .. }  => ("room", *id),
+            DomainEvent::MediaDeleted { id }            => ("media", *id),
+        }
+    }
+}
+
+/// Writes `event` to the outbox. Call it with the transaction of the mutation
+/// so the event only becomes visible if that transaction commits.
+pub async fn record<C: ConnectionTrait>(conn: &C, event: DomainEvent) -> Result<(), DbErr> {
+    let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+    let (aggregate_type, aggregate_id) = event.aggregate();
+
+    let payload = serde_json::to_value(&event)
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+    let entry = outbox::ActiveModel {
+        id                  : Set(Uuid::new_v4())
+        , event_type        : Set(event.name().to_string())
+        , aggregate_type    : Set(aggregate_type.to_string())
+        , aggregate_id      : Set(aggregate_id)
+        , payload           : Set(payload)
+        , attempts          : Set(0)
+        , last_error        : Set(None)
+        , created_at        : Set(now)
+        , processed_at      : Set(None)
+    };
+
+    entry.insert(conn).await?;
+    Ok(())
+}
+
+/// Deletes the events processed before `before`. Pending and failed events
+/// stay in the outbox.
+pub async fn prune<C: ConnectionTrait>(conn: &C, before: DateTime<FixedOffset>) -> Result<u64, DbErr> {
+    let result = outbox::Entity::delete_many()
+        .filter(outbox::Column::ProcessedAt.lt(before))
+        .exec(conn)
+        .await?;
+
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    const HOTEL_ID: &str = "0fa47671-33b8-45f6-8ca8-0a65ad8f485c";
+    const ROOM_ID: &str = "cee01d7c-0d3f-4ce6-8a23-9b3c42133ce4";
+    const GUEST_ID: &str = "dcdab376-a2c9-4c06-95b2-a285fdaa6b95";
+    const BOOKING_ID: &str = "62f3bc3f-e8e5-4189-abcb-1b0717fc76b3";
+
+    /// Reads a payload as the dispatcher does when it drains the outbox
+    fn read(payload: serde_json::Value) -> DomainEvent {
+        serde_json::from_value(payload).expect("stored payload no longer deserializes")
+    }
+
+    #[test]
+    fn reads_hotel_events_recorded_before_addresses_policies_and_reviews() {
+        let DomainEvent::HotelCreated(hotel) = read(json!({
+            "type": "HotelCreated",
+            "data": {
+                "id": HOTEL_ID,
+                "name": "Sea Hotel",
+                "address": "1 Beach Rd, Lisbon",
+                "rating": 4.5,
+                "description": null,
+                "created_at": "2024-01-10T12:00:00+00:00",
+                "updated_at": null
+            }
+        })) else { panic!("not a HotelCreated") };
+
+        assert_eq!(hotel.address, "1 Beach Rd, Lisbon");
+        assert_eq!(hotel.rating, Some(4.5));
+        assert_eq!(hotel.review_count, 0);
+        assert_eq!(hotel.policies.timezone.as_deref(), Some("UTC"));
+        assert!(hotel.amenities.is_empty() && hotel.media.is_empty());
+    }
+
+    #[test]
+    fn reads_hotel_events_recorded_before_timezones() {
+        let DomainEvent::HotelUpdated(hotel) = read(json!({
+            "type": "HotelUpdated",
+            "data": {
+                "id": HOTEL_ID,
+                "name": "Sea Hotel",
+                "address": "1 Beach Rd, 1100-148 Lisbon, PT",
+                "street": "1 Beach Rd",
+                "city": "Lisbon",
+                "region": null,
+                "postal_code": "1100-148",
+                "country": "PT",
+                "latitude": 38.7223,
+                "longitude": -9.1393,
+                "distance_km": null,
+                "rating": null,
+                "description": "sea view",
+                "policies": {
+                    "check_in_time": "14:00:00",
+                    "check_out_time": "10:00:00",
+                    "child_policy": null,
+                    "pet_policy": "No pets"
+                },
+                "amenities": ["wifi"],
+                "created_at": "2024-01-10T12:00:00+00:00",
+                "updated_at": "2024-01-11T12:00:00+00:00"
+            }
+        })) else { panic!("not a HotelUpdated") };
+
+        assert_eq!(hotel.policies.check_in_time.to_string(), "14:00:00");
+        assert_eq!(hotel.policies.timezone, None);
+        assert_eq!(hotel.amenities, vec!["wifi".to_string()]);
+    }
+
+    #[test]
+    fn reads_room_events_recorded_before_housekeeping_and_media() {
+        let DomainEvent::RoomCreated(room) = read(json!({
+            "type": "RoomCreated",
+            "data": {
+                "id": ROOM_ID,
+                "hotel_id": HOTEL_ID,
+                "room_number": "101",
+                "room_type": "double",
+                "price_per_night": "120.00",
+                "is_available": true,
+                "created_at": "2024-01-10T12:00:00+00:00",
+                "updated_at": null
+            }
+        })) else { panic!("not a RoomCreated") };
+
+        assert_eq!(room.description, None);
+        assert_eq!(room.housekeeping_status, Default::default());
+        assert!(room.media.is_empty());
+    }
+
+    #[test]
+    fn reads_guest_events_recorded_before_profiles_and_consent() {
+        let DomainEvent::GuestUpdated(guest) = read(json!({
+            "type": "GuestUpdated",
+            "data": {
+                "id": GUEST_ID,
+                "first_name": "Ann",
+                "last_name": "Lee",
+                "email": "ann.lee@example.com",
+                "phone": "+351900000000",
+                "created_at": "2024-01-10T12:00:00+00:00",
+                "updated_at": "2024-01-11T12:00:00+00:00"
+            }
+        })) else { panic!("not a GuestUpdated") };
+
+        assert_eq!(guest.city, None);
+        assert!(!guest.marketing_email_consent && !guest.marketing_sms_consent);
+        assert!(guest.sensitive.is_none() && guest.anonymized_at.is_none() && guest.merged_into_id.is_none());
+    }
+
+    #[test]
+    fn reads_booking_events_recorded_before_room_types() {
+        let DomainEvent::BookingConfirmed(booking) = read(json!({
+            "type": "BookingConfirmed",
+            "data": {
+                "id": BOOKING_ID,
+                "room_id": ROOM_ID,
+                "guest_id": GUEST_ID,
+                "check_in_date": "2024-07-01T15:00:00+00:00",
+                "check_out_date": "2024-07-03T11:00:00+00:00",
+                "total_price": "240.00",
+                "status": "Confirmed",
+                "created_at": "2024-01-10T12:00:00+00:00",
+                "updated_at": null
+            }
+        })) else { panic!("not a BookingConfirmed") };
+
+        assert_eq!(booking.room_id, Some(Uuid::parse_str(ROOM_ID).unwrap()));
+        assert_eq!(booking.hotel_id, Uuid::nil());
+        assert_eq!(booking.total_price, Decimal::new(24000, 2));
+        assert_eq!(booking.subtotal_price, Decimal::ZERO);
+    }
+
+    #[test]
+    fn reads_booking_events_recorded_before_discounts_and_loyalty() {
+        let DomainEvent::BookingCancelled(booking) = read(json!({
+            "type": "BookingCancelled",
+            "data": {
+                "id": BOOKING_ID,
+                "hotel_id": HOTEL_ID,
+                "room_type": "double",
+                "room_id": null,
+                "reservation_id": null,
+                "guest_id": GUEST_ID,
+                "check_in_date": "2024-07-01T15:00:00+00:00",
+                "check_out_date": "2024-07-03T11:00:00+00:00",
+                "total_price": "240.00",
+                "status": "Cancelled",
+                "created_at": "2024-01-10T12:00:00+00:00",
+                "updated_at": "2024-01-12T12:00:00+00:00"
+            }
+        })) else { panic!("not a BookingCancelled") };
+
+        assert_eq!(booking.room_id, None);
+        assert_eq!(booking.room_type, "double");
+        assert!(booking.discounts.is_empty());
+        assert_eq!((booking.loyalty_points_redeemed, booking.loyalty_discount), (0, Decimal::ZERO));
+    }
+}
@@ -0,0 +1,39 @@
+use chrono::{DateTime, FixedOffset};
+
+pub mod parser;
+pub mod writer;
+
+/// A calendar event exchanged in RFC 5545 iCalendar format
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub uid             : String
+    , pub stamp         : DateTime<FixedOffset>
+    , pub start         : DateTime<FixedOffset>
+    , pub end           : DateTime<FixedOffset>
+    , pub summary       : String
+    , pub description   : Option<String>
+    , pub location      : Option<String>
+    , pub status        : Option<EventStatus>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventStatus {
+    Tentative
+    , Confirmed
+    , Cancelled
+}
+
+impl EventStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventStatus::Tentative  => "TENTATIVE",
+            EventStatus::Confirmed  => "CONFIRMED",
+            EventStatus::Cancelled  => "CANCELLED",
+        }
+    }
+}
+
+/// Stable event UID for a booking, so re-imports update instead of duplicate
+pub fn booking_uid(booking_id: uuid::Uuid) -> String {
+    format!("booking-{}@hotel-booking", booking_id)
+}
@@ -0,0 +1,304 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use crate::ical::{Event, EventStatus};
+
+/// Extracts the VEVENTs of an iCalendar document.
+///
+/// Times with a `TZID` are converted from that IANA time zone, floating times
+/// and zones unknown to the tz database are read as UTC. Recurrence rules are
+/// ignored, which covers the feeds published by booking channels.
+pub fn parse_events(input: &str) -> Result<Vec<Event>, String> {
+    let lines = unfold(input);
+
+    if !lines.iter().any(|l| l.eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err("Not an iCalendar document".to_string());
+    }
+
+    let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+    let mut events = Vec::new();
+    let mut current: Option<Vec<(String, String, String)>> = None;
+
+    for line in &lines {
+        let Some((name, params, value)) = split_property(line) else { continue };
+
+        match (name.as_str(), value.to_ascii_uppercase().as_str()) {
+            ("BEGIN", "VEVENT") => current = Some(Vec::new()),
+            ("END", "VEVENT")   => {
+                if let Some(props) = current.take() {
+                    events.push(build_event(&props, events.len(), now)?);
+                }
+            }
+            _ => {
+                if let Some(props) = current.as_mut() {
+                    props.push((name, params, value));
+                }
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+fn build_event(
+    props   : &[(String, String, String)]
+    , index : usize
+    , now   : DateTime<FixedOffset>
+) -> Result<Event, String> {
+    let get = |key: &str| props.iter().find(|(n, _, _)| n == key);
+
+    let (_, start_params, start_value) = get("DTSTART")
+        .ok_or_else(|| format!("Event {} has no DTSTART", index + 1))?;
+    let (start, all_day) = parse_date_time(start_value, start_params)?;
+
+    // All-day events without an end last one day, timed ones are instantaneous
+    let end = match get("DTEND") {
+        Some((_, params, value))    => parse_date_time(value, params)?.0,
+        None if all_day             => start + chrono::Duration::days(1),
+        None                        => start,
+    };
+
+    let status = get("STATUS").and_then(|(_, _, v)| match v.to_ascii_uppercase().as_str() {
+        "TENTATIVE" => Some(EventStatus::Tentative),
+        "CONFIRMED" => Some(EventStatus::Confirmed),
+        "CANCELLED" => Some(EventStatus::Cancelled),
+        _           => None,
+    });
+
+    Ok(Event {
+        uid             : get("UID").map(|(_, _, v)| v.clone())
+            .unwrap_or_else(|| format!("{}-{}", start.timestamp(), index))
+        , stamp         : get("DTSTAMP")
+            .and_then(|(_, p, v)| parse_date_time(v, p).ok())
+            .map(|(t, _)| t)
+            .unwrap_or(now)
+        , start
+        , end
+        , summary       : get("SUMMARY").map(|(_, _, v)| unescape_text(v)).unwrap_or_default()
+        , description   : get("DESCRIPTION").map(|(_, _, v)| unescape_text(v))
+        , location      : get("LOCATION").map(|(_, _, v)| unescape_text(v))
+        , status
+    })
+}
+
+/// Joins folded content lines (RFC 5545 section 3.1)
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for raw in input.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+
+        match raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ if raw.is_empty()             => {}
+            _                               => lines.push(raw.to_string()),
+        }
+    }
+
+    lines
+}
+
+/// Splits `NAME;PARAMS:VALUE`, ignoring colons inside quoted parameter values
+fn split_property(line: &str) -> Option<(String, String, String)> {
+    let mut in_quotes = false;
+
+    for (i, ch) in line.char_indices() {
+        match ch {
+            '"'                 => in_quotes = !in_quotes,
+            ':' if !in_quotes   => {
+                let (head, value) = (&line[..i], &line[i + 1..]);
+                let (name, params) = head.split_once(';').unwrap_or((head, ""));
+
+                return Some((name.to_ascii_uppercase(), params.to_string(), value.to_string()));
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Parses DATE and DATE-TIME values, returning whether the value was a date
+fn parse_date_time(value: &str, params: &str) -> Result<(DateTime<FixedOffset>, bool), String> {
+    let utc = FixedOffset::east_opt(0).unwrap();
+    let value = value.trim();
+
+    let is_date = value.len() == 8
+        || params.split(';').any(|p| p.eq_ignore_ascii_case("VALUE=DATE"));
+
+    if is_date {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map_err(|_| format!("Invalid date: {}", value))?;
+
+        return Ok((date.and_hms_opt(0, 0, 0).unwrap().and_utc().with_timezone(&utc), true));
+    }
+
+    let (local, is_utc) = match value.strip_suffix('Z').or_else(|| value.strip_suffix('z')) {
+        Some(local) => (local, true),
+        None        => (value, false),
+    };
+
+    let naive = NaiveDateTime::parse_from_str(local, "%Y%m%dT%H%M%S")
+        .map_err(|_| format!("Invalid date-time: {}", value))?;
+
+    let time = match tzid(params).filter(|_| !is_utc) {
+        Some(tz)    => local_to_utc(tz, naive),
+        None        => naive.and_utc(),
+    };
+
+    Ok((time.with_timezone(&utc), false))
+}
+
+/// Time zone of the `TZID` parameter, when the tz database knows it
+fn tzid(params: &str) -> Option<Tz> {
+    params.split(';')
+        .find_map(|p| p.split_once('=').filter(|(k, _)| k.eq_ignore_ascii_case("TZID")))
+        .and_then(|(_, v)| v.trim_matches('"').parse().ok())
+}
+
+/// Converts a wall clock time, taking the earlier instant when the clocks go
+/// back and the instant after the gap when they go forward
+fn local_to_utc(tz: Tz, naive: NaiveDateTime) -> DateTime<Utc> {
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(naive + chrono::Duration::hours(1))).earliest())
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| naive.and_utc())
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') | Some('N')   => out.push('\n'),
+            Some(other)             => out.push(other),
+            None                    => out.push('\\'),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calendar(events: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", events)
+    }
+
+    fn utc(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn rejects_non_calendars() {
+        assert!(parse_events("BEGIN:VEVENT\nEND:VEVENT").is_err());
+    }
+
+    #[test]
+    fn parses_all_day_events() {
+        let events = parse_events(&calendar(
+            "BEGIN:VEVENT\r\nUID:a@x\r\nDTSTART;VALUE=DATE:20240701\r\nDTEND;VALUE=DATE:20240704\r\nSUMMARY:Reserved\r\nEND:VEVENT\r\n"
+        )).unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].uid, "a@x");
+        assert_eq!(events[0].start, utc("2024-07-01T00:00:00Z"));
+        assert_eq!(events[0].end, utc("2024-07-04T00:00:00Z"));
+        assert_eq!(events[0].summary, "Reserved");
+    }
+
+    #[test]
+    fn all_day_events_without_end_last_a_day() {
+        let events = parse_events(&calendar("BEGIN:VEVENT\nDTSTART:20240701\nEND:VEVENT\n")).unwrap();
+
+        assert_eq!(events[0].end, utc("2024-07-02T00:00:00Z"));
+    }
+
+    #[test]
+    fn reads_utc_times() {
+        let (time, all_day) = parse_date_time("20240701T150000Z", "").unwrap();
+
+        assert_eq!(time, utc("2024-07-01T15:00:00Z"));
+        assert!(!all_day);
+    }
+
+    #[test]
+    fn converts_times_from_their_tzid() {
+        let (summer, _) = parse_date_time("20240701T150000", "TZID=Europe/Paris").unwrap();
+        let (winter, _) = parse_date_time("20240101T150000", "TZID=\"Europe/Paris\"").unwrap();
+        let (new_york, _) = parse_date_time("20240701T150000", "VALUE=DATE-TIME;TZID=America/New_York").unwrap();
+
+        assert_eq!(summer, utc("2024-07-01T13:00:00Z"));
+        assert_eq!(winter, utc("2024-01-01T14:00:00Z"));
+        assert_eq!(new_york, utc("2024-07-01T19:00:00Z"));
+    }
+
+    #[test]
+    fn utc_suffix_wins_over_tzid() {
+        let (time, _) = parse_date_time("20240701T150000Z", "TZID=Europe/Paris").unwrap();
+
+        assert_eq!(time, utc("2024-07-01T15:00:00Z"));
+    }
+
+    #[test]
+    fn unknown_tzid_is_read_as_utc() {
+        let (time, _) = parse_date_time("20240701T150000", "TZID=W. Europe Standard Time").unwrap();
+
+        assert_eq!(time, utc("2024-07-01T15:00:00Z"));
+    }
+
+    #[test]
+    fn handles_daylight_saving_transitions() {
+        // 02:30 does not exist on the day clocks go forward, and happens twice
+        // on the day they go back
+        let (gap, _) = parse_date_time("20240331T023000", "TZID=Europe/Paris").unwrap();
+        let (overlap, _) = parse_date_time("20241027T023000", "TZID=Europe/Paris").unwrap();
+
+        assert_eq!(gap, utc("2024-03-31T01:30:00Z"));
+        assert_eq!(overlap, utc("2024-10-27T00:30:00Z"));
+    }
+
+    #[test]
+    fn applies_tzid_to_event_bounds() {
+        let events = parse_events(&calendar(
+            "BEGIN:VEVENT\nDTSTART;TZID=Europe/Paris:20240701T150000\nDTEND;TZID=Europe/Paris:20240703T110000\nEND:VEVENT\n"
+        )).unwrap();
+
+        assert_eq!(events[0].start, utc("2024-07-01T13:00:00Z"));
+        assert_eq!(events[0].end, utc("2024-07-03T09:00:00Z"));
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        assert!(parse_date_time("2024-07-01", "").is_err());
+        assert!(parse_events(&calendar("BEGIN:VEVENT\nSUMMARY:No start\nEND:VEVENT\n")).is_err());
+    }
+
+    #[test]
+    fn unfolds_lines_and_unescapes_text() {
+        let events = parse_events(&calendar(
+            "BEGIN:VEVENT\r\nDTSTART:20240701\r\nSUMMARY:Booked\\, long\r\n  stay\r\nDESCRIPTION:a\\nb\r\nSTATUS:cancelled\r\nEND:VEVENT\r\n"
+        )).unwrap();
+
+        assert_eq!(events[0].summary, "Booked, long stay");
+        assert_eq!(events[0].description.as_deref(), Some("a\nb"));
+        assert_eq!(events[0].status, Some(EventStatus::Cancelled));
+    }
+
+    #[test]
+    fn ignores_colons_in_quoted_parameters() {
+        let (name, params, value) = split_property("ATTENDEE;CN=\"a:b\":mailto:x@y").unwrap();
+
+        assert_eq!(name, "ATTENDEE");
+        assert_eq!(params, "CN=\"a:b\"");
+        assert_eq!(value, "mailto:x@y");
+    }
+}
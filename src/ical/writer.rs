@@ -0,0 +1,73 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use crate::ical::Event;
+
+const PRODID: &str = "-//Hotel Booking//Bookings Calendar//EN";
+
+/// Serializes `events` into a VCALENDAR document
+pub fn write_calendar(name: &str, events: &[Event]) -> String {
+    let mut out = String::new();
+
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, &format!("PRODID:{}", PRODID));
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, "METHOD:PUBLISH");
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape_text(name)));
+
+    for event in events {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", event.uid));
+        push_line(&mut out, &format!("DTSTAMP:{}", format_utc(event.stamp)));
+        push_line(&mut out, &format!("DTSTART:{}", format_utc(event.start)));
+        push_line(&mut out, &format!("DTEND:{}", format_utc(event.end)));
+        push_line(&mut out, &format!("SUMMARY:{}", escape_text(&event.summary)));
+
+        if let Some(description) = &event.description {
+            push_line(&mut out, &format!("DESCRIPTION:{}", escape_text(description)));
+        }
+
+        if let Some(location) = &event.location {
+            push_line(&mut out, &format!("LOCATION:{}", escape_text(location)));
+        }
+
+        if let Some(status) = event.status {
+            push_line(&mut out, &format!("STATUS:{}", status.as_str()));
+        }
+
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn format_utc(value: DateTime<FixedOffset>) -> String {
+    value.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes TEXT values (RFC 5545 section 3.3.11)
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Appends a content line, folded at 75 octets and terminated with CRLF
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+
+    out.push_str("\r\n");
+}
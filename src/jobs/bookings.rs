@@ -0,0 +1,51 @@
+use chrono::{Duration, Utc, FixedOffset};
+use sea_orm::{DatabaseConnection, DbErr};
+use crate::{
+    jobs::Job,
+    services::bookings::BookingService,
+    services::traits::BookingServiceTrait,
+};
+
+/// Marks confirmed bookings as completed once their check-out date has passed
+pub struct CompletePastBookings;
+
+#[async_trait]
+impl Job for CompletePastBookings {
+    fn name(&self) -> &'static str {
+        "complete_past_bookings"
+    }
+
+    async fn run(&self, db: &DatabaseConnection) -> Result<u64, DbErr> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        BookingService::new(db.clone())
+            .complete_past_bookings(now)
+            .await
+    }
+}
+
+/// Cancels pending bookings that were not confirmed within the TTL
+pub struct ExpirePendingBookings {
+    ttl : Duration
+}
+
+impl ExpirePendingBookings {
+    pub fn new(ttl_mins: i64) -> Self {
+        Self { ttl: Duration::minutes(ttl_mins) }
+    }
+}
+
+#[async_trait]
+impl Job for ExpirePendingBookings {
+    fn name(&self) -> &'static str {
+        "expire_pending_bookings"
+    }
+
+    async fn run(&self, db: &DatabaseConnection) -> Result<u64, DbErr> {
+        let cutoff = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()) - self.ttl;
+
+        BookingService::new(db.clone())
+            .expire_pending_bookings(cutoff)
+            .await
+    }
+}
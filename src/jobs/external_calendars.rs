@@ -0,0 +1,21 @@
+use sea_orm::{DatabaseConnection, DbErr};
+use crate::{
+    jobs::Job,
+    services::external_calendars::ExternalCalendarService,
+};
+
+/// Re-imports the iCal feeds of external channels into room blocks
+pub struct SyncExternalCalendars;
+
+#[async_trait]
+impl Job for SyncExternalCalendars {
+    fn name(&self) -> &'static str {
+        "sync_external_calendars"
+    }
+
+    async fn run(&self, db: &DatabaseConnection) -> Result<u64, DbErr> {
+        ExternalCalendarService::new(db.clone())
+            .sync_all()
+            .await
+    }
+}
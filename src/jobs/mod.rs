@@ -0,0 +1,19 @@
+use sea_orm::{DatabaseConnection, DbErr};
+
+pub mod scheduler;
+pub mod bookings;
+pub mod notifications;
+pub mod webhooks;
+pub mod external_calendars;
+pub mod outbox;
+
+pub use scheduler::Scheduler;
+
+#[async_trait]
+pub trait Job: Send + Sync {
+    /// Name recorded against every run in `job_runs`
+    fn name(&self) -> &'static str;
+
+    /// Runs one pass of the job and returns the number of affected rows
+    async fn run(&self, db: &DatabaseConnection) -> Result<u64, DbErr>;
+}
@@ -0,0 +1,86 @@
+use chrono::{Duration, Utc, FixedOffset};
+use sea_orm::{DatabaseConnection, DbErr};
+use crate::{
+    jobs::Job,
+    notifications::{Mailer, templates},
+    services::notifications::NotificationService,
+};
+
+const BATCH_SIZE: u64 = 50;
+
+/// Queues a reminder for bookings checking in within the lead time
+pub struct QueueCheckInReminders {
+    lead    : Duration
+}
+
+impl QueueCheckInReminders {
+    pub fn new(lead_hours: i64) -> Self {
+        Self { lead: Duration::hours(lead_hours) }
+    }
+}
+
+#[async_trait]
+impl Job for QueueCheckInReminders {
+    fn name(&self) -> &'static str {
+        "queue_check_in_reminders"
+    }
+
+    async fn run(&self, db: &DatabaseConnection) -> Result<u64, DbErr> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        NotificationService::new(db.clone())
+            .queue_reminders(now, now + self.lead)
+            .await
+    }
+}
+
+/// Renders and sends pending notifications over SMTP
+pub struct SendNotifications {
+    mailer          : Mailer
+    , max_attempts  : i32
+}
+
+impl SendNotifications {
+    pub fn new(mailer: Mailer, max_attempts: i32) -> Self {
+        Self { mailer, max_attempts }
+    }
+}
+
+#[async_trait]
+impl Job for SendNotifications {
+    fn name(&self) -> &'static str {
+        "send_notifications"
+    }
+
+    async fn run(&self, db: &DatabaseConnection) -> Result<u64, DbErr> {
+        let service = NotificationService::new(db.clone());
+        let mut sent = 0;
+
+        for notification in service.pending(self.max_attempts, BATCH_SIZE).await? {
+            if let Some(reason) = service.obsolete_reason(&notification).await? {
+                service.mark_failed(notification, reason.to_string(), 0).await?;
+                continue;
+            }
+
+            let ctx = match service.template_context(&notification).await? {
+                Some(ctx)   => ctx,
+                None        => {
+                    service.mark_failed(notification, "Booking or waitlist entry details not found".to_string(), 0).await?;
+                    continue;
+                }
+            };
+
+            let (subject, body) = templates::render(&notification.kind, &ctx);
+
+            match self.mailer.send(&notification.recipient, &subject, body).await {
+                Ok(())  => {
+                    service.mark_sent(notification).await?;
+                    sent += 1;
+                }
+                Err(err) => service.mark_failed(notification, err, self.max_attempts).await?,
+            }
+        }
+
+        Ok(sent)
+    }
+}
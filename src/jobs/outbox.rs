@@ -0,0 +1,30 @@
+use chrono::{Duration, Utc, FixedOffset};
+use sea_orm::{DatabaseConnection, DbErr};
+use crate::{
+    events,
+    jobs::Job,
+};
+
+/// Deletes domain events processed longer ago than the retention period
+pub struct PruneOutbox {
+    retention   : Duration
+}
+
+impl PruneOutbox {
+    pub fn new(retention_days: i64) -> Self {
+        Self { retention: Duration::days(retention_days) }
+    }
+}
+
+#[async_trait]
+impl Job for PruneOutbox {
+    fn name(&self) -> &'static str {
+        "prune_outbox"
+    }
+
+    async fn run(&self, db: &DatabaseConnection) -> Result<u64, DbErr> {
+        let before = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()) - self.retention;
+
+        events::prune(db, before).await
+    }
+}
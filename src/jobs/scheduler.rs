@@ -0,0 +1,72 @@
+use std::sync::Arc;
+use std::time::Duration;
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, MissedTickBehavior};
+use crate::{
+    jobs::Job,
+    models::job_runs,
+};
+
+pub struct Scheduler {
+    db      : Arc<DatabaseConnection>
+    , jobs  : Vec<(Duration, Arc<dyn Job>)>
+}
+
+impl Scheduler {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db, jobs: Vec::new() }
+    }
+
+    pub fn every(mut self, period: Duration, job: impl Job + 'static) -> Self {
+        self.jobs.push((period, Arc::new(job)));
+        self
+    }
+
+    /// Spawns one background task per registered job
+    pub fn start(self) -> Vec<JoinHandle<()>> {
+        self.jobs.into_iter().map(|(period, job)| {
+            let db = self.db.clone();
+
+            tokio::spawn(async move {
+                let mut ticker = interval(period);
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+                loop {
+                    ticker.tick().await;
+                    run_job(&db, job.as_ref()).await;
+                }
+            })
+        }).collect()
+    }
+}
+
+async fn run_job(db: &DatabaseConnection, job: &dyn Job) {
+    let started_at = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+    let result = job.run(db).await;
+    let finished_at = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+    let (succeeded, affected_rows, error) = match result {
+        Ok(rows)    => (true, rows as i64, None),
+        Err(err)    => {
+            println!("⚠️ Job {} failed: {}", job.name(), err);
+            (false, 0, Some(err.to_string()))
+        }
+    };
+
+    let run = job_runs::ActiveModel {
+        id              : Set(Uuid::new_v4())
+        , job_name      : Set(job.name().to_string())
+        , succeeded     : Set(succeeded)
+        , affected_rows : Set(affected_rows)
+        , error         : Set(error)
+        , started_at    : Set(started_at)
+        , finished_at   : Set(finished_at)
+    };
+
+    if let Err(err) = run.insert(db).await {
+        println!("⚠️ Failed to record run of job {}: {}", job.name(), err);
+    }
+}
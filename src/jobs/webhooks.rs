@@ -0,0 +1,102 @@
+use std::time::Duration as StdDuration;
+use chrono::{Duration, Utc, FixedOffset};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sea_orm::{DatabaseConnection, DbErr};
+use crate::{
+    jobs::Job,
+    services::webhooks::WebhookService,
+};
+
+const BATCH_SIZE: u64 = 50;
+
+/// Posts pending webhook deliveries, retrying failures with exponential backoff
+pub struct DeliverWebhooks {
+    client          : reqwest::Client
+    , max_attempts  : i32
+    , backoff       : Duration
+}
+
+impl DeliverWebhooks {
+    pub fn new(max_attempts: i32, backoff_secs: i64) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(StdDuration::from_secs(10))
+            .build()
+            .expect("Failed to build webhook HTTP client");
+
+        Self {
+            client
+            , max_attempts
+            , backoff   : Duration::seconds(backoff_secs)
+        }
+    }
+
+    /// Delay before the next try after `attempts` failures: backoff * 2^(attempts - 1)
+    fn retry_delay(&self, attempts: i32) -> Duration {
+        self.backoff * 2_i32.pow((attempts - 1).clamp(0, 16) as u32)
+    }
+}
+
+/// Hex-encoded HMAC-SHA256 of `body`, sent as `X-Webhook-Signature: sha256=<hex>`
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[async_trait]
+impl Job for DeliverWebhooks {
+    fn name(&self) -> &'static str {
+        "deliver_webhooks"
+    }
+
+    async fn run(&self, db: &DatabaseConnection) -> Result<u64, DbErr> {
+        let service = WebhookService::new(db.clone());
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let due = service.due_deliveries(now, BATCH_SIZE).await?;
+        let mut delivered = 0;
+
+        for (delivery, subscription) in due {
+            let subscription = match subscription {
+                Some(s) if s.is_active => s,
+                _ => {
+                    service.mark_failed(delivery, None, "Subscription is inactive".to_string(), None).await?;
+                    continue;
+                }
+            };
+
+            let body = serde_json::to_vec(&delivery.payload)
+                .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+            let res = self.client
+                .post(&subscription.url)
+                .header("Content-Type", "application/json")
+                .header("X-Webhook-Id", delivery.id.to_string())
+                .header("X-Webhook-Event", &delivery.event_type)
+                .header("X-Webhook-Signature", format!("sha256={}", sign_payload(&subscription.secret, &body)))
+                .body(body)
+                .send()
+                .await;
+
+            let (status, error) = match res {
+                Ok(r) if r.status().is_success() => {
+                    service.mark_delivered(delivery, r.status().as_u16() as i32).await?;
+                    delivered += 1;
+                    continue;
+                }
+                Ok(r)   => (Some(r.status().as_u16() as i32), format!("Endpoint responded with {}", r.status())),
+                Err(e)  => (None, e.to_string()),
+            };
+
+            let attempts = delivery.attempts + 1;
+            let retry_at = (attempts < self.max_attempts).then(|| now + self.retry_delay(attempts));
+
+            service.mark_failed(delivery, status, error, retry_at).await?;
+        }
+
+        Ok(delivered)
+    }
+}
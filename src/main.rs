@@ -1,8 +1,10 @@
 // Publish sub module
 #[macro_use] extern crate rocket;
 use std::sync::Arc;
+use std::time::Duration;
 
 use error::ErrorResponse;
+use rocket::data::{Limits, ToByteUnit};
 use rocket::serde::json::Json;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -15,6 +17,11 @@ pub mod schemas;
 pub mod services;
 pub mod routes;
 pub mod error;
+pub mod events;
+pub mod ical;
+pub mod jobs;
+pub mod notifications;
+pub mod storage;
 
 #[catch(500)]
 fn internal_error() -> Json<ErrorResponse> {
@@ -24,6 +31,14 @@ fn internal_error() -> Json<ErrorResponse> {
     })
 }
 
+#[catch(401)]
+fn unauthorized() -> Json<ErrorResponse> {
+    Json(ErrorResponse {
+        code        : 401
+        , message   : "Invalid API key".to_string()
+    })
+}
+
 #[catch(404)]
 fn not_found() -> Json<ErrorResponse> {
     Json(ErrorResponse {
@@ -33,18 +48,61 @@ fn not_found() -> Json<ErrorResponse> {
 }
 
 #[rocket::main]
-async fn main() -> Result<(), rocket::Error> {
+async fn main() -> Result<(), Box<rocket::Error>> {
     let config  = config::AppConfig::new();
     let db = Arc::new(config.establish_connection().await);
+    let storage: Arc<dyn storage::MediaStorage> = Arc::new(storage::LocalStorage::new(&config.media_dir));
 
     println!("📚 Configuration loaded");
     println!("✅ Database connected successfully");
+
+    let interval = Duration::from_secs(config.scheduler_interval_secs);
+
+    jobs::Scheduler::new(db.clone())
+        .every(interval, jobs::bookings::CompletePastBookings)
+        .every(interval, jobs::bookings::ExpirePendingBookings::new(config.pending_booking_ttl_mins))
+        .every(interval, jobs::outbox::PruneOutbox::new(config.outbox_retention_days))
+        .every(
+            Duration::from_secs(config.outbox_interval_secs)
+            , events::OutboxDispatcher::new()
+                .register(services::webhooks::WebhookEventHandler)
+                .register(services::notifications::NotificationEventHandler)
+                .register(services::housekeeping::HousekeepingEventHandler)
+                .register(services::waitlist::WaitlistEventHandler)
+                .register(services::media::MediaEventHandler::new(storage.clone()))
+        )
+        .every(
+            Duration::from_secs(config.webhook_interval_secs)
+            , jobs::webhooks::DeliverWebhooks::new(config.webhook_max_attempts, config.webhook_backoff_secs)
+        )
+        .every(interval, jobs::notifications::QueueCheckInReminders::new(config.reminder_lead_hours))
+        .every(
+            Duration::from_secs(config.notification_interval_secs)
+            , jobs::notifications::SendNotifications::new(
+                notifications::Mailer::new(&config)
+                , config.notification_max_attempts
+            )
+        )
+        .every(
+            Duration::from_secs(config.ical_sync_interval_secs)
+            , jobs::external_calendars::SyncExternalCalendars
+        )
+        .start();
+
+    println!("⏰ Scheduler started, running every {}s", config.scheduler_interval_secs);
     
     println!("🚀 Starting server on port {}", config.port);
 
+    // Uploads arrive as multipart forms, the form limit leaves room for the other fields
+    let limits = Limits::default()
+        .limit("file", config.media_max_upload_mib.mebibytes())
+        .limit("data-form", (config.media_max_upload_mib + 1).mebibytes());
+
     let _rocket: rocket::Rocket<rocket::Ignite> = rocket::build()
-        .configure(rocket::Config::figment().merge(("port", config.port)))
+        .configure(rocket::Config::figment().merge(("port", config.port)).merge(("limits", limits)))
         .manage(db)
+        .manage(storage)
+        .manage(services::guards::StaffApiKey(config.staff_api_key.clone()))
         .mount(
             "/api/v1"
             , routes::v1::routes()
@@ -54,7 +112,7 @@ async fn main() -> Result<(), rocket::Error> {
             SwaggerUi::new("/swagger-ui/<_..>")  
                 .url("/api-docs/openapi.json", ApiDoc::openapi()),
         )
-        .register("/", catchers![internal_error, not_found])
+        .register("/", catchers![internal_error, unauthorized, not_found])
         .launch()
         .await?;
 
@@ -8,7 +8,7 @@ use sea_orm::entity::prelude::*;
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: Uuid,
-    pub room_id: Uuid,
+    pub room_id: Option<Uuid>,
     pub guest_id: Uuid,
     pub check_in_date: DateTimeWithTimeZone,
     pub check_out_date: DateTimeWithTimeZone,
@@ -17,6 +17,16 @@ pub struct Model {
     pub status: BookingStatus,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: Option<DateTimeWithTimeZone>,
+    pub hotel_id: Uuid,
+    pub room_type: String,
+    pub reservation_id: Option<Uuid>,
+    pub loyalty_points_redeemed: i32,
+    #[sea_orm(column_type = "Decimal(Some((10, 2)))")]
+    pub loyalty_discount: Decimal,
+    pub promotion_id: Option<Uuid>,
+    pub promo_code: Option<String>,
+    #[sea_orm(column_type = "Decimal(Some((10, 2)))")]
+    pub promotion_discount: Decimal,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -29,6 +39,36 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Guests,
+    #[sea_orm(
+        belongs_to = "super::hotels::Entity",
+        from = "Column::HotelId",
+        to = "super::hotels::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Hotels,
+    #[sea_orm(has_many = "super::loyalty_entries::Entity")]
+    LoyaltyEntries,
+    #[sea_orm(has_many = "super::notifications::Entity")]
+    Notifications,
+    #[sea_orm(
+        belongs_to = "super::reservations::Entity",
+        from = "Column::ReservationId",
+        to = "super::reservations::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Reservations,
+    #[sea_orm(
+        belongs_to = "super::promotions::Entity",
+        from = "Column::PromotionId",
+        to = "super::promotions::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Promotions,
+    #[sea_orm(has_one = "super::reviews::Entity")]
+    Reviews,
     #[sea_orm(
         belongs_to = "super::rooms::Entity",
         from = "Column::RoomId",
@@ -37,6 +77,8 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Rooms,
+    #[sea_orm(has_one = "super::waitlist_entries::Entity")]
+    WaitlistEntries,
 }
 
 impl Related<super::guests::Entity> for Entity {
@@ -45,10 +87,52 @@ impl Related<super::guests::Entity> for Entity {
     }
 }
 
+impl Related<super::hotels::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Hotels.def()
+    }
+}
+
+impl Related<super::loyalty_entries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LoyaltyEntries.def()
+    }
+}
+
+impl Related<super::notifications::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notifications.def()
+    }
+}
+
+impl Related<super::promotions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Promotions.def()
+    }
+}
+
+impl Related<super::reservations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Reservations.def()
+    }
+}
+
+impl Related<super::reviews::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Reviews.def()
+    }
+}
+
 impl Related<super::rooms::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Rooms.def()
     }
 }
 
+impl Related<super::waitlist_entries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WaitlistEntries.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,5 +1,8 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.3
 
+use super::sea_orm_active_enums::BedPreference;
+use super::sea_orm_active_enums::FloorPreference;
+use super::sea_orm_active_enums::IdDocumentType;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -13,12 +16,47 @@ pub struct Model {
     pub phone: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: Option<DateTimeWithTimeZone>,
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub region: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+    pub date_of_birth: Option<Date>,
+    pub nationality: Option<String>,
+    pub id_document_type: Option<IdDocumentType>,
+    pub id_document_number: Option<String>,
+    pub floor_preference: Option<FloorPreference>,
+    pub bed_preference: Option<BedPreference>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub allergies: Option<String>,
+    pub marketing_email_consent: bool,
+    pub marketing_sms_consent: bool,
+    pub marketing_consent_at: Option<DateTimeWithTimeZone>,
+    pub anonymized_at: Option<DateTimeWithTimeZone>,
+    pub merged_into_id: Option<Uuid>,
+    pub merged_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::bookings::Entity")]
     Bookings,
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::MergedIntoId",
+        to = "Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    SelfRef,
+    #[sea_orm(has_many = "super::loyalty_entries::Entity")]
+    LoyaltyEntries,
+    #[sea_orm(has_many = "super::reservations::Entity")]
+    Reservations,
+    #[sea_orm(has_many = "super::reviews::Entity")]
+    Reviews,
+    #[sea_orm(has_many = "super::waitlist_entries::Entity")]
+    WaitlistEntries,
 }
 
 impl Related<super::bookings::Entity> for Entity {
@@ -27,4 +65,28 @@ impl Related<super::bookings::Entity> for Entity {
     }
 }
 
+impl Related<super::loyalty_entries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LoyaltyEntries.def()
+    }
+}
+
+impl Related<super::reservations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Reservations.def()
+    }
+}
+
+impl Related<super::reviews::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Reviews.def()
+    }
+}
+
+impl Related<super::waitlist_entries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WaitlistEntries.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
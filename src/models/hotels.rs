@@ -10,18 +10,85 @@ pub struct Model {
     pub name: String,
     #[sea_orm(column_type = "Text")]
     pub address: String,
-    #[sea_orm(column_type = "Double")]
-    pub rating: f64,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub rating: Option<f64>,
     #[sea_orm(column_type = "Text", nullable)]
     pub description: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: Option<DateTimeWithTimeZone>,
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub region: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub latitude: Option<f64>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub longitude: Option<f64>,
+    pub check_in_time: Time,
+    pub check_out_time: Time,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub child_policy: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub pet_policy: Option<String>,
+    pub review_count: i32,
+    pub timezone: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::bookings::Entity")]
+    Bookings,
+    #[sea_orm(has_many = "super::hotel_amenities::Entity")]
+    HotelAmenities,
+    #[sea_orm(has_many = "super::media::Entity")]
+    Media,
+    #[sea_orm(has_many = "super::promotions::Entity")]
+    Promotions,
+    #[sea_orm(has_many = "super::reviews::Entity")]
+    Reviews,
+    #[sea_orm(has_many = "super::room_type_amenities::Entity")]
+    RoomTypeAmenities,
     #[sea_orm(has_many = "super::rooms::Entity")]
     Rooms,
+    #[sea_orm(has_many = "super::waitlist_entries::Entity")]
+    WaitlistEntries,
+}
+
+impl Related<super::bookings::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Bookings.def()
+    }
+}
+
+impl Related<super::hotel_amenities::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::HotelAmenities.def()
+    }
+}
+
+impl Related<super::media::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Media.def()
+    }
+}
+
+impl Related<super::promotions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Promotions.def()
+    }
+}
+
+impl Related<super::reviews::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Reviews.def()
+    }
+}
+
+impl Related<super::room_type_amenities::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RoomTypeAmenities.def()
+    }
 }
 
 impl Related<super::rooms::Entity> for Entity {
@@ -30,4 +97,19 @@ impl Related<super::rooms::Entity> for Entity {
     }
 }
 
+impl Related<super::waitlist_entries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WaitlistEntries.def()
+    }
+}
+
+impl Related<super::amenities::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::hotel_amenities::Relation::Amenities.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(super::hotel_amenities::Relation::Hotels.def().rev())
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -2,8 +2,26 @@
 
 pub mod prelude;
 
+pub mod amenities;
+pub mod audit_log;
 pub mod bookings;
+pub mod external_blocks;
+pub mod external_calendars;
 pub mod guests;
+pub mod hotel_amenities;
 pub mod hotels;
+pub mod job_runs;
+pub mod loyalty_entries;
+pub mod media;
+pub mod notifications;
+pub mod outbox;
+pub mod promotions;
+pub mod reservations;
+pub mod reviews;
+pub mod room_blocks;
+pub mod room_type_amenities;
 pub mod rooms;
 pub mod sea_orm_active_enums;
+pub mod waitlist_entries;
+pub mod webhook_deliveries;
+pub mod webhook_subscriptions;
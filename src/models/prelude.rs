@@ -1,6 +1,24 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.3
 
+pub use super::amenities::Entity as Amenities;
+pub use super::audit_log::Entity as AuditLog;
 pub use super::bookings::Entity as Bookings;
+pub use super::external_blocks::Entity as ExternalBlocks;
+pub use super::external_calendars::Entity as ExternalCalendars;
 pub use super::guests::Entity as Guests;
+pub use super::hotel_amenities::Entity as HotelAmenities;
 pub use super::hotels::Entity as Hotels;
+pub use super::job_runs::Entity as JobRuns;
+pub use super::loyalty_entries::Entity as LoyaltyEntries;
+pub use super::media::Entity as Media;
+pub use super::notifications::Entity as Notifications;
+pub use super::outbox::Entity as Outbox;
+pub use super::promotions::Entity as Promotions;
+pub use super::reservations::Entity as Reservations;
+pub use super::reviews::Entity as Reviews;
+pub use super::room_blocks::Entity as RoomBlocks;
+pub use super::room_type_amenities::Entity as RoomTypeAmenities;
 pub use super::rooms::Entity as Rooms;
+pub use super::webhook_deliveries::Entity as WebhookDeliveries;
+pub use super::waitlist_entries::Entity as WaitlistEntries;
+pub use super::webhook_subscriptions::Entity as WebhookSubscriptions;
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.3
 
+use super::sea_orm_active_enums::HousekeepingStatus;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -15,12 +16,28 @@ pub struct Model {
     pub is_available: bool,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: Option<DateTimeWithTimeZone>,
+    pub housekeeping_status: HousekeepingStatus,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub housekeeping_note: Option<String>,
+    pub housekeeping_updated_at: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::bookings::Entity")]
     Bookings,
+    #[sea_orm(has_many = "super::external_blocks::Entity")]
+    ExternalBlocks,
+    #[sea_orm(has_many = "super::external_calendars::Entity")]
+    ExternalCalendars,
+    #[sea_orm(has_many = "super::media::Entity")]
+    Media,
+    #[sea_orm(has_many = "super::room_blocks::Entity")]
+    RoomBlocks,
+    #[sea_orm(has_many = "super::waitlist_entries::Entity")]
+    WaitlistEntries,
     #[sea_orm(
         belongs_to = "super::hotels::Entity",
         from = "Column::HotelId",
@@ -37,10 +54,40 @@ impl Related<super::bookings::Entity> for Entity {
     }
 }
 
+impl Related<super::external_blocks::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExternalBlocks.def()
+    }
+}
+
+impl Related<super::external_calendars::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExternalCalendars.def()
+    }
+}
+
 impl Related<super::hotels::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Hotels.def()
     }
 }
 
+impl Related<super::media::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Media.def()
+    }
+}
+
+impl Related<super::room_blocks::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RoomBlocks.def()
+    }
+}
+
+impl Related<super::waitlist_entries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WaitlistEntries.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
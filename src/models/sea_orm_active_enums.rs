@@ -15,4 +15,146 @@ pub enum BookingStatus {
     Cancelled,
     #[sea_orm(string_value = "completed")]
     Completed,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "webhook_delivery_status")]
+pub enum WebhookDeliveryStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "delivered")]
+    Delivered,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "notification_kind")]
+pub enum NotificationKind {
+    #[sea_orm(string_value = "confirmation")]
+    Confirmation,
+    #[sea_orm(string_value = "reminder")]
+    Reminder,
+    #[sea_orm(string_value = "cancellation")]
+    Cancellation,
+    #[sea_orm(string_value = "waitlist_offer")]
+    WaitlistOffer,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "notification_status")]
+pub enum NotificationStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "sent")]
+    Sent,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "room_block_kind")]
+pub enum RoomBlockKind {
+    #[sea_orm(string_value = "maintenance")]
+    Maintenance,
+    #[sea_orm(string_value = "out_of_order")]
+    OutOfOrder,
+    #[sea_orm(string_value = "other")]
+    Other,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "housekeeping_status")]
+pub enum HousekeepingStatus {
+    #[default]
+    #[sea_orm(string_value = "clean")]
+    Clean,
+    #[sea_orm(string_value = "dirty")]
+    Dirty,
+    #[sea_orm(string_value = "inspected")]
+    Inspected,
+    #[sea_orm(string_value = "out_of_service")]
+    OutOfService,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "waitlist_status")]
+pub enum WaitlistStatus {
+    #[sea_orm(string_value = "waiting")]
+    Waiting,
+    #[sea_orm(string_value = "offered")]
+    Offered,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "review_status")]
+pub enum ReviewStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "published")]
+    Published,
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "id_document_type")]
+pub enum IdDocumentType {
+    #[sea_orm(string_value = "passport")]
+    Passport,
+    #[sea_orm(string_value = "national_id")]
+    NationalId,
+    #[sea_orm(string_value = "driving_licence")]
+    DrivingLicence,
+    #[sea_orm(string_value = "residence_permit")]
+    ResidencePermit,
+    #[sea_orm(string_value = "other")]
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "floor_preference")]
+pub enum FloorPreference {
+    #[sea_orm(string_value = "low")]
+    Low,
+    #[sea_orm(string_value = "high")]
+    High,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "bed_preference")]
+pub enum BedPreference {
+    #[sea_orm(string_value = "single")]
+    Single,
+    #[sea_orm(string_value = "double")]
+    Double,
+    #[sea_orm(string_value = "queen")]
+    Queen,
+    #[sea_orm(string_value = "king")]
+    King,
+    #[sea_orm(string_value = "twin")]
+    Twin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "loyalty_entry_kind")]
+pub enum LoyaltyEntryKind {
+    #[sea_orm(string_value = "earned")]
+    Earned,
+    #[sea_orm(string_value = "reversed")]
+    Reversed,
+    #[sea_orm(string_value = "redeemed")]
+    Redeemed,
+    #[sea_orm(string_value = "refunded")]
+    Refunded,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "discount_kind")]
+pub enum DiscountKind {
+    #[sea_orm(string_value = "percent")]
+    Percent,
+    #[sea_orm(string_value = "fixed")]
+    Fixed,
+}
@@ -0,0 +1,54 @@
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use crate::config::AppConfig;
+
+pub mod templates;
+
+/// Sends plain-text emails through the SMTP server configured in `AppConfig`
+pub struct Mailer {
+    transport   : AsyncSmtpTransport<Tokio1Executor>
+    , from      : Mailbox
+}
+
+impl Mailer {
+    pub fn new(config: &AppConfig) -> Self {
+        // Local sinks such as MailHog speak plain SMTP, real relays use STARTTLS
+        let mut builder = if config.smtp_starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+                .expect("Failed to build SMTP transport")
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
+        };
+
+        builder = builder.port(config.smtp_port);
+
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Self {
+            transport   : builder.build()
+            , from      : config.smtp_from.parse().expect("SMTP_FROM must be a valid mailbox")
+        }
+    }
+
+    pub async fn send(&self, to: &str, subject: &str, body: String) -> Result<(), String> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to.parse().map_err(|e| format!("Invalid recipient {}: {}", to, e))?)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body)
+            .map_err(|e| e.to_string())?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
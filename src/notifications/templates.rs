@@ -0,0 +1,108 @@
+use crate::models::sea_orm_active_enums::NotificationKind;
+
+const CONFIRMATION_SUBJECT: &str = "Your booking at {{hotel_name}}";
+const CONFIRMATION_BODY: &str = "\
+Dear {{guest_name}},
+
+Thank you for booking with {{hotel_name}}. Here are your booking details:
+
+Booking reference: {{reference}}
+Hotel: {{hotel_name}}, {{hotel_address}}
+Room: {{room_number}} ({{room_type}})
+Check-in: {{check_in}}
+Check-out: {{check_out}}
+Total price: {{total_price}}
+Status: {{status}}
+
+We look forward to welcoming you.
+";
+
+const REMINDER_SUBJECT: &str = "Your stay at {{hotel_name}} starts soon";
+const REMINDER_BODY: &str = "\
+Dear {{guest_name}},
+
+This is a reminder that your stay at {{hotel_name}} begins on {{check_in}}.
+
+Booking reference: {{reference}}
+Hotel: {{hotel_name}}, {{hotel_address}}
+Room: {{room_number}} ({{room_type}})
+Check-out: {{check_out}}
+
+Safe travels and see you soon.
+";
+
+const CANCELLATION_SUBJECT: &str = "Your booking at {{hotel_name}} was cancelled";
+const CANCELLATION_BODY: &str = "\
+Dear {{guest_name}},
+
+Your booking {{reference}} at {{hotel_name}} for {{check_in}} to {{check_out}} has been cancelled.
+
+If this was not expected, please contact the hotel.
+";
+
+const WAITLIST_OFFER_SUBJECT: &str = "A {{room_type}} room is available at {{hotel_name}}";
+const WAITLIST_OFFER_BODY: &str = "\
+Dear {{guest_name}},
+
+Good news: a {{room_type}} room at {{hotel_name}} has become available for the dates you are waiting for.
+
+Waitlist reference: {{reference}}
+Hotel: {{hotel_name}}, {{hotel_address}}
+Room: {{room_number}} ({{room_type}})
+Check-in: {{check_in}}
+Check-out: {{check_out}}
+
+The room is not held for you, book soon to secure it.
+";
+
+/// Values substituted into `{{key}}` placeholders
+pub struct TemplateContext {
+    /// Booking or waitlist entry id
+    pub reference       : String
+    , pub guest_name    : String
+    , pub hotel_name    : String
+    , pub hotel_address : String
+    , pub room_number   : String
+    , pub room_type     : String
+    , pub check_in      : String
+    , pub check_out     : String
+    , pub total_price   : String
+    , pub status        : String
+}
+
+impl TemplateContext {
+    fn values(&self) -> [(&'static str, &str); 10] {
+        [
+            ("reference", &self.reference)
+            , ("guest_name", &self.guest_name)
+            , ("hotel_name", &self.hotel_name)
+            , ("hotel_address", &self.hotel_address)
+            , ("room_number", &self.room_number)
+            , ("room_type", &self.room_type)
+            , ("check_in", &self.check_in)
+            , ("check_out", &self.check_out)
+            , ("total_price", &self.total_price)
+            , ("status", &self.status)
+        ]
+    }
+}
+
+fn render_template(template: &str, ctx: &TemplateContext) -> String {
+    ctx.values()
+        .iter()
+        .fold(template.to_string(), |acc, (key, value)| {
+            acc.replace(&format!("{{{{{}}}}}", key), value)
+        })
+}
+
+/// Renders the subject and body of a notification
+pub fn render(kind: &NotificationKind, ctx: &TemplateContext) -> (String, String) {
+    let (subject, body) = match kind {
+        NotificationKind::Confirmation  => (CONFIRMATION_SUBJECT, CONFIRMATION_BODY),
+        NotificationKind::Reminder      => (REMINDER_SUBJECT, REMINDER_BODY),
+        NotificationKind::Cancellation  => (CANCELLATION_SUBJECT, CANCELLATION_BODY),
+        NotificationKind::WaitlistOffer => (WAITLIST_OFFER_SUBJECT, WAITLIST_OFFER_BODY),
+    };
+
+    (render_template(subject, ctx), render_template(body, ctx))
+}
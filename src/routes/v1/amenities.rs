@@ -0,0 +1,210 @@
+use rocket::{get, post, put, delete, serde::json::Json};
+use uuid::Uuid;
+use crate::{
+    schemas::amenities::*,
+    services::guards::ServiceGuard,
+    services::traits::AmenityServiceTrait,
+    error::ApiError,
+};
+
+/// List the amenities catalogue
+#[utoipa::path(
+    get
+    , path  = "/amenities"
+    , tag   = "amenities"
+    , responses(
+        (status = 200, description = "List of amenities by code", body = Vec<AmenitySchemaOut>)
+    )
+)]
+#[get("/amenities")]
+pub async fn list_amenities(
+    guard   : ServiceGuard
+) -> Result<Json<Vec<AmenitySchemaOut>>, ApiError> {
+    Ok(Json(guard.amenities().list_amenities().await?))
+}
+
+/// Get a specific amenity
+#[utoipa::path(
+    get
+    , path  = "/amenities/{id}"
+    , tag   = "amenities"
+    , params(
+        ("id" = String, Path, description = "Amenity UUID")
+    )
+    , responses(
+        (status     = 200, description = "Amenity found", body = AmenitySchemaOut)
+        , (status   = 404, description = "Amenity not found")
+    )
+)]
+#[get("/amenities/<id>")]
+pub async fn get_amenity(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Option<Json<AmenitySchemaOut>>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.amenities().get_amenity(id).await?.map(Json))
+}
+
+/// Add an amenity to the catalogue
+#[utoipa::path(
+    post
+    , path  = "/amenities"
+    , tag   = "amenities"
+    , request_body  = AmenitySchemaIn
+    , responses(
+        (status     = 201, description = "Amenity created", body = AmenitySchemaOut)
+        , (status   = 400, description = "Invalid input or code already taken")
+    )
+)]
+#[post("/amenities", data = "<amenity>")]
+pub async fn create_amenity(
+    guard       : ServiceGuard
+    , amenity   : Json<AmenitySchemaIn>
+) -> Result<Json<AmenitySchemaOut>, ApiError> {
+    Ok(Json(guard.amenities().create_amenity(amenity.0).await?))
+}
+
+/// Update an amenity
+#[utoipa::path(
+    put
+    , path  = "/amenities/{id}"
+    , tag   = "amenities"
+    , params(
+        ("id" = String, Path, description = "Amenity UUID")
+    )
+    , request_body  = AmenitySchemaIn
+    , responses(
+        (status     = 200, description = "Amenity updated", body = AmenitySchemaOut)
+        , (status   = 400, description = "Invalid input or code already taken")
+        , (status   = 404, description = "Amenity not found")
+    )
+)]
+#[put("/amenities/<id>", data = "<amenity>")]
+pub async fn update_amenity(
+    guard       : ServiceGuard
+    , id        : &str
+    , amenity   : Json<AmenitySchemaIn>
+) -> Result<Option<Json<AmenitySchemaOut>>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.amenities().update_amenity(id, amenity.0).await?.map(Json))
+}
+
+/// Remove an amenity from the catalogue and from every hotel and room type
+#[utoipa::path(
+    delete
+    , path  = "/amenities/{id}"
+    , tag   = "amenities"
+    , params(
+        ("id" = String, Path, description = "Amenity UUID")
+    )
+    , responses(
+        (status     = 200, description = "Amenity deleted successfully")
+        , (status   = 404, description = "Amenity not found")
+    )
+)]
+#[delete("/amenities/<id>")]
+pub async fn delete_amenity(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Json<bool>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(Json(false)) };
+    Ok(Json(guard.amenities().delete_amenity(id).await?))
+}
+
+/// List the amenities of a hotel
+#[utoipa::path(
+    get
+    , path  = "/hotels/{hotel_id}/amenities"
+    , tag   = "amenities"
+    , params(
+        ("hotel_id" = String, Path, description = "Hotel UUID")
+    )
+    , responses(
+        (status     = 200, description = "Amenities of the hotel", body = Vec<AmenitySchemaOut>)
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[get("/hotels/<hotel_id>/amenities")]
+pub async fn get_hotel_amenities(
+    guard       : ServiceGuard
+    , hotel_id  : &str
+) -> Result<Option<Json<Vec<AmenitySchemaOut>>>, ApiError> {
+    let Ok(hotel_id) = Uuid::parse_str(hotel_id) else { return Ok(None) };
+    Ok(guard.amenities().get_hotel_amenities(hotel_id).await?.map(Json))
+}
+
+/// Replace the amenities of a hotel
+#[utoipa::path(
+    put
+    , path  = "/hotels/{hotel_id}/amenities"
+    , tag   = "amenities"
+    , params(
+        ("hotel_id" = String, Path, description = "Hotel UUID")
+    )
+    , request_body  = AmenityCodesSchemaIn
+    , responses(
+        (status     = 200, description = "Amenities of the hotel", body = Vec<AmenitySchemaOut>)
+        , (status   = 400, description = "Unknown amenity codes")
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[put("/hotels/<hotel_id>/amenities", data = "<amenities>")]
+pub async fn set_hotel_amenities(
+    guard       : ServiceGuard
+    , hotel_id  : &str
+    , amenities : Json<AmenityCodesSchemaIn>
+) -> Result<Option<Json<Vec<AmenitySchemaOut>>>, ApiError> {
+    let Ok(hotel_id) = Uuid::parse_str(hotel_id) else { return Ok(None) };
+    Ok(guard.amenities().set_hotel_amenities(hotel_id, amenities.0).await?.map(Json))
+}
+
+/// List the amenities of a room type of a hotel
+#[utoipa::path(
+    get
+    , path  = "/hotels/{hotel_id}/room-types/{room_type}/amenities"
+    , tag   = "amenities"
+    , params(
+        ("hotel_id" = String, Path, description = "Hotel UUID")
+        , ("room_type" = String, Path, description = "Room type")
+    )
+    , responses(
+        (status     = 200, description = "Amenities of the room type", body = RoomTypeAmenitiesSchemaOut)
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[get("/hotels/<hotel_id>/room-types/<room_type>/amenities")]
+pub async fn get_room_type_amenities(
+    guard       : ServiceGuard
+    , hotel_id  : &str
+    , room_type : &str
+) -> Result<Option<Json<RoomTypeAmenitiesSchemaOut>>, ApiError> {
+    let Ok(hotel_id) = Uuid::parse_str(hotel_id) else { return Ok(None) };
+    Ok(guard.amenities().get_room_type_amenities(hotel_id, room_type).await?.map(Json))
+}
+
+/// Replace the amenities of a room type of a hotel
+#[utoipa::path(
+    put
+    , path  = "/hotels/{hotel_id}/room-types/{room_type}/amenities"
+    , tag   = "amenities"
+    , params(
+        ("hotel_id" = String, Path, description = "Hotel UUID")
+        , ("room_type" = String, Path, description = "Room type")
+    )
+    , request_body  = AmenityCodesSchemaIn
+    , responses(
+        (status     = 200, description = "Amenities of the room type", body = RoomTypeAmenitiesSchemaOut)
+        , (status   = 400, description = "Unknown amenity codes, or the hotel has no rooms of the type")
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[put("/hotels/<hotel_id>/room-types/<room_type>/amenities", data = "<amenities>")]
+pub async fn set_room_type_amenities(
+    guard       : ServiceGuard
+    , hotel_id  : &str
+    , room_type : &str
+    , amenities : Json<AmenityCodesSchemaIn>
+) -> Result<Option<Json<RoomTypeAmenitiesSchemaOut>>, ApiError> {
+    let Ok(hotel_id) = Uuid::parse_str(hotel_id) else { return Ok(None) };
+    Ok(guard.amenities().set_room_type_amenities(hotel_id, room_type, amenities.0).await?.map(Json))
+}
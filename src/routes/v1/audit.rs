@@ -0,0 +1,42 @@
+use rocket::{get, serde::json::Json};
+use crate::{
+    schemas::audit::*,
+    services::guards::{AccessLevel, ServiceGuard},
+    services::traits::AuditServiceTrait,
+    routes::v1::params::parse_uuid,
+    error::ApiError,
+};
+
+/// List the audit trail, newest first
+#[utoipa::path(
+    get
+    , path  = "/audit-log"
+    , tag   = "audit"
+    , params(
+        ("entity_type" = Option<String>, Query, description = "Only entries about this kind of entity, such as guest")
+        , ("entity_id" = Option<String>, Query, description = "Only entries about this entity (UUID)")
+        , ("X-Api-Key" = String, Header, description = "Staff API key")
+    )
+    , responses(
+        (status     = 200, description = "Audit entries", body = Vec<AuditEntrySchemaOut>)
+        , (status   = 400, description = "Invalid query parameters")
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "The staff API key is required")
+    )
+)]
+#[get("/audit-log?<entity_type>&<entity_id>")]
+pub async fn list_audit_entries(
+    guard           : ServiceGuard
+    , access        : AccessLevel
+    , entity_type   : Option<String>
+    , entity_id     : Option<&str>
+) -> Result<Json<Vec<AuditEntrySchemaOut>>, ApiError> {
+    access.require_staff()?;
+
+    let filter = AuditFilter {
+        entity_type
+        , entity_id : parse_uuid("entity_id", entity_id)?
+    };
+
+    Ok(Json(guard.audit().list_entries(filter).await?))
+}
@@ -1,25 +1,62 @@
-use rocket::{get, post, put, delete, serde::json::Json};
+use rocket::{get, post, put, delete, http::ContentType, serde::json::Json};
+use utoipa::IntoParams;
 use uuid::Uuid;
 use crate::{
     schemas::booking::*,
     services::guards::ServiceGuard,
     services::traits::BookingServiceTrait,
+    routes::v1::params::{parse_booking_status, parse_optional_datetime, parse_uuid},
+    error::ApiError,
 };
 
+/// Query parameters shared by the booking list and export endpoints
+#[derive(FromForm, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BookingQuery {
+    /// Only bookings with this status (pending, confirmed, cancelled, completed)
+    pub status: Option<String>,
+    /// Only bookings of rooms in this hotel (UUID)
+    pub hotel_id: Option<String>,
+    /// Only bookings of this room (UUID)
+    pub room_id: Option<String>,
+    /// Only bookings of this guest (UUID)
+    pub guest_id: Option<String>,
+    /// Only bookings checking out after this date (RFC 3339 or YYYY-MM-DD)
+    pub from: Option<String>,
+    /// Only bookings checking in before this date (RFC 3339 or YYYY-MM-DD)
+    pub to: Option<String>,
+}
+
+impl BookingQuery {
+    pub fn into_filter(self) -> Result<BookingFilter, ApiError> {
+        Ok(BookingFilter {
+            status      : parse_booking_status(self.status.as_deref())?
+            , hotel_id  : parse_uuid("hotel_id", self.hotel_id.as_deref())?
+            , room_id   : parse_uuid("room_id", self.room_id.as_deref())?
+            , guest_id  : parse_uuid("guest_id", self.guest_id.as_deref())?
+            , from      : parse_optional_datetime("from", self.from.as_deref())?
+            , to        : parse_optional_datetime("to", self.to.as_deref())?
+        })
+    }
+}
+
 /// List all bookings
 #[utoipa::path(
     get
     , path  = "/bookings"
     , tag   = "bookings"
+    , params(BookingQuery)
     , responses(
         (status = 200, description = "List of all bookings", body = Vec<BookingSchemaOut>)
+        , (status = 400, description = "Invalid query parameters")
     )
 )]
-#[get("/bookings")]
+#[get("/bookings?<query..>")]
 pub async fn list_bookings(
-    guard: ServiceGuard
-) -> Json<Vec<BookingSchemaOut>> {
-    Json(guard.bookings().list_bookings().await.unwrap())
+    guard   : ServiceGuard
+    , query : BookingQuery
+) -> Result<Json<Vec<BookingSchemaOut>>, ApiError> {
+    Ok(Json(guard.bookings().list_bookings(query.into_filter()?).await?))
 }
 
 /// Get a specific booking by ID
@@ -53,14 +90,37 @@ pub async fn get_booking(
     , responses(
         (status     = 201, description = "Booking created successfully", body = BookingSchemaOut)
         , (status   = 400, description = "Invalid input")
+        , (status   = 404, description = "Room not found")
+        , (status   = 409, description = "Room not available for the requested dates")
     )
 )]
 #[post("/bookings", data = "<booking>")]
 pub async fn create_booking(
     guard       : ServiceGuard
     , booking   : Json<BookingSchemaIn>
-) -> Json<BookingSchemaOut> {
-    Json(guard.bookings().create_booking(booking.0).await.unwrap())
+) -> Result<Json<BookingSchemaOut>, ApiError> {
+    Ok(Json(guard.bookings().create_booking(booking.0).await?))
+}
+
+/// Price a stay before booking it, with the discounts of a promo code and
+/// redeemed loyalty points
+#[utoipa::path(
+    post
+    , path  = "/bookings/quote"
+    , tag   = "bookings"
+    , request_body  = BookingQuoteSchemaIn
+    , responses(
+        (status     = 200, description = "Price of the stay", body = BookingQuoteSchemaOut)
+        , (status   = 400, description = "Invalid input, or the promo code or points do not apply")
+        , (status   = 404, description = "Hotel or room not found")
+    )
+)]
+#[post("/bookings/quote", data = "<quote>")]
+pub async fn quote_booking(
+    guard   : ServiceGuard
+    , quote : Json<BookingQuoteSchemaIn>
+) -> Result<Json<BookingQuoteSchemaOut>, ApiError> {
+    Ok(Json(guard.bookings().quote_booking(quote.0).await?))
 }
 
 /// Update an existing booking
@@ -74,7 +134,9 @@ pub async fn create_booking(
     , request_body  = BookingSchemaIn
     , responses(
         (status     = 200, description = "Booking updated successfully", body = BookingSchemaOut)
+        , (status   = 400, description = "Invalid input")
         , (status   = 404, description = "Booking not found")
+        , (status   = 409, description = "Room not available for the requested dates")
     )
 )]
 #[put("/bookings/<id>", data = "<booking>")]
@@ -82,9 +144,9 @@ pub async fn update_booking(
     guard       : ServiceGuard
     , id        : &str
     , booking   : Json<BookingSchemaIn>
-) -> Option<Json<BookingSchemaOut>> {
-    let uuid = Uuid::parse_str(id).ok()?;
-    guard.bookings().update_booking(uuid, booking.0).await.unwrap().map(Json)
+) -> Result<Option<Json<BookingSchemaOut>>, ApiError> {
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.bookings().update_booking(uuid, booking.0).await?.map(Json))
 }
 
 /// Delete a booking
@@ -113,6 +175,32 @@ pub async fn delete_booking(
     Json(guard.bookings().delete_booking(uuid).await.unwrap())
 }
 
+/// Assign a room to a room type booking, picking one when none is given
+#[utoipa::path(
+    post
+    , path  = "/bookings/{id}/assign-room"
+    , tag   = "bookings"
+    , params(
+        ("id" = String, Path, description = "Booking UUID")
+    )
+    , request_body  = AssignRoomSchemaIn
+    , responses(
+        (status     = 200, description = "Room assigned", body = BookingSchemaOut)
+        , (status   = 400, description = "Room is not of the booking's hotel and type, or the booking is not pending or confirmed")
+        , (status   = 404, description = "Booking or room not found")
+        , (status   = 409, description = "Room is out of service or not free for the stay, or no room of the type is free")
+    )
+)]
+#[post("/bookings/<id>/assign-room", data = "<assignment>")]
+pub async fn assign_booking_room(
+    guard           : ServiceGuard
+    , id            : &str
+    , assignment    : Json<AssignRoomSchemaIn>
+) -> Result<Option<Json<BookingSchemaOut>>, ApiError> {
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.bookings().assign_room(uuid, assignment.0.room_id).await?.map(Json))
+}
+
 /// Get bookings for a specific guest
 #[utoipa::path(
     get
@@ -153,4 +241,50 @@ pub async fn get_room_bookings(
 ) -> Json<Vec<BookingSchemaOut>> {
     let uuid = Uuid::parse_str(room_id).unwrap_or_default();
     Json(guard.bookings().get_room_bookings(uuid).await.unwrap())
+}
+
+/// Export bookings of a specific room as an iCalendar feed
+#[utoipa::path(
+    get
+    , path  = "/rooms/{room_id}/bookings.ics"
+    , tag   = "bookings"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+    )
+    , responses(
+        (status     = 200, description = "RFC 5545 calendar of the room's bookings", body = String, content_type = "text/calendar")
+        , (status   = 404, description = "Room not found")
+    )
+)]
+#[get("/rooms/<room_id>/bookings.ics")]
+pub async fn get_room_bookings_ics(
+    guard       : ServiceGuard
+    , room_id   : &str
+) -> Result<Option<(ContentType, String)>, ApiError> {
+    let Ok(uuid) = Uuid::parse_str(room_id) else { return Ok(None) };
+    let calendar = guard.bookings().get_room_bookings_ics(uuid).await?;
+    Ok(calendar.map(|calendar| (ContentType::Calendar, calendar)))
+}
+
+/// Export bookings of a specific guest as an iCalendar feed
+#[utoipa::path(
+    get
+    , path  = "/guests/{guest_id}/bookings.ics"
+    , tag   = "bookings"
+    , params(
+        ("guest_id" = String, Path, description = "Guest UUID")
+    )
+    , responses(
+        (status     = 200, description = "RFC 5545 calendar of the guest's bookings", body = String, content_type = "text/calendar")
+        , (status   = 404, description = "Guest not found")
+    )
+)]
+#[get("/guests/<guest_id>/bookings.ics")]
+pub async fn get_guest_bookings_ics(
+    guard       : ServiceGuard
+    , guest_id  : &str
+) -> Result<Option<(ContentType, String)>, ApiError> {
+    let Ok(uuid) = Uuid::parse_str(guest_id) else { return Ok(None) };
+    let calendar = guard.bookings().get_guest_bookings_ics(uuid).await?;
+    Ok(calendar.map(|calendar| (ContentType::Calendar, calendar)))
 }
\ No newline at end of file
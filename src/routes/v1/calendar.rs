@@ -0,0 +1,41 @@
+use rocket::{get, serde::json::Json};
+use uuid::Uuid;
+use crate::{
+    schemas::calendar::*,
+    services::guards::ServiceGuard,
+    services::traits::CalendarServiceTrait,
+    routes::v1::params::parse_date,
+    error::ApiError,
+};
+
+/// Room by night availability grid of a hotel for the front desk
+#[utoipa::path(
+    get
+    , path  = "/hotels/{id}/calendar"
+    , tag   = "calendar"
+    , params(
+        ("id" = String, Path, description = "Hotel UUID")
+        , ("from" = String, Query, description = "First night, YYYY-MM-DD")
+        , ("to" = String, Query, description = "Day after the last night, YYYY-MM-DD")
+    )
+    , responses(
+        (status     = 200, description = "Rooms as rows, nights as columns", body = CalendarGrid)
+        , (status   = 400, description = "Invalid query parameters")
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[get("/hotels/<id>/calendar?<from>&<to>")]
+pub async fn get_hotel_calendar(
+    guard   : ServiceGuard
+    , id    : &str
+    , from  : &str
+    , to    : &str
+) -> Result<Option<Json<CalendarGrid>>, ApiError> {
+    let Ok(hotel_id) = Uuid::parse_str(id) else { return Ok(None) };
+
+    let grid = guard.calendar()
+        .hotel_calendar(hotel_id, parse_date("from", from)?, parse_date("to", to)?)
+        .await?;
+
+    Ok(grid.map(Json))
+}
@@ -0,0 +1,197 @@
+use rocket::{
+    get,
+    http::{ContentType, Header},
+    request::Request,
+    response::{self, stream::TextStream, Responder, Response},
+};
+use crate::{
+    routes::v1::{bookings::BookingQuery, guests::GuestQuery, hotels::HotelQuery, rooms::RoomQuery},
+    schemas::exports::ExportFormat,
+    services::{exports::ExportStream, guards::ServiceGuard, traits::ExportServiceTrait},
+    error::ApiError,
+};
+
+/// A streamed export, served as a file download
+pub struct ExportFile {
+    name        : &'static str
+    , format    : ExportFormat
+    , stream    : ExportStream
+}
+
+impl ExportFile {
+    fn new(name: &'static str, format: ExportFormat, stream: ExportStream) -> Self {
+        Self { name, format, stream }
+    }
+}
+
+impl<'r> Responder<'r, 'r> for ExportFile {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'r> {
+        let content_type = match self.format {
+            ExportFormat::Csv       => ContentType::CSV,
+            ExportFormat::Ndjson    => ContentType::new("application", "x-ndjson"),
+        };
+
+        let disposition = format!("attachment; filename=\"{}.{}\"", self.name, self.format.extension());
+
+        Response::build_from(TextStream(self.stream).respond_to(req)?)
+            .header(content_type)
+            .header(Header::new("Content-Disposition", disposition))
+            .ok()
+    }
+}
+
+/// Export bookings as CSV
+#[utoipa::path(
+    get
+    , path  = "/exports/bookings.csv"
+    , tag   = "exports"
+    , params(BookingQuery)
+    , responses(
+        (status     = 200, description = "Bookings with hotel, room and guest names", body = String, content_type = "text/csv")
+        , (status   = 400, description = "Invalid query parameters")
+    )
+)]
+#[get("/exports/bookings.csv?<query..>")]
+pub async fn export_bookings_csv(
+    guard   : ServiceGuard
+    , query : BookingQuery
+) -> Result<ExportFile, ApiError> {
+    let stream = guard.exports().export_bookings(query.into_filter()?, ExportFormat::Csv).await?;
+    Ok(ExportFile::new("bookings", ExportFormat::Csv, stream))
+}
+
+/// Export bookings as newline-delimited JSON
+#[utoipa::path(
+    get
+    , path  = "/exports/bookings.ndjson"
+    , tag   = "exports"
+    , params(BookingQuery)
+    , responses(
+        (status     = 200, description = "Bookings with hotel, room and guest names, one JSON object per line", body = String, content_type = "application/x-ndjson")
+        , (status   = 400, description = "Invalid query parameters")
+    )
+)]
+#[get("/exports/bookings.ndjson?<query..>")]
+pub async fn export_bookings_ndjson(
+    guard   : ServiceGuard
+    , query : BookingQuery
+) -> Result<ExportFile, ApiError> {
+    let stream = guard.exports().export_bookings(query.into_filter()?, ExportFormat::Ndjson).await?;
+    Ok(ExportFile::new("bookings", ExportFormat::Ndjson, stream))
+}
+
+/// Export rooms as CSV
+#[utoipa::path(
+    get
+    , path  = "/exports/rooms.csv"
+    , tag   = "exports"
+    , params(RoomQuery)
+    , responses(
+        (status     = 200, description = "Rooms with hotel names", body = String, content_type = "text/csv")
+        , (status   = 400, description = "Invalid query parameters")
+    )
+)]
+#[get("/exports/rooms.csv?<query..>")]
+pub async fn export_rooms_csv(
+    guard   : ServiceGuard
+    , query : RoomQuery
+) -> Result<ExportFile, ApiError> {
+    let stream = guard.exports().export_rooms(query.into_filter()?, ExportFormat::Csv).await?;
+    Ok(ExportFile::new("rooms", ExportFormat::Csv, stream))
+}
+
+/// Export rooms as newline-delimited JSON
+#[utoipa::path(
+    get
+    , path  = "/exports/rooms.ndjson"
+    , tag   = "exports"
+    , params(RoomQuery)
+    , responses(
+        (status     = 200, description = "Rooms with hotel names, one JSON object per line", body = String, content_type = "application/x-ndjson")
+        , (status   = 400, description = "Invalid query parameters")
+    )
+)]
+#[get("/exports/rooms.ndjson?<query..>")]
+pub async fn export_rooms_ndjson(
+    guard   : ServiceGuard
+    , query : RoomQuery
+) -> Result<ExportFile, ApiError> {
+    let stream = guard.exports().export_rooms(query.into_filter()?, ExportFormat::Ndjson).await?;
+    Ok(ExportFile::new("rooms", ExportFormat::Ndjson, stream))
+}
+
+/// Export guests as CSV
+#[utoipa::path(
+    get
+    , path  = "/exports/guests.csv"
+    , tag   = "exports"
+    , params(GuestQuery)
+    , responses(
+        (status = 200, description = "Guests", body = String, content_type = "text/csv")
+    )
+)]
+#[get("/exports/guests.csv?<query..>")]
+pub async fn export_guests_csv(
+    guard   : ServiceGuard
+    , query : GuestQuery
+) -> Result<ExportFile, ApiError> {
+    let stream = guard.exports().export_guests(query.into_filter()?, ExportFormat::Csv).await?;
+    Ok(ExportFile::new("guests", ExportFormat::Csv, stream))
+}
+
+/// Export guests as newline-delimited JSON
+#[utoipa::path(
+    get
+    , path  = "/exports/guests.ndjson"
+    , tag   = "exports"
+    , params(GuestQuery)
+    , responses(
+        (status = 200, description = "Guests, one JSON object per line", body = String, content_type = "application/x-ndjson")
+    )
+)]
+#[get("/exports/guests.ndjson?<query..>")]
+pub async fn export_guests_ndjson(
+    guard   : ServiceGuard
+    , query : GuestQuery
+) -> Result<ExportFile, ApiError> {
+    let stream = guard.exports().export_guests(query.into_filter()?, ExportFormat::Ndjson).await?;
+    Ok(ExportFile::new("guests", ExportFormat::Ndjson, stream))
+}
+
+/// Export hotels as CSV
+#[utoipa::path(
+    get
+    , path  = "/exports/hotels.csv"
+    , tag   = "exports"
+    , params(HotelQuery)
+    , responses(
+        (status = 200, description = "Hotels", body = String, content_type = "text/csv")
+    )
+)]
+#[get("/exports/hotels.csv?<query..>")]
+pub async fn export_hotels_csv(
+    guard   : ServiceGuard
+    , query : HotelQuery
+) -> Result<ExportFile, ApiError> {
+    let stream = guard.exports().export_hotels(query.into_filter()?, ExportFormat::Csv).await?;
+    Ok(ExportFile::new("hotels", ExportFormat::Csv, stream))
+}
+
+/// Export hotels as newline-delimited JSON
+#[utoipa::path(
+    get
+    , path  = "/exports/hotels.ndjson"
+    , tag   = "exports"
+    , params(HotelQuery)
+    , responses(
+        (status = 200, description = "Hotels, one JSON object per line", body = String, content_type = "application/x-ndjson")
+    )
+)]
+#[get("/exports/hotels.ndjson?<query..>")]
+pub async fn export_hotels_ndjson(
+    guard   : ServiceGuard
+    , query : HotelQuery
+) -> Result<ExportFile, ApiError> {
+    let stream = guard.exports().export_hotels(query.into_filter()?, ExportFormat::Ndjson).await?;
+    Ok(ExportFile::new("hotels", ExportFormat::Ndjson, stream))
+}
@@ -0,0 +1,189 @@
+use rocket::{
+    get, post, delete,
+    data::{Data, ToByteUnit},
+    serde::json::Json,
+};
+use uuid::Uuid;
+use crate::{
+    schemas::external_calendars::*,
+    services::guards::ServiceGuard,
+    services::traits::ExternalCalendarServiceTrait,
+    error::ApiError,
+};
+
+const MAX_ICS_MIB: u64 = 5;
+
+/// Reads an uploaded calendar, past Rocket's small default string limit
+async fn read_ics(data: Data<'_>) -> Result<String, ApiError> {
+    let ics = data.open(MAX_ICS_MIB.mebibytes())
+        .into_string()
+        .await
+        .map_err(|e| ApiError::Validation(format!("Could not read calendar body: {}", e)))?;
+
+    if !ics.is_complete() {
+        return Err(ApiError::Validation(format!("Calendar body exceeds {} MiB", MAX_ICS_MIB)));
+    }
+
+    Ok(ics.into_inner())
+}
+
+/// List the external calendars of a room
+#[utoipa::path(
+    get
+    , path  = "/rooms/{room_id}/calendars"
+    , tag   = "external-calendars"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+    )
+    , responses(
+        (status = 200, description = "External calendars of the room", body = Vec<ExternalCalendarSchemaOut>)
+    )
+)]
+#[get("/rooms/<room_id>/calendars")]
+pub async fn list_room_calendars(
+    guard       : ServiceGuard
+    , room_id   : &str
+) -> Result<Option<Json<Vec<ExternalCalendarSchemaOut>>>, ApiError> {
+    let Ok(room_id) = Uuid::parse_str(room_id) else { return Ok(None) };
+    Ok(Some(Json(guard.external_calendars().list_calendars(room_id).await?)))
+}
+
+/// Subscribe a room to an external iCal feed
+#[utoipa::path(
+    post
+    , path  = "/rooms/{room_id}/calendars"
+    , tag   = "external-calendars"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+    )
+    , request_body  = ExternalCalendarSchemaIn
+    , responses(
+        (status     = 201, description = "External calendar created successfully", body = ExternalCalendarSchemaOut)
+        , (status   = 400, description = "Invalid input")
+        , (status   = 404, description = "Room not found")
+    )
+)]
+#[post("/rooms/<room_id>/calendars", data = "<calendar>")]
+pub async fn create_room_calendar(
+    guard       : ServiceGuard
+    , room_id   : &str
+    , calendar  : Json<ExternalCalendarSchemaIn>
+) -> Result<Json<ExternalCalendarSchemaOut>, ApiError> {
+    let room_id = Uuid::parse_str(room_id).map_err(|_| ApiError::RoomNotFound(room_id.to_string()))?;
+    Ok(Json(guard.external_calendars().create_calendar(room_id, calendar.0).await?))
+}
+
+/// Delete an external calendar and its blocks
+#[utoipa::path(
+    delete
+    , path  = "/rooms/{room_id}/calendars/{id}"
+    , tag   = "external-calendars"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+        , ("id" = String, Path, description = "External calendar UUID")
+    )
+    , responses(
+        (status     = 200, description = "External calendar deleted successfully")
+        , (status   = 404, description = "External calendar not found")
+    )
+)]
+#[delete("/rooms/<room_id>/calendars/<id>")]
+pub async fn delete_room_calendar(
+    guard       : ServiceGuard
+    , room_id   : &str
+    , id        : &str
+) -> Result<Json<bool>, ApiError> {
+    let (Ok(room_id), Ok(id)) = (Uuid::parse_str(room_id), Uuid::parse_str(id)) else {
+        return Ok(Json(false));
+    };
+
+    Ok(Json(guard.external_calendars().delete_calendar(room_id, id).await?))
+}
+
+/// Fetch the calendar feed now and replace its blocks
+#[utoipa::path(
+    post
+    , path  = "/rooms/{room_id}/calendars/{id}/sync"
+    , tag   = "external-calendars"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+        , ("id" = String, Path, description = "External calendar UUID")
+    )
+    , responses(
+        (status     = 200, description = "Calendar synced", body = ExternalCalendarSchemaOut)
+        , (status   = 400, description = "Calendar has no url or the feed is not valid iCalendar")
+        , (status   = 404, description = "External calendar not found")
+        , (status   = 502, description = "Feed could not be fetched")
+    )
+)]
+#[post("/rooms/<room_id>/calendars/<id>/sync")]
+pub async fn sync_room_calendar(
+    guard       : ServiceGuard
+    , room_id   : &str
+    , id        : &str
+) -> Result<Option<Json<ExternalCalendarSchemaOut>>, ApiError> {
+    let (Ok(room_id), Ok(id)) = (Uuid::parse_str(room_id), Uuid::parse_str(id)) else {
+        return Ok(None);
+    };
+
+    Ok(guard.external_calendars().sync_calendar(room_id, id).await?.map(Json))
+}
+
+/// Replace the calendar blocks with an uploaded .ics file
+#[utoipa::path(
+    post
+    , path  = "/rooms/{room_id}/calendars/{id}/upload"
+    , tag   = "external-calendars"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+        , ("id" = String, Path, description = "External calendar UUID")
+    )
+    , request_body(content = String, content_type = "text/calendar")
+    , responses(
+        (status     = 200, description = "Calendar imported", body = ExternalCalendarSchemaOut)
+        , (status   = 400, description = "Body is not valid iCalendar, or exceeds 5 MiB")
+        , (status   = 404, description = "External calendar not found")
+    )
+)]
+#[post("/rooms/<room_id>/calendars/<id>/upload", data = "<data>")]
+pub async fn upload_room_calendar(
+    guard       : ServiceGuard
+    , room_id   : &str
+    , id        : &str
+    , data      : Data<'_>
+) -> Result<Option<Json<ExternalCalendarSchemaOut>>, ApiError> {
+    let (Ok(room_id), Ok(id)) = (Uuid::parse_str(room_id), Uuid::parse_str(id)) else {
+        return Ok(None);
+    };
+
+    let ics = read_ics(data).await?;
+
+    Ok(guard.external_calendars().upload_calendar(room_id, id, ics).await?.map(Json))
+}
+
+/// List the blocks imported from an external calendar
+#[utoipa::path(
+    get
+    , path  = "/rooms/{room_id}/calendars/{id}/blocks"
+    , tag   = "external-calendars"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+        , ("id" = String, Path, description = "External calendar UUID")
+    )
+    , responses(
+        (status     = 200, description = "Imported blocks, by start date", body = Vec<ExternalBlockSchemaOut>)
+        , (status   = 404, description = "External calendar not found")
+    )
+)]
+#[get("/rooms/<room_id>/calendars/<id>/blocks")]
+pub async fn get_room_calendar_blocks(
+    guard       : ServiceGuard
+    , room_id   : &str
+    , id        : &str
+) -> Result<Option<Json<Vec<ExternalBlockSchemaOut>>>, ApiError> {
+    let (Ok(room_id), Ok(id)) = (Uuid::parse_str(room_id), Uuid::parse_str(id)) else {
+        return Ok(None);
+    };
+
+    Ok(guard.external_calendars().list_blocks(room_id, id).await?.map(Json))
+}
@@ -1,48 +1,149 @@
 // routes/v1/guests.rs
 use rocket::{get, post, put, delete, serde::json::Json};
+use utoipa::IntoParams;
 use uuid::Uuid;
 use crate::{
     schemas::guests::*,
-    services::guards::ServiceGuard,
+    services::guards::{AccessLevel, ServiceGuard},
     services::traits::GuestServiceTrait,
+    error::ApiError,
 };
 
-/// List all guests
+/// Query parameters shared by the guest list and export endpoints
+#[derive(FromForm, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GuestQuery {
+    /// Case-insensitive substring of the first or last name
+    pub name: Option<String>,
+    /// Case-insensitive substring of the email
+    pub email: Option<String>,
+}
+
+impl GuestQuery {
+    pub fn into_filter(self) -> Result<GuestFilter, ApiError> {
+        Ok(GuestFilter {
+            name        : self.name
+            , email     : self.email
+        })
+    }
+}
+
+/// List all guests, with their sensitive data for staff
 #[utoipa::path(
     get
     , path  = "/guests"
     , tag   = "guests"
+    , params(
+        GuestQuery
+        , ("X-Api-Key" = Option<String>, Header, description = "Staff API key, unlocks the sensitive data")
+    )
     , responses(
-        (status = 200, description = "List of all guests", body = Vec<GuestSchemaOut>)
+        (status     = 200, description = "List of all guests", body = Vec<GuestSchemaOut>)
+        , (status   = 401, description = "Invalid API key")
     )
 )]
-#[get("/guests")]
+#[get("/guests?<query..>")]
 pub async fn list_guests(
-    guard: ServiceGuard
-) -> Json<Vec<GuestSchemaOut>> {
-    Json(guard.guests().list_guests().await.unwrap())
+    guard       : ServiceGuard
+    , access    : AccessLevel
+    , query     : GuestQuery
+) -> Result<Json<Vec<GuestSchemaOut>>, ApiError> {
+    Ok(Json(guard.guests().list_guests(query.into_filter()?, access).await?))
+}
+
+/// Query parameters of the duplicate report
+#[derive(FromForm, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GuestDuplicateQuery {
+    /// Lowest score reported, from 0 to 1, defaults to 0.6
+    pub min_score: Option<f64>,
+    /// Most pairs returned, defaults to 100, at most 500
+    pub limit: Option<u64>,
 }
 
-/// Get a specific guest by ID
+/// List pairs of guests likely to be the same person, best matches first
+#[utoipa::path(
+    get
+    , path  = "/guests/duplicates"
+    , tag   = "guests"
+    , params(
+        GuestDuplicateQuery
+        , ("X-Api-Key" = String, Header, description = "Staff API key")
+    )
+    , responses(
+        (status     = 200, description = "Likely duplicate guests", body = Vec<GuestDuplicateSchemaOut>)
+        , (status   = 400, description = "Invalid query")
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "Staff API key missing")
+    )
+)]
+#[get("/guests/duplicates?<query..>")]
+pub async fn list_duplicate_guests(
+    guard       : ServiceGuard
+    , access    : AccessLevel
+    , query     : GuestDuplicateQuery
+) -> Result<Json<Vec<GuestDuplicateSchemaOut>>, ApiError> {
+    access.require_staff()?;
+    let filter = GuestDuplicateFilter {
+        min_score   : query.min_score
+        , limit     : query.limit
+    };
+    Ok(Json(guard.guests().find_duplicates(filter, access).await?))
+}
+
+/// Merge duplicate guests into this one, moving their history over and leaving tombstones
+#[utoipa::path(
+    post
+    , path  = "/guests/{id}/merge"
+    , tag   = "guests"
+    , params(
+        ("id" = String, Path, description = "UUID of the guest to keep")
+        , ("X-Api-Key" = String, Header, description = "Staff API key")
+    )
+    , request_body  = GuestMergeSchemaIn
+    , responses(
+        (status     = 200, description = "Guests merged", body = GuestSchemaOut)
+        , (status   = 400, description = "Invalid duplicates")
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "Staff API key missing")
+        , (status   = 404, description = "Guest not found")
+    )
+)]
+#[post("/guests/<id>/merge", data = "<merge>")]
+pub async fn merge_guests(
+    guard       : ServiceGuard
+    , access    : AccessLevel
+    , id        : &str
+    , merge     : Json<GuestMergeSchemaIn>
+) -> Result<Option<Json<GuestSchemaOut>>, ApiError> {
+    access.require_staff()?;
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.guests().merge_guests(uuid, merge.0, access).await?.map(Json))
+}
+
+/// Get a specific guest by ID, with their sensitive data for staff
 #[utoipa::path(
     get
     , path  = "/guests/{id}"
     , tag   = "guests"
     , params(
         ("id" = String, Path, description = "Guest UUID")
+        , ("X-Api-Key" = Option<String>, Header, description = "Staff API key, unlocks the sensitive data")
     )
     , responses(
         (status     = 200, description = "Guest found", body = GuestSchemaOut)
+        , (status   = 401, description = "Invalid API key")
         , (status   = 404, description = "Guest not found")
     )
 )]
 #[get("/guests/<id>")]
 pub async fn get_guest(
-    guard   : ServiceGuard
-    , id    : &str
-) -> Option<Json<GuestSchemaOut>> {
-    let uuid = Uuid::parse_str(id).ok()?;
-    guard.guests().get_guest(uuid).await.unwrap().map(Json)
+    guard       : ServiceGuard
+    , access    : AccessLevel
+    , id        : &str
+) -> Result<Option<Json<GuestSchemaOut>>, ApiError> {
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.guests().get_guest(uuid, access).await?.map(Json))
 }
 
 /// Create a new guest
@@ -50,42 +151,53 @@ pub async fn get_guest(
     post
     , path  = "/guests"
     , tag   = "guests"
+    , params(
+        ("X-Api-Key" = Option<String>, Header, description = "Staff API key, required to set the sensitive data")
+    )
     , request_body  = GuestSchemaIn
     , responses(
         (status     = 201, description = "Guest created successfully", body = GuestSchemaOut)
         , (status   = 400, description = "Invalid input")
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "Sensitive data sent without the staff API key")
     )
 )]
 #[post("/guests", data = "<guest>")]
 pub async fn create_guest(
     guard       : ServiceGuard
+    , access    : AccessLevel
     , guest     : Json<GuestSchemaIn>
-) -> Json<GuestSchemaOut> {
-    Json(guard.guests().create_guest(guest.0).await.unwrap())
+) -> Result<Json<GuestSchemaOut>, ApiError> {
+    Ok(Json(guard.guests().create_guest(guest.0, access).await?))
 }
 
-/// Update an existing guest
+/// Update an existing guest, without the staff API key the sensitive data is kept as is
 #[utoipa::path(
     put
     , path  = "/guests/{id}"
     , tag   = "guests"
     , params(
         ("id" = String, Path, description = "Guest UUID")
+        , ("X-Api-Key" = Option<String>, Header, description = "Staff API key, required to change the sensitive data")
     )
     , request_body  = GuestSchemaIn
     , responses(
         (status     = 200, description = "Guest updated successfully", body = GuestSchemaOut)
+        , (status   = 400, description = "Invalid input")
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "Sensitive data sent without the staff API key")
         , (status   = 404, description = "Guest not found")
     )
 )]
 #[put("/guests/<id>", data = "<guest>")]
 pub async fn update_guest(
     guard       : ServiceGuard
+    , access    : AccessLevel
     , id        : &str
     , guest     : Json<GuestSchemaIn>
-) -> Option<Json<GuestSchemaOut>> {
-    let uuid = Uuid::parse_str(id).ok()?;
-    guard.guests().update_guest(uuid, guest.0).await.unwrap().map(Json)
+) -> Result<Option<Json<GuestSchemaOut>>, ApiError> {
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.guests().update_guest(uuid, guest.0, access).await?.map(Json))
 }
 
 /// Delete a guest
@@ -1,25 +1,97 @@
 use rocket::{get, post, put, delete, serde::json::Json};
+use utoipa::IntoParams;
 use uuid::Uuid;
 use crate::{
     schemas::hotels::*,
     services::guards::ServiceGuard,
     services::traits::HotelServiceTrait,
+    routes::v1::params::{parse_datetime, parse_geo_point},
+    error::ApiError,
 };
 
+/// Query parameters shared by the hotel list and export endpoints
+#[derive(FromForm, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HotelQuery {
+    /// Case-insensitive substring of the hotel name
+    pub name: Option<String>,
+    /// Minimum rating
+    pub min_rating: Option<f64>,
+    /// City, case-insensitive
+    pub city: Option<String>,
+    /// ISO 3166-1 alpha-2 country code
+    pub country: Option<String>,
+    /// Only hotels with coordinates, nearest first: `latitude,longitude`
+    pub near: Option<String>,
+    /// Only hotels at most this many km from `near`
+    pub radius_km: Option<f64>,
+    /// Comma separated amenity codes the hotel must all have: `wifi,pool`
+    pub amenities: Option<String>,
+}
+
+impl HotelQuery {
+    pub fn into_filter(self) -> Result<HotelFilter, ApiError> {
+        let near = parse_geo_point("near", self.near.as_deref())?;
+
+        match self.radius_km {
+            Some(_) if near.is_none()       => return Err(ApiError::Validation("radius_km requires near".to_string())),
+            Some(r) if r <= 0.0             => return Err(ApiError::Validation("radius_km must be positive".to_string())),
+            _                               => {}
+        }
+
+        Ok(HotelFilter {
+            name            : self.name
+            , min_rating    : self.min_rating
+            , city          : self.city
+            , country       : self.country
+            , near
+            , radius_km     : self.radius_km
+            , amenities     : self.amenities
+                .iter()
+                .flat_map(|a| a.split(','))
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+    }
+}
+
 /// List all hotels
 #[utoipa::path(
     get
     , path  = "/hotels"
     , tag   = "hotels"
+    , params(
+        HotelQuery
+        , ("check_in" = Option<String>, Query, description = "Only hotels with a room free from check-in, RFC 3339 timestamp or YYYY-MM-DD")
+        , ("check_out" = Option<String>, Query, description = "Check-out of the stay, required with check_in")
+        , ("room_type" = Option<String>, Query, description = "With check_in, only rooms of this type count")
+    )
     , responses(
-        (status = 200, description = "List of all hotels", body = Vec<HotelSchemaOut>)
+        (status = 200, description = "List of all hotels, nearest first when searching by location", body = Vec<HotelSchemaOut>)
+        , (status = 400, description = "Invalid query parameters")
     )
 )]
-#[get("/hotels")]
+#[get("/hotels?<check_in>&<check_out>&<room_type>&<query..>")]
 pub async fn list_hotels(
-    guard: ServiceGuard
-) -> Json<Vec<HotelSchemaOut>> {
-    Json(guard.hotels().list_hotels().await.unwrap())
+    guard       : ServiceGuard
+    , check_in  : Option<&str>
+    , check_out : Option<&str>
+    , room_type : Option<String>
+    , query     : HotelQuery
+) -> Result<Json<Vec<HotelSchemaOut>>, ApiError> {
+    let stay = match (check_in, check_out) {
+        (Some(check_in), Some(check_out)) => Some(HotelStayFilter {
+            check_in    : parse_datetime("check_in", check_in)?
+            , check_out : parse_datetime("check_out", check_out)?
+            , room_type
+        }),
+        (None, None) if room_type.is_none() => None,
+        _ => return Err(ApiError::Validation("check_in and check_out must be given together, room_type only with them".to_string())),
+    };
+
+    Ok(Json(guard.hotels().list_hotels(query.into_filter()?, stay).await?))
 }
 
 /// Get a specific hotel by ID
@@ -59,8 +131,8 @@ pub async fn get_hotel(
 pub async fn create_hotel(
     guard: ServiceGuard
     , hotel: Json<HotelSchemaIn>
-) -> Json<HotelSchemaOut> {
-    Json(guard.hotels().create_hotel(hotel.0).await.unwrap())
+) -> Result<Json<HotelSchemaOut>, ApiError> {
+    Ok(Json(guard.hotels().create_hotel(hotel.0).await?))
 }
 
 /// Update an existing hotel
@@ -74,6 +146,7 @@ pub async fn create_hotel(
     , request_body  = HotelSchemaIn
     , responses(
         (status     = 200, description = "Hotel updated successfully", body = HotelSchemaOut)
+        , (status   = 400, description = "Invalid input")
         , (status   = 404, description = "Hotel not found")
     )
 )]
@@ -82,9 +155,34 @@ pub async fn update_hotel(
     guard: ServiceGuard
     , id: &str
     , hotel: Json<HotelSchemaIn>
-) -> Option<Json<HotelSchemaOut>> {
-    let uuid = Uuid::parse_str(id).ok()?;
-    guard.hotels().update_hotel(uuid, hotel.0).await.unwrap().map(Json)
+) -> Result<Option<Json<HotelSchemaOut>>, ApiError> {
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.hotels().update_hotel(uuid, hotel.0).await?.map(Json))
+}
+
+/// Set the check-in and check-out times, timezone, child and pet policies of a hotel
+#[utoipa::path(
+    put
+    , path = "/hotels/{id}/policies"
+    , tag  = "hotels"
+    , params(
+        ("id" = String, Path, description = "Hotel UUID")
+    )
+    , request_body  = HotelPoliciesSchema
+    , responses(
+        (status     = 200, description = "Policies updated successfully", body = HotelSchemaOut)
+        , (status   = 400, description = "Unknown timezone")
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[put("/hotels/<id>/policies", data = "<policies>")]
+pub async fn update_hotel_policies(
+    guard       : ServiceGuard
+    , id        : &str
+    , policies  : Json<HotelPoliciesSchema>
+) -> Result<Option<Json<HotelSchemaOut>>, ApiError> {
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.hotels().update_policies(uuid, policies.0).await?.map(Json))
 }
 
 /// Delete a hotel
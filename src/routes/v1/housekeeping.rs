@@ -0,0 +1,61 @@
+use rocket::{get, put, serde::json::Json};
+use uuid::Uuid;
+use crate::{
+    schemas::housekeeping::*,
+    services::guards::ServiceGuard,
+    services::traits::HousekeepingServiceTrait,
+    routes::v1::params::parse_housekeeping_status,
+    error::ApiError,
+};
+
+/// Housekeeping task list of a hotel, rooms with the soonest arrival first
+#[utoipa::path(
+    get
+    , path  = "/hotels/{id}/housekeeping"
+    , tag   = "housekeeping"
+    , params(
+        ("id" = String, Path, description = "Hotel UUID")
+        , ("status" = Option<String>, Query, description = "Only rooms in this status: clean, dirty, inspected or out_of_service")
+    )
+    , responses(
+        (status     = 200, description = "Rooms ordered by next arrival", body = Vec<HousekeepingTask>)
+        , (status   = 400, description = "Invalid query parameters")
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[get("/hotels/<id>/housekeeping?<status>")]
+pub async fn get_hotel_housekeeping(
+    guard       : ServiceGuard
+    , id        : &str
+    , status    : Option<&str>
+) -> Result<Option<Json<Vec<HousekeepingTask>>>, ApiError> {
+    let Ok(hotel_id) = Uuid::parse_str(id) else { return Ok(None) };
+    let status = parse_housekeeping_status(status)?;
+
+    Ok(guard.housekeeping().hotel_tasks(hotel_id, status).await?.map(Json))
+}
+
+/// Set the housekeeping status of a room
+#[utoipa::path(
+    put
+    , path  = "/rooms/{id}/housekeeping"
+    , tag   = "housekeeping"
+    , params(
+        ("id" = String, Path, description = "Room UUID")
+    )
+    , request_body  = HousekeepingSchemaIn
+    , responses(
+        (status     = 200, description = "Housekeeping status updated", body = HousekeepingSchemaOut)
+        , (status   = 400, description = "Invalid input")
+        , (status   = 404, description = "Room not found")
+    )
+)]
+#[put("/rooms/<id>/housekeeping", data = "<housekeeping>")]
+pub async fn update_room_housekeeping(
+    guard           : ServiceGuard
+    , id            : &str
+    , housekeeping  : Json<HousekeepingSchemaIn>
+) -> Result<Option<Json<HousekeepingSchemaOut>>, ApiError> {
+    let Ok(room_id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.housekeeping().update_status(room_id, housekeeping.0).await?.map(Json))
+}
@@ -0,0 +1,96 @@
+use rocket::{
+    post,
+    data::{Data, ToByteUnit},
+    http::Status,
+    serde::json::Json,
+};
+use crate::{
+    schemas::imports::*,
+    services::guards::ServiceGuard,
+    services::traits::ImportServiceTrait,
+    error::ApiError,
+};
+
+const MAX_CSV_MIB: u64 = 10;
+
+async fn read_csv(data: Data<'_>) -> Result<String, ApiError> {
+    let csv = data.open(MAX_CSV_MIB.mebibytes())
+        .into_string()
+        .await
+        .map_err(|e| ApiError::Validation(format!("Could not read CSV body: {}", e)))?;
+
+    if !csv.is_complete() {
+        return Err(ApiError::Validation(format!("CSV body exceeds {} MiB", MAX_CSV_MIB)));
+    }
+
+    Ok(csv.into_inner())
+}
+
+/// Responds 422 when a real import was refused because of row errors
+fn respond(report: ImportReport) -> (Status, Json<ImportReport>) {
+    let status = if report.dry_run || report.errors.is_empty() {
+        Status::Ok
+    } else {
+        Status::UnprocessableEntity
+    };
+
+    (status, Json(report))
+}
+
+/// Import rooms from CSV
+///
+/// Columns: `hotel` (hotel id or name), `room_number`, `room_type`, `price_per_night`
+/// and optionally `is_available` (defaults to true). Either every row is created or none.
+#[utoipa::path(
+    post
+    , path  = "/imports/rooms"
+    , tag   = "imports"
+    , params(
+        ("dry_run" = Option<bool>, Query, description = "Validate and report without writing")
+    )
+    , request_body(content = String, content_type = "text/csv")
+    , responses(
+        (status     = 200, description = "Rooms imported, or dry run report", body = ImportReport)
+        , (status   = 400, description = "Unreadable CSV or missing columns")
+        , (status   = 422, description = "Row errors, nothing was imported", body = ImportReport)
+    )
+)]
+#[post("/imports/rooms?<dry_run>", data = "<data>")]
+pub async fn import_rooms(
+    guard       : ServiceGuard
+    , dry_run   : Option<bool>
+    , data      : Data<'_>
+) -> Result<(Status, Json<ImportReport>), ApiError> {
+    let csv = read_csv(data).await?;
+    Ok(respond(guard.imports().import_rooms(&csv, dry_run.unwrap_or(false)).await?))
+}
+
+/// Import hotels from CSV
+///
+/// Columns: `name`, `street`, `city`, `country` and optionally `region`,
+/// `postal_code`, `latitude`, `longitude` and `description`. Files with a
+/// single `address` column instead of the structured one are still accepted.
+/// Either every row is created or none.
+#[utoipa::path(
+    post
+    , path  = "/imports/hotels"
+    , tag   = "imports"
+    , params(
+        ("dry_run" = Option<bool>, Query, description = "Validate and report without writing")
+    )
+    , request_body(content = String, content_type = "text/csv")
+    , responses(
+        (status     = 200, description = "Hotels imported, or dry run report", body = ImportReport)
+        , (status   = 400, description = "Unreadable CSV or missing columns")
+        , (status   = 422, description = "Row errors, nothing was imported", body = ImportReport)
+    )
+)]
+#[post("/imports/hotels?<dry_run>", data = "<data>")]
+pub async fn import_hotels(
+    guard       : ServiceGuard
+    , dry_run   : Option<bool>
+    , data      : Data<'_>
+) -> Result<(Status, Json<ImportReport>), ApiError> {
+    let csv = read_csv(data).await?;
+    Ok(respond(guard.imports().import_hotels(&csv, dry_run.unwrap_or(false)).await?))
+}
@@ -0,0 +1,36 @@
+use rocket::{get, serde::json::Json};
+use uuid::Uuid;
+use crate::{
+    schemas::loyalty::*,
+    services::guards::{AccessLevel, ServiceGuard},
+    services::traits::LoyaltyServiceTrait,
+    error::ApiError,
+};
+
+/// Get the loyalty account of a guest, with its tier, balance and history
+#[utoipa::path(
+    get
+    , path  = "/guests/{id}/loyalty"
+    , tag   = "loyalty"
+    , params(
+        ("id" = String, Path, description = "Guest UUID")
+        , ("X-Api-Key" = String, Header, description = "Staff API key")
+    )
+    , responses(
+        (status     = 200, description = "Loyalty account of the guest", body = LoyaltyAccountSchemaOut)
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "The staff API key is required")
+        , (status   = 404, description = "Guest not found")
+    )
+)]
+#[get("/guests/<id>/loyalty")]
+pub async fn get_loyalty_account(
+    guard       : ServiceGuard
+    , access    : AccessLevel
+    , id        : &str
+) -> Result<Option<Json<LoyaltyAccountSchemaOut>>, ApiError> {
+    access.require_staff()?;
+
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.loyalty().get_account(uuid).await?.map(Json))
+}
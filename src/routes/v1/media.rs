@@ -0,0 +1,311 @@
+use rocket::{get, post, put, delete, form::Form, http::ContentType, serde::json::Json};
+use tokio::io::AsyncReadExt;
+use uuid::Uuid;
+use crate::{
+    schemas::media::*,
+    services::guards::ServiceGuard,
+    services::traits::MediaServiceTrait,
+    error::ApiError,
+};
+
+/// Contents and caption of an uploaded form
+async fn read_upload(upload: MediaUploadForm<'_>) -> Result<(Vec<u8>, Option<String>), ApiError> {
+    let mut bytes = Vec::new();
+
+    let mut file = upload.file
+        .open()
+        .await
+        .map_err(|e| ApiError::Validation(format!("Could not read upload: {}", e)))?;
+
+    file.read_to_end(&mut bytes)
+        .await
+        .map_err(|e| ApiError::Validation(format!("Could not read upload: {}", e)))?;
+
+    Ok((bytes, upload.caption))
+}
+
+fn respond_file(file: MediaFile) -> (ContentType, Vec<u8>) {
+    let content_type = ContentType::parse_flexible(&file.content_type).unwrap_or(ContentType::Binary);
+    (content_type, file.bytes)
+}
+
+/// List the photos of a hotel in display order
+#[utoipa::path(
+    get
+    , path  = "/hotels/{hotel_id}/media"
+    , tag   = "media"
+    , params(
+        ("hotel_id" = String, Path, description = "Hotel UUID")
+    )
+    , responses(
+        (status     = 200, description = "Photos of the hotel", body = Vec<MediaSchemaOut>)
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[get("/hotels/<hotel_id>/media")]
+pub async fn list_hotel_media(
+    guard       : ServiceGuard
+    , hotel_id  : &str
+) -> Result<Option<Json<Vec<MediaSchemaOut>>>, ApiError> {
+    let Ok(hotel_id) = Uuid::parse_str(hotel_id) else { return Ok(None) };
+    Ok(guard.media().list_media(MediaOwner::Hotel(hotel_id)).await?.map(Json))
+}
+
+/// Upload a photo of a hotel, added last to its gallery
+#[utoipa::path(
+    post
+    , path  = "/hotels/{hotel_id}/media"
+    , tag   = "media"
+    , params(
+        ("hotel_id" = String, Path, description = "Hotel UUID")
+    )
+    , request_body(content = MediaUploadForm, content_type = "multipart/form-data")
+    , responses(
+        (status     = 200, description = "Photo uploaded", body = MediaSchemaOut)
+        , (status   = 400, description = "Not a JPEG, PNG or WebP image")
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[post("/hotels/<hotel_id>/media", data = "<upload>")]
+pub async fn upload_hotel_media(
+    guard       : ServiceGuard
+    , hotel_id  : &str
+    , upload    : Form<MediaUploadForm<'_>>
+) -> Result<Option<Json<MediaSchemaOut>>, ApiError> {
+    let Ok(hotel_id) = Uuid::parse_str(hotel_id) else { return Ok(None) };
+    let (bytes, caption) = read_upload(upload.into_inner()).await?;
+    Ok(guard.media().upload_media(MediaOwner::Hotel(hotel_id), bytes, caption).await?.map(Json))
+}
+
+/// Reorder the photos of a hotel
+#[utoipa::path(
+    put
+    , path  = "/hotels/{hotel_id}/media/order"
+    , tag   = "media"
+    , params(
+        ("hotel_id" = String, Path, description = "Hotel UUID")
+    )
+    , request_body  = MediaOrderSchemaIn
+    , responses(
+        (status     = 200, description = "Photos of the hotel in their new order", body = Vec<MediaSchemaOut>)
+        , (status   = 400, description = "The ids are not exactly the photos of the hotel")
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[put("/hotels/<hotel_id>/media/order", data = "<order>")]
+pub async fn reorder_hotel_media(
+    guard       : ServiceGuard
+    , hotel_id  : &str
+    , order     : Json<MediaOrderSchemaIn>
+) -> Result<Option<Json<Vec<MediaSchemaOut>>>, ApiError> {
+    let Ok(hotel_id) = Uuid::parse_str(hotel_id) else { return Ok(None) };
+    Ok(guard.media().reorder_media(MediaOwner::Hotel(hotel_id), order.0).await?.map(Json))
+}
+
+/// List the photos of a room in display order
+#[utoipa::path(
+    get
+    , path  = "/rooms/{room_id}/media"
+    , tag   = "media"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+    )
+    , responses(
+        (status     = 200, description = "Photos of the room", body = Vec<MediaSchemaOut>)
+        , (status   = 404, description = "Room not found")
+    )
+)]
+#[get("/rooms/<room_id>/media")]
+pub async fn list_room_media(
+    guard       : ServiceGuard
+    , room_id   : &str
+) -> Result<Option<Json<Vec<MediaSchemaOut>>>, ApiError> {
+    let Ok(room_id) = Uuid::parse_str(room_id) else { return Ok(None) };
+    Ok(guard.media().list_media(MediaOwner::Room(room_id)).await?.map(Json))
+}
+
+/// Upload a photo of a room, added last to its gallery
+#[utoipa::path(
+    post
+    , path  = "/rooms/{room_id}/media"
+    , tag   = "media"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+    )
+    , request_body(content = MediaUploadForm, content_type = "multipart/form-data")
+    , responses(
+        (status     = 200, description = "Photo uploaded", body = MediaSchemaOut)
+        , (status   = 400, description = "Not a JPEG, PNG or WebP image")
+        , (status   = 404, description = "Room not found")
+    )
+)]
+#[post("/rooms/<room_id>/media", data = "<upload>")]
+pub async fn upload_room_media(
+    guard       : ServiceGuard
+    , room_id   : &str
+    , upload    : Form<MediaUploadForm<'_>>
+) -> Result<Option<Json<MediaSchemaOut>>, ApiError> {
+    let Ok(room_id) = Uuid::parse_str(room_id) else { return Ok(None) };
+    let (bytes, caption) = read_upload(upload.into_inner()).await?;
+    Ok(guard.media().upload_media(MediaOwner::Room(room_id), bytes, caption).await?.map(Json))
+}
+
+/// Reorder the photos of a room
+#[utoipa::path(
+    put
+    , path  = "/rooms/{room_id}/media/order"
+    , tag   = "media"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+    )
+    , request_body  = MediaOrderSchemaIn
+    , responses(
+        (status     = 200, description = "Photos of the room in their new order", body = Vec<MediaSchemaOut>)
+        , (status   = 400, description = "The ids are not exactly the photos of the room")
+        , (status   = 404, description = "Room not found")
+    )
+)]
+#[put("/rooms/<room_id>/media/order", data = "<order>")]
+pub async fn reorder_room_media(
+    guard       : ServiceGuard
+    , room_id   : &str
+    , order     : Json<MediaOrderSchemaIn>
+) -> Result<Option<Json<Vec<MediaSchemaOut>>>, ApiError> {
+    let Ok(room_id) = Uuid::parse_str(room_id) else { return Ok(None) };
+    Ok(guard.media().reorder_media(MediaOwner::Room(room_id), order.0).await?.map(Json))
+}
+
+/// Get a specific photo
+#[utoipa::path(
+    get
+    , path  = "/media/{id}"
+    , tag   = "media"
+    , params(
+        ("id" = String, Path, description = "Media UUID")
+    )
+    , responses(
+        (status     = 200, description = "Photo found", body = MediaSchemaOut)
+        , (status   = 404, description = "Photo not found")
+    )
+)]
+#[get("/media/<id>")]
+pub async fn get_media(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Option<Json<MediaSchemaOut>>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.media().get_media(id).await?.map(Json))
+}
+
+/// Download the original image of a photo
+#[utoipa::path(
+    get
+    , path  = "/media/{id}/file"
+    , tag   = "media"
+    , params(
+        ("id" = String, Path, description = "Media UUID")
+    )
+    , responses(
+        (status     = 200, description = "Image as uploaded", body = Vec<u8>, content_type = "image/*")
+        , (status   = 404, description = "Photo not found")
+    )
+)]
+#[get("/media/<id>/file")]
+pub async fn get_media_file(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Option<(ContentType, Vec<u8>)>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.media().get_media_file(id, false).await?.map(respond_file))
+}
+
+/// Download the thumbnail of a photo
+#[utoipa::path(
+    get
+    , path  = "/media/{id}/thumbnail"
+    , tag   = "media"
+    , params(
+        ("id" = String, Path, description = "Media UUID")
+    )
+    , responses(
+        (status     = 200, description = "JPEG thumbnail, at most 320 pixels on its longest side", body = Vec<u8>, content_type = "image/jpeg")
+        , (status   = 404, description = "Photo not found")
+    )
+)]
+#[get("/media/<id>/thumbnail")]
+pub async fn get_media_thumbnail(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Option<(ContentType, Vec<u8>)>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.media().get_media_file(id, true).await?.map(respond_file))
+}
+
+/// Update the caption of a photo
+#[utoipa::path(
+    put
+    , path  = "/media/{id}"
+    , tag   = "media"
+    , params(
+        ("id" = String, Path, description = "Media UUID")
+    )
+    , request_body  = MediaUpdateSchemaIn
+    , responses(
+        (status     = 200, description = "Photo updated", body = MediaSchemaOut)
+        , (status   = 400, description = "Invalid input")
+        , (status   = 404, description = "Photo not found")
+    )
+)]
+#[put("/media/<id>", data = "<media>")]
+pub async fn update_media(
+    guard       : ServiceGuard
+    , id        : &str
+    , media     : Json<MediaUpdateSchemaIn>
+) -> Result<Option<Json<MediaSchemaOut>>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.media().update_media(id, media.0).await?.map(Json))
+}
+
+/// Make a photo the cover of its gallery
+#[utoipa::path(
+    post
+    , path  = "/media/{id}/cover"
+    , tag   = "media"
+    , params(
+        ("id" = String, Path, description = "Media UUID")
+    )
+    , responses(
+        (status     = 200, description = "Photo is now the cover", body = MediaSchemaOut)
+        , (status   = 404, description = "Photo not found")
+    )
+)]
+#[post("/media/<id>/cover")]
+pub async fn set_media_cover(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Option<Json<MediaSchemaOut>>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.media().set_cover(id).await?.map(Json))
+}
+
+/// Delete a photo and its files
+#[utoipa::path(
+    delete
+    , path  = "/media/{id}"
+    , tag   = "media"
+    , params(
+        ("id" = String, Path, description = "Media UUID")
+    )
+    , responses(
+        (status     = 200, description = "Photo deleted successfully")
+        , (status   = 404, description = "Photo not found")
+    )
+)]
+#[delete("/media/<id>")]
+pub async fn delete_media(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Json<bool>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(Json(false)) };
+    Ok(Json(guard.media().delete_media(id).await?))
+}
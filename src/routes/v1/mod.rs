@@ -2,9 +2,29 @@ use rocket::Route;
 use utoipa::OpenApi;
 
 pub mod hotels;
+pub mod amenities;
 pub mod rooms;
+pub mod room_blocks;
+pub mod housekeeping;
 pub mod bookings;
+pub mod reservations;
+pub mod waitlist;
+pub mod reviews;
 pub mod guests;
+pub mod privacy;
+pub mod audit;
+pub mod loyalty;
+pub mod promotions;
+pub mod webhooks;
+pub mod notifications;
+pub mod external_calendars;
+pub mod exports;
+pub mod imports;
+pub mod reports;
+pub mod calendar;
+pub mod search;
+pub mod media;
+pub mod params;
 
 pub fn routes() -> Vec<Route> {
     routes![
@@ -13,8 +33,20 @@ pub fn routes() -> Vec<Route> {
         , hotels::get_hotel
         , hotels::create_hotel
         , hotels::update_hotel
+        , hotels::update_hotel_policies
         , hotels::delete_hotel
 
+        // Amenities endpoints
+        , amenities::list_amenities
+        , amenities::get_amenity
+        , amenities::create_amenity
+        , amenities::update_amenity
+        , amenities::delete_amenity
+        , amenities::get_hotel_amenities
+        , amenities::set_hotel_amenities
+        , amenities::get_room_type_amenities
+        , amenities::set_room_type_amenities
+
         // Rooms endpoints
         , rooms::list_rooms
         , rooms::get_room
@@ -22,6 +54,18 @@ pub fn routes() -> Vec<Route> {
         , rooms::update_room
         , rooms::delete_room
         , rooms::get_hotel_rooms
+        , rooms::search_available_rooms
+
+        // Room blocks endpoints
+        , room_blocks::list_room_blocks
+        , room_blocks::get_room_block
+        , room_blocks::create_room_block
+        , room_blocks::update_room_block
+        , room_blocks::delete_room_block
+
+        // Housekeeping endpoints
+        , housekeeping::get_hotel_housekeeping
+        , housekeeping::update_room_housekeeping
 
         // Guests endpoints
         , guests::list_guests
@@ -29,15 +73,117 @@ pub fn routes() -> Vec<Route> {
         , guests::create_guest
         , guests::update_guest
         , guests::delete_guest
+        , guests::list_duplicate_guests
+        , guests::merge_guests
 
         // Booking paths
         , bookings::list_bookings
         , bookings::get_booking
         , bookings::create_booking
+        , bookings::quote_booking
         , bookings::update_booking
         , bookings::delete_booking
+        , bookings::assign_booking_room
         , bookings::get_guest_bookings
         , bookings::get_room_bookings
+        , bookings::get_guest_bookings_ics
+        , bookings::get_room_bookings_ics
+
+        // Reservations endpoints
+        , reservations::list_reservations
+        , reservations::get_reservation
+        , reservations::create_reservation
+        , reservations::update_reservation
+        , reservations::cancel_reservation
+
+        // Waitlist endpoints
+        , waitlist::list_waitlist_entries
+        , waitlist::get_waitlist_entry
+        , waitlist::create_waitlist_entry
+        , waitlist::delete_waitlist_entry
+
+        // Reviews endpoints
+        , reviews::list_reviews
+        , reviews::get_review
+        , reviews::create_review
+        , reviews::update_review
+        , reviews::moderate_review
+        , reviews::delete_review
+        , reviews::get_hotel_reviews
+        , reviews::get_hotel_review_summary
+
+        // Webhooks endpoints
+        , webhooks::list_webhooks
+        , webhooks::get_webhook
+        , webhooks::create_webhook
+        , webhooks::update_webhook
+        , webhooks::delete_webhook
+        , webhooks::get_webhook_deliveries
+
+        // Notifications endpoints
+        , notifications::get_booking_notifications
+
+        // External calendars endpoints
+        , external_calendars::list_room_calendars
+        , external_calendars::create_room_calendar
+        , external_calendars::delete_room_calendar
+        , external_calendars::sync_room_calendar
+        , external_calendars::upload_room_calendar
+        , external_calendars::get_room_calendar_blocks
+
+        // Exports endpoints
+        , exports::export_bookings_csv
+        , exports::export_bookings_ndjson
+        , exports::export_rooms_csv
+        , exports::export_rooms_ndjson
+        , exports::export_guests_csv
+        , exports::export_guests_ndjson
+        , exports::export_hotels_csv
+        , exports::export_hotels_ndjson
+
+        // Imports endpoints
+        , imports::import_hotels
+        , imports::import_rooms
+
+        // Reports endpoints
+        , reports::get_occupancy_report
+
+        // Calendar endpoints
+        , calendar::get_hotel_calendar
+
+        // Search endpoints
+        , search::search
+
+        // Media endpoints
+        , media::list_hotel_media
+        , media::upload_hotel_media
+        , media::reorder_hotel_media
+        , media::list_room_media
+        , media::upload_room_media
+        , media::reorder_room_media
+        , media::get_media
+        , media::get_media_file
+        , media::get_media_thumbnail
+        , media::update_media
+        , media::set_media_cover
+        , media::delete_media
+
+        // Privacy endpoints
+        , privacy::export_guest_data
+        , privacy::anonymize_guest
+
+        // Audit endpoints
+        , audit::list_audit_entries
+
+        // Loyalty endpoints
+        , loyalty::get_loyalty_account
+
+        // Promotions endpoints
+        , promotions::list_promotions
+        , promotions::get_promotion
+        , promotions::create_promotion
+        , promotions::update_promotion
+        , promotions::delete_promotion
     ]
 }
 
@@ -50,8 +196,20 @@ pub fn routes() -> Vec<Route> {
         , hotels::get_hotel
         , hotels::create_hotel
         , hotels::update_hotel
+        , hotels::update_hotel_policies
         , hotels::delete_hotel
 
+        // Amenities paths
+        , amenities::list_amenities
+        , amenities::get_amenity
+        , amenities::create_amenity
+        , amenities::update_amenity
+        , amenities::delete_amenity
+        , amenities::get_hotel_amenities
+        , amenities::set_hotel_amenities
+        , amenities::get_room_type_amenities
+        , amenities::set_room_type_amenities
+
         // Rooms paths
         , rooms::list_rooms
         , rooms::get_room
@@ -59,6 +217,18 @@ pub fn routes() -> Vec<Route> {
         , rooms::update_room
         , rooms::delete_room
         , rooms::get_hotel_rooms
+        , rooms::search_available_rooms
+
+        // Room blocks paths
+        , room_blocks::list_room_blocks
+        , room_blocks::get_room_block
+        , room_blocks::create_room_block
+        , room_blocks::update_room_block
+        , room_blocks::delete_room_block
+
+        // Housekeeping paths
+        , housekeeping::get_hotel_housekeeping
+        , housekeeping::update_room_housekeeping
 
         // Guest paths
         , guests::list_guests
@@ -66,41 +236,270 @@ pub fn routes() -> Vec<Route> {
         , guests::create_guest
         , guests::update_guest
         , guests::delete_guest
+        , guests::list_duplicate_guests
+        , guests::merge_guests
 
         // Bookings endpoints
         , bookings::list_bookings
         , bookings::get_booking
         , bookings::create_booking
+        , bookings::quote_booking
         , bookings::update_booking
         , bookings::delete_booking
+        , bookings::assign_booking_room
         , bookings::get_guest_bookings
         , bookings::get_room_bookings
+        , bookings::get_guest_bookings_ics
+        , bookings::get_room_bookings_ics
+
+        // Reservations paths
+        , reservations::list_reservations
+        , reservations::get_reservation
+        , reservations::create_reservation
+        , reservations::update_reservation
+        , reservations::cancel_reservation
+
+        // Waitlist paths
+        , waitlist::list_waitlist_entries
+        , waitlist::get_waitlist_entry
+        , waitlist::create_waitlist_entry
+        , waitlist::delete_waitlist_entry
+
+        // Reviews paths
+        , reviews::list_reviews
+        , reviews::get_review
+        , reviews::create_review
+        , reviews::update_review
+        , reviews::moderate_review
+        , reviews::delete_review
+        , reviews::get_hotel_reviews
+        , reviews::get_hotel_review_summary
+
+        // Webhooks paths
+        , webhooks::list_webhooks
+        , webhooks::get_webhook
+        , webhooks::create_webhook
+        , webhooks::update_webhook
+        , webhooks::delete_webhook
+        , webhooks::get_webhook_deliveries
+
+        // Notifications paths
+        , notifications::get_booking_notifications
+
+        // External calendars paths
+        , external_calendars::list_room_calendars
+        , external_calendars::create_room_calendar
+        , external_calendars::delete_room_calendar
+        , external_calendars::sync_room_calendar
+        , external_calendars::upload_room_calendar
+        , external_calendars::get_room_calendar_blocks
+
+        // Exports paths
+        , exports::export_bookings_csv
+        , exports::export_bookings_ndjson
+        , exports::export_rooms_csv
+        , exports::export_rooms_ndjson
+        , exports::export_guests_csv
+        , exports::export_guests_ndjson
+        , exports::export_hotels_csv
+        , exports::export_hotels_ndjson
+
+        // Imports paths
+        , imports::import_hotels
+        , imports::import_rooms
+
+        // Reports paths
+        , reports::get_occupancy_report
+
+        // Calendar paths
+        , calendar::get_hotel_calendar
+
+        // Search paths
+        , search::search
+
+        // Media paths
+        , media::list_hotel_media
+        , media::upload_hotel_media
+        , media::reorder_hotel_media
+        , media::list_room_media
+        , media::upload_room_media
+        , media::reorder_room_media
+        , media::get_media
+        , media::get_media_file
+        , media::get_media_thumbnail
+        , media::update_media
+        , media::set_media_cover
+        , media::delete_media
+
+        // Privacy paths
+        , privacy::export_guest_data
+        , privacy::anonymize_guest
+
+        // Audit paths
+        , audit::list_audit_entries
+
+        // Loyalty paths
+        , loyalty::get_loyalty_account
+
+        // Promotions paths
+        , promotions::list_promotions
+        , promotions::get_promotion
+        , promotions::create_promotion
+        , promotions::update_promotion
+        , promotions::delete_promotion
     ),
     components(
         schemas(
             // Hotels schemas
             crate::schemas::hotels::HotelSchemaIn
             , crate::schemas::hotels::HotelSchemaOut
+            , crate::schemas::hotels::HotelPoliciesSchema
+
+            // Amenities schemas
+            , crate::schemas::amenities::AmenitySchemaIn
+            , crate::schemas::amenities::AmenitySchemaOut
+            , crate::schemas::amenities::AmenityCodesSchemaIn
+            , crate::schemas::amenities::RoomTypeAmenitiesSchemaOut
 
             // Rooms schemas
             , crate::schemas::rooms::RoomSchemaIn
             , crate::schemas::rooms::RoomSchemaOut
 
+            // Room blocks schemas
+            , crate::schemas::room_blocks::RoomBlockSchemaIn
+            , crate::schemas::room_blocks::RoomBlockSchemaOut
+            , crate::models::sea_orm_active_enums::RoomBlockKind
+
+            // Housekeeping schemas
+            , crate::schemas::housekeeping::HousekeepingSchemaIn
+            , crate::schemas::housekeeping::HousekeepingSchemaOut
+            , crate::schemas::housekeeping::HousekeepingTask
+            , crate::models::sea_orm_active_enums::HousekeepingStatus
+
             // Guests schemas
             , crate::schemas::guests::GuestSchemaIn
             , crate::schemas::guests::GuestSchemaOut
+            , crate::schemas::guests::GuestSensitiveData
+            , crate::schemas::guests::GuestMergeSchemaIn
+            , crate::schemas::guests::GuestDuplicateSchemaOut
+            , crate::models::sea_orm_active_enums::IdDocumentType
+            , crate::models::sea_orm_active_enums::FloorPreference
+            , crate::models::sea_orm_active_enums::BedPreference
 
             // Bookings schemas
             , crate::schemas::booking::BookingSchemaIn
             , crate::schemas::booking::BookingSchemaOut
+            , crate::schemas::booking::AssignRoomSchemaIn
+            , crate::schemas::booking::BookingDiscountSchemaOut
+            , crate::schemas::booking::DiscountSource
+            , crate::schemas::booking::BookingQuoteSchemaIn
+            , crate::schemas::booking::BookingQuoteSchemaOut
             , crate::models::sea_orm_active_enums::BookingStatus
+
+            // Reservations schemas
+            , crate::schemas::reservations::ReservationRoomSchemaIn
+            , crate::schemas::reservations::ReservationSchemaIn
+            , crate::schemas::reservations::ReservationUpdateSchemaIn
+            , crate::schemas::reservations::ReservationSchemaOut
+
+            // Waitlist schemas
+            , crate::schemas::waitlist::WaitlistEntrySchemaIn
+            , crate::schemas::waitlist::WaitlistEntrySchemaOut
+            , crate::models::sea_orm_active_enums::WaitlistStatus
+
+            // Reviews schemas
+            , crate::schemas::reviews::ReviewSchemaIn
+            , crate::schemas::reviews::ReviewUpdateSchemaIn
+            , crate::schemas::reviews::ReviewModerationSchemaIn
+            , crate::schemas::reviews::ReviewSchemaOut
+            , crate::schemas::reviews::ReviewSummary
+            , crate::models::sea_orm_active_enums::ReviewStatus
+
+            // Webhooks schemas
+            , crate::schemas::webhooks::WebhookEvent
+            , crate::schemas::webhooks::WebhookSubscriptionSchemaIn
+            , crate::schemas::webhooks::WebhookSubscriptionSchemaOut
+            , crate::schemas::webhooks::WebhookDeliverySchemaOut
+            , crate::models::sea_orm_active_enums::WebhookDeliveryStatus
+
+            // Notifications schemas
+            , crate::schemas::notifications::NotificationSchemaOut
+            , crate::models::sea_orm_active_enums::NotificationKind
+            , crate::models::sea_orm_active_enums::NotificationStatus
+
+            // Imports schemas
+            , crate::schemas::imports::ImportReport
+            , crate::schemas::imports::ImportRowError
+
+            // Reports schemas
+            , crate::schemas::reports::Granularity
+            , crate::schemas::reports::OccupancyPoint
+            , crate::schemas::reports::OccupancyReport
+
+            // Calendar schemas
+            , crate::schemas::calendar::CalendarCellStatus
+            , crate::schemas::calendar::CalendarCell
+            , crate::schemas::calendar::CalendarRow
+            , crate::schemas::calendar::CalendarGrid
+
+            // Search schemas
+            , crate::schemas::search::SearchHitKind
+            , crate::schemas::search::SearchHit
+            , crate::schemas::search::SearchResults
+
+            // Media schemas
+            , crate::schemas::media::MediaUploadForm
+            , crate::schemas::media::MediaUpdateSchemaIn
+            , crate::schemas::media::MediaOrderSchemaIn
+            , crate::schemas::media::MediaSchemaOut
+
+            // Privacy schemas
+            , crate::schemas::privacy::GuestDataExport
+
+            // Audit schemas
+            , crate::schemas::audit::AuditEntrySchemaOut
+
+            // Loyalty schemas
+            , crate::schemas::loyalty::LoyaltyAccountSchemaOut
+            , crate::schemas::loyalty::LoyaltyEntrySchemaOut
+            , crate::schemas::loyalty::LoyaltyTier
+            , crate::models::sea_orm_active_enums::LoyaltyEntryKind
+
+            // Promotions schemas
+            , crate::schemas::promotions::PromotionSchemaIn
+            , crate::schemas::promotions::PromotionSchemaOut
+            , crate::models::sea_orm_active_enums::DiscountKind
+
+            // External calendars schemas
+            , crate::schemas::external_calendars::ExternalCalendarSchemaIn
+            , crate::schemas::external_calendars::ExternalCalendarSchemaOut
+            , crate::schemas::external_calendars::ExternalBlockSchemaOut
         )
     ),
     tags(
         (name = "hotels", description = "Hotel management endpoints")
+        , (name = "amenities", description = "Amenities catalogue and the amenities of hotels and room types")
         , (name = "rooms", description = "Room management endpoints")
+        , (name = "room-blocks", description = "Maintenance and out-of-order room closures")
+        , (name = "housekeeping", description = "Room cleaning status and task list")
         , (name = "guests", description = "Guest management endpoints")
         , (name = "bookings", description = "Booking management endpoints")
+        , (name = "reservations", description = "Group reservations owning several bookings")
+        , (name = "waitlist", description = "Waitlist for sold out room types, offered freed rooms on cancellation")
+        , (name = "reviews", description = "Guest reviews of completed stays, moderated before they count towards the hotel rating")
+        , (name = "webhooks", description = "Webhook subscription endpoints")
+        , (name = "notifications", description = "Guest email notification endpoints")
+        , (name = "external-calendars", description = "External channel iCal feeds blocking room availability")
+        , (name = "exports", description = "Streamed CSV and NDJSON exports")
+        , (name = "imports", description = "Bulk CSV imports with dry-run validation")
+        , (name = "reports", description = "Occupancy and revenue reporting")
+        , (name = "calendar", description = "Front desk availability grid")
+        , (name = "search", description = "Full text search over hotels and room types")
+        , (name = "media", description = "Hotel and room photo galleries")
+        , (name = "privacy", description = "Guest data export and anonymization for data subject requests")
+        , (name = "audit", description = "Audit trail of privacy sensitive operations")
+        , (name = "loyalty", description = "Loyalty points earned on completed stays and redeemed on bookings")
+        , (name = "promotions", description = "Promo codes discounting the bookings they apply to")
     ),
     servers(
         (url = "/api/v1", description = "Version 1") 
@@ -0,0 +1,30 @@
+use rocket::{get, serde::json::Json};
+use uuid::Uuid;
+use crate::{
+    schemas::notifications::*,
+    services::guards::ServiceGuard,
+    services::traits::NotificationServiceTrait,
+    error::ApiError,
+};
+
+/// Get the email notifications sent for a booking
+#[utoipa::path(
+    get
+    , path  = "/bookings/{id}/notifications"
+    , tag   = "notifications"
+    , params(
+        ("id" = String, Path, description = "Booking UUID")
+    )
+    , responses(
+        (status     = 200, description = "Notifications of the booking with their send status", body = Vec<NotificationSchemaOut>)
+        , (status   = 404, description = "Malformed booking id")
+    )
+)]
+#[get("/bookings/<id>/notifications")]
+pub async fn get_booking_notifications(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Option<Json<Vec<NotificationSchemaOut>>>, ApiError> {
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(Some(Json(guard.notifications().get_booking_notifications(uuid).await?)))
+}
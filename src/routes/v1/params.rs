@@ -0,0 +1,89 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use uuid::Uuid;
+use sea_orm::ActiveEnum;
+use crate::{error::ApiError, models::sea_orm_active_enums::{BookingStatus, HousekeepingStatus, ReviewStatus, WaitlistStatus}, schemas::hotels::GeoPoint};
+
+/// Parses an RFC 3339 timestamp or a `YYYY-MM-DD` date (midnight UTC) query parameter
+pub fn parse_datetime(field: &str, value: &str) -> Result<DateTime<FixedOffset>, ApiError> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime);
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc().fixed_offset())
+        .map_err(|_| ApiError::Validation(format!("{} must be an RFC 3339 timestamp or YYYY-MM-DD date", field)))
+}
+
+/// Parses a `YYYY-MM-DD` date query parameter
+pub fn parse_date(field: &str, value: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| ApiError::Validation(format!("{} must be a YYYY-MM-DD date", field)))
+}
+
+/// Parses an optional UUID query parameter
+pub fn parse_uuid(field: &str, value: Option<&str>) -> Result<Option<Uuid>, ApiError> {
+    value
+        .map(|v| Uuid::parse_str(v).map_err(|_| ApiError::Validation(format!("{} must be a UUID", field))))
+        .transpose()
+}
+
+/// Parses an optional non-negative integer query parameter
+pub fn parse_u64(field: &str, value: Option<&str>) -> Result<Option<u64>, ApiError> {
+    value
+        .map(|v| v.parse::<u64>().map_err(|_| ApiError::Validation(format!("{} must be a non-negative integer", field))))
+        .transpose()
+}
+
+/// Parses an optional `lat,lng` query parameter in decimal degrees
+pub fn parse_geo_point(field: &str, value: Option<&str>) -> Result<Option<GeoPoint>, ApiError> {
+    let Some(value) = value else { return Ok(None) };
+
+    let invalid = || ApiError::Validation(format!("{} must be latitude,longitude in decimal degrees", field));
+
+    let (latitude, longitude) = value.split_once(',').ok_or_else(invalid)?;
+    let latitude = latitude.trim().parse::<f64>().map_err(|_| invalid())?;
+    let longitude = longitude.trim().parse::<f64>().map_err(|_| invalid())?;
+
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return Err(invalid());
+    }
+
+    Ok(Some(GeoPoint { latitude, longitude }))
+}
+
+/// Parses an optional timestamp query parameter, see [`parse_datetime`]
+pub fn parse_optional_datetime(field: &str, value: Option<&str>) -> Result<Option<DateTime<FixedOffset>>, ApiError> {
+    value.map(|v| parse_datetime(field, v)).transpose()
+}
+
+/// Parses an optional booking status query parameter (`pending`, `confirmed`, ...)
+pub fn parse_booking_status(value: Option<&str>) -> Result<Option<BookingStatus>, ApiError> {
+    value
+        .map(|v| BookingStatus::try_from_value(&v.to_lowercase())
+            .map_err(|_| ApiError::Validation(format!("Unknown booking status {}", v))))
+        .transpose()
+}
+
+/// Parses an optional housekeeping status query parameter (`clean`, `dirty`, `inspected`, `out_of_service`)
+pub fn parse_housekeeping_status(value: Option<&str>) -> Result<Option<HousekeepingStatus>, ApiError> {
+    value
+        .map(|v| HousekeepingStatus::try_from_value(&v.to_lowercase())
+            .map_err(|_| ApiError::Validation(format!("Unknown housekeeping status {}", v))))
+        .transpose()
+}
+
+/// Parses an optional waitlist status query parameter (`waiting`, `offered`)
+pub fn parse_waitlist_status(value: Option<&str>) -> Result<Option<WaitlistStatus>, ApiError> {
+    value
+        .map(|v| WaitlistStatus::try_from_value(&v.to_lowercase())
+            .map_err(|_| ApiError::Validation(format!("Unknown waitlist status {}", v))))
+        .transpose()
+}
+
+/// Parses an optional review status query parameter (`pending`, `published`, `rejected`)
+pub fn parse_review_status(value: Option<&str>) -> Result<Option<ReviewStatus>, ApiError> {
+    value
+        .map(|v| ReviewStatus::try_from_value(&v.to_lowercase())
+            .map_err(|_| ApiError::Validation(format!("Unknown review status {}", v))))
+        .transpose()
+}
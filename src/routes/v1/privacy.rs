@@ -0,0 +1,83 @@
+use rocket::{
+    get, post,
+    http::{ContentType, Header},
+    request::Request,
+    response::{self, Responder, Response},
+    serde::json::Json,
+};
+use uuid::Uuid;
+use crate::{
+    schemas::{guests::GuestSchemaOut, privacy::GuestDataExport},
+    services::guards::{AccessLevel, ServiceGuard},
+    services::traits::PrivacyServiceTrait,
+    error::ApiError,
+};
+
+/// A guest data archive, served as a file download
+pub struct DataExportFile(GuestDataExport);
+
+impl<'r> Responder<'r, 'static> for DataExportFile {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let disposition = format!("attachment; filename=\"guest-{}.json\"", self.0.guest.id);
+
+        Response::build_from(Json(self.0).respond_to(req)?)
+            .header(ContentType::JSON)
+            .header(Header::new("Content-Disposition", disposition))
+            .ok()
+    }
+}
+
+/// Download everything stored about a guest, recorded in the audit trail
+#[utoipa::path(
+    get
+    , path  = "/guests/{id}/data-export"
+    , tag   = "privacy"
+    , params(
+        ("id" = String, Path, description = "Guest UUID")
+        , ("X-Api-Key" = String, Header, description = "Staff API key")
+    )
+    , responses(
+        (status     = 200, description = "JSON archive of the guest", body = GuestDataExport)
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "The staff API key is required")
+        , (status   = 404, description = "Guest not found")
+    )
+)]
+#[get("/guests/<id>/data-export")]
+pub async fn export_guest_data(
+    guard       : ServiceGuard
+    , access    : AccessLevel
+    , id        : &str
+) -> Result<Option<DataExportFile>, ApiError> {
+    access.require_staff()?;
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.privacy().export_guest_data(id).await?.map(DataExportFile))
+}
+
+/// Erase the personal data of a guest, keeping their bookings for accounting
+#[utoipa::path(
+    post
+    , path  = "/guests/{id}/anonymize"
+    , tag   = "privacy"
+    , params(
+        ("id" = String, Path, description = "Guest UUID")
+        , ("X-Api-Key" = String, Header, description = "Staff API key")
+    )
+    , responses(
+        (status     = 200, description = "Guest anonymized", body = GuestSchemaOut)
+        , (status   = 400, description = "The guest has already been anonymized")
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "The staff API key is required")
+        , (status   = 404, description = "Guest not found")
+    )
+)]
+#[post("/guests/<id>/anonymize")]
+pub async fn anonymize_guest(
+    guard       : ServiceGuard
+    , access    : AccessLevel
+    , id        : &str
+) -> Result<Option<Json<GuestSchemaOut>>, ApiError> {
+    access.require_staff()?;
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.privacy().anonymize_guest(id).await?.map(Json))
+}
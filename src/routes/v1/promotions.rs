@@ -0,0 +1,162 @@
+use rocket::{get, post, put, delete, serde::json::Json};
+use uuid::Uuid;
+use crate::{
+    schemas::promotions::*,
+    services::guards::{AccessLevel, ServiceGuard},
+    services::traits::PromotionServiceTrait,
+    routes::v1::params::parse_uuid,
+    error::ApiError,
+};
+
+/// List the promotions with their redemption counts
+#[utoipa::path(
+    get
+    , path  = "/promotions"
+    , tag   = "promotions"
+    , params(
+        ("hotel_id" = Option<String>, Query, description = "Only promotions scoped to this hotel (UUID)")
+        , ("is_active" = Option<bool>, Query, description = "Only active or inactive promotions")
+        , ("X-Api-Key" = String, Header, description = "Staff API key")
+    )
+    , responses(
+        (status     = 200, description = "Promotions ordered by code", body = Vec<PromotionSchemaOut>)
+        , (status   = 400, description = "Invalid query parameters")
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "The staff API key is required")
+    )
+)]
+#[get("/promotions?<hotel_id>&<is_active>")]
+pub async fn list_promotions(
+    guard           : ServiceGuard
+    , access        : AccessLevel
+    , hotel_id      : Option<&str>
+    , is_active     : Option<bool>
+) -> Result<Json<Vec<PromotionSchemaOut>>, ApiError> {
+    access.require_staff()?;
+
+    let filter = PromotionFilter {
+        hotel_id    : parse_uuid("hotel_id", hotel_id)?
+        , is_active
+    };
+
+    Ok(Json(guard.promotions().list_promotions(filter).await?))
+}
+
+/// Get a specific promotion by ID
+#[utoipa::path(
+    get
+    , path  = "/promotions/{id}"
+    , tag   = "promotions"
+    , params(
+        ("id" = String, Path, description = "Promotion UUID")
+        , ("X-Api-Key" = String, Header, description = "Staff API key")
+    )
+    , responses(
+        (status     = 200, description = "Promotion found", body = PromotionSchemaOut)
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "The staff API key is required")
+        , (status   = 404, description = "Promotion not found")
+    )
+)]
+#[get("/promotions/<id>")]
+pub async fn get_promotion(
+    guard       : ServiceGuard
+    , access    : AccessLevel
+    , id        : &str
+) -> Result<Option<Json<PromotionSchemaOut>>, ApiError> {
+    access.require_staff()?;
+
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.promotions().get_promotion(uuid).await?.map(Json))
+}
+
+/// Create a new promotion
+#[utoipa::path(
+    post
+    , path  = "/promotions"
+    , tag   = "promotions"
+    , params(
+        ("X-Api-Key" = String, Header, description = "Staff API key")
+    )
+    , request_body  = PromotionSchemaIn
+    , responses(
+        (status     = 201, description = "Promotion created successfully", body = PromotionSchemaOut)
+        , (status   = 400, description = "Invalid input, or the code is already used")
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "The staff API key is required")
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[post("/promotions", data = "<promotion>")]
+pub async fn create_promotion(
+    guard       : ServiceGuard
+    , access    : AccessLevel
+    , promotion : Json<PromotionSchemaIn>
+) -> Result<Json<PromotionSchemaOut>, ApiError> {
+    access.require_staff()?;
+
+    Ok(Json(guard.promotions().create_promotion(promotion.0).await?))
+}
+
+/// Update an existing promotion, bookings already made keep their discount
+#[utoipa::path(
+    put
+    , path  = "/promotions/{id}"
+    , tag   = "promotions"
+    , params(
+        ("id" = String, Path, description = "Promotion UUID")
+        , ("X-Api-Key" = String, Header, description = "Staff API key")
+    )
+    , request_body  = PromotionSchemaIn
+    , responses(
+        (status     = 200, description = "Promotion updated successfully", body = PromotionSchemaOut)
+        , (status   = 400, description = "Invalid input, or the code is already used")
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "The staff API key is required")
+        , (status   = 404, description = "Promotion or hotel not found")
+    )
+)]
+#[put("/promotions/<id>", data = "<promotion>")]
+pub async fn update_promotion(
+    guard       : ServiceGuard
+    , access    : AccessLevel
+    , id        : &str
+    , promotion : Json<PromotionSchemaIn>
+) -> Result<Option<Json<PromotionSchemaOut>>, ApiError> {
+    access.require_staff()?;
+
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.promotions().update_promotion(uuid, promotion.0).await?.map(Json))
+}
+
+/// Delete a promotion, bookings made with it keep their code and discount
+#[utoipa::path(
+    delete
+    , path  = "/promotions/{id}"
+    , tag   = "promotions"
+    , params(
+        ("id" = String, Path, description = "Promotion UUID")
+        , ("X-Api-Key" = String, Header, description = "Staff API key")
+    )
+    , responses(
+        (status     = 200, description = "Promotion deleted successfully")
+        , (status   = 401, description = "Invalid API key")
+        , (status   = 403, description = "The staff API key is required")
+        , (status   = 404, description = "Promotion not found")
+    )
+)]
+#[delete("/promotions/<id>")]
+pub async fn delete_promotion(
+    guard       : ServiceGuard
+    , access    : AccessLevel
+    , id        : &str
+) -> Result<Json<bool>, ApiError> {
+    access.require_staff()?;
+
+    let uuid = match Uuid::parse_str(id) {
+        Ok(id)  => id,
+        Err(_)  => return Ok(Json(false)),
+    };
+
+    Ok(Json(guard.promotions().delete_promotion(uuid).await?))
+}
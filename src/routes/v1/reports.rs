@@ -0,0 +1,49 @@
+use rocket::{get, serde::json::Json};
+use uuid::Uuid;
+use crate::{
+    schemas::reports::*,
+    services::guards::ServiceGuard,
+    services::traits::ReportServiceTrait,
+    routes::v1::params::parse_date,
+    error::ApiError,
+};
+
+/// Occupancy, ADR, RevPAR, revenue and cancellations of a hotel over time
+#[utoipa::path(
+    get
+    , path  = "/hotels/{id}/reports/occupancy"
+    , tag   = "reports"
+    , params(
+        ("id" = String, Path, description = "Hotel UUID")
+        , ("from" = String, Query, description = "First night, YYYY-MM-DD")
+        , ("to" = String, Query, description = "Day after the last night, YYYY-MM-DD")
+        , ("granularity" = Option<Granularity>, Query, description = "Period of each point, defaults to day")
+    )
+    , responses(
+        (status     = 200, description = "Time series per period plus totals", body = OccupancyReport)
+        , (status   = 400, description = "Invalid query parameters")
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[get("/hotels/<id>/reports/occupancy?<from>&<to>&<granularity>")]
+pub async fn get_occupancy_report(
+    guard           : ServiceGuard
+    , id            : &str
+    , from          : &str
+    , to            : &str
+    , granularity   : Option<&str>
+) -> Result<Option<Json<OccupancyReport>>, ApiError> {
+    let Ok(hotel_id) = Uuid::parse_str(id) else { return Ok(None) };
+
+    let granularity = match granularity {
+        Some(g) => Granularity::parse(g)
+            .ok_or_else(|| ApiError::Validation("granularity must be day, week or month".to_string()))?,
+        None    => Granularity::Day,
+    };
+
+    let report = guard.reports()
+        .occupancy_report(hotel_id, parse_date("from", from)?, parse_date("to", to)?, granularity)
+        .await?;
+
+    Ok(report.map(Json))
+}
@@ -0,0 +1,122 @@
+use rocket::{get, post, put, serde::json::Json};
+use uuid::Uuid;
+use crate::{
+    schemas::reservations::*,
+    services::guards::ServiceGuard,
+    services::traits::ReservationServiceTrait,
+    routes::v1::params::parse_uuid,
+    error::ApiError,
+};
+
+/// List group reservations, newest first
+#[utoipa::path(
+    get
+    , path  = "/reservations"
+    , tag   = "reservations"
+    , params(
+        ("guest_id" = Option<String>, Query, description = "Only reservations made by this guest (UUID)")
+    )
+    , responses(
+        (status     = 200, description = "List of reservations with their bookings", body = Vec<ReservationSchemaOut>)
+        , (status   = 400, description = "Invalid query parameters")
+    )
+)]
+#[get("/reservations?<guest_id>")]
+pub async fn list_reservations(
+    guard       : ServiceGuard
+    , guest_id  : Option<&str>
+) -> Result<Json<Vec<ReservationSchemaOut>>, ApiError> {
+    let guest_id = parse_uuid("guest_id", guest_id)?;
+    Ok(Json(guard.reservations().list_reservations(guest_id).await?))
+}
+
+/// Get a reservation with all of its bookings
+#[utoipa::path(
+    get
+    , path  = "/reservations/{id}"
+    , tag   = "reservations"
+    , params(
+        ("id" = String, Path, description = "Reservation UUID")
+    )
+    , responses(
+        (status     = 200, description = "Reservation found", body = ReservationSchemaOut)
+        , (status   = 404, description = "Reservation not found")
+    )
+)]
+#[get("/reservations/<id>")]
+pub async fn get_reservation(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Option<Json<ReservationSchemaOut>>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.reservations().get_reservation(id).await?.map(Json))
+}
+
+/// Book several rooms for the same stay, all of them or none
+#[utoipa::path(
+    post
+    , path  = "/reservations"
+    , tag   = "reservations"
+    , request_body  = ReservationSchemaIn
+    , responses(
+        (status     = 201, description = "Reservation and its bookings created", body = ReservationSchemaOut)
+        , (status   = 400, description = "Invalid input")
+        , (status   = 404, description = "Hotel or room not found")
+        , (status   = 409, description = "One of the rooms is not available for the requested dates")
+    )
+)]
+#[post("/reservations", data = "<reservation>")]
+pub async fn create_reservation(
+    guard           : ServiceGuard
+    , reservation   : Json<ReservationSchemaIn>
+) -> Result<Json<ReservationSchemaOut>, ApiError> {
+    Ok(Json(guard.reservations().create_reservation(reservation.0).await?))
+}
+
+/// Change the dates or status of every active booking of a reservation at once
+#[utoipa::path(
+    put
+    , path  = "/reservations/{id}"
+    , tag   = "reservations"
+    , params(
+        ("id" = String, Path, description = "Reservation UUID")
+    )
+    , request_body  = ReservationUpdateSchemaIn
+    , responses(
+        (status     = 200, description = "Reservation updated", body = ReservationSchemaOut)
+        , (status   = 400, description = "Invalid input")
+        , (status   = 404, description = "Reservation not found")
+        , (status   = 409, description = "One of the rooms is not available for the new dates")
+    )
+)]
+#[put("/reservations/<id>", data = "<reservation>")]
+pub async fn update_reservation(
+    guard           : ServiceGuard
+    , id            : &str
+    , reservation   : Json<ReservationUpdateSchemaIn>
+) -> Result<Option<Json<ReservationSchemaOut>>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.reservations().update_reservation(id, reservation.0).await?.map(Json))
+}
+
+/// Cancel every active booking of a reservation
+#[utoipa::path(
+    post
+    , path  = "/reservations/{id}/cancel"
+    , tag   = "reservations"
+    , params(
+        ("id" = String, Path, description = "Reservation UUID")
+    )
+    , responses(
+        (status     = 200, description = "Reservation cancelled", body = ReservationSchemaOut)
+        , (status   = 404, description = "Reservation not found")
+    )
+)]
+#[post("/reservations/<id>/cancel")]
+pub async fn cancel_reservation(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Option<Json<ReservationSchemaOut>>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.reservations().cancel_reservation(id).await?.map(Json))
+}
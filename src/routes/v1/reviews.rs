@@ -0,0 +1,197 @@
+use rocket::{get, post, put, delete, serde::json::Json};
+use uuid::Uuid;
+use crate::{
+    schemas::reviews::*,
+    services::guards::ServiceGuard,
+    services::traits::ReviewServiceTrait,
+    routes::v1::params::{parse_uuid, parse_review_status},
+    error::ApiError,
+};
+
+/// List reviews in any moderation status, newest first
+#[utoipa::path(
+    get
+    , path  = "/reviews"
+    , tag   = "reviews"
+    , params(
+        ("hotel_id" = Option<String>, Query, description = "Only reviews of this hotel (UUID)")
+        , ("guest_id" = Option<String>, Query, description = "Only reviews by this guest (UUID)")
+        , ("status" = Option<String>, Query, description = "Only reviews in this status: pending, published or rejected")
+    )
+    , responses(
+        (status     = 200, description = "List of reviews", body = Vec<ReviewSchemaOut>)
+        , (status   = 400, description = "Invalid query parameters")
+    )
+)]
+#[get("/reviews?<hotel_id>&<guest_id>&<status>")]
+pub async fn list_reviews(
+    guard       : ServiceGuard
+    , hotel_id  : Option<&str>
+    , guest_id  : Option<&str>
+    , status    : Option<&str>
+) -> Result<Json<Vec<ReviewSchemaOut>>, ApiError> {
+    let filter = ReviewFilter {
+        hotel_id    : parse_uuid("hotel_id", hotel_id)?
+        , guest_id  : parse_uuid("guest_id", guest_id)?
+        , status    : parse_review_status(status)?
+    };
+
+    Ok(Json(guard.reviews().list_reviews(filter).await?))
+}
+
+/// Get a specific review
+#[utoipa::path(
+    get
+    , path  = "/reviews/{id}"
+    , tag   = "reviews"
+    , params(
+        ("id" = String, Path, description = "Review UUID")
+    )
+    , responses(
+        (status     = 200, description = "Review found", body = ReviewSchemaOut)
+        , (status   = 404, description = "Review not found")
+    )
+)]
+#[get("/reviews/<id>")]
+pub async fn get_review(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Option<Json<ReviewSchemaOut>>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.reviews().get_review(id).await?.map(Json))
+}
+
+/// Review a completed stay, the review waits for moderation
+#[utoipa::path(
+    post
+    , path  = "/reviews"
+    , tag   = "reviews"
+    , request_body  = ReviewSchemaIn
+    , responses(
+        (status     = 201, description = "Review created", body = ReviewSchemaOut)
+        , (status   = 400, description = "Invalid scores, or the stay is not completed or already reviewed")
+    )
+)]
+#[post("/reviews", data = "<review>")]
+pub async fn create_review(
+    guard       : ServiceGuard
+    , review    : Json<ReviewSchemaIn>
+) -> Result<Json<ReviewSchemaOut>, ApiError> {
+    Ok(Json(guard.reviews().create_review(review.0).await?))
+}
+
+/// Edit a review, which goes back to moderation
+#[utoipa::path(
+    put
+    , path  = "/reviews/{id}"
+    , tag   = "reviews"
+    , params(
+        ("id" = String, Path, description = "Review UUID")
+    )
+    , request_body  = ReviewUpdateSchemaIn
+    , responses(
+        (status     = 200, description = "Review updated", body = ReviewSchemaOut)
+        , (status   = 400, description = "Invalid scores")
+        , (status   = 404, description = "Review not found")
+    )
+)]
+#[put("/reviews/<id>", data = "<review>")]
+pub async fn update_review(
+    guard       : ServiceGuard
+    , id        : &str
+    , review    : Json<ReviewUpdateSchemaIn>
+) -> Result<Option<Json<ReviewSchemaOut>>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.reviews().update_review(id, review.0).await?.map(Json))
+}
+
+/// Publish or reject a review, recomputing the hotel rating
+#[utoipa::path(
+    post
+    , path  = "/reviews/{id}/moderate"
+    , tag   = "reviews"
+    , params(
+        ("id" = String, Path, description = "Review UUID")
+    )
+    , request_body  = ReviewModerationSchemaIn
+    , responses(
+        (status     = 200, description = "Review moderated", body = ReviewSchemaOut)
+        , (status   = 400, description = "Status is not Published or Rejected")
+        , (status   = 404, description = "Review not found")
+    )
+)]
+#[post("/reviews/<id>/moderate", data = "<moderation>")]
+pub async fn moderate_review(
+    guard           : ServiceGuard
+    , id            : &str
+    , moderation    : Json<ReviewModerationSchemaIn>
+) -> Result<Option<Json<ReviewSchemaOut>>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.reviews().moderate_review(id, moderation.0).await?.map(Json))
+}
+
+/// Delete a review
+#[utoipa::path(
+    delete
+    , path  = "/reviews/{id}"
+    , tag   = "reviews"
+    , params(
+        ("id" = String, Path, description = "Review UUID")
+    )
+    , responses(
+        (status     = 200, description = "Review deleted successfully")
+        , (status   = 404, description = "Review not found")
+    )
+)]
+#[delete("/reviews/<id>")]
+pub async fn delete_review(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Json<bool>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(Json(false)) };
+    Ok(Json(guard.reviews().delete_review(id).await?))
+}
+
+/// List the published reviews of a hotel, newest first
+#[utoipa::path(
+    get
+    , path  = "/hotels/{hotel_id}/reviews"
+    , tag   = "reviews"
+    , params(
+        ("hotel_id" = String, Path, description = "Hotel UUID")
+    )
+    , responses(
+        (status     = 200, description = "Published reviews of the hotel", body = Vec<ReviewSchemaOut>)
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[get("/hotels/<hotel_id>/reviews")]
+pub async fn get_hotel_reviews(
+    guard       : ServiceGuard
+    , hotel_id  : &str
+) -> Result<Option<Json<Vec<ReviewSchemaOut>>>, ApiError> {
+    let Ok(hotel_id) = Uuid::parse_str(hotel_id) else { return Ok(None) };
+    Ok(guard.reviews().get_hotel_reviews(hotel_id).await?.map(Json))
+}
+
+/// Average overall and sub-scores of the published reviews of a hotel
+#[utoipa::path(
+    get
+    , path  = "/hotels/{hotel_id}/reviews/summary"
+    , tag   = "reviews"
+    , params(
+        ("hotel_id" = String, Path, description = "Hotel UUID")
+    )
+    , responses(
+        (status     = 200, description = "Review summary of the hotel", body = ReviewSummary)
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[get("/hotels/<hotel_id>/reviews/summary")]
+pub async fn get_hotel_review_summary(
+    guard       : ServiceGuard
+    , hotel_id  : &str
+) -> Result<Option<Json<ReviewSummary>>, ApiError> {
+    let Ok(hotel_id) = Uuid::parse_str(hotel_id) else { return Ok(None) };
+    Ok(guard.reviews().get_hotel_review_summary(hotel_id).await?.map(Json))
+}
@@ -0,0 +1,135 @@
+use rocket::{get, post, put, delete, serde::json::Json};
+use uuid::Uuid;
+use crate::{
+    schemas::room_blocks::*,
+    services::guards::ServiceGuard,
+    services::traits::RoomBlockServiceTrait,
+    error::ApiError,
+};
+
+/// List the maintenance and out-of-order blocks of a room
+#[utoipa::path(
+    get
+    , path  = "/rooms/{room_id}/blocks"
+    , tag   = "room-blocks"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+    )
+    , responses(
+        (status     = 200, description = "Blocks of the room ordered by start date", body = Vec<RoomBlockSchemaOut>)
+        , (status   = 404, description = "Room not found")
+    )
+)]
+#[get("/rooms/<room_id>/blocks")]
+pub async fn list_room_blocks(
+    guard       : ServiceGuard
+    , room_id   : &str
+) -> Result<Option<Json<Vec<RoomBlockSchemaOut>>>, ApiError> {
+    let Ok(room_id) = Uuid::parse_str(room_id) else { return Ok(None) };
+    Ok(guard.room_blocks().list_blocks(room_id).await?.map(Json))
+}
+
+/// Get a specific block of a room
+#[utoipa::path(
+    get
+    , path  = "/rooms/{room_id}/blocks/{id}"
+    , tag   = "room-blocks"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+        , ("id" = String, Path, description = "Room block UUID")
+    )
+    , responses(
+        (status     = 200, description = "Room block found", body = RoomBlockSchemaOut)
+        , (status   = 404, description = "Room block not found")
+    )
+)]
+#[get("/rooms/<room_id>/blocks/<id>")]
+pub async fn get_room_block(
+    guard       : ServiceGuard
+    , room_id   : &str
+    , id        : &str
+) -> Result<Option<Json<RoomBlockSchemaOut>>, ApiError> {
+    let (Ok(room_id), Ok(id)) = (Uuid::parse_str(room_id), Uuid::parse_str(id)) else { return Ok(None) };
+    Ok(guard.room_blocks().get_block(room_id, id).await?.map(Json))
+}
+
+/// Close a room for a date range
+#[utoipa::path(
+    post
+    , path  = "/rooms/{room_id}/blocks"
+    , tag   = "room-blocks"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+    )
+    , request_body  = RoomBlockSchemaIn
+    , responses(
+        (status     = 201, description = "Room block created successfully", body = RoomBlockSchemaOut)
+        , (status   = 400, description = "Invalid input")
+        , (status   = 404, description = "Room not found")
+        , (status   = 409, description = "Room is booked during the block")
+    )
+)]
+#[post("/rooms/<room_id>/blocks", data = "<block>")]
+pub async fn create_room_block(
+    guard       : ServiceGuard
+    , room_id   : &str
+    , block     : Json<RoomBlockSchemaIn>
+) -> Result<Json<RoomBlockSchemaOut>, ApiError> {
+    let room_id = Uuid::parse_str(room_id).map_err(|_| ApiError::RoomNotFound(room_id.to_string()))?;
+    Ok(Json(guard.room_blocks().create_block(room_id, block.0).await?))
+}
+
+/// Update a block of a room
+#[utoipa::path(
+    put
+    , path  = "/rooms/{room_id}/blocks/{id}"
+    , tag   = "room-blocks"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+        , ("id" = String, Path, description = "Room block UUID")
+    )
+    , request_body  = RoomBlockSchemaIn
+    , responses(
+        (status     = 200, description = "Room block updated successfully", body = RoomBlockSchemaOut)
+        , (status   = 400, description = "Invalid input")
+        , (status   = 404, description = "Room block not found")
+        , (status   = 409, description = "Room is booked during the block")
+    )
+)]
+#[put("/rooms/<room_id>/blocks/<id>", data = "<block>")]
+pub async fn update_room_block(
+    guard       : ServiceGuard
+    , room_id   : &str
+    , id        : &str
+    , block     : Json<RoomBlockSchemaIn>
+) -> Result<Option<Json<RoomBlockSchemaOut>>, ApiError> {
+    let (Ok(room_id), Ok(id)) = (Uuid::parse_str(room_id), Uuid::parse_str(id)) else { return Ok(None) };
+    Ok(guard.room_blocks().update_block(room_id, id, block.0).await?.map(Json))
+}
+
+/// Delete a block, reopening the room for its dates
+#[utoipa::path(
+    delete
+    , path  = "/rooms/{room_id}/blocks/{id}"
+    , tag   = "room-blocks"
+    , params(
+        ("room_id" = String, Path, description = "Room UUID")
+        , ("id" = String, Path, description = "Room block UUID")
+    )
+    , responses(
+        (status     = 200, description = "Room block deleted successfully")
+        , (status   = 404, description = "Room block not found")
+    )
+)]
+#[delete("/rooms/<room_id>/blocks/<id>")]
+pub async fn delete_room_block(
+    guard       : ServiceGuard
+    , room_id   : &str
+    , id        : &str
+) -> Result<Json<bool>, ApiError> {
+    let (Ok(room_id), Ok(id)) = (Uuid::parse_str(room_id), Uuid::parse_str(id)) else {
+        return Ok(Json(false));
+    };
+
+    Ok(Json(guard.room_blocks().delete_block(room_id, id).await?))
+}
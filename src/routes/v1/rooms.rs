@@ -1,4 +1,5 @@
 use rocket::{get, post, put, delete, serde::json::Json};
+use utoipa::IntoParams;
 use uuid::Uuid;
 use crate::{
     schemas::rooms::*,
@@ -7,21 +8,47 @@ use crate::{
 };
 
 use crate::error::ApiError;
+use crate::routes::v1::params::{parse_datetime, parse_uuid};
+
+/// Query parameters shared by the room list and export endpoints
+#[derive(FromForm, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RoomQuery {
+    /// Only rooms of this hotel (UUID)
+    pub hotel_id: Option<String>,
+    /// Only rooms of this type
+    pub room_type: Option<String>,
+    /// Only rooms with this availability flag
+    pub is_available: Option<bool>,
+}
+
+impl RoomQuery {
+    pub fn into_filter(self) -> Result<RoomFilter, ApiError> {
+        Ok(RoomFilter {
+            hotel_id        : parse_uuid("hotel_id", self.hotel_id.as_deref())?
+            , room_type     : self.room_type
+            , is_available  : self.is_available
+        })
+    }
+}
 
 /// List all rooms
 #[utoipa::path(
     get
     , path  = "/rooms"
     , tag   = "rooms"
+    , params(RoomQuery)
     , responses(
         (status = 200, description = "List of all rooms", body = Vec<RoomSchemaOut>)
+        , (status = 400, description = "Invalid query parameters")
     )
 )]
-#[get("/rooms")]
+#[get("/rooms?<query..>")]
 pub async fn list_rooms(
-    guard: ServiceGuard 
-) -> Json<Vec<RoomSchemaOut>> {
-    Json(guard.rooms().get_all_rooms().await.unwrap())
+    guard   : ServiceGuard
+    , query : RoomQuery
+) -> Result<Json<Vec<RoomSchemaOut>>, ApiError> {
+    Ok(Json(guard.rooms().get_all_rooms(query.into_filter()?).await?))
 }
 
 /// Get a specific room
@@ -138,4 +165,38 @@ pub async fn get_hotel_rooms(
 ) -> Option<Json<Vec<RoomSchemaOut>>> {
     let uuid = Uuid::parse_str(hotel_id).ok()?;
     Some(Json(guard.rooms().get_rooms_by_hotel(uuid).await.unwrap()))
+}
+
+/// Search rooms free for a date range
+#[utoipa::path(
+    get
+    , path = "/rooms/available"
+    , tag  = "rooms"
+    , params(
+        ("check_in" = String, Query, description = "Check-in, RFC 3339 timestamp or YYYY-MM-DD")
+        , ("check_out" = String, Query, description = "Check-out, RFC 3339 timestamp or YYYY-MM-DD")
+        , ("hotel_id" = Option<String>, Query, description = "Only rooms of this hotel")
+        , ("room_type" = Option<String>, Query, description = "Only rooms of this type")
+    )
+    , responses(
+        (status = 200, description = "Rooms without overlapping bookings or blocks, cheapest first", body = Vec<RoomSchemaOut>)
+        , (status = 400, description = "Invalid query parameters")
+    )
+)]
+#[get("/rooms/available?<check_in>&<check_out>&<hotel_id>&<room_type>")]
+pub async fn search_available_rooms(
+    guard: ServiceGuard
+    , check_in: &str
+    , check_out: &str
+    , hotel_id: Option<&str>
+    , room_type: Option<String>
+) -> Result<Json<Vec<RoomSchemaOut>>, ApiError> {
+    let query = RoomAvailabilityQuery {
+        check_in    : parse_datetime("check_in", check_in)?
+        , check_out : parse_datetime("check_out", check_out)?
+        , hotel_id  : parse_uuid("hotel_id", hotel_id)?
+        , room_type
+    };
+
+    Ok(Json(guard.rooms().search_available_rooms(query).await?))
 }
\ No newline at end of file
@@ -0,0 +1,39 @@
+use rocket::{get, serde::json::Json};
+use crate::{
+    schemas::search::*,
+    services::guards::ServiceGuard,
+    services::traits::SearchServiceTrait,
+    routes::v1::params::parse_u64,
+    error::ApiError,
+};
+
+/// Full text search over hotels and room types, best matches first
+#[utoipa::path(
+    get
+    , path  = "/search"
+    , tag   = "search"
+    , params(
+        ("q" = String, Query, description = "Search terms, supports \"quoted phrases\", or and -excluded words")
+        , ("page" = Option<u64>, Query, description = "Page number starting at 1, defaults to 1")
+        , ("per_page" = Option<u64>, Query, description = "Hits per page, 1 to 100, defaults to 20")
+    )
+    , responses(
+        (status     = 200, description = "Ranked hits with highlighted snippets", body = SearchResults)
+        , (status   = 400, description = "Invalid query parameters")
+    )
+)]
+#[get("/search?<q>&<page>&<per_page>")]
+pub async fn search(
+    guard       : ServiceGuard
+    , q         : &str
+    , page      : Option<&str>
+    , per_page  : Option<&str>
+) -> Result<Json<SearchResults>, ApiError> {
+    let query = SearchQuery {
+        q           : q.to_string()
+        , page      : parse_u64("page", page)?
+        , per_page  : parse_u64("per_page", per_page)?
+    };
+
+    Ok(Json(guard.search().search(query).await?))
+}
@@ -0,0 +1,104 @@
+use rocket::{get, post, delete, serde::json::Json};
+use uuid::Uuid;
+use crate::{
+    schemas::waitlist::*,
+    services::guards::ServiceGuard,
+    services::traits::WaitlistServiceTrait,
+    routes::v1::params::{parse_uuid, parse_waitlist_status},
+    error::ApiError,
+};
+
+/// List waitlist entries, oldest first
+#[utoipa::path(
+    get
+    , path  = "/waitlist"
+    , tag   = "waitlist"
+    , params(
+        ("hotel_id" = Option<String>, Query, description = "Only entries for this hotel (UUID)")
+        , ("guest_id" = Option<String>, Query, description = "Only entries of this guest (UUID)")
+        , ("status" = Option<String>, Query, description = "Only entries in this status: waiting or offered")
+    )
+    , responses(
+        (status     = 200, description = "List of waitlist entries", body = Vec<WaitlistEntrySchemaOut>)
+        , (status   = 400, description = "Invalid query parameters")
+    )
+)]
+#[get("/waitlist?<hotel_id>&<guest_id>&<status>")]
+pub async fn list_waitlist_entries(
+    guard       : ServiceGuard
+    , hotel_id  : Option<&str>
+    , guest_id  : Option<&str>
+    , status    : Option<&str>
+) -> Result<Json<Vec<WaitlistEntrySchemaOut>>, ApiError> {
+    let filter = WaitlistFilter {
+        hotel_id    : parse_uuid("hotel_id", hotel_id)?
+        , guest_id  : parse_uuid("guest_id", guest_id)?
+        , status    : parse_waitlist_status(status)?
+    };
+
+    Ok(Json(guard.waitlist().list_entries(filter).await?))
+}
+
+/// Get a specific waitlist entry
+#[utoipa::path(
+    get
+    , path  = "/waitlist/{id}"
+    , tag   = "waitlist"
+    , params(
+        ("id" = String, Path, description = "Waitlist entry UUID")
+    )
+    , responses(
+        (status     = 200, description = "Waitlist entry found", body = WaitlistEntrySchemaOut)
+        , (status   = 404, description = "Waitlist entry not found")
+    )
+)]
+#[get("/waitlist/<id>")]
+pub async fn get_waitlist_entry(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Option<Json<WaitlistEntrySchemaOut>>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.waitlist().get_entry(id).await?.map(Json))
+}
+
+/// Join the waitlist for a room type of a hotel
+#[utoipa::path(
+    post
+    , path  = "/waitlist"
+    , tag   = "waitlist"
+    , request_body  = WaitlistEntrySchemaIn
+    , responses(
+        (status     = 201, description = "Waitlist entry created", body = WaitlistEntrySchemaOut)
+        , (status   = 400, description = "Invalid input")
+        , (status   = 404, description = "Hotel not found")
+    )
+)]
+#[post("/waitlist", data = "<entry>")]
+pub async fn create_waitlist_entry(
+    guard   : ServiceGuard
+    , entry : Json<WaitlistEntrySchemaIn>
+) -> Result<Json<WaitlistEntrySchemaOut>, ApiError> {
+    Ok(Json(guard.waitlist().create_entry(entry.0).await?))
+}
+
+/// Leave the waitlist
+#[utoipa::path(
+    delete
+    , path  = "/waitlist/{id}"
+    , tag   = "waitlist"
+    , params(
+        ("id" = String, Path, description = "Waitlist entry UUID")
+    )
+    , responses(
+        (status     = 200, description = "Waitlist entry deleted successfully")
+        , (status   = 404, description = "Waitlist entry not found")
+    )
+)]
+#[delete("/waitlist/<id>")]
+pub async fn delete_waitlist_entry(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Json<bool>, ApiError> {
+    let Ok(id) = Uuid::parse_str(id) else { return Ok(Json(false)) };
+    Ok(Json(guard.waitlist().delete_entry(id).await?))
+}
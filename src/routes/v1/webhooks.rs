@@ -0,0 +1,138 @@
+use rocket::{get, post, put, delete, serde::json::Json};
+use uuid::Uuid;
+use crate::{
+    schemas::webhooks::*,
+    services::guards::ServiceGuard,
+    services::traits::WebhookServiceTrait,
+    error::ApiError,
+};
+
+/// List all webhook subscriptions
+#[utoipa::path(
+    get
+    , path  = "/webhooks"
+    , tag   = "webhooks"
+    , responses(
+        (status = 200, description = "List of all webhook subscriptions", body = Vec<WebhookSubscriptionSchemaOut>)
+    )
+)]
+#[get("/webhooks")]
+pub async fn list_webhooks(
+    guard: ServiceGuard
+) -> Result<Json<Vec<WebhookSubscriptionSchemaOut>>, ApiError> {
+    Ok(Json(guard.webhooks().list_subscriptions().await?))
+}
+
+/// Get a specific webhook subscription by ID
+#[utoipa::path(
+    get
+    , path  = "/webhooks/{id}"
+    , tag   = "webhooks"
+    , params(
+        ("id" = String, Path, description = "Webhook subscription UUID")
+    )
+    , responses(
+        (status     = 200, description = "Webhook subscription found", body = WebhookSubscriptionSchemaOut)
+        , (status   = 404, description = "Webhook subscription not found")
+    )
+)]
+#[get("/webhooks/<id>")]
+pub async fn get_webhook(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Option<Json<WebhookSubscriptionSchemaOut>>, ApiError> {
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.webhooks().get_subscription(uuid).await?.map(Json))
+}
+
+/// Create a new webhook subscription
+#[utoipa::path(
+    post
+    , path  = "/webhooks"
+    , tag   = "webhooks"
+    , request_body  = WebhookSubscriptionSchemaIn
+    , responses(
+        (status     = 201, description = "Webhook subscription created successfully, with its secret", body = WebhookSubscriptionSchemaOut)
+        , (status   = 400, description = "Invalid input")
+    )
+)]
+#[post("/webhooks", data = "<webhook>")]
+pub async fn create_webhook(
+    guard       : ServiceGuard
+    , webhook   : Json<WebhookSubscriptionSchemaIn>
+) -> Result<Json<WebhookSubscriptionSchemaOut>, ApiError> {
+    Ok(Json(guard.webhooks().create_subscription(webhook.0).await?))
+}
+
+/// Update an existing webhook subscription
+#[utoipa::path(
+    put
+    , path  = "/webhooks/{id}"
+    , tag   = "webhooks"
+    , params(
+        ("id" = String, Path, description = "Webhook subscription UUID")
+    )
+    , request_body  = WebhookSubscriptionSchemaIn
+    , responses(
+        (status     = 200, description = "Webhook subscription updated successfully", body = WebhookSubscriptionSchemaOut)
+        , (status   = 400, description = "Invalid input")
+        , (status   = 404, description = "Webhook subscription not found")
+    )
+)]
+#[put("/webhooks/<id>", data = "<webhook>")]
+pub async fn update_webhook(
+    guard       : ServiceGuard
+    , id        : &str
+    , webhook   : Json<WebhookSubscriptionSchemaIn>
+) -> Result<Option<Json<WebhookSubscriptionSchemaOut>>, ApiError> {
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.webhooks().update_subscription(uuid, webhook.0).await?.map(Json))
+}
+
+/// Delete a webhook subscription
+#[utoipa::path(
+    delete
+    , path  = "/webhooks/{id}"
+    , tag   = "webhooks"
+    , params(
+        ("id" = String, Path, description = "Webhook subscription UUID")
+    )
+    , responses(
+        (status     = 200, description = "Webhook subscription deleted successfully")
+        , (status   = 404, description = "Webhook subscription not found")
+    )
+)]
+#[delete("/webhooks/<id>")]
+pub async fn delete_webhook(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Json<bool>, ApiError> {
+    let uuid = match Uuid::parse_str(id) {
+        Ok(id)  => id,
+        Err(_)  => return Ok(Json(false)),
+    };
+
+    Ok(Json(guard.webhooks().delete_subscription(uuid).await?))
+}
+
+/// Get the delivery log of a webhook subscription
+#[utoipa::path(
+    get
+    , path  = "/webhooks/{id}/deliveries"
+    , tag   = "webhooks"
+    , params(
+        ("id" = String, Path, description = "Webhook subscription UUID")
+    )
+    , responses(
+        (status     = 200, description = "Deliveries of the subscription, newest first", body = Vec<WebhookDeliverySchemaOut>)
+        , (status   = 404, description = "Webhook subscription not found")
+    )
+)]
+#[get("/webhooks/<id>/deliveries")]
+pub async fn get_webhook_deliveries(
+    guard   : ServiceGuard
+    , id    : &str
+) -> Result<Option<Json<Vec<WebhookDeliverySchemaOut>>>, ApiError> {
+    let Ok(uuid) = Uuid::parse_str(id) else { return Ok(None) };
+    Ok(guard.webhooks().list_deliveries(uuid).await?.map(Json))
+}
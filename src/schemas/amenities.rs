@@ -0,0 +1,64 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+use crate::models::amenities;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AmenitySchemaIn {
+    /// Stable key used in filters: lowercase letters, digits, `-` and `_`
+    #[schema(example = "pet-friendly")]
+    pub code            : String,
+
+    #[schema(example = "Pet friendly")]
+    pub name            : String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct AmenitySchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id              : Uuid,
+
+    #[schema(example = "pet-friendly")]
+    pub code            : String,
+
+    #[schema(example = "Pet friendly")]
+    pub name            : String,
+
+    #[schema(example = "2024-01-01T12:00:00+00:00")]
+    pub created_at      : DateTime<FixedOffset>,
+
+    #[schema(example = "2024-01-05T09:30:00+00:00")]
+    pub updated_at      : Option<DateTime<FixedOffset>>,
+}
+
+impl From<amenities::Model> for AmenitySchemaOut {
+    fn from(a: amenities::Model) -> Self {
+        Self {
+            id          : a.id,
+            code        : a.code,
+            name        : a.name,
+            created_at  : a.created_at,
+            updated_at  : a.updated_at,
+        }
+    }
+}
+
+/// Replaces the amenities of a hotel or room type
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AmenityCodesSchemaIn {
+    /// Codes of catalogue amenities
+    #[schema(example = json!(["wifi", "pool", "parking"]))]
+    pub amenities       : Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct RoomTypeAmenitiesSchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub hotel_id        : Uuid,
+
+    #[schema(example = "Suite")]
+    pub room_type       : String,
+
+    pub amenities       : Vec<AmenitySchemaOut>,
+}
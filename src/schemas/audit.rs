@@ -0,0 +1,65 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+use crate::models::audit_log;
+
+/// Operation recorded in the audit trail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    GuestDataExported
+    , GuestAnonymized
+    , GuestMerged
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::GuestDataExported  => "GuestDataExported",
+            AuditAction::GuestAnonymized    => "GuestAnonymized",
+            AuditAction::GuestMerged        => "GuestMerged",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct AuditEntrySchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id              : Uuid,
+
+    #[schema(example = "GuestAnonymized")]
+    pub action          : String,
+
+    #[schema(example = "guest")]
+    pub entity_type     : String,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub entity_id       : Uuid,
+
+    /// What the operation touched, depends on the action
+    #[schema(value_type = Option<Object>, example = json!({"reviews_scrubbed": 1}))]
+    pub details         : Option<serde_json::Value>,
+
+    #[schema(example = "2024-01-10T12:00:00+00:00")]
+    pub created_at      : DateTime<FixedOffset>,
+}
+
+impl From<audit_log::Model> for AuditEntrySchemaOut {
+    fn from(a: audit_log::Model) -> Self {
+        Self {
+            id              : a.id,
+            action          : a.action,
+            entity_type     : a.entity_type,
+            entity_id       : a.entity_id,
+            details         : a.details,
+            created_at      : a.created_at,
+        }
+    }
+}
+
+/// Filters of the audit trail listing
+#[derive(Debug, Default)]
+pub struct AuditFilter {
+    pub entity_type : Option<String>
+    , pub entity_id : Option<Uuid>
+}
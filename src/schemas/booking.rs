@@ -3,37 +3,155 @@ use rust_decimal::Decimal;
 use serde::{Serialize, Deserialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
 use crate::models::sea_orm_active_enums::BookingStatus;
 
+/// Start or end of a stay: an RFC 3339 timestamp, or a `YYYY-MM-DD` date at
+/// the hotel's check-in or check-out time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum StayDateTime {
+    At(DateTime<FixedOffset>),
+    On(NaiveDate),
+}
+
+impl StayDateTime {
+    /// The timestamp, a date taking `time` in the hotel's timezone. A time
+    /// skipped by a DST change moves an hour later.
+    pub fn at(self, time: NaiveTime, tz: Tz) -> DateTime<FixedOffset> {
+        match self {
+            StayDateTime::At(datetime)  => datetime,
+            StayDateTime::On(date)      => {
+                let local = date.and_time(time);
+
+                tz.from_local_datetime(&local)
+                    .earliest()
+                    .or_else(|| tz.from_local_datetime(&(local + Duration::hours(1))).earliest())
+                    .map(|t| t.fixed_offset())
+                    .unwrap_or_else(|| local.and_utc().fixed_offset())
+            }
+        }
+    }
+}
+
+impl From<DateTime<FixedOffset>> for StayDateTime {
+    fn from(datetime: DateTime<FixedOffset>) -> Self {
+        StayDateTime::At(datetime)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct BookingSchemaIn {
+    // Either a concrete room, or a hotel and room type with the room assigned later
     #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
-    pub room_id         : Uuid
+    pub room_id         : Option<Uuid>
+
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      pub hotel_id      : Option<Uuid>
+
+    , #[schema(example = "double")]
+      pub room_type     : Option<String>
     
     , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
       pub guest_id      : Uuid
     
-    , #[schema(example = "2024-01-10T14:00:00+00:00")]
-      pub check_in_date : DateTime<FixedOffset>
+    // A date alone takes the hotel's check-in or check-out time
+    , #[schema(value_type = String, example = "2024-01-10T14:00:00+00:00")]
+      pub check_in_date : StayDateTime
     
-    , #[schema(example = "2024-01-15T11:00:00+00:00")]
-      pub check_out_date: DateTime<FixedOffset>
+    , #[schema(value_type = String, example = "2024-01-15")]
+      pub check_out_date: StayDateTime
     
+    // Price before the discounts of the booking
     , #[schema(value_type = f64, example = 199.99)]
       pub total_price   : Decimal
     
     , #[schema(example = "pending")]
       pub status        : BookingStatus
+
+    // Loyalty points of the guest spent on a discount off total_price, only
+    // when the booking is created
+    , #[schema(example = 500)]
+      #[serde(default)]
+      pub redeem_points : Option<i32>
+
+    // Promotion taken off total_price before the loyalty points, only when
+    // the booking is created
+    , #[schema(example = "SUMMER25")]
+      #[serde(default)]
+      pub promo_code    : Option<String>
+}
+
+/// What a discount of a booking or quote came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+pub enum DiscountSource {
+    Promotion,
+    Loyalty,
+}
+
+/// A discount taken off the subtotal of a booking or quote
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BookingDiscountSchemaOut {
+    #[schema(example = "Promotion")]
+    pub source          : DiscountSource
+
+    // Set for promotions, unset once the promotion is deleted
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      pub promotion_id  : Option<Uuid>
+
+    , #[schema(example = "SUMMER25")]
+      pub code          : Option<String>
+
+    // Set for loyalty points
+    , #[schema(example = json!(null))]
+      pub points        : Option<i32>
+
+    , #[schema(value_type = f64, example = 50.00)]
+      pub amount        : Decimal
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+impl BookingDiscountSchemaOut {
+    pub fn promotion(promotion_id: Option<Uuid>, code: String, amount: Decimal) -> Self {
+        Self {
+            source          : DiscountSource::Promotion
+            , promotion_id
+            , code          : Some(code)
+            , points        : None
+            , amount
+        }
+    }
+
+    pub fn loyalty(points: i32, amount: Decimal) -> Self {
+        Self {
+            source          : DiscountSource::Loyalty
+            , promotion_id  : None
+            , code          : None
+            , points        : Some(points)
+            , amount
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BookingSchemaOut {
     #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
     pub id              : Uuid
     
+    // Empty in events recorded before bookings of a room type existed
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      #[serde(default)]
+      pub hotel_id      : Uuid
+
+    , #[schema(example = "double")]
+      #[serde(default)]
+      pub room_type     : String
+
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      pub room_id       : Option<Uuid>
+
     , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
-      pub room_id       : Uuid
+      pub reservation_id: Option<Uuid>
     
     , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
       pub guest_id      : Uuid
@@ -44,15 +162,155 @@ pub struct BookingSchemaOut {
     , #[schema(example = "2024-01-15T11:00:00+00:00")]
       pub check_out_date: DateTime<FixedOffset>
     
+    // Price before the discounts, zero in events recorded before discounts
+    // were itemized
+    , #[schema(value_type = f64, example = 254.99)]
+      #[serde(default)]
+      pub subtotal_price: Decimal
+
+    , #[serde(default)]
+      pub discounts     : Vec<BookingDiscountSchemaOut>
+
+    // Left to pay once the discounts are taken off
     , #[schema(value_type = f64, example = 199.99)]
       pub total_price   : Decimal
     
     , #[schema(example = "confirmed")]
       pub status        : BookingStatus
+
+    // Both zero in events recorded before loyalty points existed
+    , #[schema(example = 500)]
+      #[serde(default)]
+      pub loyalty_points_redeemed   : i32
+
+    // Already taken off total_price
+    , #[schema(value_type = f64, example = 5.00)]
+      #[serde(default)]
+      pub loyalty_discount          : Decimal
     
     , #[schema(example = "2024-01-10T12:00:00+00:00")]
       pub created_at    : DateTime<FixedOffset>
     
     , #[schema(example = "2024-01-10T15:30:00+00:00")]
       pub updated_at    : Option<DateTime<FixedOffset>>
+}
+
+impl From<crate::models::bookings::Model> for BookingSchemaOut {
+    fn from(b: crate::models::bookings::Model) -> Self {
+        let mut discounts = Vec::new();
+
+        if let Some(code) = b.promo_code {
+            discounts.push(BookingDiscountSchemaOut::promotion(b.promotion_id, code, b.promotion_discount));
+        }
+
+        if b.loyalty_points_redeemed > 0 {
+            discounts.push(BookingDiscountSchemaOut::loyalty(b.loyalty_points_redeemed, b.loyalty_discount));
+        }
+
+        Self {
+            id              : b.id
+            , hotel_id      : b.hotel_id
+            , room_type     : b.room_type
+            , room_id       : b.room_id
+            , reservation_id: b.reservation_id
+            , guest_id      : b.guest_id
+            , check_in_date : b.check_in_date
+            , check_out_date: b.check_out_date
+            , subtotal_price: b.total_price + b.promotion_discount + b.loyalty_discount
+            , discounts
+            , total_price   : b.total_price
+            , status        : b.status
+            , loyalty_points_redeemed   : b.loyalty_points_redeemed
+            , loyalty_discount          : b.loyalty_discount
+            , created_at    : b.created_at
+            , updated_at    : b.updated_at
+        }
+    }
+}
+
+/// A stay to price, without booking it
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BookingQuoteSchemaIn {
+    // Either a concrete room, or a hotel and room type priced at its cheapest room
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    #[serde(default)]
+    pub room_id         : Option<Uuid>
+
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      #[serde(default)]
+      pub hotel_id      : Option<Uuid>
+
+    , #[schema(example = "double")]
+      #[serde(default)]
+      pub room_type     : Option<String>
+
+    // Needed to redeem points, and for the per guest limit of a promotion
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      #[serde(default)]
+      pub guest_id      : Option<Uuid>
+
+    , #[schema(value_type = String, example = "2024-01-10")]
+      pub check_in_date : StayDateTime
+
+    , #[schema(value_type = String, example = "2024-01-15")]
+      pub check_out_date: StayDateTime
+
+    , #[schema(example = "SUMMER25")]
+      #[serde(default)]
+      pub promo_code    : Option<String>
+
+    , #[schema(example = 500)]
+      #[serde(default)]
+      pub redeem_points : Option<i32>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BookingQuoteSchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub hotel_id        : Uuid
+
+    , #[schema(example = "double")]
+      pub room_type     : String
+
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      pub room_id       : Option<Uuid>
+
+    , #[schema(example = "2024-01-10T14:00:00+00:00")]
+      pub check_in_date : DateTime<FixedOffset>
+
+    , #[schema(example = "2024-01-15T11:00:00+00:00")]
+      pub check_out_date: DateTime<FixedOffset>
+
+    , #[schema(example = 5)]
+      pub nights        : i64
+
+    , #[schema(value_type = f64, example = 51.00)]
+      pub price_per_night   : Decimal
+
+    , #[schema(value_type = f64, example = 255.00)]
+      pub subtotal_price: Decimal
+
+    , pub discounts     : Vec<BookingDiscountSchemaOut>
+
+    , #[schema(value_type = f64, example = 186.25)]
+      pub total_price   : Decimal
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AssignRoomSchemaIn {
+    // Picked automatically when omitted
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub room_id         : Option<Uuid>
+}
+
+/// Filters shared by the booking list and export endpoints
+#[derive(Debug, Default)]
+pub struct BookingFilter {
+    pub status      : Option<BookingStatus>
+    , pub hotel_id  : Option<Uuid>
+    , pub room_id   : Option<Uuid>
+    , pub guest_id  : Option<Uuid>
+    // Only bookings overlapping the range from..to
+    , pub from      : Option<DateTime<FixedOffset>>
+    , pub to        : Option<DateTime<FixedOffset>>
 }
\ No newline at end of file
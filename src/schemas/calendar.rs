@@ -0,0 +1,61 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::NaiveDate;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CalendarCellStatus {
+    Free,
+    /// Confirmed or completed booking
+    Booked,
+    /// Pending booking
+    Held,
+    /// Closed by a room block, an external calendar or the room's availability flag
+    Blocked,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct CalendarCell {
+    #[schema(example = "booked")]
+    pub status      : CalendarCellStatus,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub booking_id  : Option<Uuid>,
+
+    /// Why a blocked cell is blocked
+    #[schema(example = "Airbnb: Reserved")]
+    pub note        : Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CalendarRow {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub room_id     : Uuid,
+
+    #[schema(example = "101")]
+    pub room_number : String,
+
+    #[schema(example = "double")]
+    pub room_type   : String,
+
+    /// One cell per entry of `dates`, in the same order
+    pub cells       : Vec<CalendarCell>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CalendarGrid {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub hotel_id    : Uuid,
+
+    #[schema(example = "2024-01-01")]
+    pub from        : NaiveDate,
+
+    #[schema(example = "2024-01-15")]
+    pub to          : NaiveDate,
+
+    /// Column headers: every night from `from` up to, not including, `to`
+    pub dates       : Vec<NaiveDate>,
+
+    pub rooms       : Vec<CalendarRow>,
+}
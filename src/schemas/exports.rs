@@ -0,0 +1,137 @@
+use rust_decimal::Decimal;
+use sea_orm::FromQueryResult;
+use serde::Serialize;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+use crate::models::sea_orm_active_enums::BookingStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv       => "csv",
+            ExportFormat::Ndjson    => "ndjson",
+        }
+    }
+
+    /// Last line of an export cut short by an error, so a truncated file is
+    /// not taken for a complete one
+    pub fn aborted_line(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv       => "# Export aborted, the file is incomplete\n",
+            ExportFormat::Ndjson    => "{\"error\":\"Export aborted, the file is incomplete\"}\n",
+        }
+    }
+}
+
+/// A flat export record; `HEADERS` is the CSV header line, in field order
+pub trait ExportRow: Serialize {
+    const HEADERS: &'static [&'static str];
+}
+
+#[derive(Debug, Serialize, FromQueryResult)]
+pub struct BookingExportRow {
+    pub id                  : Uuid
+    , pub hotel_id          : Uuid
+    , pub hotel_name        : String
+    // Empty until a room type booking is assigned a room
+    , pub room_id           : Option<Uuid>
+    , pub room_number       : Option<String>
+    , pub room_type         : String
+    , pub guest_id          : Uuid
+    , pub guest_first_name  : String
+    , pub guest_last_name   : String
+    , pub guest_email       : String
+    , pub check_in_date     : DateTime<FixedOffset>
+    , pub check_out_date    : DateTime<FixedOffset>
+    , pub total_price       : Decimal
+    , pub status            : BookingStatus
+    , pub created_at        : DateTime<FixedOffset>
+    , pub updated_at        : Option<DateTime<FixedOffset>>
+}
+
+impl ExportRow for BookingExportRow {
+    const HEADERS: &'static [&'static str] = &[
+        "id", "hotel_id", "hotel_name", "room_id", "room_number", "room_type", "guest_id"
+        , "guest_first_name", "guest_last_name", "guest_email", "check_in_date", "check_out_date"
+        , "total_price", "status", "created_at", "updated_at"
+    ];
+}
+
+#[derive(Debug, Serialize, FromQueryResult)]
+pub struct RoomExportRow {
+    pub id                  : Uuid
+    , pub hotel_id          : Uuid
+    , pub hotel_name        : String
+    , pub room_number       : String
+    , pub room_type         : String
+    , pub price_per_night   : Decimal
+    , pub is_available      : bool
+    , pub description       : Option<String>
+    , pub created_at        : DateTime<FixedOffset>
+    , pub updated_at        : Option<DateTime<FixedOffset>>
+}
+
+impl ExportRow for RoomExportRow {
+    const HEADERS: &'static [&'static str] = &[
+        "id", "hotel_id", "hotel_name", "room_number", "room_type", "price_per_night", "is_available"
+        , "description", "created_at", "updated_at"
+    ];
+}
+
+#[derive(Debug, Serialize, FromQueryResult)]
+pub struct GuestExportRow {
+    pub id              : Uuid
+    , pub first_name    : String
+    , pub last_name     : String
+    , pub email         : String
+    , pub phone         : Option<String>
+    , pub street        : Option<String>
+    , pub city          : Option<String>
+    , pub region        : Option<String>
+    , pub postal_code   : Option<String>
+    , pub country       : Option<String>
+    , pub marketing_email_consent   : bool
+    , pub marketing_sms_consent     : bool
+    , pub created_at    : DateTime<FixedOffset>
+    , pub updated_at    : Option<DateTime<FixedOffset>>
+}
+
+/// Sensitive guest data is never exported
+impl ExportRow for GuestExportRow {
+    const HEADERS: &'static [&'static str] = &[
+        "id", "first_name", "last_name", "email", "phone", "street", "city", "region", "postal_code", "country"
+        , "marketing_email_consent", "marketing_sms_consent", "created_at", "updated_at"
+    ];
+}
+
+#[derive(Debug, Serialize, FromQueryResult)]
+pub struct HotelExportRow {
+    pub id              : Uuid
+    , pub name          : String
+    , pub address       : String
+    , pub street        : Option<String>
+    , pub city          : Option<String>
+    , pub region        : Option<String>
+    , pub postal_code   : Option<String>
+    , pub country       : Option<String>
+    , pub latitude      : Option<f64>
+    , pub longitude     : Option<f64>
+    , pub rating        : Option<f64>
+    , pub review_count  : i32
+    , pub description   : Option<String>
+    , pub created_at    : DateTime<FixedOffset>
+    , pub updated_at    : Option<DateTime<FixedOffset>>
+}
+
+impl ExportRow for HotelExportRow {
+    const HEADERS: &'static [&'static str] = &[
+        "id", "name", "address", "street", "city", "region", "postal_code", "country", "latitude", "longitude"
+        , "rating", "review_count", "description", "created_at", "updated_at"
+    ];
+}
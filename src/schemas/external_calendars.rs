@@ -0,0 +1,64 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ExternalCalendarSchemaIn {
+    #[schema(example = "Airbnb")]
+    pub name    : String
+
+    // Feed polled by the sync job, empty for calendars fed by uploads only
+    , #[schema(example = "https://www.airbnb.com/calendar/ical/12345.ics")]
+      pub url   : Option<String>
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ExternalCalendarSchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id                  : Uuid
+
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      pub room_id           : Uuid
+
+    , #[schema(example = "Airbnb")]
+      pub name              : String
+
+    , #[schema(example = "https://www.airbnb.com/calendar/ical/12345.ics")]
+      pub url               : Option<String>
+
+    , #[schema(example = "2024-01-10T12:00:00+00:00")]
+      pub last_synced_at    : Option<DateTime<FixedOffset>>
+
+    , pub last_error        : Option<String>
+
+    , #[schema(example = "2024-01-10T12:00:00+00:00")]
+      pub created_at        : DateTime<FixedOffset>
+
+    , #[schema(example = "2024-01-10T12:00:00+00:00")]
+      pub updated_at        : Option<DateTime<FixedOffset>>
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ExternalBlockSchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id              : Uuid
+
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      pub calendar_id   : Uuid
+
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      pub room_id       : Uuid
+
+    , #[schema(example = "a1b2c3@airbnb.com")]
+      pub uid           : String
+
+    , #[schema(example = "Reserved")]
+      pub summary       : Option<String>
+
+    , #[schema(example = "2024-02-01T00:00:00+00:00")]
+      pub starts_at     : DateTime<FixedOffset>
+
+    , #[schema(example = "2024-02-05T00:00:00+00:00")]
+      pub ends_at       : DateTime<FixedOffset>
+}
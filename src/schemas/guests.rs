@@ -1,51 +1,291 @@
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, Deserializer};
 use utoipa::ToSchema;
 use uuid::Uuid;
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use crate::models::{
+    guests,
+    sea_orm_active_enums::{BedPreference, FloorPreference, IdDocumentType},
+};
 
+/// Tells an omitted field, `None`, from an explicit `null`, `Some(None)`
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// A guest profile. The sensitive fields are left unchanged when omitted and
+/// cleared by an explicit `null`.
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct GuestSchemaIn {
     #[schema(example = "John")]
     pub first_name      : String
-    
+
     , #[schema(example = "Doe")]
       pub last_name     : String
-    
+
     , #[schema(example = "john.doe@example.com")]
       pub email         : String
-    
+
     , #[schema(example = "+1234567890")]
       pub phone         : Option<String>
+
+    , #[schema(example = "42 Elm Street")]
+      #[serde(default)]
+      pub street        : Option<String>
+
+    , #[schema(example = "Springfield")]
+      #[serde(default)]
+      pub city          : Option<String>
+
+    , #[schema(example = "IL")]
+      #[serde(default)]
+      pub region        : Option<String>
+
+    , #[schema(example = "62704")]
+      #[serde(default)]
+      pub postal_code   : Option<String>
+
+    , /// ISO 3166-1 alpha-2 country code
+      #[schema(example = "US")]
+      #[serde(default)]
+      pub country       : Option<String>
+
+    , /// Sensitive, requires the staff API key
+      #[schema(value_type = Option<NaiveDate>, example = "1985-04-12")]
+      #[serde(default, deserialize_with = "nullable")]
+      pub date_of_birth : Option<Option<NaiveDate>>
+
+    , /// ISO 3166-1 alpha-2 country code, sensitive, requires the staff API key
+      #[schema(value_type = Option<String>, example = "US")]
+      #[serde(default, deserialize_with = "nullable")]
+      pub nationality   : Option<Option<String>>
+
+    , /// Sensitive, requires the staff API key
+      #[schema(value_type = Option<IdDocumentType>)]
+      #[serde(default, deserialize_with = "nullable")]
+      pub id_document_type      : Option<Option<IdDocumentType>>
+
+    , /// Sensitive, requires the staff API key, needs `id_document_type`
+      #[schema(value_type = Option<String>, example = "X1234567")]
+      #[serde(default, deserialize_with = "nullable")]
+      pub id_document_number    : Option<Option<String>>
+
+    , #[serde(default)]
+      pub floor_preference      : Option<FloorPreference>
+
+    , #[serde(default)]
+      pub bed_preference        : Option<BedPreference>
+
+    , /// Sensitive, requires the staff API key
+      #[schema(value_type = Option<String>, example = "Peanuts")]
+      #[serde(default, deserialize_with = "nullable")]
+      pub allergies     : Option<Option<String>>
+
+    , /// Left unchanged when omitted, new guests default to no consent
+      #[schema(example = false)]
+      #[serde(default)]
+      pub marketing_email_consent   : Option<bool>
+
+    , /// Left unchanged when omitted, new guests default to no consent
+      #[schema(example = false)]
+      #[serde(default)]
+      pub marketing_sms_consent     : Option<bool>
 }
 
-#[derive(Debug, Deserialize, Serialize, ToSchema)]
+impl GuestSchemaIn {
+    /// Whether any field only staff may write is set
+    pub fn has_sensitive_data(&self) -> bool {
+        self.date_of_birth.flatten().is_some()
+            || self.nationality.as_ref().is_some_and(Option::is_some)
+            || self.id_document_type.as_ref().is_some_and(Option::is_some)
+            || self.id_document_number.as_ref().is_some_and(Option::is_some)
+            || self.allergies.as_ref().is_some_and(Option::is_some)
+    }
+}
+
+/// Identity and health data, only returned with the staff API key
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct GuestSensitiveData {
+    #[schema(example = "1985-04-12")]
+    pub date_of_birth       : Option<NaiveDate>
+
+    , #[schema(example = "US")]
+      pub nationality       : Option<String>
+
+    , pub id_document_type  : Option<IdDocumentType>
+
+    , #[schema(example = "X1234567")]
+      pub id_document_number    : Option<String>
+
+    , #[schema(example = "Peanuts")]
+      pub allergies         : Option<String>
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 #[schema(example = json!({
     "id": "550e8400-e29b-41d4-a716-446655440000",
     "first_name": "John",
     "last_name": "Doe",
     "email": "john.doe@example.com",
     "phone": "+1234567890",
+    "street": "42 Elm Street",
+    "city": "Springfield",
+    "region": "IL",
+    "postal_code": "62704",
+    "country": "US",
+    "floor_preference": "High",
+    "bed_preference": "King",
+    "marketing_email_consent": true,
+    "marketing_sms_consent": false,
+    "marketing_consent_at": "2024-01-10T12:00:00+00:00",
+    "sensitive": {
+        "date_of_birth": "1985-04-12",
+        "nationality": "US",
+        "id_document_type": "Passport",
+        "id_document_number": "X1234567",
+        "allergies": "Peanuts"
+    },
+    "anonymized_at": null,
+    "merged_into_id": null,
+    "merged_at": null,
     "created_at": "2024-01-10T12:00:00+00:00",
     "updated_at": "2024-01-10T12:00:00+00:00"
 }))]
 pub struct GuestSchemaOut {
     #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
     pub id              : Uuid
-    
+
     , #[schema(example = "John")]
       pub first_name    : String
-    
+
     , #[schema(example = "Doe")]
       pub last_name     : String
-    
+
     , #[schema(example = "john.doe@example.com")]
       pub email         : String
-    
+
     , #[schema(example = "+1234567890")]
       pub phone         : String
-    
+
+    , pub street        : Option<String>
+    , pub city          : Option<String>
+    , pub region        : Option<String>
+    , pub postal_code   : Option<String>
+    , pub country       : Option<String>
+    , pub floor_preference  : Option<FloorPreference>
+    , pub bed_preference    : Option<BedPreference>
+    // No consent in events recorded before it was collected
+    , #[serde(default)]
+      pub marketing_email_consent   : bool
+    , #[serde(default)]
+      pub marketing_sms_consent     : bool
+
+    , /// When either marketing consent last changed
+      pub marketing_consent_at      : Option<DateTime<FixedOffset>>
+
+    , /// Only returned with the staff API key
+      #[serde(skip_serializing_if = "Option::is_none")]
+      pub sensitive     : Option<GuestSensitiveData>
+
+    , /// When the personal data of the guest was erased
+      pub anonymized_at : Option<DateTime<FixedOffset>>
+
+    , /// Guest this duplicate was merged into, set on tombstones only
+      pub merged_into_id    : Option<Uuid>
+
+    , pub merged_at     : Option<DateTime<FixedOffset>>
+
     , #[schema(example = "2024-01-10T12:00:00+00:00")]
       pub created_at    : DateTime<FixedOffset>
-    
+
     , pub updated_at    : Option<DateTime<FixedOffset>>
-}
\ No newline at end of file
+}
+
+impl GuestSchemaOut {
+    /// Same guest without the sensitive data
+    pub fn redacted(&self) -> Self {
+        Self { sensitive: None, ..self.clone() }
+    }
+}
+
+impl From<guests::Model> for GuestSchemaOut {
+    fn from(g: guests::Model) -> Self {
+        Self {
+            id              : g.id
+            , first_name    : g.first_name
+            , last_name     : g.last_name
+            , email         : g.email
+            , phone         : g.phone.unwrap_or_default()
+            , street        : g.street
+            , city          : g.city
+            , region        : g.region
+            , postal_code   : g.postal_code
+            , country       : g.country
+            , floor_preference  : g.floor_preference
+            , bed_preference    : g.bed_preference
+            , marketing_email_consent   : g.marketing_email_consent
+            , marketing_sms_consent     : g.marketing_sms_consent
+            , marketing_consent_at      : g.marketing_consent_at
+            , sensitive     : Some(GuestSensitiveData {
+                date_of_birth           : g.date_of_birth
+                , nationality           : g.nationality
+                , id_document_type      : g.id_document_type
+                , id_document_number    : g.id_document_number
+                , allergies             : g.allergies
+            })
+            , anonymized_at : g.anonymized_at
+            , merged_into_id    : g.merged_into_id
+            , merged_at     : g.merged_at
+            , created_at    : g.created_at
+            , updated_at    : g.updated_at
+        }
+    }
+}
+
+/// Records of the same person to fold into the guest merged into
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct GuestMergeSchemaIn {
+    #[schema(example = json!(["6f0043db-a538-4c9f-8ad1-f0b74fc6b499"]))]
+    pub duplicate_ids   : Vec<Uuid>
+}
+
+/// Two guests likely to be the same person
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct GuestDuplicateSchemaOut {
+    pub guest           : GuestSchemaOut
+
+    , pub duplicate     : GuestSchemaOut
+
+    , /// Average of the name similarity and the best contact match, from 0 to 1
+      #[schema(example = 0.82)]
+      pub score         : f64
+
+    , /// Trigram similarity of the full names, from 0 to 1
+      #[schema(example = 0.64)]
+      pub name_similarity   : f64
+
+    , /// Trigram similarity of the emails, from 0 to 1
+      #[schema(example = 0.85)]
+      pub email_similarity  : f64
+
+    , /// Whether the phone numbers have the same digits
+      #[schema(example = true)]
+      pub phone_match   : bool
+}
+
+/// Options of the duplicate report
+#[derive(Debug, Default)]
+pub struct GuestDuplicateFilter {
+    pub min_score   : Option<f64>
+    , pub limit     : Option<u64>
+}
+
+/// Filters shared by the guest list and export endpoints
+#[derive(Debug, Default)]
+pub struct GuestFilter {
+    pub name        : Option<String>
+    , pub email     : Option<String>
+}
@@ -1,7 +1,8 @@
 use serde::{Serialize, Deserialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, NaiveTime};
+use crate::{models::hotels, schemas::media::MediaSchemaOut};
 
 
 #[derive(Deserialize, Serialize, ToSchema)]
@@ -9,24 +10,175 @@ pub struct HotelSchemaIn {
     #[schema(example = "Grand Hotel")]
     pub name          : String
     ,
-    #[schema(example = "123 Main Street")] 
-    pub address       : String
-    , 
-    #[schema(example = 4.5)]
-    pub rating        : f64
+    /// Single line address, only used without `street`, `city` and `country`
+    #[schema(example = "123 Main Street, 10001 New York, NY, US")]
+    #[serde(default)]
+    pub address       : Option<String>
     ,
-    #[schema(example = "Luxury hotel in city center")] 
+    #[schema(example = "123 Main Street")]
+    #[serde(default)]
+    pub street        : Option<String>
+    ,
+    #[schema(example = "New York")]
+    #[serde(default)]
+    pub city          : Option<String>
+    ,
+    #[schema(example = "NY")]
+    pub region        : Option<String>
+    ,
+    #[schema(example = "10001")]
+    pub postal_code   : Option<String>
+    ,
+    /// ISO 3166-1 alpha-2 country code
+    #[schema(example = "US")]
+    #[serde(default)]
+    pub country       : Option<String>
+    ,
+    #[schema(example = 40.7506)]
+    pub latitude      : Option<f64>
+    ,
+    #[schema(example = -73.9935)]
+    pub longitude     : Option<f64>
+    ,
+    #[schema(example = "Luxury hotel in city center")]
     pub description   : Option<String>
 }
 
-#[derive(Serialize, Deserialize, ToSchema)]
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
 pub struct HotelSchemaOut {
     pub id          : Uuid
     , pub name      : String
-    , pub address   : String
-    , pub rating    : f64
+    ,
+    /// Single line address formatted from the structured fields
+    pub address     : String
+    , pub street    : Option<String>
+    , pub city      : Option<String>
+    , pub region    : Option<String>
+    , pub postal_code   : Option<String>
+    , pub country   : Option<String>
+    , pub latitude  : Option<f64>
+    , pub longitude : Option<f64>
+    ,
+    /// Distance in km from `near`, only set when searching by location
+    pub distance_km : Option<f64>
+    ,
+    /// Average score of the published reviews, empty without any
+    pub rating      : Option<f64>
+    // Zero in events recorded before reviews existed
+    , #[serde(default)]
+      pub review_count  : i32
     , pub description: Option<String>
+    // Events recorded before policies and amenities existed lack them
+    , #[serde(default)]
+      pub policies  : HotelPoliciesSchema
+    ,
+    /// Codes of the hotel's amenities
+    #[schema(example = json!(["wifi", "pool"]))]
+    #[serde(default)]
+    pub amenities   : Vec<String>
+    ,
+    /// Photo gallery in display order
+    #[serde(default)]
+    pub media       : Vec<MediaSchemaOut>
     , pub created_at    : DateTime<FixedOffset>
     , pub updated_at    : Option<DateTime<FixedOffset>>
 }
 
+impl From<hotels::Model> for HotelSchemaOut {
+    fn from(h: hotels::Model) -> Self {
+        Self {
+            id              : h.id
+            , name          : h.name
+            , address       : h.address
+            , street        : h.street
+            , city          : h.city
+            , region        : h.region
+            , postal_code   : h.postal_code
+            , country       : h.country
+            , latitude      : h.latitude
+            , longitude     : h.longitude
+            , distance_km   : None
+            , rating        : h.rating
+            , review_count  : h.review_count
+            , description   : h.description
+            , policies      : HotelPoliciesSchema {
+                check_in_time   : h.check_in_time,
+                check_out_time  : h.check_out_time,
+                timezone        : Some(h.timezone),
+                child_policy    : h.child_policy,
+                pet_policy      : h.pet_policy,
+            }
+            , amenities     : Vec::new()
+            , media         : Vec::new()
+            , created_at    : h.created_at
+            , updated_at    : h.updated_at
+        }
+    }
+}
+
+/// House rules of a hotel. Bookings given dates without times check in and
+/// out at the hotel's times.
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct HotelPoliciesSchema {
+    /// Earliest check-in, in the hotel's timezone
+    #[schema(value_type = String, example = "15:00:00")]
+    pub check_in_time   : NaiveTime,
+
+    /// Latest check-out, in the hotel's timezone
+    #[schema(value_type = String, example = "11:00:00")]
+    pub check_out_time  : NaiveTime,
+
+    /// IANA timezone of the hotel, left unchanged when omitted. New hotels
+    /// are in UTC
+    #[schema(example = "Europe/Paris")]
+    #[serde(default)]
+    pub timezone        : Option<String>,
+
+    #[schema(example = "Children under 12 stay free using existing beds")]
+    pub child_policy    : Option<String>,
+
+    #[schema(example = "Dogs up to 10 kg on request")]
+    pub pet_policy      : Option<String>,
+}
+
+/// The column defaults of a hotel
+impl Default for HotelPoliciesSchema {
+    fn default() -> Self {
+        Self {
+            check_in_time   : NaiveTime::from_hms_opt(15, 0, 0).unwrap(),
+            check_out_time  : NaiveTime::from_hms_opt(11, 0, 0).unwrap(),
+            timezone        : Some("UTC".to_string()),
+            child_policy    : None,
+            pet_policy      : None,
+        }
+    }
+}
+
+/// A point on the globe in decimal degrees
+#[derive(Debug, Clone, Copy)]
+pub struct GeoPoint {
+    pub latitude    : f64
+    , pub longitude : f64
+}
+
+/// Filters shared by the hotel list and export endpoints
+#[derive(Debug, Default)]
+pub struct HotelFilter {
+    pub name            : Option<String>
+    , pub min_rating    : Option<f64>
+    , pub city          : Option<String>
+    , pub country       : Option<String>
+    // Only hotels with coordinates, within radius_km of near when given
+    , pub near          : Option<GeoPoint>
+    , pub radius_km     : Option<f64>
+    // Codes of amenities the hotel must all have
+    , pub amenities     : Vec<String>
+}
+
+/// Only hotels with a room, of `room_type` when given, free for the stay
+#[derive(Debug)]
+pub struct HotelStayFilter {
+    pub check_in        : DateTime<FixedOffset>
+    , pub check_out     : DateTime<FixedOffset>
+    , pub room_type     : Option<String>
+}
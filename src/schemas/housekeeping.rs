@@ -0,0 +1,63 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+use crate::models::sea_orm_active_enums::HousekeepingStatus;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct HousekeepingSchemaIn {
+    #[schema(example = "Inspected")]
+    pub status      : HousekeepingStatus,
+
+    /// Free text for the next shift, cleared when omitted
+    #[schema(example = "Replace the shower curtain")]
+    pub note        : Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct HousekeepingSchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub room_id     : Uuid,
+
+    #[schema(example = "Inspected")]
+    pub status      : HousekeepingStatus,
+
+    #[schema(example = "Replace the shower curtain")]
+    pub note        : Option<String>,
+
+    #[schema(example = "2024-01-10T12:00:00+00:00")]
+    pub updated_at  : Option<DateTime<FixedOffset>>,
+}
+
+/// One room of the housekeeping task list
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct HousekeepingTask {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub room_id         : Uuid,
+
+    #[schema(example = "101")]
+    pub room_number     : String,
+
+    #[schema(example = "double")]
+    pub room_type       : String,
+
+    #[schema(example = "Dirty")]
+    pub status          : HousekeepingStatus,
+
+    #[schema(example = "Replace the shower curtain")]
+    pub note            : Option<String>,
+
+    #[schema(example = "2024-01-10T12:00:00+00:00")]
+    pub status_updated_at   : Option<DateTime<FixedOffset>>,
+
+    /// Whether a pending or confirmed stay is under way
+    #[schema(example = false)]
+    pub occupied        : bool,
+
+    /// Check-in of the next pending or confirmed booking arriving today or later
+    #[schema(example = "2024-01-11T14:00:00+00:00")]
+    pub next_arrival    : Option<DateTime<FixedOffset>>,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub next_booking_id : Option<Uuid>,
+}
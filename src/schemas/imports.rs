@@ -0,0 +1,36 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ImportRowError {
+    /// Line of the CSV file, the header being line 1
+    #[schema(example = 3)]
+    pub line        : u64,
+
+    #[schema(example = "price_per_night")]
+    pub column      : Option<String>,
+
+    #[schema(example = "price_per_night must be a non-negative number")]
+    pub message     : String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ImportReport {
+    #[schema(example = false)]
+    pub dry_run     : bool,
+
+    /// Whether the rows were written; imports are all-or-nothing
+    #[schema(example = true)]
+    pub committed   : bool,
+
+    #[schema(example = 120)]
+    pub total_rows  : usize,
+
+    #[schema(example = 120)]
+    pub valid_rows  : usize,
+
+    #[schema(example = 120)]
+    pub created     : usize,
+
+    pub errors      : Vec<ImportRowError>,
+}
@@ -0,0 +1,121 @@
+use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+use crate::models::{loyalty_entries, sea_orm_active_enums::LoyaltyEntryKind};
+
+/// Status of a guest, reached with the points earned over time whatever
+/// was redeemed since
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, ToSchema)]
+pub enum LoyaltyTier {
+    Member,
+    Silver,
+    Gold,
+    Platinum,
+}
+
+impl LoyaltyTier {
+    pub const ALL: [LoyaltyTier; 4] = [LoyaltyTier::Member, LoyaltyTier::Silver, LoyaltyTier::Gold, LoyaltyTier::Platinum];
+
+    /// Lifetime points needed to reach the tier
+    pub fn threshold(self) -> i64 {
+        match self {
+            LoyaltyTier::Member     => 0,
+            LoyaltyTier::Silver     => 1_000,
+            LoyaltyTier::Gold       => 5_000,
+            LoyaltyTier::Platinum   => 15_000,
+        }
+    }
+
+    /// Factor applied to the points earned by a stay
+    pub fn multiplier(self) -> Decimal {
+        match self {
+            LoyaltyTier::Member     => Decimal::new(100, 2),
+            LoyaltyTier::Silver     => Decimal::new(110, 2),
+            LoyaltyTier::Gold       => Decimal::new(125, 2),
+            LoyaltyTier::Platinum   => Decimal::new(150, 2),
+        }
+    }
+
+    pub fn for_points(lifetime_points: i64) -> Self {
+        Self::ALL.into_iter()
+            .rev()
+            .find(|t| lifetime_points >= t.threshold())
+            .unwrap_or(LoyaltyTier::Member)
+    }
+
+    pub fn next(self) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| *t > self)
+    }
+}
+
+/// A change of the points balance. Stays earn points once completed, taken
+/// back if they no longer are, and points redeemed on a booking come back
+/// when it is cancelled or deleted.
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct LoyaltyEntrySchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id              : Uuid,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub guest_id        : Uuid,
+
+    /// Booking the points were earned or redeemed on, unset once it is deleted
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub booking_id      : Option<Uuid>,
+
+    #[schema(example = "Earned")]
+    pub kind            : LoyaltyEntryKind,
+
+    /// Added to the balance, negative for redemptions and reversals
+    #[schema(example = 450)]
+    pub points          : i32,
+
+    #[schema(example = "2024-01-15T11:00:00+00:00")]
+    pub created_at      : DateTime<FixedOffset>,
+}
+
+impl From<loyalty_entries::Model> for LoyaltyEntrySchemaOut {
+    fn from(e: loyalty_entries::Model) -> Self {
+        Self {
+            id              : e.id,
+            guest_id        : e.guest_id,
+            booking_id      : e.booking_id,
+            kind            : e.kind,
+            points          : e.points,
+            created_at      : e.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct LoyaltyAccountSchemaOut {
+    /// Guest holding the account, the surviving guest when asked for a merged one
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub guest_id        : Uuid,
+
+    #[schema(example = "Silver")]
+    pub tier            : LoyaltyTier,
+
+    /// Points available for redemption
+    #[schema(example = 1250)]
+    pub balance         : i64,
+
+    /// Points earned over time, net of reversals, deciding the tier
+    #[schema(example = 1800)]
+    pub lifetime_points : i64,
+
+    #[schema(example = "Gold")]
+    pub next_tier       : Option<LoyaltyTier>,
+
+    #[schema(example = 3200)]
+    pub points_to_next_tier : Option<i64>,
+
+    /// Discount bought by one redeemed point
+    #[schema(value_type = f64, example = 0.01)]
+    pub point_value     : Decimal,
+
+    /// Newest first
+    pub entries         : Vec<LoyaltyEntrySchemaOut>,
+}
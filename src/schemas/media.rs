@@ -0,0 +1,127 @@
+use rocket::{FromForm, fs::TempFile};
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+use crate::models::media;
+
+/// Path the media files are served under
+const MEDIA_PATH: &str = "/api/v1/media";
+
+/// Gallery a media belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum MediaOwner {
+    Hotel(Uuid)
+    , Room(Uuid)
+}
+
+impl MediaOwner {
+    /// Storage key prefix of the files of the gallery
+    pub fn prefix(&self) -> String {
+        match self {
+            MediaOwner::Hotel(id)   => format!("hotels/{}", id),
+            MediaOwner::Room(id)    => format!("rooms/{}", id),
+        }
+    }
+}
+
+/// Multipart form of an upload
+#[derive(FromForm, ToSchema)]
+pub struct MediaUploadForm<'r> {
+    /// JPEG, PNG or WebP image
+    #[schema(value_type = String, format = Binary)]
+    pub file            : TempFile<'r>,
+
+    #[schema(example = "Sea view from the terrace")]
+    pub caption         : Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct MediaUpdateSchemaIn {
+    #[schema(example = "Sea view from the terrace")]
+    pub caption         : Option<String>,
+}
+
+/// New order of a gallery
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct MediaOrderSchemaIn {
+    /// Every media of the gallery, first shown first
+    #[schema(example = json!(["550e8400-e29b-41d4-a716-446655440000"]))]
+    pub media_ids       : Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct MediaSchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id              : Uuid,
+
+    /// Hotel of a hotel photo, empty for room photos
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub hotel_id        : Option<Uuid>,
+
+    /// Room of a room photo, empty for hotel photos
+    #[schema(example = json!(null))]
+    pub room_id         : Option<Uuid>,
+
+    #[schema(example = "/api/v1/media/550e8400-e29b-41d4-a716-446655440000/file")]
+    pub url             : String,
+
+    #[schema(example = "/api/v1/media/550e8400-e29b-41d4-a716-446655440000/thumbnail")]
+    pub thumbnail_url   : String,
+
+    #[schema(example = "image/jpeg")]
+    pub content_type    : String,
+
+    #[schema(example = 482133)]
+    pub size_bytes      : i64,
+
+    #[schema(example = 1920)]
+    pub width           : i32,
+
+    #[schema(example = 1280)]
+    pub height          : i32,
+
+    #[schema(example = "Sea view from the terrace")]
+    pub caption         : Option<String>,
+
+    /// Place in the gallery, lowest first
+    #[schema(example = 0)]
+    pub position        : i32,
+
+    /// Whether this is the main image of the gallery
+    #[schema(example = true)]
+    pub is_cover        : bool,
+
+    #[schema(example = "2024-01-01T12:00:00+00:00")]
+    pub created_at      : DateTime<FixedOffset>,
+
+    #[schema(example = "2024-01-05T09:30:00+00:00")]
+    pub updated_at      : Option<DateTime<FixedOffset>>,
+}
+
+impl From<media::Model> for MediaSchemaOut {
+    fn from(m: media::Model) -> Self {
+        Self {
+            id              : m.id,
+            hotel_id        : m.hotel_id,
+            room_id         : m.room_id,
+            url             : format!("{}/{}/file", MEDIA_PATH, m.id),
+            thumbnail_url   : format!("{}/{}/thumbnail", MEDIA_PATH, m.id),
+            content_type    : m.content_type,
+            size_bytes      : m.size_bytes,
+            width           : m.width,
+            height          : m.height,
+            caption         : m.caption,
+            position        : m.position,
+            is_cover        : m.is_cover,
+            created_at      : m.created_at,
+            updated_at      : m.updated_at,
+        }
+    }
+}
+
+/// Contents of a stored media file
+pub struct MediaFile {
+    pub content_type    : String
+    , pub bytes         : Vec<u8>
+}
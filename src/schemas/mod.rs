@@ -1,4 +1,23 @@
 pub mod hotels;
+pub mod amenities;
 pub mod guests;
 pub mod rooms;
-pub mod booking;
\ No newline at end of file
+pub mod booking;
+pub mod webhooks;
+pub mod notifications;
+pub mod external_calendars;
+pub mod exports;
+pub mod imports;
+pub mod reports;
+pub mod calendar;
+pub mod room_blocks;
+pub mod housekeeping;
+pub mod reservations;
+pub mod waitlist;
+pub mod reviews;
+pub mod search;
+pub mod media;
+pub mod audit;
+pub mod privacy;
+pub mod loyalty;
+pub mod promotions;
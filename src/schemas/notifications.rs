@@ -0,0 +1,54 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+use crate::models::{notifications, sea_orm_active_enums::{NotificationKind, NotificationStatus}};
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct NotificationSchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id              : Uuid
+
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      pub booking_id    : Option<Uuid>
+
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      pub waitlist_entry_id : Option<Uuid>
+
+    , #[schema(example = "confirmation")]
+      pub kind          : NotificationKind
+
+    , #[schema(example = "john.doe@example.com")]
+      pub recipient     : String
+
+    , #[schema(example = "sent")]
+      pub status        : NotificationStatus
+
+    , #[schema(example = 1)]
+      pub attempts      : i32
+
+    , pub last_error    : Option<String>
+
+    , #[schema(example = "2024-01-10T12:00:05+00:00")]
+      pub sent_at       : Option<DateTime<FixedOffset>>
+
+    , #[schema(example = "2024-01-10T12:00:00+00:00")]
+      pub created_at    : DateTime<FixedOffset>
+}
+
+impl From<notifications::Model> for NotificationSchemaOut {
+    fn from(n: notifications::Model) -> Self {
+        Self {
+            id              : n.id
+            , booking_id    : n.booking_id
+            , waitlist_entry_id : n.waitlist_entry_id
+            , kind          : n.kind
+            , recipient     : n.recipient
+            , status        : n.status
+            , attempts      : n.attempts
+            , last_error    : n.last_error
+            , sent_at       : n.sent_at
+            , created_at    : n.created_at
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use chrono::{DateTime, FixedOffset};
+use crate::schemas::{
+    booking::BookingSchemaOut,
+    guests::GuestSchemaOut,
+    loyalty::LoyaltyEntrySchemaOut,
+    notifications::NotificationSchemaOut,
+    reservations::ReservationSchemaOut,
+    reviews::ReviewSchemaOut,
+    waitlist::WaitlistEntrySchemaOut,
+};
+
+/// Everything stored about a guest, answering a data subject access request
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct GuestDataExport {
+    #[schema(example = "2024-01-10T12:00:00+00:00")]
+    pub exported_at     : DateTime<FixedOffset>,
+
+    /// The profile including its sensitive data
+    pub guest           : GuestSchemaOut,
+
+    /// Duplicate profiles merged into the guest
+    pub merged_guests   : Vec<GuestSchemaOut>,
+
+    /// Bookings with the price charged for each and its discounts. No payment
+    /// transactions are recorded, so these prices stand in for the payments.
+    pub bookings        : Vec<BookingSchemaOut>,
+
+    pub reservations    : Vec<ReservationSchemaOut>,
+
+    pub waitlist_entries    : Vec<WaitlistEntrySchemaOut>,
+
+    pub reviews         : Vec<ReviewSchemaOut>,
+
+    /// The points ledger
+    pub loyalty_entries : Vec<LoyaltyEntrySchemaOut>,
+
+    /// Emails sent or queued for the bookings and waitlist entries
+    pub notifications   : Vec<NotificationSchemaOut>,
+}
@@ -0,0 +1,142 @@
+use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+use crate::models::{promotions, sea_orm_active_enums::DiscountKind};
+
+/// A campaign code giving a discount on the bookings it applies to
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PromotionSchemaIn {
+    /// Code guests enter, letters, digits, `-` and `_`, matched case-insensitively
+    #[schema(example = "SUMMER25")]
+    pub code            : String,
+
+    #[schema(example = "Summer sale")]
+    pub name            : String,
+
+    #[schema(example = "Percent")]
+    pub discount_kind   : DiscountKind,
+
+    /// Percentage from 0 to 100, or amount taken off the price
+    #[schema(value_type = f64, example = 25)]
+    pub discount_value  : Decimal,
+
+    /// Only bookings at this hotel, any hotel when unset
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    #[serde(default)]
+    pub hotel_id        : Option<Uuid>,
+
+    /// Only bookings of this room type, any type when unset
+    #[schema(example = "double")]
+    #[serde(default)]
+    pub room_type       : Option<String>,
+
+    #[schema(example = 3)]
+    #[serde(default)]
+    pub min_nights      : Option<i32>,
+
+    /// When the code can first be used
+    #[schema(example = "2024-06-01T00:00:00+00:00")]
+    #[serde(default)]
+    pub starts_at       : Option<DateTime<FixedOffset>>,
+
+    /// When the code stops working
+    #[schema(example = "2024-09-01T00:00:00+00:00")]
+    #[serde(default)]
+    pub ends_at         : Option<DateTime<FixedOffset>>,
+
+    /// Bookings not cancelled that may use the code
+    #[schema(example = 100)]
+    #[serde(default)]
+    pub max_redemptions : Option<i32>,
+
+    #[schema(example = 1)]
+    #[serde(default)]
+    pub max_redemptions_per_guest   : Option<i32>,
+
+    #[schema(example = true)]
+    pub is_active       : bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct PromotionSchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id              : Uuid,
+
+    #[schema(example = "SUMMER25")]
+    pub code            : String,
+
+    #[schema(example = "Summer sale")]
+    pub name            : String,
+
+    #[schema(example = "Percent")]
+    pub discount_kind   : DiscountKind,
+
+    #[schema(value_type = f64, example = 25)]
+    pub discount_value  : Decimal,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub hotel_id        : Option<Uuid>,
+
+    #[schema(example = "double")]
+    pub room_type       : Option<String>,
+
+    #[schema(example = 3)]
+    pub min_nights      : Option<i32>,
+
+    #[schema(example = "2024-06-01T00:00:00+00:00")]
+    pub starts_at       : Option<DateTime<FixedOffset>>,
+
+    #[schema(example = "2024-09-01T00:00:00+00:00")]
+    pub ends_at         : Option<DateTime<FixedOffset>>,
+
+    #[schema(example = 100)]
+    pub max_redemptions : Option<i32>,
+
+    #[schema(example = 1)]
+    pub max_redemptions_per_guest   : Option<i32>,
+
+    /// Bookings not cancelled made with the code
+    #[schema(example = 42)]
+    pub redemption_count    : i64,
+
+    #[schema(example = true)]
+    pub is_active       : bool,
+
+    #[schema(example = "2024-05-20T12:00:00+00:00")]
+    pub created_at      : DateTime<FixedOffset>,
+
+    #[schema(example = "2024-05-21T09:30:00+00:00")]
+    pub updated_at      : Option<DateTime<FixedOffset>>,
+}
+
+impl PromotionSchemaOut {
+    pub fn new(p: promotions::Model, redemption_count: i64) -> Self {
+        Self {
+            id                  : p.id,
+            code                : p.code,
+            name                : p.name,
+            discount_kind       : p.discount_kind,
+            discount_value      : p.discount_value,
+            hotel_id            : p.hotel_id,
+            room_type           : p.room_type,
+            min_nights          : p.min_nights,
+            starts_at           : p.starts_at,
+            ends_at             : p.ends_at,
+            max_redemptions     : p.max_redemptions,
+            max_redemptions_per_guest   : p.max_redemptions_per_guest,
+            redemption_count,
+            is_active           : p.is_active,
+            created_at          : p.created_at,
+            updated_at          : p.updated_at,
+        }
+    }
+}
+
+/// Filters of the promotion list
+#[derive(Debug, Default)]
+pub struct PromotionFilter {
+    pub hotel_id    : Option<Uuid>
+    , pub is_active : Option<bool>
+}
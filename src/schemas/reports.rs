@@ -0,0 +1,91 @@
+use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::NaiveDate;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    Day,
+    Week,
+    Month,
+}
+
+impl Granularity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Granularity::Day    => "day",
+            Granularity::Week   => "week",
+            Granularity::Month  => "month",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "day"   => Some(Granularity::Day),
+            "week"  => Some(Granularity::Week),
+            "month" => Some(Granularity::Month),
+            _       => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct OccupancyPoint {
+    /// First night of the period
+    #[schema(example = "2024-01-01")]
+    pub period_start            : NaiveDate,
+
+    /// Day after the last night of the period
+    #[schema(example = "2024-01-08")]
+    pub period_end              : NaiveDate,
+
+    #[schema(example = 140)]
+    pub available_room_nights   : i64,
+
+    #[schema(example = 98)]
+    pub sold_room_nights        : i64,
+
+    /// Sold / available room nights
+    #[schema(example = 0.7)]
+    pub occupancy_rate          : f64,
+
+    /// Room revenue of the nights in the period
+    #[schema(value_type = f64, example = 11760.0)]
+    pub revenue                 : Decimal,
+
+    /// Average daily rate: revenue / sold room nights
+    #[schema(value_type = f64, example = 120.0)]
+    pub adr                     : Decimal,
+
+    /// Revenue per available room: revenue / available room nights
+    #[schema(value_type = f64, example = 84.0)]
+    pub revpar                  : Decimal,
+
+    /// Cancelled bookings whose check-in falls in the period
+    #[schema(example = 3)]
+    pub cancellations           : i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct OccupancyReport {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub hotel_id    : Uuid,
+
+    #[schema(example = "2024-01-01")]
+    pub from        : NaiveDate,
+
+    #[schema(example = "2024-02-01")]
+    pub to          : NaiveDate,
+
+    pub granularity : Granularity,
+
+    #[schema(example = 20)]
+    pub room_count  : i64,
+
+    /// The whole range as a single period
+    pub totals      : OccupancyPoint,
+
+    pub series      : Vec<OccupancyPoint>,
+}
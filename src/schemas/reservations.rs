@@ -0,0 +1,90 @@
+use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+use crate::{models::sea_orm_active_enums::BookingStatus, schemas::booking::{BookingSchemaOut, StayDateTime}};
+
+/// One room of a group reservation, booked like a single booking
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ReservationRoomSchemaIn {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub room_id         : Option<Uuid>,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub hotel_id        : Option<Uuid>,
+
+    #[schema(example = "double")]
+    pub room_type       : Option<String>,
+
+    /// Guest staying in the room, the reservation's guest when omitted
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub guest_id        : Option<Uuid>,
+
+    #[schema(value_type = f64, example = 199.99)]
+    pub total_price     : Decimal,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ReservationSchemaIn {
+    /// Guest the reservation is made by
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub guest_id        : Uuid,
+
+    #[schema(example = "Smith wedding")]
+    pub name            : Option<String>,
+
+    /// Timestamp, or date at the hotel's check-in time
+    #[schema(value_type = String, example = "2024-01-10")]
+    pub check_in_date   : StayDateTime,
+
+    /// Timestamp, or date at the hotel's check-out time
+    #[schema(value_type = String, example = "2024-01-15")]
+    pub check_out_date  : StayDateTime,
+
+    #[schema(example = "Pending")]
+    pub status          : BookingStatus,
+
+    pub rooms           : Vec<ReservationRoomSchemaIn>,
+}
+
+/// Changes applied to every pending or confirmed booking of a reservation,
+/// omitted fields are left unchanged
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ReservationUpdateSchemaIn {
+    #[schema(example = "Smith wedding")]
+    pub name            : Option<String>,
+
+    #[schema(value_type = Option<String>, example = "2024-01-11T14:00:00+00:00")]
+    pub check_in_date   : Option<StayDateTime>,
+
+    #[schema(value_type = Option<String>, example = "2024-01-16")]
+    pub check_out_date  : Option<StayDateTime>,
+
+    #[schema(example = "Confirmed")]
+    pub status          : Option<BookingStatus>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct ReservationSchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id              : Uuid,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub guest_id        : Uuid,
+
+    #[schema(example = "Smith wedding")]
+    pub name            : Option<String>,
+
+    /// Sum of the total prices of the bookings not cancelled
+    #[schema(value_type = f64, example = 2399.88)]
+    pub total_price     : Decimal,
+
+    pub bookings        : Vec<BookingSchemaOut>,
+
+    #[schema(example = "2024-01-10T12:00:00+00:00")]
+    pub created_at      : DateTime<FixedOffset>,
+
+    #[schema(example = "2024-01-10T15:30:00+00:00")]
+    pub updated_at      : Option<DateTime<FixedOffset>>,
+}
@@ -0,0 +1,154 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+use crate::models::{reviews, sea_orm_active_enums::ReviewStatus};
+
+/// Scores go from 1 to 5
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ReviewSchemaIn {
+    /// Completed booking the review is about
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub booking_id      : Uuid,
+
+    /// Overall score, averaged into the hotel rating
+    #[schema(example = 5)]
+    pub rating          : i16,
+
+    #[schema(example = 4)]
+    pub cleanliness     : i16,
+
+    #[schema(example = 5)]
+    pub location        : i16,
+
+    #[schema(example = 4)]
+    pub service         : i16,
+
+    #[schema(example = "Lovely sea view, breakfast could be better")]
+    pub comment         : Option<String>,
+}
+
+/// New scores and comment of a review, which goes back to moderation
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ReviewUpdateSchemaIn {
+    #[schema(example = 4)]
+    pub rating          : i16,
+
+    #[schema(example = 4)]
+    pub cleanliness     : i16,
+
+    #[schema(example = 5)]
+    pub location        : i16,
+
+    #[schema(example = 3)]
+    pub service         : i16,
+
+    #[schema(example = "Lovely sea view, breakfast could be better")]
+    pub comment         : Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ReviewModerationSchemaIn {
+    #[schema(example = "Published")]
+    pub status          : ReviewStatus,
+
+    /// Reason given to the guest, typically for a rejection
+    #[schema(example = "Contains personal data")]
+    pub note            : Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct ReviewSchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id              : Uuid,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub booking_id      : Uuid,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub hotel_id        : Uuid,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub guest_id        : Uuid,
+
+    #[schema(example = 5)]
+    pub rating          : i16,
+
+    #[schema(example = 4)]
+    pub cleanliness     : i16,
+
+    #[schema(example = 5)]
+    pub location        : i16,
+
+    #[schema(example = 4)]
+    pub service         : i16,
+
+    #[schema(example = "Lovely sea view, breakfast could be better")]
+    pub comment         : Option<String>,
+
+    #[schema(example = "Published")]
+    pub status          : ReviewStatus,
+
+    #[schema(example = "Contains personal data")]
+    pub moderation_note : Option<String>,
+
+    #[schema(example = "2024-01-20T09:30:00+00:00")]
+    pub moderated_at    : Option<DateTime<FixedOffset>>,
+
+    #[schema(example = "2024-01-18T12:00:00+00:00")]
+    pub created_at      : DateTime<FixedOffset>,
+
+    #[schema(example = "2024-01-20T09:30:00+00:00")]
+    pub updated_at      : Option<DateTime<FixedOffset>>,
+}
+
+impl From<reviews::Model> for ReviewSchemaOut {
+    fn from(r: reviews::Model) -> Self {
+        Self {
+            id              : r.id,
+            booking_id      : r.booking_id,
+            hotel_id        : r.hotel_id,
+            guest_id        : r.guest_id,
+            rating          : r.rating,
+            cleanliness     : r.cleanliness,
+            location        : r.location,
+            service         : r.service,
+            comment         : r.comment,
+            status          : r.status,
+            moderation_note : r.moderation_note,
+            moderated_at    : r.moderated_at,
+            created_at      : r.created_at,
+            updated_at      : r.updated_at,
+        }
+    }
+}
+
+/// Average scores of the published reviews of a hotel, empty without any
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ReviewSummary {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub hotel_id        : Uuid,
+
+    #[schema(example = 12)]
+    pub review_count    : i64,
+
+    #[schema(example = 4.42)]
+    pub rating          : Option<f64>,
+
+    #[schema(example = 4.5)]
+    pub cleanliness     : Option<f64>,
+
+    #[schema(example = 4.83)]
+    pub location        : Option<f64>,
+
+    #[schema(example = 4.08)]
+    pub service         : Option<f64>,
+}
+
+/// Filters of the review listing
+#[derive(Debug, Default)]
+pub struct ReviewFilter {
+    pub hotel_id    : Option<Uuid>
+    , pub guest_id  : Option<Uuid>
+    , pub status    : Option<ReviewStatus>
+}
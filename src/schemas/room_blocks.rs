@@ -0,0 +1,47 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use crate::models::sea_orm_active_enums::RoomBlockKind;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct RoomBlockSchemaIn {
+    #[schema(example = "Maintenance")]
+    pub kind            : RoomBlockKind
+
+    , #[schema(example = "2024-03-03")]
+      pub start_date    : NaiveDate
+
+    , #[schema(example = "2024-03-07")]
+      pub end_date      : NaiveDate
+
+    , #[schema(example = "Repainting")]
+      pub reason        : String
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct RoomBlockSchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id              : Uuid
+
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      pub room_id       : Uuid
+
+    , #[schema(example = "Maintenance")]
+      pub kind          : RoomBlockKind
+
+    , #[schema(example = "2024-03-03")]
+      pub start_date    : NaiveDate
+
+    , #[schema(example = "2024-03-07")]
+      pub end_date      : NaiveDate
+
+    , #[schema(example = "Repainting")]
+      pub reason        : String
+
+    , #[schema(example = "2024-01-10T12:00:00+00:00")]
+      pub created_at    : DateTime<FixedOffset>
+
+    , #[schema(example = "2024-01-10T12:00:00+00:00")]
+      pub updated_at    : Option<DateTime<FixedOffset>>
+}
@@ -3,6 +3,7 @@ use serde::{Serialize, Deserialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 use chrono::{DateTime, FixedOffset};
+use crate::{models::sea_orm_active_enums::HousekeepingStatus, schemas::media::MediaSchemaOut};
 
 #[derive(Deserialize, Serialize, ToSchema)]
 pub struct RoomSchemaIn {
@@ -20,9 +21,12 @@ pub struct RoomSchemaIn {
 
     #[schema(example = true)]
     pub is_available: bool,
+
+    #[schema(example = "Sea view, king size bed and a private spa bath")]
+    pub description: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, ToSchema)]
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
 pub struct RoomSchemaOut {
     #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
     pub id: Uuid,
@@ -42,9 +46,55 @@ pub struct RoomSchemaOut {
     #[schema(example = true)]
     pub is_available: bool,
 
+    #[schema(example = "Sea view, king size bed and a private spa bath")]
+    pub description: Option<String>,
+
     #[schema(example = "2024-01-10T12:00:00+00:00")]
     pub created_at: DateTime<FixedOffset>,
 
     #[schema(example = "2024-01-10T15:30:00+00:00")]
     pub updated_at: Option<DateTime<FixedOffset>>,
+
+    // Defaults for events recorded before housekeeping was tracked
+    #[schema(example = "Clean")]
+    #[serde(default)]
+    pub housekeeping_status: HousekeepingStatus,
+
+    /// Photo gallery in display order
+    #[serde(default)]
+    pub media: Vec<MediaSchemaOut>,
+}
+
+impl From<crate::models::rooms::Model> for RoomSchemaOut {
+    fn from(r: crate::models::rooms::Model) -> Self {
+        Self {
+            id                  : r.id
+            , hotel_id          : r.hotel_id
+            , room_number       : r.room_number
+            , room_type         : r.room_type
+            , price_per_night   : r.price_per_night
+            , is_available      : r.is_available
+            , description       : r.description
+            , created_at        : r.created_at
+            , updated_at        : r.updated_at
+            , housekeeping_status   : r.housekeeping_status
+            , media             : Vec::new()
+        }
+    }
+}
+
+/// Filters of the room availability search
+pub struct RoomAvailabilityQuery {
+    pub check_in        : DateTime<FixedOffset>
+    , pub check_out     : DateTime<FixedOffset>
+    , pub hotel_id      : Option<Uuid>
+    , pub room_type     : Option<String>
+}
+
+/// Filters shared by the room list and export endpoints
+#[derive(Debug, Default)]
+pub struct RoomFilter {
+    pub hotel_id        : Option<Uuid>
+    , pub room_type     : Option<String>
+    , pub is_available  : Option<bool>
 }
\ No newline at end of file
@@ -0,0 +1,59 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+pub enum SearchHitKind {
+    Hotel,
+    RoomType,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct SearchHit {
+    #[schema(example = "RoomType")]
+    pub kind            : SearchHitKind,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub hotel_id        : Uuid,
+
+    #[schema(example = "Grand Hotel")]
+    pub hotel_name      : String,
+
+    /// Matched room type, empty for hotel hits
+    #[schema(example = "Suite")]
+    pub room_type       : Option<String>,
+
+    /// Relevance, higher first
+    #[schema(example = 0.6079271)]
+    pub rank            : f32,
+
+    /// Matching fragments of the text, HTML escaped, with the terms wrapped in `<mark>`
+    #[schema(example = "Suite · <mark>Sea</mark> <mark>view</mark>, king size bed and a private <mark>spa</mark> bath")]
+    pub snippet         : String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct SearchResults {
+    #[schema(example = "sea view spa")]
+    pub query           : String,
+
+    #[schema(example = 1)]
+    pub page            : u64,
+
+    #[schema(example = 20)]
+    pub per_page        : u64,
+
+    /// Hits over all pages
+    #[schema(example = 1)]
+    pub total           : i64,
+
+    pub results         : Vec<SearchHit>,
+}
+
+/// Search terms and page of the full text search
+#[derive(Debug)]
+pub struct SearchQuery {
+    pub q               : String
+    , pub page          : Option<u64>
+    , pub per_page      : Option<u64>
+}
@@ -0,0 +1,72 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+use crate::models::sea_orm_active_enums::WaitlistStatus;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct WaitlistEntrySchemaIn {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub guest_id        : Uuid,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub hotel_id        : Uuid,
+
+    #[schema(example = "double")]
+    pub room_type       : String,
+
+    #[schema(example = "2024-01-10T14:00:00+00:00")]
+    pub check_in_date   : DateTime<FixedOffset>,
+
+    #[schema(example = "2024-01-15T11:00:00+00:00")]
+    pub check_out_date  : DateTime<FixedOffset>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct WaitlistEntrySchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id              : Uuid,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub guest_id        : Uuid,
+
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub hotel_id        : Uuid,
+
+    #[schema(example = "double")]
+    pub room_type       : String,
+
+    #[schema(example = "2024-01-10T14:00:00+00:00")]
+    pub check_in_date   : DateTime<FixedOffset>,
+
+    #[schema(example = "2024-01-15T11:00:00+00:00")]
+    pub check_out_date  : DateTime<FixedOffset>,
+
+    #[schema(example = "Offered")]
+    pub status          : WaitlistStatus,
+
+    /// Room of the cancelled booking, when it is free for the whole stay
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub offered_room_id : Option<Uuid>,
+
+    /// Cancelled booking that freed the offered room
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub offered_booking_id: Option<Uuid>,
+
+    #[schema(example = "2024-01-05T09:30:00+00:00")]
+    pub offered_at      : Option<DateTime<FixedOffset>>,
+
+    #[schema(example = "2024-01-01T12:00:00+00:00")]
+    pub created_at      : DateTime<FixedOffset>,
+
+    #[schema(example = "2024-01-05T09:30:00+00:00")]
+    pub updated_at      : Option<DateTime<FixedOffset>>,
+}
+
+/// Filters of the waitlist listing
+#[derive(Debug, Default)]
+pub struct WaitlistFilter {
+    pub hotel_id    : Option<Uuid>
+    , pub guest_id  : Option<Uuid>
+    , pub status    : Option<WaitlistStatus>
+}
@@ -0,0 +1,109 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use chrono::{DateTime, FixedOffset};
+use crate::models::sea_orm_active_enums::WebhookDeliveryStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+pub enum WebhookEvent {
+    #[serde(rename = "booking.created")]
+    BookingCreated
+    , #[serde(rename = "booking.confirmed")]
+      BookingConfirmed
+    , #[serde(rename = "booking.cancelled")]
+      BookingCancelled
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::BookingCreated    => "booking.created",
+            WebhookEvent::BookingConfirmed  => "booking.confirmed",
+            WebhookEvent::BookingCancelled  => "booking.cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "booking.created"   => Some(WebhookEvent::BookingCreated),
+            "booking.confirmed" => Some(WebhookEvent::BookingConfirmed),
+            "booking.cancelled" => Some(WebhookEvent::BookingCancelled),
+            _                   => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct WebhookSubscriptionSchemaIn {
+    #[schema(example = "https://crm.example.com/hooks/bookings")]
+    pub url             : String
+
+    , #[schema(example = "whsec_4f1c2b7e9a0d4c3b8e6f5a2d1c0b9a8e")]
+      pub secret        : Option<String>
+
+    , #[schema(example = json!(["booking.created", "booking.cancelled"]))]
+      pub events        : Vec<WebhookEvent>
+
+    , #[schema(example = true)]
+      pub is_active     : bool
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct WebhookSubscriptionSchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id              : Uuid
+
+    , #[schema(example = "https://crm.example.com/hooks/bookings")]
+      pub url           : String
+
+    , /// Signing secret, only returned when the subscription is created
+      #[schema(example = "whsec_4f1c2b7e9a0d4c3b8e6f5a2d1c0b9a8e")]
+      #[serde(default, skip_serializing_if = "Option::is_none")]
+      pub secret        : Option<String>
+
+    , #[schema(example = json!(["booking.created", "booking.cancelled"]))]
+      pub events        : Vec<WebhookEvent>
+
+    , #[schema(example = true)]
+      pub is_active     : bool
+
+    , #[schema(example = "2024-01-10T12:00:00+00:00")]
+      pub created_at    : DateTime<FixedOffset>
+
+    , pub updated_at    : Option<DateTime<FixedOffset>>
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct WebhookDeliverySchemaOut {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id                  : Uuid
+
+    , #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+      pub subscription_id   : Uuid
+
+    , #[schema(example = "booking.created")]
+      pub event_type        : String
+
+    , #[schema(value_type = Object)]
+      pub payload           : serde_json::Value
+
+    , #[schema(example = "delivered")]
+      pub status            : WebhookDeliveryStatus
+
+    , #[schema(example = 1)]
+      pub attempts          : i32
+
+    , #[schema(example = 200)]
+      pub response_status   : Option<i32>
+
+    , pub last_error        : Option<String>
+
+    , #[schema(example = "2024-01-10T12:00:30+00:00")]
+      pub next_attempt_at   : DateTime<FixedOffset>
+
+    , #[schema(example = "2024-01-10T12:00:01+00:00")]
+      pub delivered_at      : Option<DateTime<FixedOffset>>
+
+    , #[schema(example = "2024-01-10T12:00:00+00:00")]
+      pub created_at        : DateTime<FixedOffset>
+}
@@ -0,0 +1,334 @@
+use std::collections::BTreeSet;
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use crate::{
+    events::{self, DomainEvent},
+    models::{amenities, hotel_amenities, hotels, room_type_amenities, rooms},
+    schemas::{amenities::*, hotels::HotelSchemaOut},
+    services::{hotels::HotelService, media::MediaService, traits::AmenityServiceTrait},
+    error::ApiError,
+};
+
+const MAX_CODE_CHARS: usize = 50;
+
+#[derive(Clone)]
+pub struct AmenityService {
+    db  : DatabaseConnection
+}
+
+impl AmenityService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Lowercased `code`, if it is a valid amenity code
+    fn clean_code(code: &str) -> Result<String, ApiError> {
+        let code = code.trim().to_lowercase();
+
+        if code.is_empty() || code.chars().count() > MAX_CODE_CHARS {
+            return Err(ApiError::Validation(format!("code must be 1 to {} characters", MAX_CODE_CHARS)));
+        }
+
+        if !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(ApiError::Validation("code may only contain letters, digits, - and _".to_string()));
+        }
+
+        Ok(code)
+    }
+
+    /// Validates `req`, rejecting a code taken by another amenity
+    async fn check<C: ConnectionTrait>(conn: &C, req: AmenitySchemaIn, id: Option<Uuid>) -> Result<(String, String), ApiError> {
+        let code = Self::clean_code(&req.code)?;
+        let name = req.name.trim().to_string();
+
+        if name.is_empty() {
+            return Err(ApiError::Validation("name must not be empty".to_string()));
+        }
+
+        let mut taken = amenities::Entity::find().filter(amenities::Column::Code.eq(code.as_str()));
+
+        if let Some(id) = id {
+            taken = taken.filter(amenities::Column::Id.ne(id));
+        }
+
+        if taken.one(conn).await?.is_some() {
+            return Err(ApiError::Validation(format!("Amenity {} already exists", code)));
+        }
+
+        Ok((code, name))
+    }
+
+    /// Maps a unique violation on `code`, by a concurrent write that passed
+    /// `check` too, to the same error `check` gives
+    fn code_taken(err: DbErr, code: &str) -> ApiError {
+        match err.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => {
+                ApiError::Validation(format!("Amenity {} already exists", code))
+            }
+            _ => err.into(),
+        }
+    }
+
+    /// Catalogue amenities of `codes`, rejecting unknown ones
+    async fn resolve<C: ConnectionTrait>(conn: &C, codes: &[String]) -> Result<Vec<amenities::Model>, ApiError> {
+        let codes = codes
+            .iter()
+            .map(|c| c.trim().to_lowercase())
+            .collect::<BTreeSet<_>>();
+
+        let found = amenities::Entity::find()
+            .filter(amenities::Column::Code.is_in(codes.iter().cloned()))
+            .order_by_asc(amenities::Column::Code)
+            .all(conn)
+            .await?;
+
+        let unknown = codes
+            .iter()
+            .filter(|c| !found.iter().any(|a| &a.code == *c))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !unknown.is_empty() {
+            return Err(ApiError::Validation(format!("Unknown amenities: {}", unknown.join(", "))));
+        }
+
+        Ok(found)
+    }
+
+    async fn hotel_amenities<C: ConnectionTrait>(conn: &C, hotel_id: Uuid) -> Result<Vec<AmenitySchemaOut>, DbErr> {
+        let res = amenities::Entity::find()
+            .inner_join(hotel_amenities::Entity)
+            .filter(hotel_amenities::Column::HotelId.eq(hotel_id))
+            .order_by_asc(amenities::Column::Code)
+            .all(conn)
+            .await?;
+
+        Ok(res.into_iter().map(AmenitySchemaOut::from).collect())
+    }
+
+    async fn room_type_amenities<C: ConnectionTrait>(
+        conn            : &C
+        , hotel_id      : Uuid
+        , room_type     : &str
+    ) -> Result<RoomTypeAmenitiesSchemaOut, DbErr> {
+        let res = amenities::Entity::find()
+            .inner_join(room_type_amenities::Entity)
+            .filter(room_type_amenities::Column::HotelId.eq(hotel_id))
+            .filter(room_type_amenities::Column::RoomType.eq(room_type))
+            .order_by_asc(amenities::Column::Code)
+            .all(conn)
+            .await?;
+
+        Ok(RoomTypeAmenitiesSchemaOut {
+            hotel_id,
+            room_type   : room_type.to_string(),
+            amenities   : res.into_iter().map(AmenitySchemaOut::from).collect(),
+        })
+    }
+}
+
+#[async_trait]
+impl AmenityServiceTrait for AmenityService {
+    async fn list_amenities(&self) -> Result<Vec<AmenitySchemaOut>, ApiError> {
+        let res = amenities::Entity::find()
+            .order_by_asc(amenities::Column::Code)
+            .all(&self.db)
+            .await?;
+
+        Ok(res.into_iter().map(AmenitySchemaOut::from).collect())
+    }
+
+    async fn get_amenity(&self, id: Uuid) -> Result<Option<AmenitySchemaOut>, ApiError> {
+        let res = amenities::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?;
+
+        Ok(res.map(AmenitySchemaOut::from))
+    }
+
+    async fn create_amenity(&self, req: AmenitySchemaIn) -> Result<AmenitySchemaOut, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let txn = self.db.begin().await?;
+
+        let (code, name) = Self::check(&txn, req, None).await?;
+
+        let amenity = amenities::ActiveModel {
+            id              : Set(Uuid::new_v4())
+            , code          : Set(code.clone())
+            , name          : Set(name)
+            , created_at    : Set(now)
+            , updated_at    : Set(None)
+        };
+
+        let amenity = amenity.insert(&txn).await.map_err(|err| Self::code_taken(err, &code))?;
+        let amenity = AmenitySchemaOut::from(amenity);
+
+        events::record(&txn, DomainEvent::AmenityCreated(amenity.clone())).await?;
+        txn.commit().await?;
+
+        Ok(amenity)
+    }
+
+    async fn update_amenity(&self, id: Uuid, req: AmenitySchemaIn) -> Result<Option<AmenitySchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let txn = self.db.begin().await?;
+
+        let Some(amenity) = amenities::Entity::find_by_id(id).one(&txn).await? else {
+            return Ok(None);
+        };
+
+        let (code, name) = Self::check(&txn, req, Some(id)).await?;
+
+        let mut amenity: amenities::ActiveModel = amenity.into();
+
+        amenity.code        = Set(code.clone());
+        amenity.name        = Set(name);
+        amenity.updated_at  = Set(Some(now));
+
+        let amenity = amenity.update(&txn).await.map_err(|err| Self::code_taken(err, &code))?;
+        let amenity = AmenitySchemaOut::from(amenity);
+
+        events::record(&txn, DomainEvent::AmenityUpdated(amenity.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(amenity))
+    }
+
+    async fn delete_amenity(&self, id: Uuid) -> Result<bool, ApiError> {
+        let txn = self.db.begin().await?;
+
+        // Links to hotels and room types go with it
+        let res = amenities::Entity::delete_by_id(id)
+            .exec(&txn)
+            .await?;
+
+        if res.rows_affected > 0 {
+            events::record(&txn, DomainEvent::AmenityDeleted { id }).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(res.rows_affected > 0)
+    }
+
+    async fn get_hotel_amenities(&self, hotel_id: Uuid) -> Result<Option<Vec<AmenitySchemaOut>>, ApiError> {
+        if hotels::Entity::find_by_id(hotel_id).one(&self.db).await?.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self::hotel_amenities(&self.db, hotel_id).await?))
+    }
+
+    async fn set_hotel_amenities(
+        &self
+        , hotel_id  : Uuid
+        , req       : AmenityCodesSchemaIn
+    ) -> Result<Option<Vec<AmenitySchemaOut>>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let txn = self.db.begin().await?;
+
+        // Locking the hotel serializes concurrent replacements of its links
+        let Some(hotel) = hotels::Entity::find_by_id(hotel_id).lock_exclusive().one(&txn).await? else {
+            return Ok(None);
+        };
+
+        let amenities = Self::resolve(&txn, &req.amenities).await?;
+
+        hotel_amenities::Entity::delete_many()
+            .filter(hotel_amenities::Column::HotelId.eq(hotel_id))
+            .exec(&txn)
+            .await?;
+
+        if !amenities.is_empty() {
+            hotel_amenities::Entity::insert_many(amenities.iter().map(|a| hotel_amenities::ActiveModel {
+                hotel_id        : Set(hotel_id)
+                , amenity_id    : Set(a.id)
+            }))
+            .exec(&txn)
+            .await?;
+        }
+
+        let mut hotel: hotels::ActiveModel = hotel.into();
+        hotel.updated_at = Set(Some(now));
+
+        let mut hotel = [HotelSchemaOut::from(hotel.update(&txn).await?)];
+        HotelService::attach_amenities(&txn, &mut hotel).await?;
+        MediaService::attach_hotel_media(&txn, &mut hotel).await?;
+
+        let [hotel] = hotel;
+
+        events::record(&txn, DomainEvent::HotelUpdated(hotel)).await?;
+        txn.commit().await?;
+
+        Ok(Some(amenities.into_iter().map(AmenitySchemaOut::from).collect()))
+    }
+
+    async fn get_room_type_amenities(
+        &self
+        , hotel_id  : Uuid
+        , room_type : &str
+    ) -> Result<Option<RoomTypeAmenitiesSchemaOut>, ApiError> {
+        if hotels::Entity::find_by_id(hotel_id).one(&self.db).await?.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self::room_type_amenities(&self.db, hotel_id, room_type).await?))
+    }
+
+    async fn set_room_type_amenities(
+        &self
+        , hotel_id  : Uuid
+        , room_type : &str
+        , req       : AmenityCodesSchemaIn
+    ) -> Result<Option<RoomTypeAmenitiesSchemaOut>, ApiError> {
+        let txn = self.db.begin().await?;
+
+        if hotels::Entity::find_by_id(hotel_id).lock_exclusive().one(&txn).await?.is_none() {
+            return Ok(None);
+        }
+
+        let has_type = rooms::Entity::find()
+            .filter(rooms::Column::HotelId.eq(hotel_id))
+            .filter(rooms::Column::RoomType.eq(room_type))
+            .one(&txn)
+            .await?
+            .is_some();
+
+        if !has_type {
+            return Err(ApiError::Validation(format!("The hotel has no {} rooms", room_type)));
+        }
+
+        let amenities = Self::resolve(&txn, &req.amenities).await?;
+
+        room_type_amenities::Entity::delete_many()
+            .filter(room_type_amenities::Column::HotelId.eq(hotel_id))
+            .filter(room_type_amenities::Column::RoomType.eq(room_type))
+            .exec(&txn)
+            .await?;
+
+        if !amenities.is_empty() {
+            room_type_amenities::Entity::insert_many(amenities.iter().map(|a| room_type_amenities::ActiveModel {
+                hotel_id        : Set(hotel_id)
+                , room_type     : Set(room_type.to_string())
+                , amenity_id    : Set(a.id)
+            }))
+            .exec(&txn)
+            .await?;
+        }
+
+        let res = RoomTypeAmenitiesSchemaOut {
+            hotel_id,
+            room_type   : room_type.to_string(),
+            amenities   : amenities.into_iter().map(AmenitySchemaOut::from).collect(),
+        };
+
+        events::record(&txn, DomainEvent::RoomTypeAmenitiesUpdated(res.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(res))
+    }
+}
@@ -0,0 +1,66 @@
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use crate::{
+    models::audit_log,
+    schemas::audit::*,
+    services::traits::AuditServiceTrait,
+    error::ApiError,
+};
+
+#[derive(Clone)]
+pub struct AuditService {
+    db  : DatabaseConnection
+}
+
+impl AuditService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Appends `action` on an entity to the audit trail. Takes any connection
+    /// so the entry commits or rolls back with the operation itself.
+    pub async fn record<C: ConnectionTrait>(
+        conn            : &C
+        , action        : AuditAction
+        , entity_type   : &str
+        , entity_id     : Uuid
+        , details       : serde_json::Value
+    ) -> Result<(), DbErr> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let entry = audit_log::ActiveModel {
+            id              : Set(Uuid::new_v4())
+            , action        : Set(action.as_str().to_string())
+            , entity_type   : Set(entity_type.to_string())
+            , entity_id     : Set(entity_id)
+            , details       : Set(Some(details))
+            , created_at    : Set(now)
+        };
+
+        entry.insert(conn).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AuditServiceTrait for AuditService {
+    async fn list_entries(&self, filter: AuditFilter) -> Result<Vec<AuditEntrySchemaOut>, ApiError> {
+        let mut query = audit_log::Entity::find();
+
+        if let Some(entity_type) = filter.entity_type {
+            query = query.filter(audit_log::Column::EntityType.eq(entity_type));
+        }
+
+        if let Some(entity_id) = filter.entity_id {
+            query = query.filter(audit_log::Column::EntityId.eq(entity_id));
+        }
+
+        let res = query
+            .order_by_desc(audit_log::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        Ok(res.into_iter().map(AuditEntrySchemaOut::from).collect())
+    }
+}
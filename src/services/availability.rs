@@ -0,0 +1,288 @@
+use std::collections::HashSet;
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, TimeZone};
+use chrono_tz::Tz;
+use crate::models::{bookings, external_blocks, hotels, room_blocks, rooms, sea_orm_active_enums::BookingStatus};
+
+/// Booking statuses that hold a room for their dates
+pub const HOLDING_STATUSES: [BookingStatus; 2] = [BookingStatus::Pending, BookingStatus::Confirmed];
+
+/// Timezone named by a hotel, UTC when the name is unknown
+pub fn timezone(name: &str) -> Tz {
+    name.parse().unwrap_or(Tz::UTC)
+}
+
+/// Timezone the dates of a hotel's stays are in, UTC for a missing hotel
+pub async fn hotel_timezone<C: ConnectionTrait>(conn: &C, hotel_id: Uuid) -> Result<Tz, DbErr> {
+    let name: Option<String> = hotels::Entity::find_by_id(hotel_id)
+        .select_only()
+        .column(hotels::Column::Timezone)
+        .into_tuple()
+        .one(conn)
+        .await?;
+
+    Ok(name.map_or(Tz::UTC, |name| timezone(&name)))
+}
+
+/// Local dates, in the hotel's timezone `tz`, of the first night of a stay and
+/// of its check-out day. Room blocks close whole nights, so a stay clashes with
+/// a block when any night in `[first_night, check_out_day)` is blocked.
+pub fn stay_nights(check_in: DateTime<FixedOffset>, check_out: DateTime<FixedOffset>, tz: Tz) -> (NaiveDate, NaiveDate) {
+    (check_in.with_timezone(&tz).date_naive(), check_out.with_timezone(&tz).date_naive())
+}
+
+/// Start of `date` in the hotel's timezone `tz`. A midnight skipped by a DST
+/// change moves an hour later.
+pub fn start_of_day(date: NaiveDate, tz: Tz) -> DateTime<FixedOffset> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+
+    tz.from_local_datetime(&midnight)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(midnight + Duration::hours(1))).earliest())
+        .map_or_else(|| midnight.and_utc().fixed_offset(), |t| t.fixed_offset())
+}
+
+/// Whether `room_id` has no holding booking, room block or external block
+/// overlapping `[check_in, check_out)`, ignoring `exclude_booking` when updating
+/// a booking. `tz` is the timezone of the room's hotel.
+pub async fn room_is_free<C: ConnectionTrait>(
+    conn                : &C
+    , room_id           : Uuid
+    , check_in          : DateTime<FixedOffset>
+    , check_out         : DateTime<FixedOffset>
+    , exclude_booking   : Option<Uuid>
+    , tz                : Tz
+) -> Result<bool, DbErr> {
+    let mut overlapping = bookings::Entity::find()
+        .filter(bookings::Column::RoomId.eq(room_id))
+        .filter(bookings::Column::Status.is_in(HOLDING_STATUSES))
+        .filter(bookings::Column::CheckInDate.lt(check_out))
+        .filter(bookings::Column::CheckOutDate.gt(check_in));
+
+    if let Some(id) = exclude_booking {
+        overlapping = overlapping.filter(bookings::Column::Id.ne(id));
+    }
+
+    if overlapping.count(conn).await? > 0 {
+        return Ok(false);
+    }
+
+    let blocks = external_blocks::Entity::find()
+        .filter(external_blocks::Column::RoomId.eq(room_id))
+        .filter(external_blocks::Column::StartsAt.lt(check_out))
+        .filter(external_blocks::Column::EndsAt.gt(check_in))
+        .count(conn)
+        .await?;
+
+    if blocks > 0 {
+        return Ok(false);
+    }
+
+    let (first_night, check_out_day) = stay_nights(check_in, check_out, tz);
+
+    let room_blocks = room_blocks::Entity::find()
+        .filter(room_blocks::Column::RoomId.eq(room_id))
+        .filter(room_blocks::Column::StartDate.lt(check_out_day))
+        .filter(room_blocks::Column::EndDate.gte(first_night))
+        .count(conn)
+        .await?;
+
+    Ok(room_blocks == 0)
+}
+
+/// Whether `hotel_id` still has a `room_type` room to sell for every night of
+/// `[check_in, check_out)`. A night is sold out once the holding bookings of the
+/// type, assigned to a room or not, fill every room of the type not closed by
+/// a room block or an external block that night, nights being local to the
+/// hotel's timezone `tz`.
+pub async fn room_type_has_inventory<C: ConnectionTrait>(
+    conn                : &C
+    , hotel_id          : Uuid
+    , room_type         : &str
+    , check_in          : DateTime<FixedOffset>
+    , check_out         : DateTime<FixedOffset>
+    , exclude_booking   : Option<Uuid>
+    , tz                : Tz
+) -> Result<bool, DbErr> {
+    let room_ids: Vec<Uuid> = rooms::Entity::find()
+        .select_only()
+        .column(rooms::Column::Id)
+        .filter(rooms::Column::HotelId.eq(hotel_id))
+        .filter(rooms::Column::RoomType.eq(room_type))
+        .filter(rooms::Column::IsAvailable.eq(true))
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    if room_ids.is_empty() {
+        return Ok(false);
+    }
+
+    let (first_night, check_out_day) = stay_nights(check_in, check_out, tz);
+
+    let mut booked = bookings::Entity::find()
+        .filter(bookings::Column::HotelId.eq(hotel_id))
+        .filter(bookings::Column::RoomType.eq(room_type))
+        .filter(bookings::Column::Status.is_in(HOLDING_STATUSES))
+        .filter(bookings::Column::CheckInDate.lt(check_out))
+        .filter(bookings::Column::CheckOutDate.gt(check_in));
+
+    if let Some(id) = exclude_booking {
+        booked = booked.filter(bookings::Column::Id.ne(id));
+    }
+
+    let booked = booked.all(conn).await?;
+
+    let room_blocks = room_blocks::Entity::find()
+        .filter(room_blocks::Column::RoomId.is_in(room_ids.clone()))
+        .filter(room_blocks::Column::StartDate.lt(check_out_day))
+        .filter(room_blocks::Column::EndDate.gte(first_night))
+        .all(conn)
+        .await?;
+
+    let external_blocks = external_blocks::Entity::find()
+        .filter(external_blocks::Column::RoomId.is_in(room_ids.clone()))
+        .filter(external_blocks::Column::StartsAt.lt(check_out))
+        .filter(external_blocks::Column::EndsAt.gt(check_in))
+        .all(conn)
+        .await?;
+
+    // A stay shorter than a night still takes the room on its check-in date
+    let last_night = (check_out_day - Duration::days(1)).max(first_night);
+
+    for night in first_night.iter_days().take_while(|d| *d <= last_night) {
+        let covers = |start: DateTime<FixedOffset>, end: DateTime<FixedOffset>| {
+            let (start, end) = stay_nights(start, end, tz);
+            start <= night && night < end.max(start + Duration::days(1))
+        };
+
+        let closed = room_blocks.iter()
+            .filter(|b| b.start_date <= night && night <= b.end_date)
+            .map(|b| b.room_id)
+            .chain(external_blocks.iter().filter(|b| covers(b.starts_at, b.ends_at)).map(|b| b.room_id))
+            .collect::<HashSet<_>>();
+
+        let sold = booked.iter().filter(|b| covers(b.check_in_date, b.check_out_date)).count();
+
+        if sold + closed.len() >= room_ids.len() {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Holding bookings of `room_id` staying any of the nights `start_date..=end_date`,
+/// local to the hotel's timezone `tz`
+pub async fn bookings_during<C: ConnectionTrait>(
+    conn            : &C
+    , room_id       : Uuid
+    , start_date    : NaiveDate
+    , end_date      : NaiveDate
+    , tz            : Tz
+) -> Result<Vec<bookings::Model>, DbErr> {
+    bookings::Entity::find()
+        .filter(bookings::Column::RoomId.eq(room_id))
+        .filter(bookings::Column::Status.is_in(HOLDING_STATUSES))
+        .filter(bookings::Column::CheckInDate.lt(start_of_day(end_date + Duration::days(1), tz)))
+        .filter(bookings::Column::CheckOutDate.gte(start_of_day(start_date + Duration::days(1), tz)))
+        .all(conn)
+        .await
+}
+
+/// Rooms with a holding booking, room block or external block overlapping `[check_in, check_out)`
+pub async fn occupied_room_ids<C: ConnectionTrait>(
+    conn        : &C
+    , check_in  : DateTime<FixedOffset>
+    , check_out : DateTime<FixedOffset>
+) -> Result<HashSet<Uuid>, DbErr> {
+    let booked: Vec<Option<Uuid>> = bookings::Entity::find()
+        .select_only()
+        .column(bookings::Column::RoomId)
+        .filter(bookings::Column::Status.is_in(HOLDING_STATUSES))
+        .filter(bookings::Column::CheckInDate.lt(check_out))
+        .filter(bookings::Column::CheckOutDate.gt(check_in))
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    let blocked: Vec<Uuid> = external_blocks::Entity::find()
+        .select_only()
+        .column(external_blocks::Column::RoomId)
+        .filter(external_blocks::Column::StartsAt.lt(check_out))
+        .filter(external_blocks::Column::EndsAt.gt(check_in))
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    // Room blocks are in the local dates of each room's hotel, any timezone is
+    // within a day of UTC
+    let (first_night, check_out_day) = stay_nights(check_in, check_out, Tz::UTC);
+
+    let closed: Vec<(Uuid, NaiveDate, NaiveDate, String)> = room_blocks::Entity::find()
+        .select_only()
+        .column(room_blocks::Column::RoomId)
+        .column(room_blocks::Column::StartDate)
+        .column(room_blocks::Column::EndDate)
+        .column(hotels::Column::Timezone)
+        .join(JoinType::InnerJoin, room_blocks::Relation::Rooms.def())
+        .join(JoinType::InnerJoin, rooms::Relation::Hotels.def())
+        .filter(room_blocks::Column::StartDate.lt(check_out_day + Duration::days(1)))
+        .filter(room_blocks::Column::EndDate.gte(first_night - Duration::days(1)))
+        .into_tuple()
+        .all(conn)
+        .await?;
+
+    let closed = closed.into_iter()
+        .filter(|(_, start_date, end_date, name)| {
+            let (first_night, check_out_day) = stay_nights(check_in, check_out, timezone(name));
+            *start_date < check_out_day && *end_date >= first_night
+        })
+        .map(|(room_id, ..)| room_id);
+
+    Ok(booked.into_iter().flatten().chain(blocked).chain(closed).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn nights_are_local_to_the_hotel() {
+        // A 06:00 check-out in Phnom Penh (UTC+7) is the evening before in UTC
+        let (check_in, check_out) = (at("2024-07-01T08:00:00Z"), at("2024-07-02T23:00:00Z"));
+
+        assert_eq!(stay_nights(check_in, check_out, Tz::UTC), (date("2024-07-01"), date("2024-07-02")));
+        assert_eq!(stay_nights(check_in, check_out, chrono_tz::Asia::Phnom_Penh), (date("2024-07-01"), date("2024-07-03")));
+
+        // Check-in at 01:00 in Auckland (UTC+12) is the previous day in UTC
+        let (check_in, check_out) = (at("2024-06-30T13:00:00Z"), at("2024-07-02T13:00:00Z"));
+
+        assert_eq!(stay_nights(check_in, check_out, Tz::UTC), (date("2024-06-30"), date("2024-07-02")));
+        assert_eq!(stay_nights(check_in, check_out, chrono_tz::Pacific::Auckland), (date("2024-07-01"), date("2024-07-03")));
+    }
+
+    #[test]
+    fn days_start_at_local_midnight() {
+        assert_eq!(start_of_day(date("2024-07-01"), Tz::UTC), at("2024-07-01T00:00:00Z"));
+        assert_eq!(start_of_day(date("2024-07-01"), chrono_tz::Europe::Paris), at("2024-06-30T22:00:00Z"));
+
+        // Santiago skips from midnight to 01:00 when DST starts
+        assert_eq!(start_of_day(date("2024-09-08"), chrono_tz::America::Santiago), at("2024-09-08T04:00:00Z"));
+    }
+
+    #[test]
+    fn unknown_timezones_are_utc() {
+        assert_eq!(timezone("Europe/Paris"), chrono_tz::Europe::Paris);
+        assert_eq!(timezone("Mars/Base"), Tz::UTC);
+    }
+}
@@ -1,12 +1,30 @@
+use std::collections::HashMap;
+use rust_decimal::Decimal;
 use sea_orm::*;
 use uuid::Uuid;
-use chrono::{Utc, FixedOffset};
+use chrono::{DateTime, Utc, FixedOffset};
+use chrono_tz::Tz;
 use crate::{
-    models::bookings,
+    events::{self, DomainEvent},
+    ical::{self, Event, EventStatus},
+    models::{bookings, guests, hotels, rooms, sea_orm_active_enums::BookingStatus},
     schemas::booking::*,
-    services::traits::BookingServiceTrait,
+    services::{availability, loyalty::LoyaltyService, promotions::{PromotionService, PromotionTarget}, traits::BookingServiceTrait},
+    error::ApiError,
 };
 
+/// Gap counted for a side of a stay without a neighbouring stay when auto-assigning
+const MAX_GAP_NIGHTS: i64 = 30;
+
+/// Where and when a checked booking stays
+pub struct BookableStay {
+    pub hotel_id        : Uuid
+    , pub room_type     : String
+    , pub check_in      : DateTime<FixedOffset>
+    , pub check_out     : DateTime<FixedOffset>
+    , pub timezone      : Tz
+}
+
 #[derive(Clone)]
 pub struct BookingService {
     db  : DatabaseConnection
@@ -16,6 +34,389 @@ impl BookingService {
     pub fn new(db   : DatabaseConnection) -> Self {
         Self { db }
     }
+
+    /// Where clause of the list and export endpoints
+    pub fn filter_condition(filter: &BookingFilter) -> Condition {
+        let mut condition = Condition::all();
+
+        if let Some(status) = &filter.status {
+            condition = condition.add(bookings::Column::Status.eq(status.clone()));
+        }
+
+        if let Some(hotel_id) = filter.hotel_id {
+            condition = condition.add(bookings::Column::HotelId.eq(hotel_id));
+        }
+
+        if let Some(room_id) = filter.room_id {
+            condition = condition.add(bookings::Column::RoomId.eq(room_id));
+        }
+
+        if let Some(guest_id) = filter.guest_id {
+            condition = condition.add(bookings::Column::GuestId.eq(guest_id));
+        }
+
+        if let Some(from) = filter.from {
+            condition = condition.add(bookings::Column::CheckOutDate.gt(from));
+        }
+
+        if let Some(to) = filter.to {
+            condition = condition.add(bookings::Column::CheckInDate.lt(to));
+        }
+
+        condition
+    }
+
+    /// Rejects guests kept for history only: anonymized ones and duplicates
+    /// merged into another guest. A missing guest is left to the foreign key.
+    async fn ensure_guest_bookable(txn: &DatabaseTransaction, guest_id: Uuid) -> Result<(), ApiError> {
+        let Some(guest) = guests::Entity::find_by_id(guest_id).one(txn).await? else { return Ok(()) };
+
+        if guest.anonymized_at.is_some() {
+            return Err(ApiError::Validation("The guest has been anonymized and cannot be booked".to_string()));
+        }
+
+        if let Some(survivor) = guest.merged_into_id {
+            return Err(ApiError::Validation(format!("The guest was merged into guest {}, book that one instead", survivor)));
+        }
+
+        Ok(())
+    }
+
+    /// Checks and inserts a booking, optionally as part of a reservation
+    pub async fn insert(
+        txn                 : &DatabaseTransaction
+        , req               : BookingSchemaIn
+        , reservation_id    : Option<Uuid>
+    ) -> Result<BookingSchemaOut, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        // The price asked for is before the promotion and the discount bought
+        // with loyalty points
+        let redeem_points = req.redeem_points.unwrap_or(0);
+
+        if redeem_points < 0 {
+            return Err(ApiError::Validation("redeem_points must not be negative".to_string()));
+        }
+
+        if (redeem_points > 0 || req.promo_code.is_some()) && req.status == BookingStatus::Cancelled {
+            return Err(ApiError::Validation("Discounts cannot be applied to a cancelled booking".to_string()));
+        }
+
+        Self::ensure_guest_bookable(txn, req.guest_id).await?;
+
+        let stay = Self::ensure_bookable(txn, &req, None).await?;
+
+        // The promotion stays locked until the booking is committed, so it is
+        // redeemed at most as many times as allowed
+        let promotion = match &req.promo_code {
+            Some(code) => Some(PromotionService::apply(txn, code, &PromotionTarget {
+                hotel_id            : stay.hotel_id
+                , room_type         : &stay.room_type
+                , nights            : Self::nights(stay.check_in, stay.check_out, stay.timezone)
+                , guest_id          : Some(req.guest_id)
+                , exclude_booking   : None
+            }, req.total_price, true).await?),
+            None => None,
+        };
+
+        let promotion_discount = promotion.as_ref().map_or(Decimal::ZERO, |(_, d)| *d);
+        let loyalty_discount = LoyaltyService::discount(redeem_points);
+
+        if loyalty_discount > req.total_price - promotion_discount {
+            return Err(ApiError::Validation("redeem_points must not buy a discount above what is left of total_price".to_string()));
+        }
+
+        let booking = bookings::ActiveModel {
+            id                  : Set(Uuid::new_v4())
+            , hotel_id          : Set(stay.hotel_id)
+            , room_type         : Set(stay.room_type)
+            , room_id           : Set(req.room_id)
+            , guest_id          : Set(req.guest_id)
+            , check_in_date     : Set(stay.check_in)
+            , check_out_date    : Set(stay.check_out)
+            , total_price       : Set(req.total_price - promotion_discount - loyalty_discount)
+            , status            : Set(req.status)
+            , reservation_id    : Set(reservation_id)
+            , loyalty_points_redeemed   : Set(redeem_points)
+            , loyalty_discount  : Set(loyalty_discount)
+            , promotion_id      : Set(promotion.as_ref().map(|(p, _)| p.id))
+            , promo_code        : Set(promotion.map(|(p, _)| p.code))
+            , promotion_discount: Set(promotion_discount)
+            , created_at        : Set(now)
+            , updated_at        : Set(None)
+        };
+
+        let booking = BookingSchemaOut::from(booking.insert(txn).await?);
+
+        events::record(txn, DomainEvent::BookingCreated(booking.clone())).await?;
+        LoyaltyService::sync_booking(txn, &booking).await?;
+
+        Ok(booking)
+    }
+
+    /// Checks and applies `req` to `booking`, keeping its reservation
+    pub async fn update(
+        txn         : &DatabaseTransaction
+        , booking   : bookings::Model
+        , req       : BookingSchemaIn
+    ) -> Result<BookingSchemaOut, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        if req.redeem_points.is_some_and(|p| p != booking.loyalty_points_redeemed) {
+            return Err(ApiError::Validation("redeem_points can only be set when the booking is created".to_string()));
+        }
+
+        if req.promo_code.as_deref().is_some_and(|c| Some(PromotionService::clean_code(c)) != booking.promo_code) {
+            return Err(ApiError::Validation("promo_code can only be set when the booking is created".to_string()));
+        }
+
+        // As on creation the price sent is before the discounts, which were
+        // fixed when the booking was made
+        let discounts = booking.promotion_discount + booking.loyalty_discount;
+
+        if req.total_price < discounts {
+            return Err(ApiError::Validation(format!("total_price must cover the {} of discounts of the booking", discounts)));
+        }
+
+        if req.guest_id != booking.guest_id {
+            Self::ensure_guest_bookable(txn, req.guest_id).await?;
+        }
+
+        let stay = Self::ensure_bookable(txn, &req, Some(booking.id)).await?;
+
+        let previous_status = booking.status.clone();
+        let mut booking: bookings::ActiveModel = booking.into();
+
+        booking.hotel_id       = Set(stay.hotel_id);
+        booking.room_type      = Set(stay.room_type);
+        booking.room_id        = Set(req.room_id);
+        booking.guest_id       = Set(req.guest_id);
+        booking.check_in_date  = Set(stay.check_in);
+        booking.check_out_date = Set(stay.check_out);
+        booking.total_price    = Set(req.total_price - discounts);
+        booking.status         = Set(req.status);
+        booking.updated_at     = Set(Some(now));
+
+        let booking = BookingSchemaOut::from(booking.update(txn).await?);
+
+        Self::record_update(txn, &booking, previous_status).await?;
+
+        Ok(booking)
+    }
+
+    /// Records the update of a booking previously in `previous_status`
+    pub async fn record_update(
+        txn                 : &DatabaseTransaction
+        , booking           : &BookingSchemaOut
+        , previous_status   : BookingStatus
+    ) -> Result<(), ApiError> {
+        events::record(txn, DomainEvent::BookingUpdated(booking.clone())).await?;
+
+        // A cancelled booking no longer counts as a redemption of its
+        // promotion. The promotion is locked before the guest, as on creation.
+        if previous_status == BookingStatus::Cancelled && booking.status != BookingStatus::Cancelled {
+            PromotionService::reclaim(txn, booking).await?;
+        }
+
+        LoyaltyService::sync_booking(txn, booking).await?;
+
+        // Status transitions get their own event on top of the update
+        if booking.status != previous_status {
+            let event = match booking.status {
+                BookingStatus::Confirmed    => Some(DomainEvent::BookingConfirmed(booking.clone())),
+                BookingStatus::Cancelled    => Some(DomainEvent::BookingCancelled(booking.clone())),
+                BookingStatus::Completed    => Some(DomainEvent::BookingCompleted(booking.clone())),
+                BookingStatus::Pending      => None,
+            };
+
+            if let Some(event) = event {
+                events::record(txn, event).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Nights of a stay at a hotel in timezone `tz`, a day use counting as one
+    pub fn nights(check_in: DateTime<FixedOffset>, check_out: DateTime<FixedOffset>, tz: Tz) -> i64 {
+        let (from, to) = availability::stay_nights(check_in, check_out, tz);
+        (to - from).num_days().max(1)
+    }
+
+    /// Timestamps of a stay at a hotel, dates alone take the hotel's check-in
+    /// and check-out times
+    pub async fn stay_times<C: ConnectionTrait>(
+        conn            : &C
+        , hotel_id      : Uuid
+        , check_in      : StayDateTime
+        , check_out     : StayDateTime
+    ) -> Result<(DateTime<FixedOffset>, DateTime<FixedOffset>), ApiError> {
+        let (check_in, check_out) = match (check_in, check_out) {
+            (StayDateTime::At(check_in), StayDateTime::At(check_out)) => (check_in, check_out),
+            _ => {
+                let hotel = hotels::Entity::find_by_id(hotel_id)
+                    .one(conn)
+                    .await?
+                    .ok_or_else(|| ApiError::HotelNotFound(hotel_id.to_string()))?;
+
+                let tz = availability::timezone(&hotel.timezone);
+
+                (check_in.at(hotel.check_in_time, tz), check_out.at(hotel.check_out_time, tz))
+            }
+        };
+
+        if check_out <= check_in {
+            return Err(ApiError::Validation("check_out_date must be after check_in_date".to_string()));
+        }
+
+        Ok((check_in, check_out))
+    }
+
+    /// Resolves the hotel, room type and times a booking is for, from its room
+    /// or as given. Locks every room of the type so concurrent bookings of it
+    /// are serialized, then rejects dates overlapping another booking or a
+    /// block of the chosen room, or for which the room type is sold out.
+    pub async fn ensure_bookable(
+        txn                 : &DatabaseTransaction
+        , req               : &BookingSchemaIn
+        , exclude_booking   : Option<Uuid>
+    ) -> Result<BookableStay, ApiError> {
+        let (hotel_id, room_type) = match req.room_id {
+            Some(room_id) => {
+                let room = rooms::Entity::find_by_id(room_id)
+                    .one(txn)
+                    .await?
+                    .ok_or_else(|| ApiError::RoomNotFound(room_id.to_string()))?;
+
+                if req.hotel_id.is_some_and(|h| h != room.hotel_id) {
+                    return Err(ApiError::Validation("room_id does not belong to hotel_id".to_string()));
+                }
+
+                if req.room_type.as_ref().is_some_and(|t| *t != room.room_type) {
+                    return Err(ApiError::Validation("room_id is not of room_type".to_string()));
+                }
+
+                (room.hotel_id, room.room_type)
+            }
+            None => {
+                let (Some(hotel_id), Some(room_type)) = (req.hotel_id, req.room_type.clone()) else {
+                    return Err(ApiError::Validation("room_id, or hotel_id and room_type, are required".to_string()));
+                };
+
+                if hotels::Entity::find_by_id(hotel_id).one(txn).await?.is_none() {
+                    return Err(ApiError::HotelNotFound(hotel_id.to_string()));
+                }
+
+                (hotel_id, room_type)
+            }
+        };
+
+        let (check_in, check_out) = Self::stay_times(txn, hotel_id, req.check_in_date, req.check_out_date).await?;
+
+        let rooms = Self::lock_rooms(txn, hotel_id, &room_type).await?;
+
+        if rooms.is_empty() {
+            return Err(ApiError::Validation(format!("The hotel has no {} rooms", room_type)));
+        }
+
+        let timezone = availability::hotel_timezone(txn, hotel_id).await?;
+        let stay = BookableStay { hotel_id, room_type, check_in, check_out, timezone };
+
+        // Cancelled and completed bookings do not hold the room
+        if !availability::HOLDING_STATUSES.contains(&req.status) {
+            return Ok(stay);
+        }
+
+        if let Some(room_id) = req.room_id {
+            if !availability::room_is_free(txn, room_id, check_in, check_out, exclude_booking, timezone).await? {
+                return Err(ApiError::RoomUnavailable(room_id.to_string()));
+            }
+        }
+
+        if !availability::room_type_has_inventory(
+            txn, hotel_id, &stay.room_type, check_in, check_out, exclude_booking, timezone
+        ).await? {
+            return Err(ApiError::RoomTypeSoldOut(stay.room_type));
+        }
+
+        Ok(stay)
+    }
+
+    /// Locks and returns the rooms of a hotel's room type, ordered by number
+    async fn lock_rooms(
+        txn             : &DatabaseTransaction
+        , hotel_id      : Uuid
+        , room_type     : &str
+    ) -> Result<Vec<rooms::Model>, DbErr> {
+        rooms::Entity::find()
+            .filter(rooms::Column::HotelId.eq(hotel_id))
+            .filter(rooms::Column::RoomType.eq(room_type))
+            .order_by_asc(rooms::Column::RoomNumber)
+            .lock_exclusive()
+            .all(txn)
+            .await
+    }
+
+    /// Picks the free room of the booking's type that fits tightest between the
+    /// stays before and after it, so the nights left over stay in as few and as
+    /// long runs as possible. A side without a neighbouring stay counts as a
+    /// gap of `MAX_GAP_NIGHTS`.
+    async fn pick_room(
+        txn         : &DatabaseTransaction
+        , booking   : &bookings::Model
+    ) -> Result<Option<rooms::Model>, DbErr> {
+        let tz = availability::hotel_timezone(txn, booking.hotel_id).await?;
+        let mut free = Vec::new();
+
+        for room in Self::lock_rooms(txn, booking.hotel_id, &booking.room_type).await? {
+            if room.is_available
+                && availability::room_is_free(txn, room.id, booking.check_in_date, booking.check_out_date, Some(booking.id), tz).await? {
+                free.push(room);
+            }
+        }
+
+        let neighbours = bookings::Entity::find()
+            .filter(bookings::Column::RoomId.is_in(free.iter().map(|r| r.id)))
+            .filter(bookings::Column::Status.is_in(availability::HOLDING_STATUSES))
+            .filter(bookings::Column::Id.ne(booking.id))
+            .all(txn)
+            .await?;
+
+        let nights = |from: DateTime<FixedOffset>, to: DateTime<FixedOffset>| {
+            let (from, to) = availability::stay_nights(from, to, tz);
+            (to - from).num_days().clamp(0, MAX_GAP_NIGHTS)
+        };
+
+        let gap = |room: &rooms::Model| {
+            let stays = neighbours.iter().filter(|b| b.room_id == Some(room.id));
+
+            let before = stays.clone()
+                .filter(|b| b.check_out_date <= booking.check_in_date)
+                .map(|b| nights(b.check_out_date, booking.check_in_date))
+                .min()
+                .unwrap_or(MAX_GAP_NIGHTS);
+
+            let after = stays
+                .filter(|b| b.check_in_date >= booking.check_out_date)
+                .map(|b| nights(booking.check_out_date, b.check_in_date))
+                .min()
+                .unwrap_or(MAX_GAP_NIGHTS);
+
+            before + after
+        };
+
+        // Rooms are ordered by number, min_by_key keeps the first of equal gaps
+        Ok(free.into_iter().min_by_key(gap))
+    }
+
+    fn event_status(status: &BookingStatus) -> EventStatus {
+        match status {
+            BookingStatus::Pending      => EventStatus::Tentative,
+            BookingStatus::Confirmed
+            | BookingStatus::Completed  => EventStatus::Confirmed,
+            BookingStatus::Cancelled    => EventStatus::Cancelled,
+        }
+    }
 }
 
 #[async_trait]
@@ -23,34 +424,14 @@ impl BookingServiceTrait for BookingService {
     async fn create_booking(
         &self
         , req   : BookingSchemaIn
-    ) -> Result<BookingSchemaOut, DbErr> {
-        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+    ) -> Result<BookingSchemaOut, ApiError> {
+        let txn = self.db.begin().await?;
 
-        let booking = bookings::ActiveModel {
-            id              : Set(Uuid::new_v4())
-            , room_id       : Set(req.room_id)
-            , guest_id      : Set(req.guest_id)
-            , check_in_date : Set(req.check_in_date)
-            , check_out_date: Set(req.check_out_date)
-            , total_price   : Set(req.total_price)
-            , status        : Set(req.status)
-            , created_at    : Set(now)
-            , updated_at    : Set(None)
-        };
+        let booking = Self::insert(&txn, req, None).await?;
 
-        let res = booking.insert(&self.db).await?;
-        
-        Ok(BookingSchemaOut {
-            id              : res.id
-            , room_id       : res.room_id
-            , guest_id      : res.guest_id
-            , check_in_date : res.check_in_date
-            , check_out_date: res.check_out_date
-            , total_price   : res.total_price
-            , status        : res.status
-            , created_at    : res.created_at
-            , updated_at    : res.updated_at
-        })
+        txn.commit().await?;
+
+        Ok(booking)
     }
 
     async fn get_booking(
@@ -61,85 +442,230 @@ impl BookingServiceTrait for BookingService {
             .one(&self.db)
             .await?;
 
-        Ok(res.map(|b| BookingSchemaOut {
-            id              : b.id
-            , room_id       : b.room_id
-            , guest_id      : b.guest_id
-            , check_in_date : b.check_in_date
-            , check_out_date: b.check_out_date
-            , total_price   : b.total_price
-            , status        : b.status
-            , created_at    : b.created_at
-            , updated_at    : b.updated_at
-        }))
+        Ok(res.map(BookingSchemaOut::from))
     }
 
-    async fn list_bookings(&self) -> Result<Vec<BookingSchemaOut>, DbErr> {
+    async fn list_bookings(&self, filter: BookingFilter) -> Result<Vec<BookingSchemaOut>, DbErr> {
         let res = bookings::Entity::find()
+            .filter(Self::filter_condition(&filter))
             .all(&self.db)
             .await?;
 
-        Ok(res.into_iter().map(|b| BookingSchemaOut {
-            id              : b.id
-            , room_id       : b.room_id
-            , guest_id      : b.guest_id
-            , check_in_date : b.check_in_date
-            , check_out_date: b.check_out_date
-            , total_price   : b.total_price
-            , status        : b.status
-            , created_at    : b.created_at
-            , updated_at    : b.updated_at
-        }).collect())
+        Ok(res.into_iter().map(BookingSchemaOut::from).collect())
     }
 
     async fn update_booking(
         &self
         , id    : Uuid
         , req   : BookingSchemaIn
-    ) -> Result<Option<BookingSchemaOut>, DbErr> {
-        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+    ) -> Result<Option<BookingSchemaOut>, ApiError> {
+        let txn = self.db.begin().await?;
         
-        let booking = match bookings::Entity::find_by_id(id).one(&self.db).await? {
+        let booking = match bookings::Entity::find_by_id(id).one(&txn).await? {
             Some(b) => b,
             None    => return Ok(None),
         };
 
-        let mut booking: bookings::ActiveModel = booking.into();
+        let booking = Self::update(&txn, booking, req).await?;
 
-        booking.room_id        = Set(req.room_id);
-        booking.guest_id       = Set(req.guest_id);
-        booking.check_in_date  = Set(req.check_in_date);
-        booking.check_out_date = Set(req.check_out_date);
-        booking.total_price    = Set(req.total_price);
-        booking.status         = Set(req.status);
-        booking.updated_at     = Set(Some(now));
-
-        let updated = booking.update(&self.db).await?;
+        txn.commit().await?;
 
-        Ok(Some(BookingSchemaOut {
-            id              : updated.id
-            , room_id       : updated.room_id
-            , guest_id      : updated.guest_id
-            , check_in_date : updated.check_in_date
-            , check_out_date: updated.check_out_date
-            , total_price   : updated.total_price
-            , status        : updated.status
-            , created_at    : updated.created_at
-            , updated_at    : updated.updated_at
-        }))
+        Ok(Some(booking))
     }
 
     async fn delete_booking(
         &self
         , id    : Uuid
     ) -> Result<bool, DbErr> {
+        let txn = self.db.begin().await?;
+
+        let Some(booking) = bookings::Entity::find_by_id(id).lock_exclusive().one(&txn).await? else { return Ok(false) };
+
+        LoyaltyService::release_booking(&txn, &BookingSchemaOut::from(booking)).await?;
+
         let res = bookings::Entity::delete_by_id(id)
-            .exec(&self.db)
+            .exec(&txn)
             .await?;
 
+        if res.rows_affected > 0 {
+            events::record(&txn, DomainEvent::BookingDeleted { id }).await?;
+        }
+
+        txn.commit().await?;
+
         Ok(res.rows_affected > 0)
     }
 
+    async fn assign_room(
+        &self
+        , id        : Uuid
+        , room_id   : Option<Uuid>
+    ) -> Result<Option<BookingSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let txn = self.db.begin().await?;
+
+        let booking = match bookings::Entity::find_by_id(id).lock_exclusive().one(&txn).await? {
+            Some(b) => b,
+            None    => return Ok(None),
+        };
+
+        if !availability::HOLDING_STATUSES.contains(&booking.status) {
+            return Err(ApiError::Validation("Only pending or confirmed bookings can be assigned a room".to_string()));
+        }
+
+        let room = match room_id {
+            Some(room_id) => {
+                let room = rooms::Entity::find_by_id(room_id)
+                    .lock_exclusive()
+                    .one(&txn)
+                    .await?
+                    .ok_or_else(|| ApiError::RoomNotFound(room_id.to_string()))?;
+
+                if room.hotel_id != booking.hotel_id || room.room_type != booking.room_type {
+                    return Err(ApiError::Validation(format!(
+                        "room_id must be a {} room of the booking's hotel", booking.room_type
+                    )));
+                }
+
+                // Rooms taken out of service are not handed out, as when picking
+                if !room.is_available {
+                    return Err(ApiError::RoomUnavailable(room.id.to_string()));
+                }
+
+                let tz = availability::hotel_timezone(&txn, booking.hotel_id).await?;
+
+                if !availability::room_is_free(&txn, room.id, booking.check_in_date, booking.check_out_date, Some(id), tz).await? {
+                    return Err(ApiError::RoomUnavailable(room.id.to_string()));
+                }
+
+                room
+            }
+            None => Self::pick_room(&txn, &booking)
+                .await?
+                .ok_or_else(|| ApiError::RoomTypeSoldOut(booking.room_type.clone()))?,
+        };
+
+        let mut booking: bookings::ActiveModel = booking.into();
+
+        booking.room_id     = Set(Some(room.id));
+        booking.updated_at  = Set(Some(now));
+
+        let booking = BookingSchemaOut::from(booking.update(&txn).await?);
+
+        events::record(&txn, DomainEvent::BookingRoomAssigned(booking.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(booking))
+    }
+
+    async fn quote_booking(
+        &self
+        , req   : BookingQuoteSchemaIn
+    ) -> Result<BookingQuoteSchemaOut, ApiError> {
+        let redeem_points = req.redeem_points.unwrap_or(0);
+
+        if redeem_points < 0 {
+            return Err(ApiError::Validation("redeem_points must not be negative".to_string()));
+        }
+
+        let (hotel_id, room_type, price_per_night) = match req.room_id {
+            Some(room_id) => {
+                let room = rooms::Entity::find_by_id(room_id)
+                    .one(&self.db)
+                    .await?
+                    .ok_or_else(|| ApiError::RoomNotFound(room_id.to_string()))?;
+
+                if req.hotel_id.is_some_and(|h| h != room.hotel_id) {
+                    return Err(ApiError::Validation("room_id does not belong to hotel_id".to_string()));
+                }
+
+                if req.room_type.as_ref().is_some_and(|t| *t != room.room_type) {
+                    return Err(ApiError::Validation("room_id is not of room_type".to_string()));
+                }
+
+                (room.hotel_id, room.room_type, room.price_per_night)
+            }
+            None => {
+                let (Some(hotel_id), Some(room_type)) = (req.hotel_id, req.room_type) else {
+                    return Err(ApiError::Validation("room_id, or hotel_id and room_type, are required".to_string()));
+                };
+
+                if hotels::Entity::find_by_id(hotel_id).one(&self.db).await?.is_none() {
+                    return Err(ApiError::HotelNotFound(hotel_id.to_string()));
+                }
+
+                // A room type is quoted at its cheapest room
+                let cheapest = rooms::Entity::find()
+                    .filter(rooms::Column::HotelId.eq(hotel_id))
+                    .filter(rooms::Column::RoomType.eq(room_type.as_str()))
+                    .filter(rooms::Column::IsAvailable.eq(true))
+                    .order_by_asc(rooms::Column::PricePerNight)
+                    .one(&self.db)
+                    .await?
+                    .ok_or_else(|| ApiError::Validation(format!("The hotel has no {} rooms", room_type)))?;
+
+                (hotel_id, room_type, cheapest.price_per_night)
+            }
+        };
+
+        let (check_in, check_out) = Self::stay_times(&self.db, hotel_id, req.check_in_date, req.check_out_date).await?;
+
+        let nights = Self::nights(check_in, check_out, availability::hotel_timezone(&self.db, hotel_id).await?);
+        let subtotal = price_per_night * Decimal::from(nights);
+        let mut total = subtotal;
+        let mut discounts = Vec::new();
+
+        if let Some(code) = &req.promo_code {
+            let (promotion, discount) = PromotionService::apply(&self.db, code, &PromotionTarget {
+                hotel_id
+                , room_type         : &room_type
+                , nights
+                , guest_id          : req.guest_id
+                , exclude_booking   : None
+            }, subtotal, false).await?;
+
+            total -= discount;
+            discounts.push(BookingDiscountSchemaOut::promotion(Some(promotion.id), promotion.code, discount));
+        }
+
+        if redeem_points > 0 {
+            let Some(guest_id) = req.guest_id else {
+                return Err(ApiError::Validation("guest_id is required to redeem points".to_string()));
+            };
+
+            let discount = LoyaltyService::discount(redeem_points);
+
+            if discount > total {
+                return Err(ApiError::Validation("redeem_points must not buy a discount above what is left of the price".to_string()));
+            }
+
+            let balance = LoyaltyService::balance(&self.db, guest_id).await?;
+
+            if balance < i64::from(redeem_points) {
+                return Err(ApiError::Validation(format!(
+                    "The guest has {} loyalty points, {} cannot be redeemed", balance.max(0), redeem_points
+                )));
+            }
+
+            total -= discount;
+            discounts.push(BookingDiscountSchemaOut::loyalty(redeem_points, discount));
+        }
+
+        Ok(BookingQuoteSchemaOut {
+            hotel_id
+            , room_type
+            , room_id           : req.room_id
+            , check_in_date     : check_in
+            , check_out_date    : check_out
+            , nights
+            , price_per_night
+            , subtotal_price    : subtotal
+            , discounts
+            , total_price       : total
+        })
+    }
+
     async fn get_guest_bookings(
         &self
         , guest_id: Uuid
@@ -149,17 +675,7 @@ impl BookingServiceTrait for BookingService {
             .all(&self.db)
             .await?;
 
-        Ok(res.into_iter().map(|b| BookingSchemaOut {
-            id              : b.id
-            , room_id       : b.room_id
-            , guest_id      : b.guest_id
-            , check_in_date : b.check_in_date
-            , check_out_date: b.check_out_date
-            , total_price   : b.total_price
-            , status        : b.status
-            , created_at    : b.created_at
-            , updated_at    : b.updated_at
-        }).collect())
+        Ok(res.into_iter().map(BookingSchemaOut::from).collect())
     }
 
     async fn get_room_bookings(
@@ -171,16 +687,274 @@ impl BookingServiceTrait for BookingService {
             .all(&self.db)
             .await?;
 
-        Ok(res.into_iter().map(|b| BookingSchemaOut {
-            id              : b.id
-            , room_id       : b.room_id
-            , guest_id      : b.guest_id
-            , check_in_date : b.check_in_date
-            , check_out_date: b.check_out_date
-            , total_price   : b.total_price
-            , status        : b.status
-            , created_at    : b.created_at
-            , updated_at    : b.updated_at
-        }).collect())
-    }
-}
\ No newline at end of file
+        Ok(res.into_iter().map(BookingSchemaOut::from).collect())
+    }
+
+    async fn complete_past_bookings(
+        &self
+        , now   : DateTime<FixedOffset>
+    ) -> Result<u64, DbErr> {
+        let txn = self.db.begin().await?;
+
+        // Confirmed stays whose check-out has passed are finished
+        let completed = bookings::Entity::update_many()
+            .set(bookings::ActiveModel {
+                status          : Set(BookingStatus::Completed)
+                , updated_at    : Set(Some(now))
+                , ..Default::default()
+            })
+            .filter(bookings::Column::Status.eq(BookingStatus::Confirmed))
+            .filter(bookings::Column::CheckOutDate.lt(now))
+            .exec_with_returning(&txn)
+            .await?;
+
+        for booking in completed.iter().cloned().map(BookingSchemaOut::from) {
+            LoyaltyService::sync_earning(&txn, &booking).await?;
+            events::record(&txn, DomainEvent::BookingCompleted(booking)).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(completed.len() as u64)
+    }
+
+    async fn expire_pending_bookings(
+        &self
+        , cutoff    : DateTime<FixedOffset>
+    ) -> Result<u64, DbErr> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let txn = self.db.begin().await?;
+
+        // Pending bookings never confirmed before the cutoff are released
+        let cancelled = bookings::Entity::update_many()
+            .set(bookings::ActiveModel {
+                status          : Set(BookingStatus::Cancelled)
+                , updated_at    : Set(Some(now))
+                , ..Default::default()
+            })
+            .filter(bookings::Column::Status.eq(BookingStatus::Pending))
+            .filter(bookings::Column::CreatedAt.lt(cutoff))
+            .exec_with_returning(&txn)
+            .await?;
+
+        // Points redeemed on them go back to the guests, as on any cancellation
+        for booking in cancelled.iter().cloned().map(BookingSchemaOut::from) {
+            LoyaltyService::sync_booking(&txn, &booking)
+                .await
+                .map_err(|e| DbErr::Custom(e.to_string()))?;
+            events::record(&txn, DomainEvent::BookingCancelled(booking)).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(cancelled.len() as u64)
+    }
+
+    async fn get_room_bookings_ics(
+        &self
+        , room_id   : Uuid
+    ) -> Result<Option<String>, ApiError> {
+        let (room, hotel) = match rooms::Entity::find_by_id(room_id)
+            .find_also_related(hotels::Entity)
+            .one(&self.db)
+            .await? {
+                Some(r) => r,
+                None    => return Ok(None),
+            };
+
+        let bookings = bookings::Entity::find()
+            .filter(bookings::Column::RoomId.eq(room_id))
+            .order_by_asc(bookings::Column::CheckInDate)
+            .all(&self.db)
+            .await?;
+
+        // Load every guest in one query
+        let guests: HashMap<Uuid, guests::Model> = guests::Entity::find()
+            .filter(guests::Column::Id.is_in(bookings.iter().map(|b| b.guest_id)))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|g| (g.id, g))
+            .collect();
+
+        let location = hotel.map(|h| format!("{}, {}", h.name, h.address));
+
+        let events: Vec<Event> = bookings.into_iter().map(|b| {
+            let guest_name = guests.get(&b.guest_id)
+                .map(|g| format!("{} {}", g.first_name, g.last_name))
+                .unwrap_or_else(|| "Unknown guest".to_string());
+
+            Event {
+                uid             : ical::booking_uid(b.id)
+                , stamp         : b.updated_at.unwrap_or(b.created_at)
+                , start         : b.check_in_date
+                , end           : b.check_out_date
+                , summary       : format!("Room {} - {}", room.room_number, guest_name)
+                , description   : Some(format!("Booking {}\nGuest: {}\nStatus: {:?}", b.id, guest_name, b.status))
+                , location      : location.clone()
+                , status        : Some(Self::event_status(&b.status))
+            }
+        }).collect();
+
+        Ok(Some(ical::writer::write_calendar(&format!("Room {} bookings", room.room_number), &events)))
+    }
+
+    async fn get_guest_bookings_ics(
+        &self
+        , guest_id  : Uuid
+    ) -> Result<Option<String>, ApiError> {
+        let guest = match guests::Entity::find_by_id(guest_id).one(&self.db).await? {
+            Some(g) => g,
+            None    => return Ok(None),
+        };
+
+        let bookings = bookings::Entity::find()
+            .filter(bookings::Column::GuestId.eq(guest_id))
+            .find_also_related(rooms::Entity)
+            .order_by_asc(bookings::Column::CheckInDate)
+            .all(&self.db)
+            .await?;
+
+        // Load every hotel in one query
+        let hotels: HashMap<Uuid, hotels::Model> = hotels::Entity::find()
+            .filter(hotels::Column::Id.is_in(bookings.iter().map(|(b, _)| b.hotel_id)))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|h| (h.id, h))
+            .collect();
+
+        let events: Vec<Event> = bookings.into_iter().map(|(b, room)| {
+            let hotel = hotels.get(&b.hotel_id);
+            let room_label = match room {
+                Some(r) => format!("Room {} ({})", r.room_number, r.room_type),
+                None    => format!("Room type {}", b.room_type),
+            };
+
+            Event {
+                uid             : ical::booking_uid(b.id)
+                , stamp         : b.updated_at.unwrap_or(b.created_at)
+                , start         : b.check_in_date
+                , end           : b.check_out_date
+                , summary       : match hotel {
+                    Some(h) => format!("Stay at {}", h.name),
+                    None    => "Hotel stay".to_string(),
+                }
+                , description   : Some(format!("Booking {}\n{}\nTotal price: {}", b.id, room_label, b.total_price))
+                , location      : hotel.map(|h| format!("{}, {}", h.name, h.address))
+                , status        : Some(Self::event_status(&b.status))
+            }
+        }).collect();
+
+        let name = format!("{} {} bookings", guest.first_name, guest.last_name);
+        Ok(Some(ical::writer::write_calendar(&name, &events)))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::sea_query::Expr;
+    use crate::models::{loyalty_entries, sea_orm_active_enums::{HousekeepingStatus, LoyaltyEntryKind}};
+
+    async fn connect() -> DatabaseConnection {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+        Database::connect(url).await.expect("Failed to connect to database")
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated Postgres database in DATABASE_URL"]
+    async fn expiring_a_booking_refunds_its_redeemed_points() {
+        let db = connect().await;
+        let now = Utc::now().fixed_offset();
+        let long_ago = DateTime::parse_from_rfc3339("2000-01-01T00:00:00Z").unwrap();
+
+        let hotel = hotels::ActiveModel {
+            id              : Set(Uuid::new_v4())
+            , name          : Set(format!("Expiry test {}", Uuid::new_v4()))
+            , address       : Set("1 Test Street".to_string())
+            , review_count  : Set(0)
+            , created_at    : Set(now)
+            , ..Default::default()
+        }.insert(&db).await.unwrap();
+
+        let room = rooms::ActiveModel {
+            id                      : Set(Uuid::new_v4())
+            , hotel_id              : Set(hotel.id)
+            , room_number           : Set("101".to_string())
+            , room_type             : Set("double".to_string())
+            , price_per_night       : Set(Decimal::from(100))
+            , is_available          : Set(true)
+            , housekeeping_status   : Set(HousekeepingStatus::Clean)
+            , created_at            : Set(now)
+            , ..Default::default()
+        }.insert(&db).await.unwrap();
+
+        let guest = guests::ActiveModel {
+            id                          : Set(Uuid::new_v4())
+            , first_name                : Set("Expiry".to_string())
+            , last_name                 : Set("Test".to_string())
+            , email                     : Set(format!("expiry-{}@example.com", Uuid::new_v4()))
+            , marketing_email_consent   : Set(false)
+            , marketing_sms_consent     : Set(false)
+            , created_at                : Set(now)
+            , ..Default::default()
+        }.insert(&db).await.unwrap();
+
+        loyalty_entries::ActiveModel {
+            id              : Set(Uuid::new_v4())
+            , guest_id      : Set(guest.id)
+            , booking_id    : Set(None)
+            , kind          : Set(LoyaltyEntryKind::Earned)
+            , points        : Set(100)
+            , created_at    : Set(now)
+        }.insert(&db).await.unwrap();
+
+        let check_in = DateTime::parse_from_rfc3339("2040-07-01T15:00:00Z").unwrap();
+
+        let booking = BookingService::new(db.clone()).create_booking(BookingSchemaIn {
+            room_id         : Some(room.id)
+            , hotel_id      : None
+            , room_type     : None
+            , guest_id      : guest.id
+            , check_in_date : check_in.into()
+            , check_out_date: (check_in + chrono::Duration::days(2)).into()
+            , total_price   : Decimal::from(200)
+            , status        : BookingStatus::Pending
+            , redeem_points : Some(40)
+            , promo_code    : None
+        }).await.unwrap();
+
+        assert_eq!(LoyaltyService::balance(&db, guest.id).await.unwrap(), 60);
+
+        // Only this booking is old enough to expire
+        bookings::Entity::update_many()
+            .col_expr(bookings::Column::CreatedAt, Expr::value(long_ago))
+            .filter(bookings::Column::Id.eq(booking.id))
+            .exec(&db)
+            .await
+            .unwrap();
+
+        let expired = BookingService::new(db.clone())
+            .expire_pending_bookings(long_ago + chrono::Duration::days(1))
+            .await;
+
+        let status = bookings::Entity::find_by_id(booking.id).one(&db).await.unwrap().map(|b| b.status);
+        let balance = LoyaltyService::balance(&db, guest.id).await.unwrap();
+
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres
+            , "DELETE FROM outbox WHERE payload::text LIKE '%' || $1 || '%'"
+            , [guest.id.to_string().into()]
+        )).await.unwrap();
+        loyalty_entries::Entity::delete_many().filter(loyalty_entries::Column::GuestId.eq(guest.id)).exec(&db).await.unwrap();
+        bookings::Entity::delete_by_id(booking.id).exec(&db).await.unwrap();
+        guests::Entity::delete_by_id(guest.id).exec(&db).await.unwrap();
+        rooms::Entity::delete_by_id(room.id).exec(&db).await.unwrap();
+        hotels::Entity::delete_by_id(hotel.id).exec(&db).await.unwrap();
+
+        assert_eq!(expired.unwrap(), 1);
+        assert_eq!(status, Some(BookingStatus::Cancelled));
+        assert_eq!(balance, 100);
+    }
+}
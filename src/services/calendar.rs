@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate};
+use chrono_tz::Tz;
+use crate::{
+    models::{bookings, external_blocks, external_calendars, hotels, room_blocks, rooms, sea_orm_active_enums::BookingStatus},
+    schemas::calendar::*,
+    services::{availability::{self, start_of_day}, traits::CalendarServiceTrait},
+    error::ApiError,
+};
+
+/// Longest range a calendar may cover
+const MAX_CALENDAR_DAYS: i64 = 366;
+
+#[derive(Clone)]
+pub struct CalendarService {
+    db  : DatabaseConnection
+}
+
+/// Index of the first and one past the last night of `[start, end)` within a
+/// grid starting at `from` with `days` columns, `None` when outside the grid.
+/// Nights are local to the hotel's timezone `tz`.
+fn night_span(
+    from    : NaiveDate
+    , days  : usize
+    , start : DateTime<FixedOffset>
+    , end   : DateTime<FixedOffset>
+    , tz    : Tz
+) -> Option<(usize, usize)> {
+    let (start, end) = availability::stay_nights(start, end, tz);
+    let first = (start - from).num_days().max(0);
+    let last = (end - from).num_days().min(days as i64);
+
+    (first < last).then_some((first as usize, last as usize))
+}
+
+impl CalendarService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Fills the cells of `[start, end)` that are still free, so stronger
+    /// claims placed first (bookings before holds before blocks) win
+    fn mark(
+        cells   : &mut [CalendarCell]
+        , from  : NaiveDate
+        , start : DateTime<FixedOffset>
+        , end   : DateTime<FixedOffset>
+        , tz    : Tz
+        , cell  : CalendarCell
+    ) {
+        let Some((first, last)) = night_span(from, cells.len(), start, end, tz) else { return };
+
+        for slot in &mut cells[first..last] {
+            if slot.status == CalendarCellStatus::Free {
+                *slot = cell.clone();
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl CalendarServiceTrait for CalendarService {
+    async fn hotel_calendar(
+        &self
+        , hotel_id  : Uuid
+        , from      : NaiveDate
+        , to        : NaiveDate
+    ) -> Result<Option<CalendarGrid>, ApiError> {
+        if to <= from {
+            return Err(ApiError::Validation("to must be after from".to_string()));
+        }
+
+        if (to - from).num_days() > MAX_CALENDAR_DAYS {
+            return Err(ApiError::Validation(format!("The calendar covers at most {} days", MAX_CALENDAR_DAYS)));
+        }
+
+        let Some(hotel) = hotels::Entity::find_by_id(hotel_id).one(&self.db).await? else {
+            return Ok(None);
+        };
+
+        let tz = availability::timezone(&hotel.timezone);
+        let dates = from.iter_days().take_while(|d| *d < to).collect::<Vec<_>>();
+        let range_start = start_of_day(from, tz);
+        let range_end = start_of_day(to, tz);
+
+        let rooms = rooms::Entity::find()
+            .filter(rooms::Column::HotelId.eq(hotel_id))
+            .order_by_asc(rooms::Column::RoomNumber)
+            .all(&self.db)
+            .await?;
+
+        let room_ids = rooms.iter().map(|r| r.id).collect::<Vec<_>>();
+
+        // One query each for bookings and blocks of all rooms, never per room
+        let bookings = bookings::Entity::find()
+            .filter(bookings::Column::RoomId.is_in(room_ids.clone()))
+            .filter(bookings::Column::Status.is_in([BookingStatus::Pending, BookingStatus::Confirmed, BookingStatus::Completed]))
+            .filter(bookings::Column::CheckInDate.lt(range_end))
+            .filter(bookings::Column::CheckOutDate.gt(range_start))
+            .all(&self.db)
+            .await?;
+
+        let room_blocks = room_blocks::Entity::find()
+            .filter(room_blocks::Column::RoomId.is_in(room_ids.clone()))
+            .filter(room_blocks::Column::StartDate.lt(to))
+            .filter(room_blocks::Column::EndDate.gte(from))
+            .all(&self.db)
+            .await?;
+
+        let blocks = external_blocks::Entity::find()
+            .find_also_related(external_calendars::Entity)
+            .filter(external_blocks::Column::RoomId.is_in(room_ids))
+            .filter(external_blocks::Column::StartsAt.lt(range_end))
+            .filter(external_blocks::Column::EndsAt.gt(range_start))
+            .all(&self.db)
+            .await?;
+
+        let free = CalendarCell {
+            status      : CalendarCellStatus::Free,
+            booking_id  : None,
+            note        : None,
+        };
+
+        let mut grid = rooms.iter()
+            .map(|r| {
+                let cell = match r.is_available {
+                    true    => free.clone(),
+                    false   => CalendarCell {
+                        status      : CalendarCellStatus::Blocked,
+                        booking_id  : None,
+                        note        : Some("Room not available".to_string()),
+                    },
+                };
+
+                (r.id, vec![cell; dates.len()])
+            })
+            .collect::<HashMap<_, _>>();
+
+        let (confirmed, pending): (Vec<_>, Vec<_>) = bookings.into_iter()
+            .partition(|b| b.status != BookingStatus::Pending);
+
+        for (booking, status) in confirmed.into_iter().map(|b| (b, CalendarCellStatus::Booked))
+            .chain(pending.into_iter().map(|b| (b, CalendarCellStatus::Held))) {
+            if let Some(cells) = booking.room_id.and_then(|id| grid.get_mut(&id)) {
+                Self::mark(cells, from, booking.check_in_date, booking.check_out_date, tz, CalendarCell {
+                    status,
+                    booking_id  : Some(booking.id),
+                    note        : None,
+                });
+            }
+        }
+
+        for block in room_blocks {
+            if let Some(cells) = grid.get_mut(&block.room_id) {
+                let end = start_of_day(block.end_date + Duration::days(1), tz);
+
+                Self::mark(cells, from, start_of_day(block.start_date, tz), end, tz, CalendarCell {
+                    status      : CalendarCellStatus::Blocked,
+                    booking_id  : None,
+                    note        : Some(block.reason),
+                });
+            }
+        }
+
+        for (block, calendar) in blocks {
+            if let Some(cells) = grid.get_mut(&block.room_id) {
+                let note = match (calendar, block.summary) {
+                    (Some(c), Some(summary))    => format!("{}: {}", c.name, summary),
+                    (Some(c), None)             => c.name,
+                    (None, summary)             => summary.unwrap_or_else(|| "External calendar".to_string()),
+                };
+
+                Self::mark(cells, from, block.starts_at, block.ends_at, tz, CalendarCell {
+                    status      : CalendarCellStatus::Blocked,
+                    booking_id  : None,
+                    note        : Some(note),
+                });
+            }
+        }
+
+        Ok(Some(CalendarGrid {
+            hotel_id,
+            from,
+            to,
+            dates,
+            rooms       : rooms.into_iter()
+                .map(|r| CalendarRow {
+                    cells       : grid.remove(&r.id).unwrap_or_default(),
+                    room_id     : r.id,
+                    room_number : r.room_number,
+                    room_type   : r.room_type,
+                })
+                .collect(),
+        }))
+    }
+}
@@ -0,0 +1,164 @@
+use std::pin::Pin;
+use rocket::futures::{Stream, StreamExt};
+use sea_orm::*;
+use crate::{
+    models::{bookings, guests, hotels, rooms},
+    schemas::{booking::BookingFilter, guests::GuestFilter, hotels::HotelFilter, rooms::RoomFilter, exports::*},
+    services::{
+        bookings::BookingService, guests::GuestService, hotels::HotelService, rooms::RoomService
+        , traits::ExportServiceTrait
+    },
+    error::ApiError,
+};
+
+/// Encoded export lines, produced while the rows are read from the database
+pub type ExportStream = Pin<Box<dyn Stream<Item = String> + Send>>;
+
+#[derive(Clone)]
+pub struct ExportService {
+    db  : DatabaseConnection
+}
+
+impl ExportService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Streams `select` row by row, so exports never hold the whole table in memory.
+    /// The first row is read before the response starts, so a failing query is
+    /// reported as an error. A later failure ends the file with the format's
+    /// aborted line, the status line being already sent.
+    async fn export<R>(&self, select: Selector<SelectModel<R>>, format: ExportFormat) -> Result<ExportStream, ApiError>
+    where
+        R: ExportRow + FromQueryResult + Send + Sync + 'static,
+    {
+        let db = self.db.clone();
+
+        let mut lines = Box::pin(async_stream::stream! {
+            let mut rows = match select.stream(&db).await {
+                Ok(rows)    => rows,
+                Err(err)    => {
+                    yield Err(err);
+                    return;
+                }
+            };
+
+            while let Some(row) = rows.next().await {
+                yield row.and_then(|row| match format {
+                    ExportFormat::Csv       => encode_csv(&row).map_err(|e| DbErr::Custom(e.to_string())),
+                    ExportFormat::Ndjson    => serde_json::to_string(&row)
+                        .map(|json| json + "\n")
+                        .map_err(|e| DbErr::Custom(e.to_string())),
+                });
+            }
+        });
+
+        let first = lines.next().await.transpose()?;
+
+        Ok(Box::pin(async_stream::stream! {
+            if format == ExportFormat::Csv {
+                yield R::HEADERS.join(",") + "\n";
+            }
+
+            let Some(first) = first else { return };
+            yield first;
+
+            while let Some(line) = lines.next().await {
+                match line {
+                    Ok(line)    => yield line,
+                    Err(err)    => {
+                        println!("⚠️ Export aborted: {}", err);
+                        yield format.aborted_line().to_string();
+                        break;
+                    }
+                }
+            }
+        }))
+    }
+}
+
+/// One CSV record, quoted where needed and terminated by a newline
+fn encode_csv<T: serde::Serialize>(record: &T) -> Result<String, csv::Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new());
+
+    writer.serialize(record)?;
+
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[async_trait]
+impl ExportServiceTrait for ExportService {
+    async fn export_bookings(&self, filter: BookingFilter, format: ExportFormat) -> Result<ExportStream, ApiError> {
+        let select = bookings::Entity::find()
+            .select_only()
+            .column(bookings::Column::Id)
+            .column_as(hotels::Column::Id, "hotel_id")
+            .column_as(hotels::Column::Name, "hotel_name")
+            .column(bookings::Column::RoomId)
+            .column(rooms::Column::RoomNumber)
+            .column(bookings::Column::RoomType)
+            .column(bookings::Column::GuestId)
+            .column_as(guests::Column::FirstName, "guest_first_name")
+            .column_as(guests::Column::LastName, "guest_last_name")
+            .column_as(guests::Column::Email, "guest_email")
+            .column(bookings::Column::CheckInDate)
+            .column(bookings::Column::CheckOutDate)
+            .column(bookings::Column::TotalPrice)
+            .column(bookings::Column::Status)
+            .column(bookings::Column::CreatedAt)
+            .column(bookings::Column::UpdatedAt)
+            .join(JoinType::LeftJoin, bookings::Relation::Rooms.def())
+            .join(JoinType::InnerJoin, bookings::Relation::Hotels.def())
+            .join(JoinType::InnerJoin, bookings::Relation::Guests.def())
+            .filter(BookingService::filter_condition(&filter))
+            .order_by_asc(bookings::Column::CheckInDate)
+            .order_by_asc(bookings::Column::Id)
+            .into_model::<BookingExportRow>();
+
+        self.export(select, format).await
+    }
+
+    async fn export_rooms(&self, filter: RoomFilter, format: ExportFormat) -> Result<ExportStream, ApiError> {
+        let select = rooms::Entity::find()
+            .select_only()
+            .column(rooms::Column::Id)
+            .column(rooms::Column::HotelId)
+            .column_as(hotels::Column::Name, "hotel_name")
+            .column(rooms::Column::RoomNumber)
+            .column(rooms::Column::RoomType)
+            .column(rooms::Column::PricePerNight)
+            .column(rooms::Column::IsAvailable)
+            .column(rooms::Column::Description)
+            .column(rooms::Column::CreatedAt)
+            .column(rooms::Column::UpdatedAt)
+            .join(JoinType::InnerJoin, rooms::Relation::Hotels.def())
+            .filter(RoomService::filter_condition(&filter))
+            .order_by_asc(hotels::Column::Name)
+            .order_by_asc(rooms::Column::RoomNumber)
+            .into_model::<RoomExportRow>();
+
+        self.export(select, format).await
+    }
+
+    async fn export_guests(&self, filter: GuestFilter, format: ExportFormat) -> Result<ExportStream, ApiError> {
+        let select = guests::Entity::find()
+            .filter(GuestService::filter_condition(&filter))
+            .order_by_asc(guests::Column::LastName)
+            .order_by_asc(guests::Column::FirstName)
+            .into_model::<GuestExportRow>();
+
+        self.export(select, format).await
+    }
+
+    async fn export_hotels(&self, filter: HotelFilter, format: ExportFormat) -> Result<ExportStream, ApiError> {
+        let select = hotels::Entity::find()
+            .filter(HotelService::filter_condition(&filter))
+            .order_by_asc(hotels::Column::Name)
+            .into_model::<HotelExportRow>();
+
+        self.export(select, format).await
+    }
+}
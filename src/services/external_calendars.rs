@@ -0,0 +1,268 @@
+use std::time::Duration;
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use crate::{
+    ical::{parser, EventStatus},
+    models::{external_blocks, external_calendars, rooms},
+    schemas::external_calendars::*,
+    services::traits::ExternalCalendarServiceTrait,
+    error::ApiError,
+};
+
+const FETCH_TIMEOUT_SECS: u64 = 20;
+
+#[derive(Clone)]
+pub struct ExternalCalendarService {
+    db  : DatabaseConnection
+}
+
+impl ExternalCalendarService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Re-imports every calendar with a feed URL, returning how many synced cleanly.
+    /// Failures are recorded on the calendar instead of aborting the run.
+    pub async fn sync_all(&self) -> Result<u64, DbErr> {
+        let calendars = external_calendars::Entity::find()
+            .filter(external_calendars::Column::Url.is_not_null())
+            .all(&self.db)
+            .await?;
+
+        let mut synced = 0;
+
+        for calendar in calendars {
+            match self.sync(calendar).await {
+                Ok(_)                           => synced += 1,
+                Err(ApiError::Database(e))      => return Err(e),
+                Err(_)                          => {}
+            }
+        }
+
+        Ok(synced)
+    }
+
+    async fn sync(&self, calendar: external_calendars::Model) -> Result<external_calendars::Model, ApiError> {
+        let Some(url) = calendar.url.clone() else {
+            return Err(ApiError::Validation("Calendar has no feed url to sync from".to_string()));
+        };
+
+        match Self::fetch(&url).await {
+            Ok(body)    => self.import(calendar, &body).await,
+            Err(e)      => {
+                self.record_error(calendar, e.clone()).await?;
+                Err(ApiError::ExternalCalendar(e))
+            }
+        }
+    }
+
+    async fn fetch(url: &str) -> Result<String, String> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        let res = client.get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+
+        res.text().await.map_err(|e| format!("Failed to read {}: {}", url, e))
+    }
+
+    /// Replaces the blocks of `calendar` with the events of `ics` in one transaction
+    async fn import(
+        &self
+        , calendar  : external_calendars::Model
+        , ics       : &str
+    ) -> Result<external_calendars::Model, ApiError> {
+        let events = match parser::parse_events(ics) {
+            Ok(events)  => events,
+            Err(e)      => {
+                self.record_error(calendar, e.clone()).await?;
+                return Err(ApiError::Validation(format!("Invalid iCalendar data: {}", e)));
+            }
+        };
+
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let blocks = events.into_iter()
+            .filter(|e| e.status != Some(EventStatus::Cancelled) && e.end > e.start)
+            .map(|e| external_blocks::ActiveModel {
+                id              : Set(Uuid::new_v4())
+                , calendar_id   : Set(calendar.id)
+                , room_id       : Set(calendar.room_id)
+                , uid           : Set(e.uid)
+                , summary       : Set(Some(e.summary).filter(|s| !s.is_empty()))
+                , starts_at     : Set(e.start)
+                , ends_at       : Set(e.end)
+                , created_at    : Set(now)
+            })
+            .collect::<Vec<_>>();
+
+        let txn = self.db.begin().await?;
+
+        external_blocks::Entity::delete_many()
+            .filter(external_blocks::Column::CalendarId.eq(calendar.id))
+            .exec(&txn)
+            .await?;
+
+        if !blocks.is_empty() {
+            external_blocks::Entity::insert_many(blocks)
+                .exec_without_returning(&txn)
+                .await?;
+        }
+
+        let mut calendar: external_calendars::ActiveModel = calendar.into();
+
+        calendar.last_synced_at = Set(Some(now));
+        calendar.last_error     = Set(None);
+
+        let calendar = calendar.update(&txn).await?;
+
+        txn.commit().await?;
+        Ok(calendar)
+    }
+
+    async fn record_error(&self, calendar: external_calendars::Model, error: String) -> Result<(), DbErr> {
+        let mut calendar: external_calendars::ActiveModel = calendar.into();
+
+        calendar.last_error = Set(Some(error));
+
+        calendar.update(&self.db).await?;
+        Ok(())
+    }
+
+    async fn find(&self, room_id: Uuid, id: Uuid) -> Result<Option<external_calendars::Model>, DbErr> {
+        external_calendars::Entity::find_by_id(id)
+            .filter(external_calendars::Column::RoomId.eq(room_id))
+            .one(&self.db)
+            .await
+    }
+
+    fn validate(req: &ExternalCalendarSchemaIn) -> Result<(), ApiError> {
+        if req.name.trim().is_empty() {
+            return Err(ApiError::Validation("name must not be empty".to_string()));
+        }
+
+        if let Some(url) = &req.url {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(ApiError::Validation("url must start with http:// or https://".to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn to_schema(c: external_calendars::Model) -> ExternalCalendarSchemaOut {
+        ExternalCalendarSchemaOut {
+            id                  : c.id
+            , room_id           : c.room_id
+            , name              : c.name
+            , url               : c.url
+            , last_synced_at    : c.last_synced_at
+            , last_error        : c.last_error
+            , created_at        : c.created_at
+            , updated_at        : c.updated_at
+        }
+    }
+}
+
+#[async_trait]
+impl ExternalCalendarServiceTrait for ExternalCalendarService {
+    async fn list_calendars(&self, room_id: Uuid) -> Result<Vec<ExternalCalendarSchemaOut>, ApiError> {
+        let res = external_calendars::Entity::find()
+            .filter(external_calendars::Column::RoomId.eq(room_id))
+            .order_by_asc(external_calendars::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(res.into_iter().map(Self::to_schema).collect())
+    }
+
+    async fn create_calendar(
+        &self
+        , room_id   : Uuid
+        , req       : ExternalCalendarSchemaIn
+    ) -> Result<ExternalCalendarSchemaOut, ApiError> {
+        Self::validate(&req)?;
+
+        rooms::Entity::find_by_id(room_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| ApiError::RoomNotFound(room_id.to_string()))?;
+
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let calendar = external_calendars::ActiveModel {
+            id                  : Set(Uuid::new_v4())
+            , room_id           : Set(room_id)
+            , name              : Set(req.name)
+            , url               : Set(req.url)
+            , last_synced_at    : Set(None)
+            , last_error        : Set(None)
+            , created_at        : Set(now)
+            , updated_at        : Set(None)
+        };
+
+        let res = calendar.insert(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(Self::to_schema(res))
+    }
+
+    async fn delete_calendar(&self, room_id: Uuid, id: Uuid) -> Result<bool, ApiError> {
+        let res = external_calendars::Entity::delete_many()
+            .filter(external_calendars::Column::Id.eq(id))
+            .filter(external_calendars::Column::RoomId.eq(room_id))
+            .exec(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(res.rows_affected > 0)
+    }
+
+    async fn sync_calendar(&self, room_id: Uuid, id: Uuid) -> Result<Option<ExternalCalendarSchemaOut>, ApiError> {
+        let Some(calendar) = self.find(room_id, id).await? else { return Ok(None) };
+
+        Ok(Some(Self::to_schema(self.sync(calendar).await?)))
+    }
+
+    async fn upload_calendar(
+        &self
+        , room_id   : Uuid
+        , id        : Uuid
+        , ics       : String
+    ) -> Result<Option<ExternalCalendarSchemaOut>, ApiError> {
+        let Some(calendar) = self.find(room_id, id).await? else { return Ok(None) };
+
+        Ok(Some(Self::to_schema(self.import(calendar, &ics).await?)))
+    }
+
+    async fn list_blocks(&self, room_id: Uuid, id: Uuid) -> Result<Option<Vec<ExternalBlockSchemaOut>>, ApiError> {
+        if self.find(room_id, id).await?.is_none() {
+            return Ok(None);
+        }
+
+        let res = external_blocks::Entity::find()
+            .filter(external_blocks::Column::CalendarId.eq(id))
+            .order_by_asc(external_blocks::Column::StartsAt)
+            .all(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(Some(res.into_iter().map(|b| ExternalBlockSchemaOut {
+            id              : b.id
+            , calendar_id   : b.calendar_id
+            , room_id       : b.room_id
+            , uid           : b.uid
+            , summary       : b.summary
+            , starts_at     : b.starts_at
+            , ends_at       : b.ends_at
+        }).collect()))
+    }
+}
@@ -1,18 +1,45 @@
 use rocket::Request;
+use rocket::http::Status;
 use rocket::request::{FromRequest, Outcome};
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
+use crate::storage::MediaStorage;
+use crate::error::ApiError;
 use crate::services::{
     rooms::RoomService
+    , room_blocks::RoomBlockService
+    , housekeeping::HousekeepingService
     , hotels::HotelService
+    , amenities::AmenityService
     , guests::GuestService
     , bookings::BookingService
-    , traits::{RoomServiceTrait, HotelServiceTrait, GuestServiceTrait, BookingServiceTrait}
+    , reservations::ReservationService
+    , waitlist::WaitlistService
+    , reviews::ReviewService
+    , media::MediaService
+    , privacy::PrivacyService
+    , audit::AuditService
+    , loyalty::LoyaltyService
+    , promotions::PromotionService
+    , webhooks::WebhookService
+    , notifications::NotificationService
+    , external_calendars::ExternalCalendarService
+    , exports::ExportService
+    , imports::ImportService
+    , reports::ReportService
+    , calendar::CalendarService
+    , search::SearchService
+    , traits::{
+        RoomServiceTrait, RoomBlockServiceTrait, HousekeepingServiceTrait, HotelServiceTrait, AmenityServiceTrait, GuestServiceTrait, BookingServiceTrait, ReservationServiceTrait, WaitlistServiceTrait, ReviewServiceTrait, MediaServiceTrait, PrivacyServiceTrait, AuditServiceTrait, LoyaltyServiceTrait, PromotionServiceTrait, WebhookServiceTrait
+        , NotificationServiceTrait, ExternalCalendarServiceTrait, ExportServiceTrait
+        , ImportServiceTrait, ReportServiceTrait, CalendarServiceTrait, SearchServiceTrait
+    }
 };
 
 
 pub struct ServiceGuard {
     db: Arc<DatabaseConnection>,
+    storage: Arc<dyn MediaStorage>,
 }
 
 #[rocket::async_trait]
@@ -22,19 +49,77 @@ impl<'r> FromRequest<'r> for ServiceGuard {
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
         let db = request.rocket().state::<Arc<DatabaseConnection>>()
             .expect("database connection not managed");
-        Outcome::Success(ServiceGuard { db: db.clone() })
+        let storage = request.rocket().state::<Arc<dyn MediaStorage>>()
+            .expect("media storage not managed");
+        Outcome::Success(ServiceGuard { db: db.clone(), storage: storage.clone() })
+    }
+}
+
+/// Key unlocking the sensitive guest data, without one it is never returned
+pub struct StaffApiKey(pub Option<String>);
+
+/// Who is calling, staff send the key in the `X-Api-Key` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessLevel {
+    Public
+    , Staff
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AccessLevel {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(given) = request.headers().get_one("X-Api-Key") else {
+            return Outcome::Success(AccessLevel::Public);
+        };
+
+        let expected = request.rocket().state::<StaffApiKey>()
+            .and_then(|key| key.0.as_deref());
+
+        // A wrong key is an error rather than a silent downgrade to public
+        match expected {
+            Some(expected) if constant_time_eq(given.as_bytes(), expected.as_bytes()) => Outcome::Success(AccessLevel::Staff),
+            _ => Outcome::Error((Status::Unauthorized, ())),
+        }
+    }
+}
+
+impl AccessLevel {
+    /// Refuses anything but staff
+    pub fn require_staff(self) -> Result<(), ApiError> {
+        match self {
+            AccessLevel::Staff  => Ok(()),
+            AccessLevel::Public => Err(ApiError::Forbidden("the staff API key is required".to_string())),
+        }
     }
 }
 
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 impl ServiceGuard {
     pub fn rooms(&self) -> impl RoomServiceTrait + '_ {
         RoomService::new((*self.db).clone())
     }
 
+    pub fn room_blocks(&self) -> impl RoomBlockServiceTrait + '_ {
+        RoomBlockService::new((*self.db).clone())
+    }
+
+    pub fn housekeeping(&self) -> impl HousekeepingServiceTrait + '_ {
+        HousekeepingService::new((*self.db).clone())
+    }
+
     pub fn hotels(&self) -> impl HotelServiceTrait + '_ {
         HotelService::new((*self.db).clone())
     }
 
+    pub fn amenities(&self) -> impl AmenityServiceTrait + '_ {
+        AmenityService::new((*self.db).clone())
+    }
+
     pub fn guests(&self) -> impl GuestServiceTrait + '_ {
         GuestService::new((*self.db).clone())
     }
@@ -42,5 +127,69 @@ impl ServiceGuard {
     pub fn bookings(&self) -> impl BookingServiceTrait + '_ {
         BookingService::new((*self.db).clone())
     }
+
+    pub fn reservations(&self) -> impl ReservationServiceTrait + '_ {
+        ReservationService::new((*self.db).clone())
+    }
+
+    pub fn waitlist(&self) -> impl WaitlistServiceTrait + '_ {
+        WaitlistService::new((*self.db).clone())
+    }
+
+    pub fn reviews(&self) -> impl ReviewServiceTrait + '_ {
+        ReviewService::new((*self.db).clone())
+    }
+
+    pub fn media(&self) -> impl MediaServiceTrait + '_ {
+        MediaService::new((*self.db).clone(), self.storage.clone())
+    }
+
+    pub fn privacy(&self) -> impl PrivacyServiceTrait + '_ {
+        PrivacyService::new((*self.db).clone())
+    }
+
+    pub fn audit(&self) -> impl AuditServiceTrait + '_ {
+        AuditService::new((*self.db).clone())
+    }
+
+    pub fn loyalty(&self) -> impl LoyaltyServiceTrait + '_ {
+        LoyaltyService::new((*self.db).clone())
+    }
+
+    pub fn promotions(&self) -> impl PromotionServiceTrait + '_ {
+        PromotionService::new((*self.db).clone())
+    }
+
+    pub fn webhooks(&self) -> impl WebhookServiceTrait + '_ {
+        WebhookService::new((*self.db).clone())
+    }
+
+    pub fn notifications(&self) -> impl NotificationServiceTrait + '_ {
+        NotificationService::new((*self.db).clone())
+    }
+
+    pub fn external_calendars(&self) -> impl ExternalCalendarServiceTrait + '_ {
+        ExternalCalendarService::new((*self.db).clone())
+    }
+
+    pub fn exports(&self) -> impl ExportServiceTrait + '_ {
+        ExportService::new((*self.db).clone())
+    }
+
+    pub fn imports(&self) -> impl ImportServiceTrait + '_ {
+        ImportService::new((*self.db).clone())
+    }
+
+    pub fn reports(&self) -> impl ReportServiceTrait + '_ {
+        ReportService::new((*self.db).clone())
+    }
+
+    pub fn calendar(&self) -> impl CalendarServiceTrait + '_ {
+        CalendarService::new((*self.db).clone())
+    }
+
+    pub fn search(&self) -> impl SearchServiceTrait + '_ {
+        SearchService::new((*self.db).clone())
+    }
  }
 
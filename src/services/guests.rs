@@ -1,13 +1,70 @@
 // services/guests.rs
+use std::collections::{HashMap, HashSet};
 use sea_orm::*;
+use sea_orm::sea_query::{extension::postgres::PgExpr, Expr};
 use uuid::Uuid;
 use chrono::{Utc, FixedOffset};
 use crate::{
-    models::guests,
-    schemas::guests::*,
-    services::traits::GuestServiceTrait,
+    events::{self, DomainEvent},
+    models::{bookings, guests, loyalty_entries, reservations, reviews, waitlist_entries},
+    schemas::{audit::AuditAction, guests::*},
+    services::{audit::AuditService, guards::AccessLevel, search, traits::GuestServiceTrait},
+    error::ApiError,
 };
 
+const MAX_ALLERGIES_CHARS: usize = 2000;
+
+const DEFAULT_MIN_DUPLICATE_SCORE: f64 = 0.6;
+const DEFAULT_DUPLICATE_LIMIT: u64 = 100;
+const MAX_DUPLICATE_LIMIT: u64 = 500;
+
+/// Pairs of live guests whose names or emails are trigram-similar or whose
+/// phones have the same digits, scored by the average of the name similarity
+/// and the best contact match. The `%` candidates use the trigram indexes.
+const DUPLICATES_SQL: &str = r#"
+WITH live AS (
+    SELECT id
+        , lower(first_name || ' ' || last_name) AS full_name
+        , lower(email) AS email
+        , nullif(regexp_replace(coalesce(phone, ''), '\D', '', 'g'), '') AS phone
+    FROM guests
+    WHERE merged_into_id IS NULL AND anonymized_at IS NULL
+),
+pairs AS (
+    SELECT a.id AS guest_id
+        , b.id AS duplicate_id
+        , similarity(a.full_name, b.full_name)::double precision AS name_similarity
+        , similarity(a.email, b.email)::double precision AS email_similarity
+        , coalesce(a.phone = b.phone AND length(a.phone) >= 6, false) AS phone_match
+    FROM live a
+    JOIN live b ON a.id < b.id
+        AND (a.full_name % b.full_name OR a.email % b.email OR a.phone = b.phone)
+),
+scored AS (
+    SELECT *
+        , (name_similarity + greatest(email_similarity, CASE WHEN phone_match THEN 1 ELSE 0 END)) / 2 AS score
+    FROM pairs
+)
+SELECT guest_id, duplicate_id, round(score::numeric, 3)::double precision AS score
+    , round(name_similarity::numeric, 3)::double precision AS name_similarity
+    , round(email_similarity::numeric, 3)::double precision AS email_similarity
+    , phone_match
+FROM scored
+WHERE score >= $1
+ORDER BY score DESC, guest_id, duplicate_id
+LIMIT $2
+"#;
+
+#[derive(Debug, FromQueryResult)]
+struct DuplicateRow {
+    guest_id            : Uuid
+    , duplicate_id      : Uuid
+    , score             : f64
+    , name_similarity   : f64
+    , email_similarity  : f64
+    , phone_match       : bool
+}
+
 #[derive(Clone)]
 pub struct GuestService {
     db  : DatabaseConnection
@@ -17,6 +74,175 @@ impl GuestService {
     pub fn new(db   : DatabaseConnection) -> Self {
         Self { db }
     }
+
+    /// Where clause of the list and export endpoints
+    pub fn filter_condition(filter: &GuestFilter) -> Condition {
+        // Tombstones of merged guests are only reachable by id
+        let mut condition = Condition::all().add(guests::Column::MergedIntoId.is_null());
+
+        if let Some(name) = &filter.name {
+            let pattern = search::contains_pattern(name);
+
+            condition = condition.add(
+                Condition::any()
+                    .add(Expr::col(guests::Column::FirstName).ilike(pattern.clone()))
+                    .add(Expr::col(guests::Column::LastName).ilike(pattern))
+            );
+        }
+
+        if let Some(email) = &filter.email {
+            condition = condition.add(Expr::col(guests::Column::Email).ilike(search::contains_pattern(email)));
+        }
+
+        condition
+    }
+
+    /// Guest as `access` may see it
+    fn present(guest: guests::Model, access: AccessLevel) -> GuestSchemaOut {
+        let guest = GuestSchemaOut::from(guest);
+
+        match access {
+            AccessLevel::Staff  => guest,
+            AccessLevel::Public => guest.redacted(),
+        }
+    }
+
+    /// Trimmed `value`, blank ones are dropped
+    /// Whether a column of the guest, as it will be saved, holds a value
+    fn is_filled<T>(value: &ActiveValue<Option<T>>) -> bool
+    where
+        Option<T>: Into<Value>,
+    {
+        matches!(value, ActiveValue::Set(Some(_)) | ActiveValue::Unchanged(Some(_)))
+    }
+
+    fn clean(value: Option<String>) -> Option<String> {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Upper cased ISO 3166-1 alpha-2 `code`
+    fn clean_country(field: &str, code: Option<String>) -> Result<Option<String>, ApiError> {
+        let Some(code) = Self::clean(code) else { return Ok(None) };
+
+        if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(ApiError::Validation(format!("{} must be an ISO 3166-1 alpha-2 code", field)));
+        }
+
+        Ok(Some(code.to_uppercase()))
+    }
+
+    /// Fills the empty profile fields of `survivor` from `duplicate`
+    fn fill_gaps(survivor: &mut guests::Model, duplicate: &guests::Model) {
+        fn fill<T: Clone>(field: &mut Option<T>, other: &Option<T>) {
+            if field.is_none() {
+                field.clone_from(other);
+            }
+        }
+
+        fill(&mut survivor.phone, &duplicate.phone);
+        fill(&mut survivor.date_of_birth, &duplicate.date_of_birth);
+        fill(&mut survivor.nationality, &duplicate.nationality);
+        fill(&mut survivor.floor_preference, &duplicate.floor_preference);
+        fill(&mut survivor.bed_preference, &duplicate.bed_preference);
+        fill(&mut survivor.allergies, &duplicate.allergies);
+
+        // Fields that only make sense together are taken as a whole
+        if survivor.street.is_none() && survivor.city.is_none() && survivor.country.is_none() {
+            survivor.street.clone_from(&duplicate.street);
+            survivor.city.clone_from(&duplicate.city);
+            survivor.region.clone_from(&duplicate.region);
+            survivor.postal_code.clone_from(&duplicate.postal_code);
+            survivor.country.clone_from(&duplicate.country);
+        }
+
+        if survivor.id_document_type.is_none() {
+            survivor.id_document_type.clone_from(&duplicate.id_document_type);
+            survivor.id_document_number.clone_from(&duplicate.id_document_number);
+        }
+    }
+
+    /// Validates `req` and writes it to `guest`, the sensitive fields only
+    /// when `access` is staff
+    fn apply(
+        guest       : &mut guests::ActiveModel
+        , req       : GuestSchemaIn
+        , access    : AccessLevel
+        , now       : chrono::DateTime<FixedOffset>
+    ) -> Result<(), ApiError> {
+        if access != AccessLevel::Staff && req.has_sensitive_data() {
+            return Err(ApiError::Forbidden(
+                "date_of_birth, nationality, id_document_type, id_document_number and allergies require the staff API key".to_string()
+            ));
+        }
+
+        let country = Self::clean_country("country", req.country)?;
+
+        // Omitted consents are kept, changes are timestamped, new guests
+        // only when they opt in
+        let (email, sms) = match (&guest.marketing_email_consent, &guest.marketing_sms_consent) {
+            (ActiveValue::Unchanged(email), ActiveValue::Unchanged(sms)) => (*email, *sms),
+            _ => (false, false),
+        };
+        let email_consent = req.marketing_email_consent.unwrap_or(email);
+        let sms_consent = req.marketing_sms_consent.unwrap_or(sms);
+
+        if email_consent != email || sms_consent != sms {
+            guest.marketing_consent_at = Set(Some(now));
+        }
+
+        // Omitted sensitive fields are kept, null clears them
+        if access == AccessLevel::Staff {
+            if let Some(date_of_birth) = req.date_of_birth {
+                if date_of_birth.is_some_and(|d| d >= now.date_naive()) {
+                    return Err(ApiError::Validation("date_of_birth must be in the past".to_string()));
+                }
+
+                guest.date_of_birth = Set(date_of_birth);
+            }
+
+            if let Some(nationality) = req.nationality {
+                guest.nationality = Set(Self::clean_country("nationality", nationality)?);
+            }
+
+            if let Some(allergies) = req.allergies.map(Self::clean) {
+                if allergies.as_ref().is_some_and(|a| a.chars().count() > MAX_ALLERGIES_CHARS) {
+                    return Err(ApiError::Validation(format!("allergies must be at most {} characters", MAX_ALLERGIES_CHARS)));
+                }
+
+                guest.allergies = Set(allergies);
+            }
+
+            if let Some(id_document_type) = req.id_document_type {
+                guest.id_document_type = Set(id_document_type);
+            }
+
+            if let Some(id_document_number) = req.id_document_number {
+                guest.id_document_number = Set(Self::clean(id_document_number));
+            }
+
+            if Self::is_filled(&guest.id_document_type) != Self::is_filled(&guest.id_document_number) {
+                return Err(ApiError::Validation("id_document_type and id_document_number must be given together".to_string()));
+            }
+        }
+
+        guest.first_name    = Set(req.first_name);
+        guest.last_name     = Set(req.last_name);
+        guest.email         = Set(req.email);
+        guest.phone         = Set(req.phone);
+        guest.street        = Set(Self::clean(req.street));
+        guest.city          = Set(Self::clean(req.city));
+        guest.region        = Set(Self::clean(req.region));
+        guest.postal_code   = Set(Self::clean(req.postal_code));
+        guest.country       = Set(country);
+        guest.floor_preference  = Set(req.floor_preference);
+        guest.bed_preference    = Set(req.bed_preference);
+        guest.marketing_email_consent   = Set(email_consent);
+        guest.marketing_sms_consent     = Set(sms_consent);
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -24,108 +250,310 @@ impl GuestServiceTrait for GuestService {
     
     async fn create_guest(
         &self
-        , req   : GuestSchemaIn
-    ) -> Result<GuestSchemaOut, DbErr> {
+        , req       : GuestSchemaIn
+        , access    : AccessLevel
+    ) -> Result<GuestSchemaOut, ApiError> {
         let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
 
-        let guest = guests::ActiveModel {
-            id              : Set(Uuid::new_v4())
-            , first_name    : Set(req.first_name)
-            , last_name     : Set(req.last_name)
-            , email         : Set(req.email)
-            , phone         : Set(req.phone)
-            , created_at    : Set(now)
-            , updated_at    : Set(None)
+        let mut guest = guests::ActiveModel {
+            id                          : Set(Uuid::new_v4())
+            , created_at                : Set(now)
+            , updated_at                : Set(None)
+            , date_of_birth             : Set(None)
+            , nationality               : Set(None)
+            , id_document_type          : Set(None)
+            , id_document_number        : Set(None)
+            , allergies                 : Set(None)
+            , marketing_consent_at      : Set(None)
+            , anonymized_at             : Set(None)
+            , merged_into_id            : Set(None)
+            , merged_at                 : Set(None)
+            , ..Default::default()
         };
 
-        let res = guest.insert(&self.db).await?;
-        Ok(GuestSchemaOut {
-            id              : res.id
-            , first_name    : res.first_name
-            , last_name     : res.last_name
-            , email         : res.email
-            , phone         : res.phone.unwrap_or_default()
-            , created_at    : res.created_at
-            , updated_at    : res.updated_at
-        })
+        Self::apply(&mut guest, req, access, now)?;
+
+        let txn = self.db.begin().await?;
+
+        let res = guest.insert(&txn).await?;
+        let guest = Self::present(res, access);
+
+        // The outbox feeds webhooks, the sensitive data never leaves the API
+        events::record(&txn, DomainEvent::GuestCreated(guest.redacted())).await?;
+        txn.commit().await?;
+
+        Ok(guest)
     }
 
     async fn get_guest(
         &self
-        , id    : Uuid
+        , id        : Uuid
+        , access    : AccessLevel
     ) -> Result<Option<GuestSchemaOut>, DbErr> {
         let res = guests::Entity::find_by_id(id)
             .one(&self.db)
             .await?;
 
-        Ok(res.map(|g| GuestSchemaOut {
-            id              : g.id
-            , first_name    : g.first_name
-            , last_name     : g.last_name
-            , email         : g.email
-            , phone         : g.phone.unwrap_or_default()
-            , created_at    : g.created_at
-            , updated_at    : g.updated_at
-        }))
+        Ok(res.map(|g| Self::present(g, access)))
     }
 
-    async fn list_guests(&self) -> Result<Vec<GuestSchemaOut>, DbErr> {
+    async fn list_guests(&self, filter: GuestFilter, access: AccessLevel) -> Result<Vec<GuestSchemaOut>, DbErr> {
         let res = guests::Entity::find()
+            .filter(Self::filter_condition(&filter))
             .all(&self.db)
             .await?;
 
-        Ok(res.into_iter().map(|g| GuestSchemaOut {
-            id              : g.id
-            , first_name    : g.first_name
-            , last_name     : g.last_name
-            , email         : g.email
-            , phone         : g.phone.unwrap_or_default()
-            , created_at    : g.created_at
-            , updated_at    : g.updated_at
-        }).collect())
+        Ok(res.into_iter().map(|g| Self::present(g, access)).collect())
     }
 
     async fn update_guest(
         &self
-        , id    : Uuid
-        , req   : GuestSchemaIn
-    ) -> Result<Option<GuestSchemaOut>, DbErr> {
+        , id        : Uuid
+        , req       : GuestSchemaIn
+        , access    : AccessLevel
+    ) -> Result<Option<GuestSchemaOut>, ApiError> {
         let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
-        
-        let guest = match guests::Entity::find_by_id(id).one(&self.db).await? {
+
+        let txn = self.db.begin().await?;
+
+        let guest = match guests::Entity::find_by_id(id).lock_exclusive().one(&txn).await? {
             Some(g) => g,
             None    => return Ok(None),
         };
 
+        if guest.anonymized_at.is_some() {
+            return Err(ApiError::Validation("An anonymized guest cannot be updated".to_string()));
+        }
+
+        if guest.merged_into_id.is_some() {
+            return Err(ApiError::Validation("A merged guest cannot be updated, update the guest it was merged into".to_string()));
+        }
+
         let mut guest: guests::ActiveModel = guest.into();
 
-        guest.first_name    = Set(req.first_name);
-        guest.last_name     = Set(req.last_name);
-        guest.email         = Set(req.email);
-        guest.phone         = Set(req.phone);
+        Self::apply(&mut guest, req, access, now)?;
         guest.updated_at    = Set(Some(now));
 
-        let updated: guests::Model = guest.update(&self.db).await?;
-
-        Ok(Some(GuestSchemaOut {
-            id              : updated.id
-            , first_name    : updated.first_name
-            , last_name     : updated.last_name
-            , email         : updated.email
-            , phone         : updated.phone.unwrap_or_default()
-            , created_at    : updated.created_at
-            , updated_at    : updated.updated_at
-        }))
+        let updated: guests::Model = guest.update(&txn).await?;
+        let guest = Self::present(updated, access);
+
+        events::record(&txn, DomainEvent::GuestUpdated(guest.redacted())).await?;
+        txn.commit().await?;
+
+        Ok(Some(guest))
+    }
+
+    async fn find_duplicates(
+        &self
+        , filter    : GuestDuplicateFilter
+        , access    : AccessLevel
+    ) -> Result<Vec<GuestDuplicateSchemaOut>, ApiError> {
+        let min_score = filter.min_score.unwrap_or(DEFAULT_MIN_DUPLICATE_SCORE);
+        if !(0.0..=1.0).contains(&min_score) {
+            return Err(ApiError::Validation("min_score must be between 0 and 1".to_string()));
+        }
+
+        let limit = filter.limit.unwrap_or(DEFAULT_DUPLICATE_LIMIT);
+        if !(1..=MAX_DUPLICATE_LIMIT).contains(&limit) {
+            return Err(ApiError::Validation(format!("limit must be between 1 and {}", MAX_DUPLICATE_LIMIT)));
+        }
+
+        let rows = DuplicateRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres
+            , DUPLICATES_SQL
+            , [min_score.into(), (limit as i64).into()]
+        ))
+        .all(&self.db)
+        .await?;
+
+        let ids: HashSet<Uuid> = rows.iter().flat_map(|r| [r.guest_id, r.duplicate_id]).collect();
+
+        let guests: HashMap<Uuid, guests::Model> = guests::Entity::find()
+            .filter(guests::Column::Id.is_in(ids))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|g| (g.id, g))
+            .collect();
+
+        // A pair whose guest was merged or deleted since the query is dropped
+        Ok(rows.into_iter().filter_map(|r| {
+            Some(GuestDuplicateSchemaOut {
+                guest               : Self::present(guests.get(&r.guest_id)?.clone(), access)
+                , duplicate         : Self::present(guests.get(&r.duplicate_id)?.clone(), access)
+                , score             : r.score
+                , name_similarity   : r.name_similarity
+                , email_similarity  : r.email_similarity
+                , phone_match       : r.phone_match
+            })
+        }).collect())
+    }
+
+    async fn merge_guests(
+        &self
+        , id        : Uuid
+        , req       : GuestMergeSchemaIn
+        , access    : AccessLevel
+    ) -> Result<Option<GuestSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let duplicate_ids = req.duplicate_ids;
+        let unique: HashSet<Uuid> = duplicate_ids.iter().copied().collect();
+
+        if duplicate_ids.is_empty() {
+            return Err(ApiError::Validation("duplicate_ids must not be empty".to_string()));
+        }
+
+        if unique.len() != duplicate_ids.len() {
+            return Err(ApiError::Validation("duplicate_ids must not repeat a guest".to_string()));
+        }
+
+        if unique.contains(&id) {
+            return Err(ApiError::Validation("A guest cannot be merged into itself".to_string()));
+        }
+
+        let txn = self.db.begin().await?;
+
+        // Locked in id order so concurrent merges of overlapping guests cannot deadlock
+        let mut locked: HashMap<Uuid, guests::Model> = guests::Entity::find()
+            .filter(guests::Column::Id.is_in(unique.iter().copied().chain([id])))
+            .order_by_asc(guests::Column::Id)
+            .lock_exclusive()
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|g| (g.id, g))
+            .collect();
+
+        let Some(mut survivor) = locked.remove(&id) else { return Ok(None) };
+
+        if survivor.merged_into_id.is_some() || survivor.anonymized_at.is_some() {
+            return Err(ApiError::Validation("Guests can only be merged into a guest that is neither merged nor anonymized".to_string()));
+        }
+
+        let mut duplicates = Vec::with_capacity(duplicate_ids.len());
+        for duplicate_id in &duplicate_ids {
+            let Some(duplicate) = locked.remove(duplicate_id) else {
+                return Err(ApiError::Validation(format!("Guest {} not found", duplicate_id)));
+            };
+
+            if duplicate.merged_into_id.is_some() || duplicate.anonymized_at.is_some() {
+                return Err(ApiError::Validation(format!("Guest {} is already merged or anonymized", duplicate_id)));
+            }
+
+            duplicates.push(duplicate);
+        }
+
+        // The history of the duplicates moves over in full
+        let moved_bookings = bookings::Entity::update_many()
+            .set(bookings::ActiveModel {
+                guest_id        : Set(id)
+                , ..Default::default()
+            })
+            .filter(bookings::Column::GuestId.is_in(duplicate_ids.clone()))
+            .exec(&txn)
+            .await?;
+
+        let moved_reservations = reservations::Entity::update_many()
+            .set(reservations::ActiveModel {
+                guest_id        : Set(id)
+                , ..Default::default()
+            })
+            .filter(reservations::Column::GuestId.is_in(duplicate_ids.clone()))
+            .exec(&txn)
+            .await?;
+
+        let moved_entries = waitlist_entries::Entity::update_many()
+            .set(waitlist_entries::ActiveModel {
+                guest_id        : Set(id)
+                , ..Default::default()
+            })
+            .filter(waitlist_entries::Column::GuestId.is_in(duplicate_ids.clone()))
+            .exec(&txn)
+            .await?;
+
+        let moved_reviews = reviews::Entity::update_many()
+            .set(reviews::ActiveModel {
+                guest_id        : Set(id)
+                , ..Default::default()
+            })
+            .filter(reviews::Column::GuestId.is_in(duplicate_ids.clone()))
+            .exec(&txn)
+            .await?;
+
+        // Points follow the bookings they were earned or redeemed on
+        let moved_points = loyalty_entries::Entity::update_many()
+            .set(loyalty_entries::ActiveModel {
+                guest_id        : Set(id)
+                , ..Default::default()
+            })
+            .filter(loyalty_entries::Column::GuestId.is_in(duplicate_ids.clone()))
+            .exec(&txn)
+            .await?;
+
+        // Earlier tombstones of the duplicates now point at the survivor
+        guests::Entity::update_many()
+            .set(guests::ActiveModel {
+                merged_into_id  : Set(Some(id))
+                , ..Default::default()
+            })
+            .filter(guests::Column::MergedIntoId.is_in(duplicate_ids.clone()))
+            .exec(&txn)
+            .await?;
+
+        guests::Entity::update_many()
+            .set(guests::ActiveModel {
+                merged_into_id  : Set(Some(id))
+                , merged_at     : Set(Some(now))
+                , updated_at    : Set(Some(now))
+                , ..Default::default()
+            })
+            .filter(guests::Column::Id.is_in(duplicate_ids.clone()))
+            .exec(&txn)
+            .await?;
+
+        for duplicate in &duplicates {
+            Self::fill_gaps(&mut survivor, duplicate);
+        }
+
+        let mut survivor = guests::ActiveModel::from(survivor).reset_all();
+        survivor.updated_at = Set(Some(now));
+
+        let guest = Self::present(survivor.update(&txn).await?, access);
+
+        events::record(&txn, DomainEvent::GuestMerged { id, merged_ids: duplicate_ids.clone() }).await?;
+        events::record(&txn, DomainEvent::GuestUpdated(guest.redacted())).await?;
+
+        AuditService::record(&txn, AuditAction::GuestMerged, "guest", id, serde_json::json!({
+            "merged_ids"                : duplicate_ids
+            , "bookings_moved"          : moved_bookings.rows_affected
+            , "reservations_moved"      : moved_reservations.rows_affected
+            , "waitlist_entries_moved"  : moved_entries.rows_affected
+            , "reviews_moved"           : moved_reviews.rows_affected
+            , "loyalty_entries_moved"   : moved_points.rows_affected
+        })).await?;
+
+        txn.commit().await?;
+
+        Ok(Some(guest))
     }
 
     async fn delete_guest(
         &self
         , id    : Uuid
     ) -> Result<bool, DbErr> {
+        let txn = self.db.begin().await?;
+
         let res = guests::Entity::delete_by_id(id)
-            .exec(&self.db)
+            .exec(&txn)
             .await?;
 
+        if res.rows_affected > 0 {
+            events::record(&txn, DomainEvent::GuestDeleted { id }).await?;
+        }
+
+        txn.commit().await?;
+
         Ok(res.rows_affected > 0)
     }
 }
\ No newline at end of file
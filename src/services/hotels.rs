@@ -1,12 +1,34 @@
+use std::collections::{HashMap, HashSet};
 use sea_orm::*;
+use sea_orm::sea_query::{extension::postgres::PgExpr, Expr, Func, Query, SimpleExpr};
 use uuid::Uuid;
 use chrono::{Utc, FixedOffset};
+use chrono_tz::Tz;
 use crate::{
-    models::hotels,
+    events::{self, DomainEvent},
+    models::{amenities, hotel_amenities, hotels, rooms},
     schemas::hotels::*,
-    services::traits::HotelServiceTrait,
+    services::{availability, media::MediaService, search, traits::HotelServiceTrait},
+    error::ApiError,
 };
 
+/// Mean radius of the earth
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+/// Length of one degree of latitude
+const KM_PER_DEGREE: f64 = EARTH_RADIUS_KM * std::f64::consts::PI / 180.0;
+
+/// Checked address of a hotel and its one line form
+#[derive(Debug)]
+pub struct HotelAddress {
+    pub address         : String
+    , pub street        : Option<String>
+    , pub city          : Option<String>
+    , pub region        : Option<String>
+    , pub postal_code   : Option<String>
+    , pub country       : Option<String>
+}
+
 #[derive(Clone)]
 pub struct HotelService {
     db  : DatabaseConnection
@@ -16,6 +38,298 @@ impl HotelService {
     pub fn new(db   : DatabaseConnection) -> Self {
         Self { db }
     }
+
+    /// Where clause of the list and export endpoints
+    pub fn filter_condition(filter: &HotelFilter) -> Condition {
+        let mut condition = Condition::all();
+
+        if let Some(name) = &filter.name {
+            condition = condition.add(Expr::col(hotels::Column::Name).ilike(search::contains_pattern(name)));
+        }
+
+        if let Some(min_rating) = filter.min_rating {
+            condition = condition.add(hotels::Column::Rating.gte(min_rating));
+        }
+
+        if let Some(city) = &filter.city {
+            condition = condition.add(Expr::expr(Func::lower(Expr::col(hotels::Column::City))).eq(city.trim().to_lowercase()));
+        }
+
+        if let Some(country) = &filter.country {
+            condition = condition.add(hotels::Column::Country.eq(country.trim().to_uppercase()));
+        }
+
+        if let Some(near) = filter.near {
+            condition = condition
+                .add(hotels::Column::Latitude.is_not_null())
+                .add(hotels::Column::Longitude.is_not_null());
+
+            if let Some(radius_km) = filter.radius_km {
+                condition = condition
+                    .add(Self::bounding_box(near, radius_km))
+                    .add(Expr::expr(Self::distance_expr(near)).lte(radius_km));
+            }
+        }
+
+        for code in &filter.amenities {
+            condition = condition.add(hotels::Column::Id.in_subquery(
+                Query::select()
+                    .column((hotel_amenities::Entity, hotel_amenities::Column::HotelId))
+                    .from(hotel_amenities::Entity)
+                    .inner_join(
+                        amenities::Entity,
+                        Expr::col((amenities::Entity, amenities::Column::Id))
+                            .equals((hotel_amenities::Entity, hotel_amenities::Column::AmenityId)),
+                    )
+                    .and_where(Expr::col((amenities::Entity, amenities::Column::Code)).eq(code.trim().to_lowercase()))
+                    .to_owned()
+            ));
+        }
+
+        condition
+    }
+
+    /// Fills in the amenity codes of `hotels`
+    pub async fn attach_amenities<C: ConnectionTrait>(conn: &C, hotels: &mut [HotelSchemaOut]) -> Result<(), DbErr> {
+        let links: Vec<(Uuid, String)> = hotel_amenities::Entity::find()
+            .select_only()
+            .column(hotel_amenities::Column::HotelId)
+            .column(amenities::Column::Code)
+            .join(JoinType::InnerJoin, hotel_amenities::Relation::Amenities.def())
+            .filter(hotel_amenities::Column::HotelId.is_in(hotels.iter().map(|h| h.id)))
+            .order_by_asc(amenities::Column::Code)
+            .into_tuple()
+            .all(conn)
+            .await?;
+
+        let mut codes: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for (hotel_id, code) in links {
+            codes.entry(hotel_id).or_default().push(code);
+        }
+
+        for hotel in hotels {
+            hotel.amenities = codes.remove(&hotel.id).unwrap_or_default();
+        }
+
+        Ok(())
+    }
+
+    /// Haversine distance in km from `point` to the coordinates of a hotel row
+    fn distance_expr(point: GeoPoint) -> SimpleExpr {
+        Expr::cust_with_values(
+            r#"2 * $1 * asin(least(1, sqrt(
+                power(sin(radians("hotels"."latitude" - $2) / 2), 2)
+                + cos(radians($2)) * cos(radians("hotels"."latitude"))
+                * power(sin(radians("hotels"."longitude" - $3) / 2), 2)
+            )))"#,
+            [EARTH_RADIUS_KM, point.latitude, point.longitude],
+        )
+    }
+
+    /// Coordinates box around the search circle, lets the index skip far away
+    /// hotels before distances are computed. Longitudes are left open near the
+    /// poles and when the box would cross the antimeridian.
+    fn bounding_box(point: GeoPoint, radius_km: f64) -> Condition {
+        let lat_delta = radius_km / KM_PER_DEGREE;
+
+        let mut condition = Condition::all()
+            .add(hotels::Column::Latitude.between(point.latitude - lat_delta, point.latitude + lat_delta));
+
+        let cos_lat = point.latitude.to_radians().cos();
+
+        if cos_lat > 0.01 {
+            let lng_delta = radius_km / (KM_PER_DEGREE * cos_lat);
+
+            if point.longitude - lng_delta >= -180.0 && point.longitude + lng_delta <= 180.0 {
+                condition = condition.add(
+                    hotels::Column::Longitude.between(point.longitude - lng_delta, point.longitude + lng_delta)
+                );
+            }
+        }
+
+        condition
+    }
+
+    /// Haversine distance between two points in km, the value `distance_expr` filters on
+    pub fn distance_km(a: GeoPoint, b: GeoPoint) -> f64 {
+        let (lat_a, lat_b) = (a.latitude.to_radians(), b.latitude.to_radians());
+        let lat_delta = lat_b - lat_a;
+        let lng_delta = (b.longitude - a.longitude).to_radians();
+
+        let h = (lat_delta / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (lng_delta / 2.0).sin().powi(2);
+
+        2.0 * EARTH_RADIUS_KM * h.sqrt().min(1.0).asin()
+    }
+
+    /// One line address such as `123 Main Street, 10001 New York, NY, US`
+    pub fn format_address(
+        street          : &str
+        , postal_code   : Option<&str>
+        , city          : &str
+        , region        : Option<&str>
+        , country       : &str
+    ) -> String {
+        let locality = [postal_code.unwrap_or_default(), city]
+            .into_iter()
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        [street, &locality, region.unwrap_or_default(), country]
+            .into_iter()
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Checks the address of a hotel, returning the offending field and why.
+    ///
+    /// A structured address needs `street`, `city` and `country`. Without a
+    /// city or country, the single `address` line of older clients, or the
+    /// street of a hotel created before structured addresses, is kept as the
+    /// street alone, as the migration to structured addresses did.
+    pub fn resolve_address(
+        address         : Option<&str>
+        , street        : Option<&str>
+        , city          : Option<&str>
+        , region        : Option<&str>
+        , postal_code   : Option<&str>
+        , country       : Option<&str>
+    ) -> Result<HotelAddress, (&'static str, String)> {
+        let clean = |v: Option<&str>| v.map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+
+        let (street, city, country) = (clean(street), clean(city), clean(country).map(|c| c.to_uppercase()));
+        let (region, postal_code) = (clean(region), clean(postal_code));
+
+        if city.is_none() && country.is_none() {
+            let Some(line) = street.or_else(|| clean(address)) else {
+                return Err(("street", "street, city and country, or address, are required".to_string()));
+            };
+
+            return Ok(HotelAddress {
+                address         : Self::format_address(&line, postal_code.as_deref(), "", region.as_deref(), "")
+                , street        : Some(line)
+                , city          : None
+                , region
+                , postal_code
+                , country       : None
+            });
+        }
+
+        let Some(street) = street else {
+            return Err(("street", "street must not be empty".to_string()));
+        };
+
+        let Some(city) = city else {
+            return Err(("city", "city must not be empty".to_string()));
+        };
+
+        let country = country.unwrap_or_default();
+        if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(("country", "country must be an ISO 3166-1 alpha-2 code".to_string()));
+        }
+
+        Ok(HotelAddress {
+            address         : Self::format_address(&street, postal_code.as_deref(), &city, region.as_deref(), &country)
+            , street        : Some(street)
+            , city          : Some(city)
+            , region
+            , postal_code
+            , country       : Some(country)
+        })
+    }
+
+    /// Checks the coordinates of a hotel, returning the offending field and why
+    pub fn check_coordinates(
+        latitude        : Option<f64>
+        , longitude     : Option<f64>
+    ) -> Result<(), (&'static str, String)> {
+        match (latitude, longitude) {
+            (Some(lat), Some(lng)) => {
+                if !(-90.0..=90.0).contains(&lat) {
+                    return Err(("latitude", "latitude must be between -90 and 90".to_string()));
+                }
+
+                if !(-180.0..=180.0).contains(&lng) {
+                    return Err(("longitude", "longitude must be between -180 and 180".to_string()));
+                }
+            }
+            (None, None)    => {}
+            _               => return Err(("latitude", "latitude and longitude must be given together".to_string())),
+        }
+
+        Ok(())
+    }
+
+    /// Validates `req` and writes it to `hotel`
+    fn apply(hotel: &mut hotels::ActiveModel, req: HotelSchemaIn) -> Result<(), ApiError> {
+        let address = Self::resolve_address(
+            req.address.as_deref()
+            , req.street.as_deref()
+            , req.city.as_deref()
+            , req.region.as_deref()
+            , req.postal_code.as_deref()
+            , req.country.as_deref()
+        ).map_err(|(_, message)| ApiError::Validation(message))?;
+
+        Self::check_coordinates(req.latitude, req.longitude)
+            .map_err(|(_, message)| ApiError::Validation(message))?;
+
+        hotel.name          = Set(req.name);
+        hotel.address       = Set(address.address);
+        hotel.street        = Set(address.street);
+        hotel.city          = Set(address.city);
+        hotel.region        = Set(address.region);
+        hotel.postal_code   = Set(address.postal_code);
+        hotel.country       = Set(address.country);
+        hotel.latitude      = Set(req.latitude);
+        hotel.longitude     = Set(req.longitude);
+        hotel.description   = Set(req.description);
+
+        Ok(())
+    }
+
+    /// Ids of the hotels among `hotel_ids` with a room free for the stay, the
+    /// same rules as the room availability search
+    async fn hotels_with_free_room(
+        &self
+        , hotel_ids : Vec<Uuid>
+        , stay      : &HotelStayFilter
+    ) -> Result<HashSet<Uuid>, ApiError> {
+        let occupied = availability::occupied_room_ids(&self.db, stay.check_in, stay.check_out).await?;
+
+        let mut free_rooms = rooms::Entity::find()
+            .select_only()
+            .column(rooms::Column::HotelId)
+            .column(rooms::Column::RoomType)
+            .distinct()
+            .filter(rooms::Column::HotelId.is_in(hotel_ids))
+            .filter(rooms::Column::IsAvailable.eq(true))
+            .filter(rooms::Column::Id.is_not_in(occupied));
+
+        if let Some(room_type) = &stay.room_type {
+            free_rooms = free_rooms.filter(rooms::Column::RoomType.eq(room_type.as_str()));
+        }
+
+        let types: Vec<(Uuid, String)> = free_rooms.into_tuple().all(&self.db).await?;
+
+        // A free room is not enough when unassigned bookings sold out its type
+        let mut available = HashSet::new();
+
+        for (hotel_id, room_type) in types {
+            if available.contains(&hotel_id) {
+                continue;
+            }
+
+            let tz = availability::hotel_timezone(&self.db, hotel_id).await?;
+
+            if availability::room_type_has_inventory(&self.db, hotel_id, &room_type, stay.check_in, stay.check_out, None, tz).await? {
+                available.insert(hotel_id);
+            }
+        }
+
+        Ok(available)
+    }
 }
 
 #[async_trait]
@@ -23,73 +337,93 @@ impl HotelServiceTrait for HotelService {
     async fn create_hotel(
         &self
         , req   : HotelSchemaIn
-    ) -> Result<HotelSchemaOut, DbErr> {
+    ) -> Result<HotelSchemaOut, ApiError> {
         let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
 
-        let hotel = hotels::ActiveModel {
-            id      : Set(Uuid::new_v4())
-            , name      : Set(req.name)
-            , address   : Set(req.address)
-            , rating    : Set(req.rating)
-            , description   : Set(req.description)
+        let mut hotel = hotels::ActiveModel {
+            id              : Set(Uuid::new_v4())
             , created_at    : Set(now)
             , updated_at    : Set(None)
+            , ..Default::default()
         };
 
-        let res = hotel.insert(&self.db).await?;
-        Ok(HotelSchemaOut {
-            id      : res.id
-            , name  : res.name
-            , address   : res.address
-            , rating    : res.rating
-            , description   : res.description
-            , created_at    : res.created_at
-            , updated_at    : res.updated_at 
-        })
+        Self::apply(&mut hotel, req)?;
+
+        let txn = self.db.begin().await?;
+
+        let hotel = HotelSchemaOut::from(hotel.insert(&txn).await?);
+
+        events::record(&txn, DomainEvent::HotelCreated(hotel.clone())).await?;
+        txn.commit().await?;
+
+        Ok(hotel)
     }
 
     async fn get_hotel(
         &self
         , id  : Uuid
     ) -> Result<Option<HotelSchemaOut>, DbErr> {
-        let res = hotels::Entity::find_by_id(id)
-            .one(&self.db)
-            .await?;
+        let Some(hotel) = hotels::Entity::find_by_id(id).one(&self.db).await? else {
+            return Ok(None);
+        };
 
-        Ok(res.map(|h| HotelSchemaOut {
-            id      : h.id
-            , name  : h.name
-            , address   : h.address
-            , rating    : h.rating
-            , description   : h.description
-            , created_at    : h.created_at
-            , updated_at    : h.updated_at           
-        }))
+        let mut hotel = [HotelSchemaOut::from(hotel)];
+        Self::attach_amenities(&self.db, &mut hotel).await?;
+        MediaService::attach_hotel_media(&self.db, &mut hotel).await?;
+
+        let [hotel] = hotel;
+        Ok(Some(hotel))
     }
 
-    async fn list_hotels(&self) -> Result<Vec<HotelSchemaOut>, DbErr> {
-        let res = hotels::Entity::find()
+    async fn list_hotels(
+        &self
+        , filter    : HotelFilter
+        , stay      : Option<HotelStayFilter>
+    ) -> Result<Vec<HotelSchemaOut>, ApiError> {
+        let mut hotels = hotels::Entity::find()
+            .filter(Self::filter_condition(&filter));
+
+        if let Some(near) = filter.near {
+            hotels = hotels.order_by(Self::distance_expr(near), Order::Asc);
+        }
+
+        let mut res = hotels
             .all(&self.db)
-            .await?;
+            .await?
+            .into_iter()
+            .map(HotelSchemaOut::from)
+            .collect::<Vec<_>>();
+
+        if let Some(near) = filter.near {
+            for hotel in &mut res {
+                if let (Some(latitude), Some(longitude)) = (hotel.latitude, hotel.longitude) {
+                    hotel.distance_km = Some(Self::distance_km(near, GeoPoint { latitude, longitude }));
+                }
+            }
+        }
+
+        if let Some(stay) = stay {
+            if stay.check_out <= stay.check_in {
+                return Err(ApiError::Validation("check_out must be after check_in".to_string()));
+            }
 
-        Ok(res.into_iter().map(|h| HotelSchemaOut {
-            id      : h.id
-            , name  : h.name
-            , address   : h.address
-            , rating    : h.rating
-            , description   : h.description
-            , created_at    : h.created_at
-            , updated_at    : h.updated_at    
-        }).collect())
+            let available = self.hotels_with_free_room(res.iter().map(|h| h.id).collect(), &stay).await?;
+            res.retain(|h| available.contains(&h.id));
+        }
+
+        Self::attach_amenities(&self.db, &mut res).await?;
+        MediaService::attach_hotel_media(&self.db, &mut res).await?;
+
+        Ok(res)
     }
 
     async fn update_hotel(
         &self
         , id  : Uuid
         , req : HotelSchemaIn
-    ) -> Result<Option<HotelSchemaOut>, DbErr> {
+    ) -> Result<Option<HotelSchemaOut>, ApiError> {
         let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
-        
+
         let hotel = match hotels::Entity::find_by_id(id).one(&self.db).await? {
             Some(h) => h,
             None => return Ok(None),
@@ -97,33 +431,82 @@ impl HotelServiceTrait for HotelService {
 
         let mut hotel : hotels::ActiveModel = hotel.into();
 
-        hotel.name          = Set(req.name);
-        hotel.address       = Set(req.address);
-        hotel.rating        = Set(req.rating);
-        hotel.description   = Set(req.description);
+        Self::apply(&mut hotel, req)?;
         hotel.updated_at    = Set(Some(now));
 
-        let updated: hotels::Model = hotel.update(&self.db).await?;
+        let txn = self.db.begin().await?;
+
+        let mut hotel = [HotelSchemaOut::from(hotel.update(&txn).await?)];
+        Self::attach_amenities(&txn, &mut hotel).await?;
+        MediaService::attach_hotel_media(&txn, &mut hotel).await?;
+
+        let [hotel] = hotel;
+
+        events::record(&txn, DomainEvent::HotelUpdated(hotel.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(hotel))
+    }
+
+    async fn update_policies(
+        &self
+        , id        : Uuid
+        , req       : HotelPoliciesSchema
+    ) -> Result<Option<HotelSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let clean = |text: Option<String>| text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+
+        let timezone = req.timezone.as_deref().map(str::trim);
+        if let Some(timezone) = timezone.filter(|t| t.parse::<Tz>().is_err()) {
+            return Err(ApiError::Validation(format!("Unknown timezone {}", timezone)));
+        }
+
+        let txn = self.db.begin().await?;
+
+        let Some(hotel) = hotels::Entity::find_by_id(id).one(&txn).await? else {
+            return Ok(None);
+        };
+
+        let mut hotel: hotels::ActiveModel = hotel.into();
+
+        hotel.check_in_time     = Set(req.check_in_time);
+        hotel.check_out_time    = Set(req.check_out_time);
+        if let Some(timezone) = timezone {
+            hotel.timezone      = Set(timezone.to_string());
+        }
+        hotel.child_policy      = Set(clean(req.child_policy));
+        hotel.pet_policy        = Set(clean(req.pet_policy));
+        hotel.updated_at        = Set(Some(now));
 
-        Ok(Some(HotelSchemaOut {
-            id      : updated.id
-            , name  : updated.name
-            , address   : updated.address
-            , rating    : updated.rating
-            , description   : updated.description
-            , created_at    : updated.created_at
-            , updated_at    : updated.updated_at           
-        }))
+        let mut hotel = [HotelSchemaOut::from(hotel.update(&txn).await?)];
+        Self::attach_amenities(&txn, &mut hotel).await?;
+        MediaService::attach_hotel_media(&txn, &mut hotel).await?;
+
+        let [hotel] = hotel;
+
+        events::record(&txn, DomainEvent::HotelUpdated(hotel.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(hotel))
     }
 
     async fn delete_hotel(
         &self
         , id  : Uuid
     ) -> Result<bool, DbErr> {
+        let txn = self.db.begin().await?;
+
         let res = hotels::Entity::delete_by_id(id)
-            .exec(&self.db)
+            .exec(&txn)
             .await?;
 
+        if res.rows_affected > 0 {
+            events::record(&txn, DomainEvent::HotelDeleted { id }).await?;
+        }
+
+        txn.commit().await?;
+
         Ok(res.rows_affected > 0)
     }
-}
\ No newline at end of file
+}
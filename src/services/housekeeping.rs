@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use crate::{
+    events::{self, DomainEvent, EventHandler},
+    models::{bookings, hotels, rooms, sea_orm_active_enums::HousekeepingStatus},
+    schemas::{booking::BookingSchemaOut, housekeeping::*},
+    services::{availability::{self, start_of_day}, traits::HousekeepingServiceTrait},
+    error::ApiError,
+};
+
+#[derive(Clone)]
+pub struct HousekeepingService {
+    db  : DatabaseConnection
+}
+
+impl HousekeepingService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Marks the room of a finished stay dirty. Rooms out of service are left
+    /// alone, as are rooms whose status was set after the guest left so a
+    /// redelivered event does not undo the cleaning.
+    pub async fn mark_checked_out<C: ConnectionTrait>(conn: &C, booking: &BookingSchemaOut) -> Result<(), DbErr> {
+        let Some(room_id) = booking.room_id else { return Ok(()) };
+
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        let left_at = booking.updated_at.map_or(booking.check_out_date, |u| u.min(booking.check_out_date));
+
+        let updated = rooms::Entity::update_many()
+            .set(rooms::ActiveModel {
+                housekeeping_status         : Set(HousekeepingStatus::Dirty)
+                , housekeeping_note         : Set(None)
+                , housekeeping_updated_at   : Set(Some(now))
+                , ..Default::default()
+            })
+            .filter(rooms::Column::Id.eq(room_id))
+            .filter(rooms::Column::HousekeepingStatus.ne(HousekeepingStatus::OutOfService))
+            .filter(
+                Condition::any()
+                    .add(rooms::Column::HousekeepingUpdatedAt.is_null())
+                    .add(rooms::Column::HousekeepingUpdatedAt.lt(left_at))
+            )
+            .exec_with_returning(conn)
+            .await?;
+
+        for room in updated {
+            events::record(conn, DomainEvent::RoomHousekeepingChanged(Self::to_schema(room))).await?;
+        }
+
+        Ok(())
+    }
+
+    fn to_schema(r: rooms::Model) -> HousekeepingSchemaOut {
+        HousekeepingSchemaOut {
+            room_id         : r.id
+            , status        : r.housekeeping_status
+            , note          : r.housekeeping_note
+            , updated_at    : r.housekeeping_updated_at
+        }
+    }
+}
+
+/// Sends rooms to cleaning when their booking is completed
+pub struct HousekeepingEventHandler;
+
+#[async_trait]
+impl EventHandler for HousekeepingEventHandler {
+    fn name(&self) -> &'static str {
+        "housekeeping"
+    }
+
+    async fn handle(&self, txn: &DatabaseTransaction, event: &DomainEvent) -> Result<(), DbErr> {
+        match event {
+            DomainEvent::BookingCompleted(booking)  => HousekeepingService::mark_checked_out(txn, booking).await,
+            _                                       => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl HousekeepingServiceTrait for HousekeepingService {
+    async fn hotel_tasks(
+        &self
+        , hotel_id  : Uuid
+        , status    : Option<HousekeepingStatus>
+    ) -> Result<Option<Vec<HousekeepingTask>>, ApiError> {
+        let Some(hotel) = hotels::Entity::find_by_id(hotel_id).one(&self.db).await? else {
+            return Ok(None);
+        };
+
+        let mut rooms = rooms::Entity::find()
+            .filter(rooms::Column::HotelId.eq(hotel_id));
+
+        if let Some(status) = status {
+            rooms = rooms.filter(rooms::Column::HousekeepingStatus.eq(status));
+        }
+
+        let rooms = rooms.all(&self.db).await?;
+
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        let tz = availability::timezone(&hotel.timezone);
+        let today = start_of_day(now.with_timezone(&tz).date_naive(), tz);
+
+        // Every stay not over yet, earliest arrival first
+        let stays = bookings::Entity::find()
+            .filter(bookings::Column::RoomId.is_in(rooms.iter().map(|r| r.id)))
+            .filter(bookings::Column::Status.is_in(availability::HOLDING_STATUSES))
+            .filter(bookings::Column::CheckOutDate.gt(now))
+            .order_by_asc(bookings::Column::CheckInDate)
+            .all(&self.db)
+            .await?;
+
+        let mut occupied: HashMap<Uuid, bool> = HashMap::new();
+        let mut arrivals: HashMap<Uuid, &bookings::Model> = HashMap::new();
+
+        for stay in &stays {
+            let Some(room_id) = stay.room_id else { continue };
+
+            if stay.check_in_date <= now {
+                occupied.insert(room_id, true);
+            }
+
+            if stay.check_in_date >= today {
+                arrivals.entry(room_id).or_insert(stay);
+            }
+        }
+
+        let mut tasks = rooms.into_iter().map(|r| {
+            let arrival = arrivals.get(&r.id);
+
+            HousekeepingTask {
+                room_id             : r.id
+                , occupied          : occupied.contains_key(&r.id)
+                , next_arrival      : arrival.map(|b| b.check_in_date)
+                , next_booking_id   : arrival.map(|b| b.id)
+                , room_number       : r.room_number
+                , room_type         : r.room_type
+                , status            : r.housekeeping_status
+                , note              : r.housekeeping_note
+                , status_updated_at : r.housekeeping_updated_at
+            }
+        }).collect::<Vec<_>>();
+
+        // Rooms with the soonest arrival first, rooms nobody is arriving in last
+        tasks.sort_by(|a, b| match (a.next_arrival, b.next_arrival) {
+            (Some(x), Some(y))  => x.cmp(&y),
+            (Some(_), None)     => std::cmp::Ordering::Less,
+            (None, Some(_))     => std::cmp::Ordering::Greater,
+            (None, None)        => std::cmp::Ordering::Equal,
+        }.then_with(|| a.room_number.cmp(&b.room_number)));
+
+        Ok(Some(tasks))
+    }
+
+    async fn update_status(
+        &self
+        , room_id   : Uuid
+        , req       : HousekeepingSchemaIn
+    ) -> Result<Option<HousekeepingSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let room = match rooms::Entity::find_by_id(room_id)
+            .one(&self.db)
+            .await
+            .map_err(ApiError::Database)? {
+                Some(r) => r,
+                None    => return Ok(None),
+            };
+
+        let mut room: rooms::ActiveModel = room.into();
+
+        room.housekeeping_status        = Set(req.status);
+        room.housekeeping_note          = Set(req.note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()));
+        room.housekeeping_updated_at    = Set(Some(now));
+
+        let txn = self.db.begin().await?;
+
+        let housekeeping = Self::to_schema(room.update(&txn).await.map_err(ApiError::Database)?);
+
+        events::record(&txn, DomainEvent::RoomHousekeepingChanged(housekeeping.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(housekeeping))
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::{HashMap, HashSet};
+use rust_decimal::Decimal;
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use crate::{
+    events::{self, DomainEvent},
+    models::{hotels, rooms, sea_orm_active_enums::HousekeepingStatus},
+    schemas::{hotels::HotelSchemaOut, rooms::RoomSchemaOut, imports::*},
+    services::{hotels::HotelService, traits::ImportServiceTrait},
+    error::ApiError,
+};
+
+#[derive(Clone)]
+pub struct ImportService {
+    db  : DatabaseConnection
+}
+
+/// A parsed CSV file: column positions by lower-cased header and the records
+/// with their line numbers, or the reason a record could not be read
+struct CsvTable {
+    columns     : HashMap<String, usize>
+    , records   : Vec<(u64, Result<csv::StringRecord, String>)>
+}
+
+impl CsvTable {
+    fn parse(input: &str, required: &[&str]) -> Result<Self, ApiError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .flexible(true)
+            .from_reader(input.as_bytes());
+
+        let columns = reader.headers()
+            .map_err(|e| ApiError::Validation(format!("Invalid CSV header: {}", e)))?
+            .iter()
+            .enumerate()
+            .map(|(i, h)| (h.to_lowercase(), i))
+            .collect::<HashMap<_, _>>();
+
+        let missing = required.iter()
+            .filter(|c| !columns.contains_key(**c))
+            .copied()
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            return Err(ApiError::Validation(format!("Missing CSV columns: {}", missing.join(", "))));
+        }
+
+        let records = reader.records()
+            .enumerate()
+            .map(|(i, r)| match r {
+                Ok(record)  => (record.position().map(|p| p.line()).unwrap_or(i as u64 + 2), Ok(record)),
+                Err(e)      => (e.position().map(|p| p.line()).unwrap_or(i as u64 + 2), Err(e.to_string())),
+            })
+            .collect();
+
+        Ok(Self { columns, records })
+    }
+
+    /// Trimmed value of `column`, empty when the column or the cell is missing
+    fn get<'a>(&self, record: &'a csv::StringRecord, column: &str) -> &'a str {
+        self.columns.get(column)
+            .and_then(|i| record.get(*i))
+            .unwrap_or("")
+    }
+}
+
+/// Collects the errors of one row
+struct RowErrors<'a> {
+    line        : u64
+    , errors    : &'a mut Vec<ImportRowError>
+    , failed    : bool
+}
+
+impl<'a> RowErrors<'a> {
+    fn new(line: u64, errors: &'a mut Vec<ImportRowError>) -> Self {
+        Self { line, errors, failed: false }
+    }
+
+    fn push(&mut self, column: Option<&str>, message: impl Into<String>) {
+        self.failed = true;
+        self.errors.push(ImportRowError {
+            line        : self.line,
+            column      : column.map(str::to_string),
+            message     : message.into(),
+        });
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "" | "true" | "yes" | "y" | "1"     => Some(true),
+        "false" | "no" | "n" | "0"          => Some(false),
+        _                                   => None,
+    }
+}
+
+impl ImportService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    fn report(dry_run: bool, total_rows: usize, valid_rows: usize, created: usize, errors: Vec<ImportRowError>) -> ImportReport {
+        ImportReport {
+            dry_run,
+            committed   : !dry_run && errors.is_empty(),
+            total_rows,
+            valid_rows,
+            created,
+            errors,
+        }
+    }
+}
+
+#[async_trait]
+impl ImportServiceTrait for ImportService {
+    async fn import_rooms(&self, csv: &str, dry_run: bool) -> Result<ImportReport, ApiError> {
+        let table = CsvTable::parse(csv, &["hotel", "room_number", "room_type", "price_per_night"])?;
+
+        // Validation and inserts share one transaction, so a concurrent import
+        // cannot slip in duplicate room numbers between the two
+        let txn = self.db.begin().await?;
+
+        // Hotels are referenced by id or by (unique) name
+        let hotels = hotels::Entity::find().all(&txn).await?;
+        let hotel_ids = hotels.iter().map(|h| h.id).collect::<HashSet<_>>();
+        let mut hotels_by_name: HashMap<String, Vec<Uuid>> = HashMap::new();
+        for hotel in &hotels {
+            hotels_by_name.entry(hotel.name.to_lowercase()).or_default().push(hotel.id);
+        }
+
+        let mut taken = rooms::Entity::find()
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|r| (r.hotel_id, r.room_number.to_lowercase()))
+            .collect::<HashSet<_>>();
+
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        let mut errors = Vec::new();
+        let mut valid = Vec::new();
+
+        for (line, record) in &table.records {
+            let mut row = RowErrors::new(*line, &mut errors);
+
+            let record = match record {
+                Ok(record)  => record,
+                Err(e)      => {
+                    row.push(None, e.clone());
+                    continue;
+                }
+            };
+
+            let hotel = table.get(record, "hotel");
+            let hotel_id = match Uuid::parse_str(hotel) {
+                Ok(id) if hotel_ids.contains(&id)   => Some(id),
+                Ok(_)                               => None,
+                Err(_)  => match hotels_by_name.get(&hotel.to_lowercase()).map(Vec::as_slice) {
+                    Some([id])  => Some(*id),
+                    Some(_)     => {
+                        row.push(Some("hotel"), format!("Hotel name {} is ambiguous, use the hotel id", hotel));
+                        None
+                    }
+                    None        => None,
+                },
+            };
+
+            if hotel_id.is_none() && !row.failed {
+                row.push(Some("hotel"), format!("Hotel {} not found", hotel));
+            }
+
+            let room_number = table.get(record, "room_number");
+            if room_number.is_empty() {
+                row.push(Some("room_number"), "room_number must not be empty");
+            }
+
+            let room_type = table.get(record, "room_type");
+            if room_type.is_empty() {
+                row.push(Some("room_type"), "room_type must not be empty");
+            }
+
+            let price_per_night = match table.get(record, "price_per_night").parse::<Decimal>() {
+                Ok(price) if price >= Decimal::ZERO => Some(price),
+                _                                   => {
+                    row.push(Some("price_per_night"), "price_per_night must be a non-negative number");
+                    None
+                }
+            };
+
+            let is_available = parse_bool(table.get(record, "is_available"));
+            if is_available.is_none() {
+                row.push(Some("is_available"), "is_available must be true or false");
+            }
+
+            let description = Some(table.get(record, "description"))
+                .filter(|d| !d.is_empty())
+                .map(str::to_string);
+
+            if let Some(hotel_id) = hotel_id {
+                if !room_number.is_empty() && !taken.insert((hotel_id, room_number.to_lowercase())) {
+                    row.push(Some("room_number"), format!("Room {} already exists in this hotel", room_number));
+                }
+            }
+
+            if let (false, Some(hotel_id), Some(price_per_night), Some(is_available))
+                = (row.failed, hotel_id, price_per_night, is_available) {
+                valid.push(rooms::ActiveModel {
+                    id                  : Set(Uuid::new_v4())
+                    , hotel_id          : Set(hotel_id)
+                    , room_number       : Set(room_number.to_string())
+                    , room_type         : Set(room_type.to_string())
+                    , price_per_night   : Set(price_per_night)
+                    , is_available      : Set(is_available)
+                    , description       : Set(description)
+                    , created_at        : Set(now)
+                    , updated_at        : Set(None)
+                    , housekeeping_status       : Set(HousekeepingStatus::Clean)
+                    , housekeeping_note         : Set(None)
+                    , housekeeping_updated_at   : Set(None)
+                });
+            }
+        }
+
+        let total_rows = table.records.len();
+        let valid_rows = valid.len();
+
+        if dry_run || !errors.is_empty() {
+            txn.rollback().await?;
+            return Ok(Self::report(dry_run, total_rows, valid_rows, 0, errors));
+        }
+
+        for room in valid {
+            let res = room.insert(&txn).await?;
+
+            events::record(&txn, DomainEvent::RoomCreated(RoomSchemaOut::from(res))).await?;
+        }
+
+        txn.commit().await?;
+        Ok(Self::report(dry_run, total_rows, valid_rows, valid_rows, errors))
+    }
+
+    async fn import_hotels(&self, csv: &str, dry_run: bool) -> Result<ImportReport, ApiError> {
+        let table = CsvTable::parse(csv, &["name"])?;
+
+        // Files predating the structured address have a single address column
+        if !table.columns.contains_key("address") {
+            let missing = ["street", "city", "country"].into_iter()
+                .filter(|c| !table.columns.contains_key(*c))
+                .collect::<Vec<_>>();
+
+            if !missing.is_empty() {
+                return Err(ApiError::Validation(format!("Missing CSV columns: {}, or address", missing.join(", "))));
+            }
+        }
+
+        let txn = self.db.begin().await?;
+
+        let mut taken = hotels::Entity::find()
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|h| (h.name.to_lowercase(), h.address.to_lowercase()))
+            .collect::<HashSet<_>>();
+
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        let mut errors = Vec::new();
+        let mut valid = Vec::new();
+
+        for (line, record) in &table.records {
+            let mut row = RowErrors::new(*line, &mut errors);
+
+            let record = match record {
+                Ok(record)  => record,
+                Err(e)      => {
+                    row.push(None, e.clone());
+                    continue;
+                }
+            };
+
+            let name = table.get(record, "name");
+            if name.is_empty() {
+                row.push(Some("name"), "name must not be empty");
+            }
+
+            let mut coordinate = |column: &'static str| match table.get(record, column) {
+                ""      => None,
+                value   => value.parse::<f64>().map_err(|_| row.push(Some(column), format!("{} must be a number", column))).ok(),
+            };
+            let (latitude, longitude) = (coordinate("latitude"), coordinate("longitude"));
+
+            if let Err((column, message)) = HotelService::check_coordinates(latitude, longitude) {
+                row.push(Some(column), message);
+            }
+
+            let address = HotelService::resolve_address(
+                Some(table.get(record, "address"))
+                , Some(table.get(record, "street"))
+                , Some(table.get(record, "city"))
+                , Some(table.get(record, "region"))
+                , Some(table.get(record, "postal_code"))
+                , Some(table.get(record, "country"))
+            ).map_err(|(column, message)| row.push(Some(column), message)).ok();
+
+            if let Some(address) = &address {
+                if !name.is_empty() && !taken.insert((name.to_lowercase(), address.address.to_lowercase())) {
+                    row.push(Some("name"), format!("Hotel {} at {} already exists", name, address.address));
+                }
+            }
+
+            let description = Some(table.get(record, "description"))
+                .filter(|d| !d.is_empty())
+                .map(str::to_string);
+
+            if let (false, Some(address)) = (row.failed, address) {
+                valid.push(hotels::ActiveModel {
+                    id              : Set(Uuid::new_v4())
+                    , name          : Set(name.to_string())
+                    , address       : Set(address.address)
+                    , street        : Set(address.street)
+                    , city          : Set(address.city)
+                    , region        : Set(address.region)
+                    , postal_code   : Set(address.postal_code)
+                    , country       : Set(address.country)
+                    , latitude      : Set(latitude)
+                    , longitude     : Set(longitude)
+                    , rating        : Set(None)
+                    , review_count  : Set(0)
+                    , description   : Set(description)
+                    , created_at    : Set(now)
+                    , updated_at    : Set(None)
+                    // Policies take the column defaults
+                    , ..Default::default()
+                });
+            }
+        }
+
+        let total_rows = table.records.len();
+        let valid_rows = valid.len();
+
+        if dry_run || !errors.is_empty() {
+            txn.rollback().await?;
+            return Ok(Self::report(dry_run, total_rows, valid_rows, 0, errors));
+        }
+
+        for hotel in valid {
+            let res = hotel.insert(&txn).await?;
+
+            events::record(&txn, DomainEvent::HotelCreated(HotelSchemaOut::from(res))).await?;
+        }
+
+        txn.commit().await?;
+        Ok(Self::report(dry_run, total_rows, valid_rows, valid_rows, errors))
+    }
+}
@@ -0,0 +1,223 @@
+use rust_decimal::{Decimal, prelude::ToPrimitive};
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use crate::{
+    events::{self, DomainEvent},
+    models::{guests, loyalty_entries, sea_orm_active_enums::{BookingStatus, LoyaltyEntryKind}},
+    schemas::{booking::BookingSchemaOut, loyalty::*},
+    services::traits::LoyaltyServiceTrait,
+    error::ApiError,
+};
+
+/// Points earned per currency unit of a completed stay, before the tier multiplier
+const POINTS_PER_UNIT: i64 = 1;
+
+/// Discount bought by one redeemed point
+pub const POINT_VALUE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// Entries counting towards the tier
+const EARNING_KINDS: [LoyaltyEntryKind; 2] = [LoyaltyEntryKind::Earned, LoyaltyEntryKind::Reversed];
+
+const REDEMPTION_KINDS: [LoyaltyEntryKind; 2] = [LoyaltyEntryKind::Redeemed, LoyaltyEntryKind::Refunded];
+
+#[derive(Clone)]
+pub struct LoyaltyService {
+    db  : DatabaseConnection
+}
+
+impl LoyaltyService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Discount bought by redeeming `points`
+    pub fn discount(points: i32) -> Decimal {
+        POINT_VALUE * Decimal::from(points)
+    }
+
+    /// Sum of the points of the entries matching `condition`
+    async fn sum<C: ConnectionTrait>(conn: &C, condition: Condition) -> Result<i64, DbErr> {
+        let sum: Option<Option<i64>> = loyalty_entries::Entity::find()
+            .select_only()
+            .column_as(loyalty_entries::Column::Points.sum(), "points")
+            .filter(condition)
+            .into_tuple()
+            .one(conn)
+            .await?;
+
+        Ok(sum.flatten().unwrap_or(0))
+    }
+
+    pub async fn balance<C: ConnectionTrait>(conn: &C, guest_id: Uuid) -> Result<i64, DbErr> {
+        Self::sum(conn, Condition::all().add(loyalty_entries::Column::GuestId.eq(guest_id))).await
+    }
+
+    pub async fn lifetime_points<C: ConnectionTrait>(conn: &C, guest_id: Uuid) -> Result<i64, DbErr> {
+        Self::sum(conn, Condition::all()
+            .add(loyalty_entries::Column::GuestId.eq(guest_id))
+            .add(loyalty_entries::Column::Kind.is_in(EARNING_KINDS))
+        ).await
+    }
+
+    /// Points a booking currently holds of the given kinds
+    async fn booking_points(
+        txn         : &DatabaseTransaction
+        , booking_id: Uuid
+        , kinds     : [LoyaltyEntryKind; 2]
+    ) -> Result<i64, DbErr> {
+        Self::sum(txn, Condition::all()
+            .add(loyalty_entries::Column::BookingId.eq(booking_id))
+            .add(loyalty_entries::Column::Kind.is_in(kinds))
+        ).await
+    }
+
+    async fn record(
+        txn         : &DatabaseTransaction
+        , booking   : &BookingSchemaOut
+        , kind      : LoyaltyEntryKind
+        , points    : i64
+    ) -> Result<(), DbErr> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let entry = loyalty_entries::ActiveModel {
+            id              : Set(Uuid::new_v4())
+            , guest_id      : Set(booking.guest_id)
+            , booking_id    : Set(Some(booking.id))
+            , kind          : Set(kind)
+            , points        : Set(points.clamp(i32::MIN.into(), i32::MAX.into()) as i32)
+            , created_at    : Set(now)
+        };
+
+        let entry = LoyaltyEntrySchemaOut::from(entry.insert(txn).await?);
+
+        events::record(txn, DomainEvent::LoyaltyPointsChanged(entry)).await
+    }
+
+    /// Earns the points of `booking` when it is completed and takes them back
+    /// when it no longer is
+    pub async fn sync_earning(txn: &DatabaseTransaction, booking: &BookingSchemaOut) -> Result<(), DbErr> {
+        Self::settle_earning(txn, booking, booking.status == BookingStatus::Completed).await
+    }
+
+    async fn settle_earning(
+        txn         : &DatabaseTransaction
+        , booking   : &BookingSchemaOut
+        , earns     : bool
+    ) -> Result<(), DbErr> {
+        let earned = Self::booking_points(txn, booking.id, EARNING_KINDS).await?;
+
+        if earns && earned == 0 {
+            // The tier reached before the stay sets its multiplier
+            let tier = LoyaltyTier::for_points(Self::lifetime_points(txn, booking.guest_id).await?);
+
+            let points = (booking.total_price * Decimal::from(POINTS_PER_UNIT) * tier.multiplier())
+                .floor()
+                .to_i64()
+                .unwrap_or(0);
+
+            if points > 0 {
+                Self::record(txn, booking, LoyaltyEntryKind::Earned, points).await?;
+            }
+        } else if !earns && earned != 0 {
+            Self::record(txn, booking, LoyaltyEntryKind::Reversed, -earned).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Brings the ledger in line with the status of `booking`: a completed
+    /// stay has earned its points, and the points redeemed on a booking are
+    /// spent unless it is cancelled
+    pub async fn sync_booking(txn: &DatabaseTransaction, booking: &BookingSchemaOut) -> Result<(), ApiError> {
+        Self::sync_earning(txn, booking).await?;
+
+        if booking.status == BookingStatus::Cancelled {
+            Self::refund(txn, booking).await?;
+        } else {
+            Self::redeem(txn, booking).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Takes back the points earned by `booking` and refunds those redeemed on
+    /// it, before it is deleted
+    pub async fn release_booking(txn: &DatabaseTransaction, booking: &BookingSchemaOut) -> Result<(), DbErr> {
+        Self::settle_earning(txn, booking, false).await?;
+        Self::refund(txn, booking).await
+    }
+
+    async fn refund(txn: &DatabaseTransaction, booking: &BookingSchemaOut) -> Result<(), DbErr> {
+        let redeemed = Self::booking_points(txn, booking.id, REDEMPTION_KINDS).await?;
+
+        if redeemed != 0 {
+            Self::record(txn, booking, LoyaltyEntryKind::Refunded, -redeemed).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn redeem(txn: &DatabaseTransaction, booking: &BookingSchemaOut) -> Result<(), ApiError> {
+        let points = i64::from(booking.loyalty_points_redeemed);
+
+        if points == 0 || Self::booking_points(txn, booking.id, REDEMPTION_KINDS).await? != 0 {
+            return Ok(());
+        }
+
+        // Locking the guest serializes the redemptions of their points, so
+        // concurrent bookings cannot spend the same balance
+        guests::Entity::find_by_id(booking.guest_id)
+            .lock_exclusive()
+            .one(txn)
+            .await?;
+
+        let balance = Self::balance(txn, booking.guest_id).await?;
+
+        if balance < points {
+            return Err(ApiError::Validation(format!(
+                "The guest has {} loyalty points, {} cannot be redeemed", balance.max(0), points
+            )));
+        }
+
+        Self::record(txn, booking, LoyaltyEntryKind::Redeemed, -points).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LoyaltyServiceTrait for LoyaltyService {
+    async fn get_account(&self, guest_id: Uuid) -> Result<Option<LoyaltyAccountSchemaOut>, DbErr> {
+        let Some(guest) = guests::Entity::find_by_id(guest_id).one(&self.db).await? else { return Ok(None) };
+
+        // The points of a merged guest moved to the guest it was merged into
+        let guest_id = guest.merged_into_id.unwrap_or(guest.id);
+
+        let entries = loyalty_entries::Entity::find()
+            .filter(loyalty_entries::Column::GuestId.eq(guest_id))
+            .order_by_desc(loyalty_entries::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        let balance: i64 = entries.iter().map(|e| i64::from(e.points)).sum();
+        let lifetime_points: i64 = entries.iter()
+            .filter(|e| EARNING_KINDS.contains(&e.kind))
+            .map(|e| i64::from(e.points))
+            .sum();
+
+        let tier = LoyaltyTier::for_points(lifetime_points);
+        let next_tier = tier.next();
+
+        Ok(Some(LoyaltyAccountSchemaOut {
+            guest_id,
+            tier,
+            balance,
+            lifetime_points,
+            next_tier,
+            points_to_next_tier : next_tier.map(|t| t.threshold() - lifetime_points),
+            point_value         : POINT_VALUE,
+            entries             : entries.into_iter().map(LoyaltyEntrySchemaOut::from).collect(),
+        }))
+    }
+}
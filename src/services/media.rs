@@ -0,0 +1,593 @@
+use std::{collections::HashMap, io::Cursor, sync::Arc};
+use image::ImageFormat;
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use crate::{
+    events::{self, DomainEvent, EventHandler},
+    models::{hotels, media, rooms},
+    schemas::{hotels::HotelSchemaOut, media::*, rooms::RoomSchemaOut},
+    services::traits::MediaServiceTrait,
+    storage::MediaStorage,
+    error::ApiError,
+};
+
+/// Longest side of the generated thumbnails, in pixels
+const THUMBNAIL_SIZE: u32 = 320;
+
+const MAX_CAPTION_CHARS: usize = 500;
+
+/// What is kept of a validated upload besides the original bytes
+struct ProcessedImage {
+    content_type    : &'static str
+    , extension     : &'static str
+    , width         : u32
+    , height        : u32
+    , thumbnail     : Vec<u8>
+}
+
+#[derive(Clone)]
+pub struct MediaService {
+    db          : DatabaseConnection
+    , storage   : Arc<dyn MediaStorage>
+}
+
+impl MediaService {
+    pub fn new(db: DatabaseConnection, storage: Arc<dyn MediaStorage>) -> Self {
+        Self { db, storage }
+    }
+
+    /// Decodes an uploaded image and renders its JPEG thumbnail
+    fn process_image(bytes: &[u8]) -> Result<ProcessedImage, ApiError> {
+        let unsupported = || ApiError::Validation("Only JPEG, PNG and WebP images are accepted".to_string());
+
+        let format = image::guess_format(bytes).map_err(|_| unsupported())?;
+
+        let (content_type, extension) = match format {
+            ImageFormat::Jpeg   => ("image/jpeg", "jpg"),
+            ImageFormat::Png    => ("image/png", "png"),
+            ImageFormat::WebP   => ("image/webp", "webp"),
+            _                   => return Err(unsupported()),
+        };
+
+        let image = image::load_from_memory_with_format(bytes, format)
+            .map_err(|e| ApiError::Validation(format!("Could not decode image: {}", e)))?;
+
+        let mut thumbnail = Vec::new();
+
+        image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+            .to_rgb8()
+            .write_to(&mut Cursor::new(&mut thumbnail), ImageFormat::Jpeg)
+            .map_err(|e| ApiError::Storage(format!("Could not render thumbnail: {}", e)))?;
+
+        Ok(ProcessedImage {
+            content_type
+            , extension
+            , width     : image.width()
+            , height    : image.height()
+            , thumbnail
+        })
+    }
+
+    /// Trimmed `caption`, blank ones are dropped
+    fn clean_caption(caption: Option<String>) -> Result<Option<String>, ApiError> {
+        let caption = caption
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+
+        if caption.as_ref().is_some_and(|c| c.chars().count() > MAX_CAPTION_CHARS) {
+            return Err(ApiError::Validation(format!("caption must be at most {} characters", MAX_CAPTION_CHARS)));
+        }
+
+        Ok(caption)
+    }
+
+    fn owner_of(m: &media::Model) -> MediaOwner {
+        match (m.hotel_id, m.room_id) {
+            (_, Some(room_id))  => MediaOwner::Room(room_id),
+            (hotel_id, None)    => MediaOwner::Hotel(hotel_id.unwrap_or_default()),
+        }
+    }
+
+    fn owner_condition(owner: MediaOwner) -> Condition {
+        match owner {
+            MediaOwner::Hotel(id)   => Condition::all().add(media::Column::HotelId.eq(id)),
+            MediaOwner::Room(id)    => Condition::all().add(media::Column::RoomId.eq(id)),
+        }
+    }
+
+    /// Locks the hotel or room owning a gallery, which serializes every change
+    /// to the gallery. Returns false when it does not exist.
+    async fn lock_owner(txn: &DatabaseTransaction, owner: MediaOwner) -> Result<bool, DbErr> {
+        Ok(match owner {
+            MediaOwner::Hotel(id)   => hotels::Entity::find_by_id(id).lock_exclusive().one(txn).await?.is_some(),
+            MediaOwner::Room(id)    => rooms::Entity::find_by_id(id).lock_exclusive().one(txn).await?.is_some(),
+        })
+    }
+
+    async fn owner_exists<C: ConnectionTrait>(conn: &C, owner: MediaOwner) -> Result<bool, DbErr> {
+        Ok(match owner {
+            MediaOwner::Hotel(id)   => hotels::Entity::find_by_id(id).one(conn).await?.is_some(),
+            MediaOwner::Room(id)    => rooms::Entity::find_by_id(id).one(conn).await?.is_some(),
+        })
+    }
+
+    /// Position and cover flag of a photo added to `gallery`: it goes last,
+    /// and the first photo of a gallery becomes its cover
+    fn appended(gallery: &[media::Model]) -> (i32, bool) {
+        let position = gallery.iter().map(|m| m.position).max().map_or(0, |p| p + 1);
+        (position, gallery.is_empty())
+    }
+
+    /// Rejects an order not listing every media of `gallery` exactly once
+    fn check_order(gallery: &HashMap<Uuid, media::Model>, media_ids: &[Uuid]) -> Result<(), ApiError> {
+        let mut listed = media_ids.to_vec();
+        listed.sort();
+        listed.dedup();
+
+        if listed.len() != media_ids.len() || listed.len() != gallery.len() || listed.iter().any(|id| !gallery.contains_key(id)) {
+            return Err(ApiError::Validation("media_ids must list every media of the gallery exactly once".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Photo taking over as cover from a deleted one: the first left in the
+    /// gallery's order
+    fn next_cover(gallery: Vec<media::Model>) -> Option<media::Model> {
+        gallery.into_iter().min_by_key(|m| (m.position, m.created_at))
+    }
+
+    async fn gallery<C: ConnectionTrait>(conn: &C, owner: MediaOwner) -> Result<Vec<media::Model>, DbErr> {
+        media::Entity::find()
+            .filter(Self::owner_condition(owner))
+            .order_by_asc(media::Column::Position)
+            .order_by_asc(media::Column::CreatedAt)
+            .all(conn)
+            .await
+    }
+
+    /// Removes the files of a media, after its row is gone
+    async fn remove_files(&self, storage_key: &str, thumbnail_key: &str) {
+        for key in [storage_key, thumbnail_key] {
+            if let Err(err) = self.storage.delete(key).await {
+                println!("⚠️ Failed to delete media file {}: {}", key, err);
+            }
+        }
+    }
+
+    /// Fills in the photo galleries of `hotels`
+    pub async fn attach_hotel_media<C: ConnectionTrait>(conn: &C, hotels: &mut [HotelSchemaOut]) -> Result<(), DbErr> {
+        let res = media::Entity::find()
+            .filter(media::Column::HotelId.is_in(hotels.iter().map(|h| h.id)))
+            .order_by_asc(media::Column::Position)
+            .order_by_asc(media::Column::CreatedAt)
+            .all(conn)
+            .await?;
+
+        let mut galleries: HashMap<Uuid, Vec<MediaSchemaOut>> = HashMap::new();
+        for m in res {
+            galleries.entry(m.hotel_id.unwrap_or_default()).or_default().push(MediaSchemaOut::from(m));
+        }
+
+        for hotel in hotels {
+            hotel.media = galleries.remove(&hotel.id).unwrap_or_default();
+        }
+
+        Ok(())
+    }
+
+    /// Fills in the photo galleries of `rooms`
+    pub async fn attach_room_media<C: ConnectionTrait>(conn: &C, rooms: &mut [RoomSchemaOut]) -> Result<(), DbErr> {
+        let res = media::Entity::find()
+            .filter(media::Column::RoomId.is_in(rooms.iter().map(|r| r.id)))
+            .order_by_asc(media::Column::Position)
+            .order_by_asc(media::Column::CreatedAt)
+            .all(conn)
+            .await?;
+
+        let mut galleries: HashMap<Uuid, Vec<MediaSchemaOut>> = HashMap::new();
+        for m in res {
+            galleries.entry(m.room_id.unwrap_or_default()).or_default().push(MediaSchemaOut::from(m));
+        }
+
+        for room in rooms {
+            room.media = galleries.remove(&room.id).unwrap_or_default();
+        }
+
+        Ok(())
+    }
+}
+
+/// Deletes the stored files of deleted hotels and rooms, whose media rows go
+/// with them
+pub struct MediaEventHandler {
+    storage : Arc<dyn MediaStorage>
+}
+
+impl MediaEventHandler {
+    pub fn new(storage: Arc<dyn MediaStorage>) -> Self {
+        Self { storage }
+    }
+}
+
+#[async_trait]
+impl EventHandler for MediaEventHandler {
+    fn name(&self) -> &'static str {
+        "media"
+    }
+
+    async fn handle(&self, _txn: &DatabaseTransaction, event: &DomainEvent) -> Result<(), DbErr> {
+        let owner = match event {
+            DomainEvent::HotelDeleted { id }    => MediaOwner::Hotel(*id),
+            DomainEvent::RoomDeleted { id }     => MediaOwner::Room(*id),
+            _                                   => return Ok(()),
+        };
+
+        self.storage
+            .delete_prefix(&owner.prefix())
+            .await
+            .map_err(|e| DbErr::Custom(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl MediaServiceTrait for MediaService {
+    async fn list_media(&self, owner: MediaOwner) -> Result<Option<Vec<MediaSchemaOut>>, ApiError> {
+        if !Self::owner_exists(&self.db, owner).await? {
+            return Ok(None);
+        }
+
+        let res = Self::gallery(&self.db, owner).await?;
+
+        Ok(Some(res.into_iter().map(MediaSchemaOut::from).collect()))
+    }
+
+    async fn get_media(&self, id: Uuid) -> Result<Option<MediaSchemaOut>, ApiError> {
+        let res = media::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?;
+
+        Ok(res.map(MediaSchemaOut::from))
+    }
+
+    async fn get_media_file(&self, id: Uuid, thumbnail: bool) -> Result<Option<MediaFile>, ApiError> {
+        let Some(m) = media::Entity::find_by_id(id).one(&self.db).await? else {
+            return Ok(None);
+        };
+
+        // Thumbnails are always rendered as JPEG
+        let (key, content_type) = if thumbnail {
+            (m.thumbnail_key, "image/jpeg".to_string())
+        } else {
+            (m.storage_key, m.content_type)
+        };
+
+        let bytes = self.storage
+            .get(&key)
+            .await
+            .map_err(|e| ApiError::Storage(e.to_string()))?;
+
+        Ok(bytes.map(|bytes| MediaFile { content_type, bytes }))
+    }
+
+    async fn upload_media(
+        &self
+        , owner     : MediaOwner
+        , bytes     : Vec<u8>
+        , caption   : Option<String>
+    ) -> Result<Option<MediaSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let caption = Self::clean_caption(caption)?;
+
+        if bytes.is_empty() {
+            return Err(ApiError::Validation("file must not be empty".to_string()));
+        }
+
+        // Decoding and resizing are CPU bound
+        let (bytes, image) = tokio::task::spawn_blocking(move || Self::process_image(&bytes).map(|image| (bytes, image)))
+            .await
+            .map_err(|e| ApiError::Storage(e.to_string()))??;
+
+        if !Self::owner_exists(&self.db, owner).await? {
+            return Ok(None);
+        }
+
+        let id = Uuid::new_v4();
+        let storage_key = format!("{}/{}.{}", owner.prefix(), id, image.extension);
+        let thumbnail_key = format!("{}/{}_thumb.jpg", owner.prefix(), id);
+
+        // Files are written first and removed again if the row cannot be saved
+        for (key, contents) in [(&storage_key, &bytes), (&thumbnail_key, &image.thumbnail)] {
+            if let Err(err) = self.storage.put(key, contents).await {
+                self.remove_files(&storage_key, &thumbnail_key).await;
+                return Err(ApiError::Storage(err.to_string()));
+            }
+        }
+
+        let saved: Result<Option<MediaSchemaOut>, ApiError> = async {
+            let txn = self.db.begin().await?;
+
+            if !Self::lock_owner(&txn, owner).await? {
+                return Ok(None);
+            }
+
+            let (position, is_cover) = Self::appended(&Self::gallery(&txn, owner).await?);
+
+            let m = media::ActiveModel {
+                id                  : Set(id)
+                , hotel_id          : Set(match owner { MediaOwner::Hotel(id) => Some(id), MediaOwner::Room(_) => None })
+                , room_id           : Set(match owner { MediaOwner::Room(id) => Some(id), MediaOwner::Hotel(_) => None })
+                , storage_key       : Set(storage_key.clone())
+                , thumbnail_key     : Set(thumbnail_key.clone())
+                , content_type      : Set(image.content_type.to_string())
+                , size_bytes        : Set(bytes.len() as i64)
+                , width             : Set(image.width as i32)
+                , height            : Set(image.height as i32)
+                , caption           : Set(caption)
+                , position          : Set(position)
+                , is_cover          : Set(is_cover)
+                , created_at        : Set(now)
+                , updated_at        : Set(None)
+            };
+
+            let m = MediaSchemaOut::from(m.insert(&txn).await?);
+
+            events::record(&txn, DomainEvent::MediaCreated(m.clone())).await?;
+            txn.commit().await?;
+
+            Ok(Some(m))
+        }.await;
+
+        if !matches!(saved, Ok(Some(_))) {
+            self.remove_files(&storage_key, &thumbnail_key).await;
+        }
+
+        saved
+    }
+
+    async fn update_media(&self, id: Uuid, req: MediaUpdateSchemaIn) -> Result<Option<MediaSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let caption = Self::clean_caption(req.caption)?;
+
+        let txn = self.db.begin().await?;
+
+        let Some(m) = media::Entity::find_by_id(id).lock_exclusive().one(&txn).await? else {
+            return Ok(None);
+        };
+
+        let mut m: media::ActiveModel = m.into();
+
+        m.caption       = Set(caption);
+        m.updated_at    = Set(Some(now));
+
+        let m = MediaSchemaOut::from(m.update(&txn).await?);
+
+        events::record(&txn, DomainEvent::MediaUpdated(m.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(m))
+    }
+
+    async fn set_cover(&self, id: Uuid) -> Result<Option<MediaSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let txn = self.db.begin().await?;
+
+        let Some(m) = media::Entity::find_by_id(id).one(&txn).await? else {
+            return Ok(None);
+        };
+
+        let owner = Self::owner_of(&m);
+
+        // Read again once the gallery is locked, it may have been deleted meanwhile
+        if !Self::lock_owner(&txn, owner).await? {
+            return Ok(None);
+        }
+
+        let Some(m) = media::Entity::find_by_id(id).one(&txn).await? else {
+            return Ok(None);
+        };
+
+        media::Entity::update_many()
+            .col_expr(media::Column::IsCover, Expr::value(false))
+            .filter(Self::owner_condition(owner))
+            .filter(media::Column::IsCover.eq(true))
+            .exec(&txn)
+            .await?;
+
+        let mut m: media::ActiveModel = m.into();
+
+        m.is_cover      = Set(true);
+        m.updated_at    = Set(Some(now));
+
+        let m = MediaSchemaOut::from(m.update(&txn).await?);
+
+        events::record(&txn, DomainEvent::MediaUpdated(m.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(m))
+    }
+
+    async fn reorder_media(&self, owner: MediaOwner, req: MediaOrderSchemaIn) -> Result<Option<Vec<MediaSchemaOut>>, ApiError> {
+        let txn = self.db.begin().await?;
+
+        if !Self::lock_owner(&txn, owner).await? {
+            return Ok(None);
+        }
+
+        let mut gallery = Self::gallery(&txn, owner)
+            .await?
+            .into_iter()
+            .map(|m| (m.id, m))
+            .collect::<HashMap<_, _>>();
+
+        Self::check_order(&gallery, &req.media_ids)?;
+
+        let mut res = Vec::with_capacity(req.media_ids.len());
+
+        for (position, id) in req.media_ids.iter().enumerate() {
+            let Some(m) = gallery.remove(id) else { continue };
+
+            if m.position == position as i32 {
+                res.push(MediaSchemaOut::from(m));
+                continue;
+            }
+
+            let mut m: media::ActiveModel = m.into();
+            m.position = Set(position as i32);
+
+            res.push(MediaSchemaOut::from(m.update(&txn).await?));
+        }
+
+        events::record(&txn, DomainEvent::MediaReordered { owner, media_ids: req.media_ids }).await?;
+        txn.commit().await?;
+
+        Ok(Some(res))
+    }
+
+    async fn delete_media(&self, id: Uuid) -> Result<bool, ApiError> {
+        let txn = self.db.begin().await?;
+
+        let Some(m) = media::Entity::find_by_id(id).one(&txn).await? else {
+            return Ok(false);
+        };
+
+        let owner = Self::owner_of(&m);
+
+        if !Self::lock_owner(&txn, owner).await? {
+            return Ok(false);
+        }
+
+        let Some(m) = media::Entity::find_by_id(id).one(&txn).await? else {
+            return Ok(false);
+        };
+
+        media::Entity::delete_by_id(id)
+            .exec(&txn)
+            .await?;
+
+        if m.is_cover {
+            if let Some(next) = Self::next_cover(Self::gallery(&txn, owner).await?) {
+                let mut next: media::ActiveModel = next.into();
+                next.is_cover = Set(true);
+                next.update(&txn).await?;
+            }
+        }
+
+        events::record(&txn, DomainEvent::MediaDeleted { id }).await?;
+        txn.commit().await?;
+
+        self.remove_files(&m.storage_key, &m.thumbnail_key).await;
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Duration};
+    use crate::storage::LocalStorage;
+
+    fn photo(position: i32, is_cover: bool, created_at: DateTime<FixedOffset>) -> media::Model {
+        let id = Uuid::new_v4();
+
+        media::Model {
+            id
+            , hotel_id          : Some(Uuid::nil())
+            , room_id           : None
+            , storage_key       : format!("hotels/{}/{}.jpg", Uuid::nil(), id)
+            , thumbnail_key     : format!("hotels/{}/{}_thumb.jpg", Uuid::nil(), id)
+            , content_type      : "image/jpeg".to_string()
+            , size_bytes        : 1
+            , width             : 1
+            , height            : 1
+            , caption           : None
+            , position
+            , is_cover
+            , created_at
+            , updated_at        : None
+        }
+    }
+
+    fn now() -> DateTime<FixedOffset> {
+        Utc::now().fixed_offset()
+    }
+
+    fn by_id(gallery: &[media::Model]) -> HashMap<Uuid, media::Model> {
+        gallery.iter().map(|m| (m.id, m.clone())).collect()
+    }
+
+    #[test]
+    fn first_photo_becomes_the_cover() {
+        assert_eq!(MediaService::appended(&[]), (0, true));
+    }
+
+    #[test]
+    fn new_photos_go_last_without_taking_the_cover() {
+        let gallery = [photo(0, true, now()), photo(4, false, now()), photo(2, false, now())];
+
+        assert_eq!(MediaService::appended(&gallery), (5, false));
+    }
+
+    #[test]
+    fn first_photo_left_takes_over_the_cover() {
+        let earlier = now();
+        let gallery = vec![
+            photo(3, false, earlier)
+            , photo(1, false, earlier + Duration::seconds(1))
+            , photo(1, false, earlier)
+        ];
+        let expected = gallery[2].id;
+
+        assert_eq!(MediaService::next_cover(gallery).map(|m| m.id), Some(expected));
+        assert!(MediaService::next_cover(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn accepts_an_order_listing_every_photo_once() {
+        let gallery = [photo(0, true, now()), photo(1, false, now())];
+
+        assert!(MediaService::check_order(&by_id(&gallery), &[gallery[1].id, gallery[0].id]).is_ok());
+    }
+
+    #[test]
+    fn rejects_incomplete_duplicate_or_foreign_orders() {
+        let gallery = [photo(0, true, now()), photo(1, false, now())];
+        let gallery_ids = by_id(&gallery);
+        let (a, b) = (gallery[0].id, gallery[1].id);
+
+        for media_ids in [vec![a], vec![a, a], vec![a, b, a], vec![a, Uuid::new_v4()], vec![a, b, Uuid::new_v4()]] {
+            assert!(
+                matches!(MediaService::check_order(&gallery_ids, &media_ids), Err(ApiError::Validation(_))),
+                "{:?} was accepted", media_ids
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn storage_refuses_keys_escaping_its_root() {
+        let root = std::env::temp_dir().join(format!("media-test-{}", Uuid::new_v4()));
+        let storage = LocalStorage::new(&root);
+
+        for key in ["", "../outside.jpg", "hotels/../../outside.jpg", "/etc/passwd", "./a.jpg"] {
+            let err = storage.put(key, b"x").await.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{:?}", key);
+
+            assert_eq!(storage.get(key).await.unwrap_err().kind(), std::io::ErrorKind::InvalidInput, "{:?}", key);
+            assert_eq!(storage.delete(key).await.unwrap_err().kind(), std::io::ErrorKind::InvalidInput, "{:?}", key);
+        }
+
+        assert_eq!(storage.delete_prefix("..").await.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+        assert!(!std::env::temp_dir().join("outside.jpg").exists());
+
+        storage.put("hotels/a/b.jpg", b"x").await.unwrap();
+        assert_eq!(storage.get("hotels/a/b.jpg").await.unwrap(), Some(b"x".to_vec()));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -1,8 +1,28 @@
 
 pub mod guards;
 pub mod traits;
+pub mod availability;
 
 pub mod hotels;
+pub mod amenities;
 pub mod guests;
 pub mod rooms;
-pub mod bookings;
\ No newline at end of file
+pub mod room_blocks;
+pub mod housekeeping;
+pub mod bookings;
+pub mod reservations;
+pub mod waitlist;
+pub mod reviews;
+pub mod media;
+pub mod webhooks;
+pub mod notifications;
+pub mod external_calendars;
+pub mod exports;
+pub mod imports;
+pub mod reports;
+pub mod calendar;
+pub mod search;
+pub mod audit;
+pub mod privacy;
+pub mod loyalty;
+pub mod promotions;
@@ -0,0 +1,301 @@
+use sea_orm::*;
+use sea_orm::sea_query::OnConflict;
+use uuid::Uuid;
+use chrono::{DateTime, Utc, FixedOffset};
+use chrono_tz::Tz;
+use crate::{
+    events::{DomainEvent, EventHandler},
+    models::{bookings, guests, hotels, notifications, rooms, waitlist_entries, sea_orm_active_enums::{BookingStatus, NotificationKind, NotificationStatus}},
+    notifications::templates::TemplateContext,
+    schemas::{notifications::*, waitlist::WaitlistEntrySchemaOut},
+    services::{availability, traits::NotificationServiceTrait},
+    error::ApiError,
+};
+
+#[derive(Clone)]
+pub struct NotificationService {
+    db  : DatabaseConnection
+}
+
+impl NotificationService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Check-in or check-out as the guest reads it, on the hotel's clock
+    fn stay_time(at: DateTime<FixedOffset>, timezone: Tz) -> String {
+        at.with_timezone(&timezone).format("%A %-d %B %Y, %H:%M").to_string()
+    }
+
+    /// Pending notification of `kind` addressed to `guest_id`, `None` when the
+    /// guest is gone or anonymized
+    async fn pending_for<C: ConnectionTrait>(
+        conn        : &C
+        , guest_id  : Uuid
+        , kind      : NotificationKind
+    ) -> Result<Option<notifications::ActiveModel>, DbErr> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let guest = match guests::Entity::find_by_id(guest_id).one(conn).await? {
+            Some(g) if g.anonymized_at.is_none() => g,
+            _       => return Ok(None),
+        };
+
+        Ok(Some(notifications::ActiveModel {
+            id                  : Set(Uuid::new_v4())
+            , booking_id        : Set(None)
+            , waitlist_entry_id : Set(None)
+            , kind              : Set(kind)
+            , recipient         : Set(guest.email)
+            , status            : Set(NotificationStatus::Pending)
+            , attempts          : Set(0)
+            , last_error        : Set(None)
+            , sent_at           : Set(None)
+            , created_at        : Set(now)
+        }))
+    }
+
+    /// Queues a notification of `kind` for the guest of a booking, returning
+    /// how many were queued. Queuing the same kind twice for one booking is a
+    /// no-op.
+    pub async fn queue<C: ConnectionTrait>(
+        conn        : &C
+        , booking_id: Uuid
+        , guest_id  : Uuid
+        , kind      : NotificationKind
+    ) -> Result<u64, DbErr> {
+        let Some(mut notification) = Self::pending_for(conn, guest_id, kind).await? else { return Ok(0) };
+
+        notification.booking_id = Set(Some(booking_id));
+
+        notifications::Entity::insert(notification)
+            .on_conflict(
+                OnConflict::columns([notifications::Column::BookingId, notifications::Column::Kind])
+                    .do_nothing()
+                    .to_owned()
+            )
+            .exec_without_returning(conn)
+            .await
+    }
+
+    /// Queues the email offering a freed room to a waitlisted guest, once per entry
+    pub async fn queue_waitlist_offer<C: ConnectionTrait>(conn: &C, entry: &WaitlistEntrySchemaOut) -> Result<(), DbErr> {
+        let Some(mut notification) = Self::pending_for(conn, entry.guest_id, NotificationKind::WaitlistOffer).await? else {
+            return Ok(());
+        };
+
+        notification.waitlist_entry_id = Set(Some(entry.id));
+
+        notifications::Entity::insert(notification)
+            .on_conflict(
+                OnConflict::columns([notifications::Column::WaitlistEntryId, notifications::Column::Kind])
+                    .do_nothing()
+                    .to_owned()
+            )
+            .exec_without_returning(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Queues reminders for confirmed bookings checking in before `until`
+    pub async fn queue_reminders(
+        &self
+        , now   : DateTime<FixedOffset>
+        , until : DateTime<FixedOffset>
+    ) -> Result<u64, DbErr> {
+        let upcoming = bookings::Entity::find()
+            .filter(bookings::Column::Status.eq(BookingStatus::Confirmed))
+            .filter(bookings::Column::CheckInDate.gt(now))
+            .filter(bookings::Column::CheckInDate.lte(until))
+            .all(&self.db)
+            .await?;
+
+        // Bookings already reminded are queued again without effect
+        let mut queued = 0;
+        for booking in &upcoming {
+            queued += Self::queue(&self.db, booking.id, booking.guest_id, NotificationKind::Reminder).await?;
+        }
+
+        Ok(queued)
+    }
+
+    /// Why a pending booking email should no longer be sent: the booking was
+    /// cancelled after the confirmation or reminder was queued
+    pub async fn obsolete_reason(&self, notification: &notifications::Model) -> Result<Option<&'static str>, DbErr> {
+        let Some(booking_id) = notification.booking_id else { return Ok(None) };
+
+        let Some(booking) = bookings::Entity::find_by_id(booking_id).one(&self.db).await? else { return Ok(None) };
+
+        Ok(match notification.kind {
+            NotificationKind::Confirmation | NotificationKind::Reminder
+                if booking.status != BookingStatus::Confirmed => Some("Booking is no longer confirmed"),
+            _   => None,
+        })
+    }
+
+    pub async fn pending(
+        &self
+        , max_attempts  : i32
+        , limit         : u64
+    ) -> Result<Vec<notifications::Model>, DbErr> {
+        notifications::Entity::find()
+            .filter(notifications::Column::Status.eq(NotificationStatus::Pending))
+            .filter(notifications::Column::Attempts.lt(max_attempts))
+            .order_by_asc(notifications::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+    }
+
+    /// Details used to render a notification, from its booking or waitlist entry
+    pub async fn template_context(&self, notification: &notifications::Model) -> Result<Option<TemplateContext>, DbErr> {
+        match (notification.booking_id, notification.waitlist_entry_id) {
+            (Some(booking_id), _)           => self.booking_context(booking_id).await,
+            (None, Some(waitlist_entry_id)) => self.waitlist_context(waitlist_entry_id).await,
+            (None, None)                    => Ok(None),
+        }
+    }
+
+    /// Booking, room, hotel and guest details used to render a notification
+    async fn booking_context(&self, booking_id: Uuid) -> Result<Option<TemplateContext>, DbErr> {
+        let (booking, room) = match bookings::Entity::find_by_id(booking_id)
+            .find_also_related(rooms::Entity)
+            .one(&self.db)
+            .await? {
+                Some(found) => found,
+                None        => return Ok(None),
+            };
+
+        let hotel = hotels::Entity::find_by_id(booking.hotel_id).one(&self.db).await?;
+        let guest = guests::Entity::find_by_id(booking.guest_id).one(&self.db).await?;
+
+        let (Some(hotel), Some(guest)) = (hotel, guest) else { return Ok(None) };
+        let timezone = availability::timezone(&hotel.timezone);
+
+        Ok(Some(TemplateContext {
+            reference       : booking.id.to_string()
+            , guest_name    : format!("{} {}", guest.first_name, guest.last_name)
+            , hotel_name    : hotel.name
+            , hotel_address : hotel.address
+            // Room type bookings learn their room number at check-in
+            , room_number   : room.map_or_else(|| "assigned at check-in".to_string(), |r| r.room_number)
+            , room_type     : booking.room_type
+            , check_in      : Self::stay_time(booking.check_in_date, timezone)
+            , check_out     : Self::stay_time(booking.check_out_date, timezone)
+            , total_price   : booking.total_price.to_string()
+            , status        : format!("{:?}", booking.status)
+        }))
+    }
+
+    /// Waitlist entry, offered room, hotel and guest details used to render an offer
+    async fn waitlist_context(&self, waitlist_entry_id: Uuid) -> Result<Option<TemplateContext>, DbErr> {
+        let (entry, room) = match waitlist_entries::Entity::find_by_id(waitlist_entry_id)
+            .find_also_related(rooms::Entity)
+            .one(&self.db)
+            .await? {
+                Some(found) => found,
+                None        => return Ok(None),
+            };
+
+        let hotel = hotels::Entity::find_by_id(entry.hotel_id).one(&self.db).await?;
+        let guest = guests::Entity::find_by_id(entry.guest_id).one(&self.db).await?;
+
+        let (Some(hotel), Some(guest)) = (hotel, guest) else { return Ok(None) };
+        let timezone = availability::timezone(&hotel.timezone);
+
+        Ok(Some(TemplateContext {
+            reference       : entry.id.to_string()
+            , guest_name    : format!("{} {}", guest.first_name, guest.last_name)
+            , hotel_name    : hotel.name
+            , hotel_address : hotel.address
+            , room_number   : room.map_or_else(|| "assigned at check-in".to_string(), |r| r.room_number)
+            , room_type     : entry.room_type
+            , check_in      : Self::stay_time(entry.check_in_date, timezone)
+            , check_out     : Self::stay_time(entry.check_out_date, timezone)
+            , total_price   : String::new()
+            , status        : format!("{:?}", entry.status)
+        }))
+    }
+
+    pub async fn mark_sent(&self, notification: notifications::Model) -> Result<(), DbErr> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        let attempts = notification.attempts + 1;
+
+        let mut notification: notifications::ActiveModel = notification.into();
+
+        notification.status     = Set(NotificationStatus::Sent);
+        notification.attempts   = Set(attempts);
+        notification.last_error = Set(None);
+        notification.sent_at    = Set(Some(now));
+
+        notification.update(&self.db).await?;
+        Ok(())
+    }
+
+    /// Records a failed send, giving up once `max_attempts` is reached
+    pub async fn mark_failed(
+        &self
+        , notification  : notifications::Model
+        , error         : String
+        , max_attempts  : i32
+    ) -> Result<(), DbErr> {
+        let attempts = notification.attempts + 1;
+
+        let mut notification: notifications::ActiveModel = notification.into();
+
+        notification.attempts   = Set(attempts);
+        notification.last_error = Set(Some(error));
+
+        if attempts >= max_attempts {
+            notification.status = Set(NotificationStatus::Failed);
+        }
+
+        notification.update(&self.db).await?;
+        Ok(())
+    }
+}
+
+/// Queues confirmation and cancellation emails from booking events, and offer
+/// emails from waitlist events
+pub struct NotificationEventHandler;
+
+#[async_trait]
+impl EventHandler for NotificationEventHandler {
+    fn name(&self) -> &'static str {
+        "notifications"
+    }
+
+    async fn handle(&self, txn: &DatabaseTransaction, event: &DomainEvent) -> Result<(), DbErr> {
+        // Bookings created confirmed get no separate confirmation event
+        let (kind, booking) = match event {
+            DomainEvent::BookingCreated(b)
+                if b.status == BookingStatus::Confirmed => (NotificationKind::Confirmation, b),
+            DomainEvent::BookingConfirmed(b)    => (NotificationKind::Confirmation, b),
+            DomainEvent::BookingCancelled(b)    => (NotificationKind::Cancellation, b),
+            DomainEvent::WaitlistOffered(entry) => return NotificationService::queue_waitlist_offer(txn, entry).await,
+            _                                   => return Ok(()),
+        };
+
+        NotificationService::queue(txn, booking.id, booking.guest_id, kind).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl NotificationServiceTrait for NotificationService {
+    async fn get_booking_notifications(
+        &self
+        , booking_id    : Uuid
+    ) -> Result<Vec<NotificationSchemaOut>, ApiError> {
+        let res = notifications::Entity::find()
+            .filter(notifications::Column::BookingId.eq(booking_id))
+            .order_by_asc(notifications::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(res.into_iter().map(NotificationSchemaOut::from).collect())
+    }
+}
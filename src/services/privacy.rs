@@ -0,0 +1,331 @@
+use sea_orm::*;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use crate::{
+    events::{self, DomainEvent},
+    models::{
+        bookings, guests, loyalty_entries, notifications, outbox, reservations, reviews, waitlist_entries, webhook_deliveries,
+        sea_orm_active_enums::NotificationStatus,
+    },
+    schemas::{
+        audit::AuditAction, booking::BookingSchemaOut, guests::GuestSchemaOut, loyalty::LoyaltyEntrySchemaOut, notifications::NotificationSchemaOut,
+        privacy::GuestDataExport, reviews::ReviewSchemaOut,
+    },
+    services::{audit::AuditService, reservations::ReservationService, waitlist::WaitlistService, traits::PrivacyServiceTrait},
+    error::ApiError,
+};
+
+#[derive(Clone)]
+pub struct PrivacyService {
+    db  : DatabaseConnection
+}
+
+impl PrivacyService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Email left on an anonymized guest, unique and undeliverable
+    fn anonymized_email(id: Uuid) -> String {
+        format!("{}@anonymized.invalid", id.simple())
+    }
+
+    /// `guest` with every personal field erased
+    fn scrubbed(guest: guests::Model, now: DateTimeWithTimeZone) -> guests::ActiveModel {
+        let email = Self::anonymized_email(guest.id);
+        let mut guest: guests::ActiveModel = guest.into();
+
+        guest.first_name            = Set("Anonymized".to_string());
+        guest.last_name             = Set("Guest".to_string());
+        guest.email                 = Set(email);
+        guest.phone                 = Set(None);
+        guest.street                = Set(None);
+        guest.city                  = Set(None);
+        guest.region                = Set(None);
+        guest.postal_code           = Set(None);
+        guest.country               = Set(None);
+        guest.date_of_birth         = Set(None);
+        guest.nationality           = Set(None);
+        guest.id_document_type      = Set(None);
+        guest.id_document_number    = Set(None);
+        guest.floor_preference      = Set(None);
+        guest.bed_preference        = Set(None);
+        guest.allergies             = Set(None);
+        guest.marketing_email_consent   = Set(false);
+        guest.marketing_sms_consent     = Set(false);
+        guest.marketing_consent_at  = Set(Some(now));
+        guest.anonymized_at         = Set(Some(now));
+        guest.updated_at            = Set(Some(now));
+
+        guest
+    }
+
+    /// `guest_id` of the data of an outbox or webhook payload, as text
+    fn payload_guest_id() -> Expr {
+        Expr::expr(Expr::cust("(payload -> 'data' ->> 'guest_id')"))
+    }
+
+    /// An event payload reduced to the id of what it was about
+    fn scrubbed_payload() -> SimpleExpr {
+        Expr::cust("jsonb_set(payload, '{data}', jsonb_build_object('id', payload -> 'data' -> 'id'))")
+    }
+
+    fn id_strings(ids: &[Uuid]) -> Vec<String> {
+        ids.iter().map(Uuid::to_string).collect()
+    }
+
+    /// Notifications about the bookings or waitlist entries of a guest
+    fn notifications_condition(booking_ids: Vec<Uuid>, entry_ids: Vec<Uuid>) -> Condition {
+        Condition::any()
+            .add(notifications::Column::BookingId.is_in(booking_ids))
+            .add(notifications::Column::WaitlistEntryId.is_in(entry_ids))
+    }
+}
+
+#[async_trait]
+impl PrivacyServiceTrait for PrivacyService {
+    async fn export_guest_data(&self, guest_id: Uuid) -> Result<Option<GuestDataExport>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        // One transaction so the archive is a consistent snapshot and the
+        // audit entry only exists when the archive was produced
+        let txn = self.db.begin().await?;
+
+        let Some(guest) = guests::Entity::find_by_id(guest_id).one(&txn).await? else { return Ok(None) };
+
+        let merged_guests = guests::Entity::find()
+            .filter(guests::Column::MergedIntoId.eq(guest_id))
+            .order_by_asc(guests::Column::MergedAt)
+            .all(&txn)
+            .await?;
+
+        let bookings = bookings::Entity::find()
+            .filter(bookings::Column::GuestId.eq(guest_id))
+            .order_by_asc(bookings::Column::CreatedAt)
+            .all(&txn)
+            .await?;
+
+        let mut reservations = Vec::new();
+        for reservation in reservations::Entity::find()
+            .filter(reservations::Column::GuestId.eq(guest_id))
+            .order_by_asc(reservations::Column::CreatedAt)
+            .all(&txn)
+            .await?
+        {
+            reservations.push(ReservationService::load(&txn, reservation).await?);
+        }
+
+        let waitlist_entries = waitlist_entries::Entity::find()
+            .filter(waitlist_entries::Column::GuestId.eq(guest_id))
+            .order_by_asc(waitlist_entries::Column::CreatedAt)
+            .all(&txn)
+            .await?;
+
+        let reviews = reviews::Entity::find()
+            .filter(reviews::Column::GuestId.eq(guest_id))
+            .order_by_asc(reviews::Column::CreatedAt)
+            .all(&txn)
+            .await?;
+
+        let loyalty_entries = loyalty_entries::Entity::find()
+            .filter(loyalty_entries::Column::GuestId.eq(guest_id))
+            .order_by_asc(loyalty_entries::Column::CreatedAt)
+            .all(&txn)
+            .await?;
+
+        let notifications = notifications::Entity::find()
+            .filter(Self::notifications_condition(
+                bookings.iter().map(|b| b.id).collect()
+                , waitlist_entries.iter().map(|w| w.id).collect()
+            ))
+            .order_by_asc(notifications::Column::CreatedAt)
+            .all(&txn)
+            .await?;
+
+        let export = GuestDataExport {
+            exported_at         : now,
+            guest               : GuestSchemaOut::from(guest),
+            merged_guests       : merged_guests.into_iter().map(GuestSchemaOut::from).collect(),
+            bookings            : bookings.into_iter().map(BookingSchemaOut::from).collect(),
+            reservations,
+            waitlist_entries    : waitlist_entries.into_iter().map(WaitlistService::to_schema).collect(),
+            reviews             : reviews.into_iter().map(ReviewSchemaOut::from).collect(),
+            loyalty_entries     : loyalty_entries.into_iter().map(LoyaltyEntrySchemaOut::from).collect(),
+            notifications       : notifications.into_iter().map(NotificationSchemaOut::from).collect(),
+        };
+
+        AuditService::record(&txn, AuditAction::GuestDataExported, "guest", guest_id, serde_json::json!({
+            "bookings"          : export.bookings.len()
+            , "merged_guests"   : export.merged_guests.len()
+            , "reservations"    : export.reservations.len()
+            , "waitlist_entries": export.waitlist_entries.len()
+            , "reviews"         : export.reviews.len()
+            , "loyalty_entries" : export.loyalty_entries.len()
+            , "notifications"   : export.notifications.len()
+        })).await?;
+
+        txn.commit().await?;
+
+        Ok(Some(export))
+    }
+
+    async fn anonymize_guest(&self, guest_id: Uuid) -> Result<Option<GuestSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        let email = Self::anonymized_email(guest_id);
+
+        let txn = self.db.begin().await?;
+
+        let guest = match guests::Entity::find_by_id(guest_id).lock_exclusive().one(&txn).await? {
+            Some(g) => g,
+            None    => return Ok(None),
+        };
+
+        if guest.anonymized_at.is_some() {
+            return Err(ApiError::Validation("The guest has already been anonymized".to_string()));
+        }
+
+        // Bookings are kept for accounting, they hold no personal data
+        let booking_ids: Vec<Uuid> = bookings::Entity::find()
+            .select_only()
+            .column(bookings::Column::Id)
+            .filter(bookings::Column::GuestId.eq(guest_id))
+            .into_tuple()
+            .all(&txn)
+            .await?;
+
+        let entry_ids: Vec<Uuid> = waitlist_entries::Entity::find()
+            .select_only()
+            .column(waitlist_entries::Column::Id)
+            .filter(waitlist_entries::Column::GuestId.eq(guest_id))
+            .into_tuple()
+            .all(&txn)
+            .await?;
+
+        // Unsent emails are dropped, sent ones lose their address
+        let cancelled = notifications::Entity::update_many()
+            .set(notifications::ActiveModel {
+                status          : Set(NotificationStatus::Failed)
+                , last_error    : Set(Some("Guest anonymized".to_string()))
+                , ..Default::default()
+            })
+            .filter(Self::notifications_condition(booking_ids.clone(), entry_ids.clone()))
+            .filter(notifications::Column::Status.eq(NotificationStatus::Pending))
+            .exec(&txn)
+            .await?;
+
+        let notifications = notifications::Entity::update_many()
+            .set(notifications::ActiveModel {
+                recipient       : Set(email.clone())
+                , ..Default::default()
+            })
+            .filter(Self::notifications_condition(booking_ids, entry_ids.clone()))
+            .exec(&txn)
+            .await?;
+
+        // Waitlist entries are wishes for future stays, nothing to account for
+        waitlist_entries::Entity::delete_many()
+            .filter(waitlist_entries::Column::Id.is_in(entry_ids.clone()))
+            .exec(&txn)
+            .await?;
+
+        for id in &entry_ids {
+            events::record(&txn, DomainEvent::WaitlistEntryDeleted { id: *id }).await?;
+        }
+
+        let reservations = reservations::Entity::update_many()
+            .set(reservations::ActiveModel {
+                name            : Set(None)
+                , updated_at    : Set(Some(now))
+                , ..Default::default()
+            })
+            .filter(reservations::Column::GuestId.eq(guest_id))
+            .filter(reservations::Column::Name.is_not_null())
+            .exec(&txn)
+            .await?;
+
+        // Scores keep counting towards the hotel rating, the free text goes
+        let reviews = reviews::Entity::update_many()
+            .set(reviews::ActiveModel {
+                comment         : Set(None)
+                , updated_at    : Set(Some(now))
+                , ..Default::default()
+            })
+            .filter(reviews::Column::GuestId.eq(guest_id))
+            .filter(reviews::Column::Comment.is_not_null())
+            .exec(&txn)
+            .await?;
+
+        // Tombstones of merged duplicates still hold their own profiles
+        let tombstones = guests::Entity::find()
+            .filter(guests::Column::MergedIntoId.eq(guest_id))
+            .filter(guests::Column::AnonymizedAt.is_null())
+            .all(&txn)
+            .await?;
+
+        let tombstones_scrubbed = tombstones.len();
+        let mut guest_ids = vec![guest_id];
+
+        for tombstone in tombstones {
+            guest_ids.push(tombstone.id);
+            Self::scrubbed(tombstone, now).update(&txn).await?;
+        }
+
+        // Events of the guests, or carrying their id such as reservation events
+        // with their name
+        let guest_events = Condition::any()
+            .add(Condition::all()
+                .add(outbox::Column::AggregateType.eq("guest"))
+                .add(outbox::Column::AggregateId.is_in(guest_ids.clone())))
+            .add(Self::payload_guest_id().is_in(Self::id_strings(&guest_ids)));
+
+        // Past ones are purged
+        let events = outbox::Entity::delete_many()
+            .filter(outbox::Column::ProcessedAt.is_not_null())
+            .filter(guest_events.clone())
+            .exec(&txn)
+            .await?;
+
+        // Pending ones keep their id but are not handed out anymore, except
+        // booking events which hold no personal data and drive waitlist offers
+        // and housekeeping
+        let events_scrubbed = outbox::Entity::update_many()
+            .col_expr(outbox::Column::Payload, Self::scrubbed_payload())
+            .col_expr(outbox::Column::ProcessedAt, Expr::value(now))
+            .col_expr(outbox::Column::LastError, Expr::value("Guest anonymized"))
+            .filter(outbox::Column::ProcessedAt.is_null())
+            .filter(outbox::Column::AggregateType.ne("booking"))
+            .filter(guest_events)
+            .exec(&txn)
+            .await?;
+
+        // Webhooks only keep the id of what they were about, pending ones are
+        // still delivered that way
+        let webhook_deliveries = webhook_deliveries::Entity::update_many()
+            .col_expr(webhook_deliveries::Column::Payload, Self::scrubbed_payload())
+            .filter(Self::payload_guest_id().is_in(Self::id_strings(&guest_ids)))
+            .exec(&txn)
+            .await?;
+
+        let guest = GuestSchemaOut::from(Self::scrubbed(guest, now).update(&txn).await?);
+
+        events::record(&txn, DomainEvent::GuestAnonymized { id: guest_id }).await?;
+
+        AuditService::record(&txn, AuditAction::GuestAnonymized, "guest", guest_id, serde_json::json!({
+            "notifications_scrubbed"    : notifications.rows_affected
+            , "notifications_cancelled" : cancelled.rows_affected
+            , "waitlist_entries_deleted": entry_ids.len()
+            , "reservations_scrubbed"   : reservations.rows_affected
+            , "reviews_scrubbed"        : reviews.rows_affected
+            , "events_purged"           : events.rows_affected
+            , "events_scrubbed"         : events_scrubbed.rows_affected
+            , "webhook_deliveries_scrubbed" : webhook_deliveries.rows_affected
+            , "merged_guests_scrubbed"  : tombstones_scrubbed
+        })).await?;
+
+        txn.commit().await?;
+
+        Ok(Some(guest))
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use rust_decimal::Decimal;
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{DateTime, Utc, FixedOffset};
+use crate::{
+    events::{self, DomainEvent},
+    models::{bookings, hotels, promotions, sea_orm_active_enums::{BookingStatus, DiscountKind}},
+    schemas::{booking::BookingSchemaOut, promotions::*},
+    services::traits::PromotionServiceTrait,
+    error::ApiError,
+};
+
+const MAX_CODE_CHARS: usize = 32;
+
+/// Booking a promotion is checked against
+pub struct PromotionTarget<'a> {
+    pub hotel_id            : Uuid
+    , pub room_type         : &'a str
+    , pub nights            : i64
+    // Unknown for quotes made without a guest, skipping the per guest limit
+    , pub guest_id          : Option<Uuid>
+    // The booking itself, when checking it again
+    , pub exclude_booking   : Option<Uuid>
+}
+
+#[derive(Clone)]
+pub struct PromotionService {
+    db  : DatabaseConnection
+}
+
+impl PromotionService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Uppercased `code`, the form promotions are stored and looked up in
+    pub fn clean_code(code: &str) -> String {
+        code.trim().to_uppercase()
+    }
+
+    /// Bookings not cancelled made with the promotion, optionally of a guest
+    async fn redemptions<C: ConnectionTrait>(
+        conn                : &C
+        , promotion_id      : Uuid
+        , guest_id          : Option<Uuid>
+        , exclude_booking   : Option<Uuid>
+    ) -> Result<u64, DbErr> {
+        let mut query = bookings::Entity::find()
+            .filter(bookings::Column::PromotionId.eq(promotion_id))
+            .filter(bookings::Column::Status.ne(BookingStatus::Cancelled));
+
+        if let Some(guest_id) = guest_id {
+            query = query.filter(bookings::Column::GuestId.eq(guest_id));
+        }
+
+        if let Some(id) = exclude_booking {
+            query = query.filter(bookings::Column::Id.ne(id));
+        }
+
+        query.count(conn).await
+    }
+
+    /// Redemption counts of the promotions of `ids`
+    async fn redemption_counts<C: ConnectionTrait>(conn: &C, ids: Vec<Uuid>) -> Result<HashMap<Uuid, i64>, DbErr> {
+        let counts: Vec<(Uuid, i64)> = bookings::Entity::find()
+            .select_only()
+            .column(bookings::Column::PromotionId)
+            .column_as(bookings::Column::Id.count(), "count")
+            .filter(bookings::Column::PromotionId.is_in(ids))
+            .filter(bookings::Column::Status.ne(BookingStatus::Cancelled))
+            .group_by(bookings::Column::PromotionId)
+            .into_tuple()
+            .all(conn)
+            .await?;
+
+        Ok(counts.into_iter().collect())
+    }
+
+    async fn present<C: ConnectionTrait>(conn: &C, promotion: promotions::Model) -> Result<PromotionSchemaOut, DbErr> {
+        let count = Self::redemptions(conn, promotion.id, None, None).await?;
+        Ok(PromotionSchemaOut::new(promotion, count as i64))
+    }
+
+    /// Rejects a redemption above the limits of `promotion`
+    async fn check_limits<C: ConnectionTrait>(
+        conn                : &C
+        , promotion         : &promotions::Model
+        , guest_id          : Option<Uuid>
+        , exclude_booking   : Option<Uuid>
+    ) -> Result<(), ApiError> {
+        if let Some(max) = promotion.max_redemptions {
+            if Self::redemptions(conn, promotion.id, None, exclude_booking).await? >= max as u64 {
+                return Err(ApiError::Validation(format!("Promo code {} has been fully redeemed", promotion.code)));
+            }
+        }
+
+        if let (Some(max), Some(guest_id)) = (promotion.max_redemptions_per_guest, guest_id) {
+            if Self::redemptions(conn, promotion.id, Some(guest_id), exclude_booking).await? >= max as u64 {
+                return Err(ApiError::Validation(format!("The guest has already redeemed promo code {}", promotion.code)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Promotion of `code` and the discount it takes off `subtotal`, rejecting
+    /// a code that does not apply to `target`. With `lock` the promotion stays
+    /// locked until the transaction ends, so concurrent bookings are counted
+    /// one at a time against its limits.
+    pub async fn apply<C: ConnectionTrait>(
+        conn        : &C
+        , code      : &str
+        , target    : &PromotionTarget<'_>
+        , subtotal  : Decimal
+        , lock      : bool
+    ) -> Result<(promotions::Model, Decimal), ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        let code = Self::clean_code(code);
+
+        let mut query = promotions::Entity::find().filter(promotions::Column::Code.eq(code.as_str()));
+
+        if lock {
+            query = query.lock_exclusive();
+        }
+
+        let Some(promotion) = query.one(conn).await? else {
+            return Err(ApiError::Validation(format!("Unknown promo code {}", code)));
+        };
+
+        let started = promotion.starts_at.is_none_or(|t| t <= now);
+        let ended = promotion.ends_at.is_some_and(|t| t <= now);
+
+        if !promotion.is_active || !started || ended {
+            return Err(ApiError::Validation(format!("Promo code {} is not valid at this time", code)));
+        }
+
+        if promotion.hotel_id.is_some_and(|h| h != target.hotel_id) {
+            return Err(ApiError::Validation(format!("Promo code {} does not apply to this hotel", code)));
+        }
+
+        if promotion.room_type.as_ref().is_some_and(|t| t != target.room_type) {
+            return Err(ApiError::Validation(format!("Promo code {} does not apply to {} rooms", code, target.room_type)));
+        }
+
+        if let Some(min_nights) = promotion.min_nights {
+            if target.nights < i64::from(min_nights) {
+                return Err(ApiError::Validation(format!("Promo code {} needs a stay of at least {} nights", code, min_nights)));
+            }
+        }
+
+        Self::check_limits(conn, &promotion, target.guest_id, target.exclude_booking).await?;
+
+        let discount = match promotion.discount_kind {
+            DiscountKind::Percent   => (subtotal * promotion.discount_value / Decimal::ONE_HUNDRED).round_dp(2),
+            DiscountKind::Fixed     => promotion.discount_value,
+        };
+
+        Ok((promotion, discount.min(subtotal)))
+    }
+
+    /// Counts `booking` against the limits of its promotion again, when it is
+    /// no longer cancelled
+    pub async fn reclaim(txn: &DatabaseTransaction, booking: &BookingSchemaOut) -> Result<(), ApiError> {
+        let Some(promotion_id) = booking.discounts.iter().find_map(|d| d.promotion_id) else { return Ok(()) };
+
+        let Some(promotion) = promotions::Entity::find_by_id(promotion_id).lock_exclusive().one(txn).await? else {
+            return Ok(());
+        };
+
+        Self::check_limits(txn, &promotion, Some(booking.guest_id), Some(booking.id)).await
+    }
+
+    /// Validates `req` into the fields of a promotion, rejecting a code taken
+    /// by another one
+    async fn check<C: ConnectionTrait>(
+        conn    : &C
+        , req   : PromotionSchemaIn
+        , id    : Option<Uuid>
+        , now   : DateTime<FixedOffset>
+    ) -> Result<promotions::ActiveModel, ApiError> {
+        let code = Self::clean_code(&req.code);
+        let name = req.name.trim().to_string();
+        let room_type = req.room_type.map(|t| t.trim().to_string());
+
+        if code.is_empty() || code.chars().count() > MAX_CODE_CHARS {
+            return Err(ApiError::Validation(format!("code must be 1 to {} characters", MAX_CODE_CHARS)));
+        }
+
+        if !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(ApiError::Validation("code may only contain letters, digits, - and _".to_string()));
+        }
+
+        if name.is_empty() {
+            return Err(ApiError::Validation("name must not be empty".to_string()));
+        }
+
+        if req.discount_value <= Decimal::ZERO {
+            return Err(ApiError::Validation("discount_value must be positive".to_string()));
+        }
+
+        if req.discount_kind == DiscountKind::Percent && req.discount_value > Decimal::ONE_HUNDRED {
+            return Err(ApiError::Validation("A percent discount_value must be at most 100".to_string()));
+        }
+
+        if room_type.as_ref().is_some_and(|t| t.is_empty()) {
+            return Err(ApiError::Validation("room_type must not be empty".to_string()));
+        }
+
+        if [req.min_nights, req.max_redemptions, req.max_redemptions_per_guest].iter().flatten().any(|n| *n < 1) {
+            return Err(ApiError::Validation("min_nights and the redemption limits must be at least 1".to_string()));
+        }
+
+        if let (Some(starts_at), Some(ends_at)) = (req.starts_at, req.ends_at) {
+            if ends_at <= starts_at {
+                return Err(ApiError::Validation("ends_at must be after starts_at".to_string()));
+            }
+        }
+
+        if let Some(hotel_id) = req.hotel_id {
+            if hotels::Entity::find_by_id(hotel_id).one(conn).await?.is_none() {
+                return Err(ApiError::HotelNotFound(hotel_id.to_string()));
+            }
+        }
+
+        let mut taken = promotions::Entity::find().filter(promotions::Column::Code.eq(code.as_str()));
+
+        if let Some(id) = id {
+            taken = taken.filter(promotions::Column::Id.ne(id));
+        }
+
+        if taken.one(conn).await?.is_some() {
+            return Err(ApiError::Validation(format!("Promo code {} already exists", code)));
+        }
+
+        Ok(promotions::ActiveModel {
+            id                  : Set(id.unwrap_or_else(Uuid::new_v4))
+            , code              : Set(code)
+            , name              : Set(name)
+            , discount_kind     : Set(req.discount_kind)
+            , discount_value    : Set(req.discount_value)
+            , hotel_id          : Set(req.hotel_id)
+            , room_type         : Set(room_type)
+            , min_nights        : Set(req.min_nights)
+            , starts_at         : Set(req.starts_at)
+            , ends_at           : Set(req.ends_at)
+            , max_redemptions   : Set(req.max_redemptions)
+            , max_redemptions_per_guest : Set(req.max_redemptions_per_guest)
+            , is_active         : Set(req.is_active)
+            , created_at        : Set(now)
+            , updated_at        : Set(None)
+        })
+    }
+}
+
+#[async_trait]
+impl PromotionServiceTrait for PromotionService {
+    async fn list_promotions(&self, filter: PromotionFilter) -> Result<Vec<PromotionSchemaOut>, ApiError> {
+        let mut query = promotions::Entity::find().order_by_asc(promotions::Column::Code);
+
+        if let Some(hotel_id) = filter.hotel_id {
+            query = query.filter(promotions::Column::HotelId.eq(hotel_id));
+        }
+
+        if let Some(is_active) = filter.is_active {
+            query = query.filter(promotions::Column::IsActive.eq(is_active));
+        }
+
+        let promotions = query.all(&self.db).await?;
+
+        // Count the redemptions of every promotion in one query
+        let counts = Self::redemption_counts(&self.db, promotions.iter().map(|p| p.id).collect()).await?;
+
+        Ok(promotions.into_iter().map(|p| {
+            let count = counts.get(&p.id).copied().unwrap_or(0);
+            PromotionSchemaOut::new(p, count)
+        }).collect())
+    }
+
+    async fn get_promotion(&self, id: Uuid) -> Result<Option<PromotionSchemaOut>, ApiError> {
+        let Some(promotion) = promotions::Entity::find_by_id(id).one(&self.db).await? else { return Ok(None) };
+
+        Ok(Some(Self::present(&self.db, promotion).await?))
+    }
+
+    async fn create_promotion(&self, req: PromotionSchemaIn) -> Result<PromotionSchemaOut, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let txn = self.db.begin().await?;
+
+        let promotion = Self::check(&txn, req, None, now).await?;
+        let promotion = PromotionSchemaOut::new(promotion.insert(&txn).await?, 0);
+
+        events::record(&txn, DomainEvent::PromotionCreated(promotion.clone())).await?;
+        txn.commit().await?;
+
+        Ok(promotion)
+    }
+
+    async fn update_promotion(&self, id: Uuid, req: PromotionSchemaIn) -> Result<Option<PromotionSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let txn = self.db.begin().await?;
+
+        // Locked like a redemption, so limits are never checked against a half updated promotion
+        let Some(existing) = promotions::Entity::find_by_id(id).lock_exclusive().one(&txn).await? else { return Ok(None) };
+
+        let mut promotion = Self::check(&txn, req, Some(id), now).await?;
+        promotion.created_at = Set(existing.created_at);
+        promotion.updated_at = Set(Some(now));
+
+        let promotion = Self::present(&txn, promotion.update(&txn).await?).await?;
+
+        events::record(&txn, DomainEvent::PromotionUpdated(promotion.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(promotion))
+    }
+
+    async fn delete_promotion(&self, id: Uuid) -> Result<bool, ApiError> {
+        let txn = self.db.begin().await?;
+
+        // Bookings made with the promotion keep its code and discount
+        let res = promotions::Entity::delete_by_id(id)
+            .exec(&txn)
+            .await?;
+
+        if res.rows_affected > 0 {
+            events::record(&txn, DomainEvent::PromotionDeleted { id }).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(res.rows_affected > 0)
+    }
+}
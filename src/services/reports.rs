@@ -0,0 +1,162 @@
+use rust_decimal::Decimal;
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::NaiveDate;
+use crate::{
+    models::{hotels, rooms},
+    schemas::reports::*,
+    services::{availability, traits::ReportServiceTrait},
+    error::ApiError,
+};
+
+/// Longest range a report may cover, keeps the generated day series bounded
+const MAX_REPORT_DAYS: i64 = 3 * 366;
+
+/// Per night a confirmed or completed booking occupies its room and earns its
+/// total price spread evenly over its nights. Nights are calendar dates in the
+/// hotel's timezone, $6.
+const OCCUPANCY_SQL: &str = r#"
+WITH days AS (
+    SELECT d::date AS day
+    FROM generate_series($2::date, $3::date - 1, interval '1 day') AS d
+),
+sold AS (
+    SELECT days.day
+        , count(*) AS room_nights
+        , sum(b.total_price / GREATEST((b.check_out_date AT TIME ZONE $6)::date - (b.check_in_date AT TIME ZONE $6)::date, 1)) AS revenue
+    FROM bookings b
+    JOIN days ON days.day >= (b.check_in_date AT TIME ZONE $6)::date
+        AND days.day < (b.check_out_date AT TIME ZONE $6)::date
+    WHERE b.hotel_id = $1
+        AND b.status IN ('confirmed', 'completed')
+    GROUP BY days.day
+),
+cancelled AS (
+    SELECT (b.check_in_date AT TIME ZONE $6)::date AS day
+        , count(*) AS cancellations
+    FROM bookings b
+    WHERE b.hotel_id = $1
+        AND b.status = 'cancelled'
+        AND (b.check_in_date AT TIME ZONE $6)::date >= $2::date
+        AND (b.check_in_date AT TIME ZONE $6)::date < $3::date
+    GROUP BY 1
+)
+SELECT min(days.day) AS period_start
+    , max(days.day) + 1 AS period_end
+    , (count(*) * $5)::bigint AS available_room_nights
+    , COALESCE(sum(sold.room_nights), 0)::bigint AS sold_room_nights
+    , COALESCE(sum(sold.revenue), 0)::numeric AS revenue
+    , COALESCE(sum(cancelled.cancellations), 0)::bigint AS cancellations
+FROM days
+LEFT JOIN sold ON sold.day = days.day
+LEFT JOIN cancelled ON cancelled.day = days.day
+GROUP BY date_trunc($4, days.day)
+ORDER BY period_start
+"#;
+
+#[derive(Debug, FromQueryResult)]
+struct OccupancyRow {
+    period_start            : NaiveDate
+    , period_end            : NaiveDate
+    , available_room_nights : i64
+    , sold_room_nights      : i64
+    , revenue               : Decimal
+    , cancellations         : i64
+}
+
+impl OccupancyRow {
+    fn into_point(self) -> OccupancyPoint {
+        let ratio = |value: Decimal, nights: i64| match nights {
+            0 => Decimal::ZERO,
+            n => (value / Decimal::from(n)).round_dp(2),
+        };
+
+        OccupancyPoint {
+            period_start                : self.period_start
+            , period_end                : self.period_end
+            , available_room_nights     : self.available_room_nights
+            , sold_room_nights          : self.sold_room_nights
+            , occupancy_rate            : match self.available_room_nights {
+                0 => 0.0,
+                n => self.sold_room_nights as f64 / n as f64,
+            }
+            , revenue                   : self.revenue.round_dp(2)
+            , adr                       : ratio(self.revenue, self.sold_room_nights)
+            , revpar                    : ratio(self.revenue, self.available_room_nights)
+            , cancellations             : self.cancellations
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ReportService {
+    db  : DatabaseConnection
+}
+
+impl ReportService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ReportServiceTrait for ReportService {
+    async fn occupancy_report(
+        &self
+        , hotel_id      : Uuid
+        , from          : NaiveDate
+        , to            : NaiveDate
+        , granularity   : Granularity
+    ) -> Result<Option<OccupancyReport>, ApiError> {
+        if to <= from {
+            return Err(ApiError::Validation("to must be after from".to_string()));
+        }
+
+        if (to - from).num_days() > MAX_REPORT_DAYS {
+            return Err(ApiError::Validation(format!("Reports cover at most {} days", MAX_REPORT_DAYS)));
+        }
+
+        let Some(hotel) = hotels::Entity::find_by_id(hotel_id).one(&self.db).await? else {
+            return Ok(None);
+        };
+
+        let room_count = rooms::Entity::find()
+            .filter(rooms::Column::HotelId.eq(hotel_id))
+            .count(&self.db)
+            .await? as i64;
+
+        let rows = OccupancyRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres
+            , OCCUPANCY_SQL
+            , [
+                hotel_id.into()
+                , from.into()
+                , to.into()
+                , granularity.as_str().into()
+                , room_count.into()
+                , availability::timezone(&hotel.timezone).name().into()
+            ]
+        ))
+        .all(&self.db)
+        .await?;
+
+        let totals = OccupancyRow {
+            period_start            : from
+            , period_end            : to
+            , available_room_nights : rows.iter().map(|r| r.available_room_nights).sum()
+            , sold_room_nights      : rows.iter().map(|r| r.sold_room_nights).sum()
+            , revenue               : rows.iter().map(|r| r.revenue).sum()
+            , cancellations         : rows.iter().map(|r| r.cancellations).sum()
+        };
+
+        Ok(Some(OccupancyReport {
+            hotel_id
+            , from
+            , to
+            , granularity
+            , room_count
+            , totals        : totals.into_point()
+            , series        : rows.into_iter().map(OccupancyRow::into_point).collect()
+        }))
+    }
+}
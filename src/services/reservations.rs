@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use rust_decimal::Decimal;
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use crate::{
+    events::{self, DomainEvent},
+    models::{bookings, reservations, rooms, sea_orm_active_enums::BookingStatus},
+    schemas::{booking::*, reservations::*},
+    services::{availability, bookings::BookingService, traits::ReservationServiceTrait},
+    error::ApiError,
+};
+
+/// Most rooms a single reservation may hold
+const MAX_RESERVATION_ROOMS: usize = 100;
+
+#[derive(Clone)]
+pub struct ReservationService {
+    db  : DatabaseConnection
+}
+
+impl ReservationService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    fn to_schema(r: reservations::Model, bookings: Vec<bookings::Model>) -> ReservationSchemaOut {
+        let total_price = bookings.iter()
+            .filter(|b| b.status != BookingStatus::Cancelled)
+            .map(|b| b.total_price)
+            .sum::<Decimal>();
+
+        ReservationSchemaOut {
+            id              : r.id
+            , guest_id      : r.guest_id
+            , name          : r.name
+            , total_price
+            , bookings      : bookings.into_iter().map(BookingSchemaOut::from).collect()
+            , created_at    : r.created_at
+            , updated_at    : r.updated_at
+        }
+    }
+
+    /// Locks the reservation row so group operations on it are serialized
+    async fn lock<C: ConnectionTrait>(conn: &C, id: Uuid) -> Result<Option<reservations::Model>, DbErr> {
+        reservations::Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(conn)
+            .await
+    }
+
+    /// Reservation with its bookings in creation order
+    pub async fn load<C: ConnectionTrait>(conn: &C, reservation: reservations::Model) -> Result<ReservationSchemaOut, DbErr> {
+        let bookings = reservation.find_related(bookings::Entity)
+            .order_by_asc(bookings::Column::CreatedAt)
+            .order_by_asc(bookings::Column::Id)
+            .all(conn)
+            .await?;
+
+        Ok(Self::to_schema(reservation, bookings))
+    }
+
+    /// Bookings of the reservation a group change applies to
+    async fn holding_bookings<C: ConnectionTrait>(conn: &C, id: Uuid) -> Result<Vec<bookings::Model>, DbErr> {
+        bookings::Entity::find()
+            .filter(bookings::Column::ReservationId.eq(id))
+            .filter(bookings::Column::Status.is_in(availability::HOLDING_STATUSES))
+            .all(conn)
+            .await
+    }
+
+    fn clean_name(name: Option<String>) -> Option<String> {
+        name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty())
+    }
+}
+
+#[async_trait]
+impl ReservationServiceTrait for ReservationService {
+    async fn list_reservations(&self, guest_id: Option<Uuid>) -> Result<Vec<ReservationSchemaOut>, ApiError> {
+        let mut reservations = reservations::Entity::find();
+
+        if let Some(guest_id) = guest_id {
+            reservations = reservations.filter(reservations::Column::GuestId.eq(guest_id));
+        }
+
+        let reservations = reservations
+            .order_by_desc(reservations::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        // Load the bookings of every reservation in one query
+        let mut bookings: HashMap<Uuid, Vec<bookings::Model>> = HashMap::new();
+
+        for booking in bookings::Entity::find()
+            .filter(bookings::Column::ReservationId.is_in(reservations.iter().map(|r| r.id)))
+            .order_by_asc(bookings::Column::CreatedAt)
+            .order_by_asc(bookings::Column::Id)
+            .all(&self.db)
+            .await? {
+            if let Some(reservation_id) = booking.reservation_id {
+                bookings.entry(reservation_id).or_default().push(booking);
+            }
+        }
+
+        Ok(reservations.into_iter().map(|r| {
+            let group = bookings.remove(&r.id).unwrap_or_default();
+            Self::to_schema(r, group)
+        }).collect())
+    }
+
+    async fn get_reservation(&self, id: Uuid) -> Result<Option<ReservationSchemaOut>, ApiError> {
+        let Some(reservation) = reservations::Entity::find_by_id(id).one(&self.db).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self::load(&self.db, reservation).await?))
+    }
+
+    async fn create_reservation(&self, req: ReservationSchemaIn) -> Result<ReservationSchemaOut, ApiError> {
+        if req.rooms.is_empty() {
+            return Err(ApiError::Validation("rooms must not be empty".to_string()));
+        }
+
+        if req.rooms.len() > MAX_RESERVATION_ROOMS {
+            return Err(ApiError::Validation(format!("A reservation holds at most {} rooms", MAX_RESERVATION_ROOMS)));
+        }
+
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let txn = self.db.begin().await?;
+
+        let reservation = reservations::ActiveModel {
+            id              : Set(Uuid::new_v4())
+            , guest_id      : Set(req.guest_id)
+            , name          : Set(Self::clean_name(req.name))
+            , created_at    : Set(now)
+            , updated_at    : Set(None)
+        };
+
+        let reservation = reservation.insert(&txn).await?;
+
+        // Booking a room locks every room of its type, so the rooms are booked
+        // in (hotel_id, room_type) order to keep concurrent reservations from
+        // deadlocking. Unknown rooms sort first and fail on their own.
+        let room_ids: Vec<Uuid> = req.rooms.iter().filter_map(|room| room.room_id).collect();
+        let types: HashMap<Uuid, (Uuid, String)> = rooms::Entity::find()
+            .filter(rooms::Column::Id.is_in(room_ids))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|room| (room.id, (room.hotel_id, room.room_type)))
+            .collect();
+
+        let mut rooms = req.rooms;
+        rooms.sort_by_cached_key(|room| match room.room_id {
+            Some(room_id)   => types.get(&room_id).cloned(),
+            None            => room.hotel_id.zip(room.room_type.clone()),
+        });
+
+        // Every room goes through the single booking checks, the first one
+        // failing rolls back the whole group
+        for room in rooms {
+            BookingService::insert(&txn, BookingSchemaIn {
+                room_id         : room.room_id
+                , hotel_id      : room.hotel_id
+                , room_type     : room.room_type
+                , guest_id      : room.guest_id.unwrap_or(req.guest_id)
+                , check_in_date : req.check_in_date
+                , check_out_date: req.check_out_date
+                , total_price   : room.total_price
+                , status        : req.status.clone()
+                , redeem_points : None
+                , promo_code    : None
+            }, Some(reservation.id)).await?;
+        }
+
+        let reservation = Self::load(&txn, reservation).await?;
+
+        events::record(&txn, DomainEvent::ReservationCreated(reservation.clone())).await?;
+        txn.commit().await?;
+
+        Ok(reservation)
+    }
+
+    async fn update_reservation(
+        &self
+        , id    : Uuid
+        , req   : ReservationUpdateSchemaIn
+    ) -> Result<Option<ReservationSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let txn = self.db.begin().await?;
+
+        let Some(reservation) = Self::lock(&txn, id).await? else { return Ok(None) };
+
+        if let Some(BookingStatus::Cancelled | BookingStatus::Completed) = req.status {
+            return Err(ApiError::Validation("status must be Pending or Confirmed, cancel the reservation instead".to_string()));
+        }
+
+        // Move every booking first and check them afterwards, so the group's
+        // bookings are not checked against their own old dates
+        let mut moved = Vec::new();
+
+        for booking in Self::holding_bookings(&txn, id).await? {
+            let (check_in, check_out) = BookingService::stay_times(
+                &txn
+                , booking.hotel_id
+                , req.check_in_date.unwrap_or(booking.check_in_date.into())
+                , req.check_out_date.unwrap_or(booking.check_out_date.into())
+            ).await?;
+
+            let previous_status = booking.status.clone();
+            let mut booking: bookings::ActiveModel = booking.into();
+
+            booking.check_in_date   = Set(check_in);
+            booking.check_out_date  = Set(check_out);
+
+            if let Some(status) = &req.status {
+                booking.status = Set(status.clone());
+            }
+
+            booking.updated_at = Set(Some(now));
+
+            moved.push((booking.update(&txn).await?, previous_status));
+        }
+
+        // Checked in the same (hotel_id, room_type) order bookings are made in
+        moved.sort_by(|(a, _), (b, _)| (a.hotel_id, &a.room_type).cmp(&(b.hotel_id, &b.room_type)));
+
+        for (booking, previous_status) in moved {
+            BookingService::ensure_bookable(&txn, &BookingSchemaIn {
+                room_id         : booking.room_id
+                , hotel_id      : Some(booking.hotel_id)
+                , room_type     : Some(booking.room_type.clone())
+                , guest_id      : booking.guest_id
+                , check_in_date : booking.check_in_date.into()
+                , check_out_date: booking.check_out_date.into()
+                , total_price   : booking.total_price
+                , status        : booking.status.clone()
+                , redeem_points : None
+                , promo_code    : None
+            }, Some(booking.id)).await?;
+
+            BookingService::record_update(&txn, &BookingSchemaOut::from(booking), previous_status).await?;
+        }
+
+        let mut reservation: reservations::ActiveModel = reservation.into();
+
+        if req.name.is_some() {
+            reservation.name = Set(Self::clean_name(req.name));
+        }
+
+        reservation.updated_at = Set(Some(now));
+
+        let reservation = Self::load(&txn, reservation.update(&txn).await?).await?;
+
+        events::record(&txn, DomainEvent::ReservationUpdated(reservation.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(reservation))
+    }
+
+    async fn cancel_reservation(&self, id: Uuid) -> Result<Option<ReservationSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let txn = self.db.begin().await?;
+
+        let Some(reservation) = Self::lock(&txn, id).await? else { return Ok(None) };
+
+        for booking in Self::holding_bookings(&txn, id).await? {
+            let previous_status = booking.status.clone();
+            let mut booking: bookings::ActiveModel = booking.into();
+
+            booking.status      = Set(BookingStatus::Cancelled);
+            booking.updated_at  = Set(Some(now));
+
+            let booking = BookingSchemaOut::from(booking.update(&txn).await?);
+            BookingService::record_update(&txn, &booking, previous_status).await?;
+        }
+
+        let mut reservation: reservations::ActiveModel = reservation.into();
+
+        reservation.updated_at = Set(Some(now));
+
+        let reservation = Self::load(&txn, reservation.update(&txn).await?).await?;
+
+        events::record(&txn, DomainEvent::ReservationCancelled(reservation.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(reservation))
+    }
+}
@@ -0,0 +1,343 @@
+use sea_orm::*;
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use crate::{
+    events::{self, DomainEvent},
+    models::{bookings, hotels, reviews, sea_orm_active_enums::{BookingStatus, ReviewStatus}},
+    schemas::{hotels::HotelSchemaOut, reviews::*},
+    services::{hotels::HotelService, media::MediaService, traits::ReviewServiceTrait},
+    error::ApiError,
+};
+
+const MAX_COMMENT_CHARS: usize = 5000;
+
+#[derive(Debug, FromQueryResult)]
+struct ScoreAverages {
+    review_count    : i64,
+    rating          : Option<f64>,
+    cleanliness     : Option<f64>,
+    location        : Option<f64>,
+    service         : Option<f64>,
+}
+
+#[derive(Clone)]
+pub struct ReviewService {
+    db  : DatabaseConnection
+}
+
+impl ReviewService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    fn check_scores(scores: [(&str, i16); 4]) -> Result<(), ApiError> {
+        for (field, score) in scores {
+            if !(1..=5).contains(&score) {
+                return Err(ApiError::Validation(format!("{} must be between 1 and 5", field)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Trimmed `comment`, blank ones are dropped
+    fn clean_comment(comment: Option<String>) -> Result<Option<String>, ApiError> {
+        let comment = comment
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+
+        if comment.as_ref().is_some_and(|c| c.chars().count() > MAX_COMMENT_CHARS) {
+            return Err(ApiError::Validation(format!("comment must be at most {} characters", MAX_COMMENT_CHARS)));
+        }
+
+        Ok(comment)
+    }
+
+    /// Average of `column` rounded to two decimals
+    fn average(column: reviews::Column) -> SimpleExpr {
+        Expr::cust_with_expr("round(avg($1), 2)::double precision", Expr::col(column))
+    }
+
+    async fn averages<C: ConnectionTrait>(conn: &C, hotel_id: Uuid) -> Result<ScoreAverages, DbErr> {
+        let res = reviews::Entity::find()
+            .select_only()
+            .column_as(reviews::Column::Id.count(), "review_count")
+            .column_as(Self::average(reviews::Column::Rating), "rating")
+            .column_as(Self::average(reviews::Column::Cleanliness), "cleanliness")
+            .column_as(Self::average(reviews::Column::Location), "location")
+            .column_as(Self::average(reviews::Column::Service), "service")
+            .filter(reviews::Column::HotelId.eq(hotel_id))
+            .filter(reviews::Column::Status.eq(ReviewStatus::Published))
+            .into_model::<ScoreAverages>()
+            .one(conn)
+            .await?;
+
+        Ok(res.unwrap_or(ScoreAverages {
+            review_count    : 0,
+            rating          : None,
+            cleanliness     : None,
+            location        : None,
+            service         : None,
+        }))
+    }
+
+    /// Recomputes the rating and review count of a hotel from its published
+    /// reviews. The hotel row is locked first so concurrent moderations of the
+    /// same hotel apply one after the other and the last one sees every change.
+    /// A changed rating is recorded as a `HotelUpdated` event.
+    async fn refresh_rating(txn: &DatabaseTransaction, hotel_id: Uuid) -> Result<(), DbErr> {
+        let Some(hotel) = hotels::Entity::find_by_id(hotel_id).lock_exclusive().one(txn).await? else {
+            return Ok(());
+        };
+
+        let averages = Self::averages(txn, hotel_id).await?;
+
+        if hotel.rating == averages.rating && hotel.review_count == averages.review_count as i32 {
+            return Ok(());
+        }
+
+        let mut hotel: hotels::ActiveModel = hotel.into();
+
+        hotel.rating        = Set(averages.rating);
+        hotel.review_count  = Set(averages.review_count as i32);
+
+        let mut hotel = [HotelSchemaOut::from(hotel.update(txn).await?)];
+        HotelService::attach_amenities(txn, &mut hotel).await?;
+        MediaService::attach_hotel_media(txn, &mut hotel).await?;
+
+        let [hotel] = hotel;
+
+        events::record(txn, DomainEvent::HotelUpdated(hotel)).await
+    }
+}
+
+#[async_trait]
+impl ReviewServiceTrait for ReviewService {
+    async fn list_reviews(&self, filter: ReviewFilter) -> Result<Vec<ReviewSchemaOut>, ApiError> {
+        let mut query = reviews::Entity::find();
+
+        if let Some(hotel_id) = filter.hotel_id {
+            query = query.filter(reviews::Column::HotelId.eq(hotel_id));
+        }
+
+        if let Some(guest_id) = filter.guest_id {
+            query = query.filter(reviews::Column::GuestId.eq(guest_id));
+        }
+
+        if let Some(status) = filter.status {
+            query = query.filter(reviews::Column::Status.eq(status));
+        }
+
+        let res = query
+            .order_by_desc(reviews::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        Ok(res.into_iter().map(ReviewSchemaOut::from).collect())
+    }
+
+    async fn get_review(&self, id: Uuid) -> Result<Option<ReviewSchemaOut>, ApiError> {
+        let res = reviews::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?;
+
+        Ok(res.map(ReviewSchemaOut::from))
+    }
+
+    async fn create_review(&self, req: ReviewSchemaIn) -> Result<ReviewSchemaOut, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        Self::check_scores([
+            ("rating", req.rating)
+            , ("cleanliness", req.cleanliness)
+            , ("location", req.location)
+            , ("service", req.service)
+        ])?;
+
+        let comment = Self::clean_comment(req.comment)?;
+
+        let txn = self.db.begin().await?;
+
+        // Locking the booking serializes concurrent reviews of the same stay
+        let Some(booking) = bookings::Entity::find_by_id(req.booking_id).lock_exclusive().one(&txn).await? else {
+            return Err(ApiError::Validation(format!("Booking {} does not exist", req.booking_id)));
+        };
+
+        if booking.status != BookingStatus::Completed {
+            return Err(ApiError::Validation("Only completed stays can be reviewed".to_string()));
+        }
+
+        let reviewed = reviews::Entity::find()
+            .filter(reviews::Column::BookingId.eq(booking.id))
+            .one(&txn)
+            .await?
+            .is_some();
+
+        if reviewed {
+            return Err(ApiError::Validation("The stay has already been reviewed".to_string()));
+        }
+
+        // Reviews wait for moderation, so they do not count towards the rating yet
+        let review = reviews::ActiveModel {
+            id                  : Set(Uuid::new_v4())
+            , booking_id        : Set(booking.id)
+            , hotel_id          : Set(booking.hotel_id)
+            , guest_id          : Set(booking.guest_id)
+            , rating            : Set(req.rating)
+            , cleanliness       : Set(req.cleanliness)
+            , location          : Set(req.location)
+            , service           : Set(req.service)
+            , comment           : Set(comment)
+            , status            : Set(ReviewStatus::Pending)
+            , moderation_note   : Set(None)
+            , moderated_at      : Set(None)
+            , created_at        : Set(now)
+            , updated_at        : Set(None)
+        };
+
+        let review = ReviewSchemaOut::from(review.insert(&txn).await?);
+
+        events::record(&txn, DomainEvent::ReviewCreated(review.clone())).await?;
+        txn.commit().await?;
+
+        Ok(review)
+    }
+
+    async fn update_review(&self, id: Uuid, req: ReviewUpdateSchemaIn) -> Result<Option<ReviewSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        Self::check_scores([
+            ("rating", req.rating)
+            , ("cleanliness", req.cleanliness)
+            , ("location", req.location)
+            , ("service", req.service)
+        ])?;
+
+        let comment = Self::clean_comment(req.comment)?;
+
+        let txn = self.db.begin().await?;
+
+        let Some(review) = reviews::Entity::find_by_id(id).lock_exclusive().one(&txn).await? else {
+            return Ok(None);
+        };
+
+        let was_published = review.status == ReviewStatus::Published;
+
+        // An edited review goes back to moderation
+        let mut review: reviews::ActiveModel = review.into();
+
+        review.rating           = Set(req.rating);
+        review.cleanliness      = Set(req.cleanliness);
+        review.location         = Set(req.location);
+        review.service          = Set(req.service);
+        review.comment          = Set(comment);
+        review.status           = Set(ReviewStatus::Pending);
+        review.moderation_note  = Set(None);
+        review.moderated_at     = Set(None);
+        review.updated_at       = Set(Some(now));
+
+        let review = ReviewSchemaOut::from(review.update(&txn).await?);
+
+        if was_published {
+            Self::refresh_rating(&txn, review.hotel_id).await?;
+        }
+
+        events::record(&txn, DomainEvent::ReviewUpdated(review.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(review))
+    }
+
+    async fn moderate_review(&self, id: Uuid, req: ReviewModerationSchemaIn) -> Result<Option<ReviewSchemaOut>, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        if req.status == ReviewStatus::Pending {
+            return Err(ApiError::Validation("status must be Published or Rejected".to_string()));
+        }
+
+        let note = req.note
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty());
+
+        let txn = self.db.begin().await?;
+
+        let Some(review) = reviews::Entity::find_by_id(id).lock_exclusive().one(&txn).await? else {
+            return Ok(None);
+        };
+
+        // Only publishing or withdrawing a review changes the rating
+        let affects_rating = (review.status == ReviewStatus::Published) != (req.status == ReviewStatus::Published);
+
+        let mut review: reviews::ActiveModel = review.into();
+
+        review.status           = Set(req.status);
+        review.moderation_note  = Set(note);
+        review.moderated_at     = Set(Some(now));
+        review.updated_at       = Set(Some(now));
+
+        let review = ReviewSchemaOut::from(review.update(&txn).await?);
+
+        if affects_rating {
+            Self::refresh_rating(&txn, review.hotel_id).await?;
+        }
+
+        events::record(&txn, DomainEvent::ReviewModerated(review.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(review))
+    }
+
+    async fn delete_review(&self, id: Uuid) -> Result<bool, ApiError> {
+        let txn = self.db.begin().await?;
+
+        let Some(review) = reviews::Entity::find_by_id(id).lock_exclusive().one(&txn).await? else {
+            return Ok(false);
+        };
+
+        reviews::Entity::delete_by_id(id)
+            .exec(&txn)
+            .await?;
+
+        if review.status == ReviewStatus::Published {
+            Self::refresh_rating(&txn, review.hotel_id).await?;
+        }
+
+        events::record(&txn, DomainEvent::ReviewDeleted { id }).await?;
+        txn.commit().await?;
+
+        Ok(true)
+    }
+
+    async fn get_hotel_reviews(&self, hotel_id: Uuid) -> Result<Option<Vec<ReviewSchemaOut>>, ApiError> {
+        if hotels::Entity::find_by_id(hotel_id).one(&self.db).await?.is_none() {
+            return Ok(None);
+        }
+
+        let res = reviews::Entity::find()
+            .filter(reviews::Column::HotelId.eq(hotel_id))
+            .filter(reviews::Column::Status.eq(ReviewStatus::Published))
+            .order_by_desc(reviews::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        Ok(Some(res.into_iter().map(ReviewSchemaOut::from).collect()))
+    }
+
+    async fn get_hotel_review_summary(&self, hotel_id: Uuid) -> Result<Option<ReviewSummary>, ApiError> {
+        if hotels::Entity::find_by_id(hotel_id).one(&self.db).await?.is_none() {
+            return Ok(None);
+        }
+
+        let averages = Self::averages(&self.db, hotel_id).await?;
+
+        Ok(Some(ReviewSummary {
+            hotel_id,
+            review_count    : averages.review_count,
+            rating          : averages.rating,
+            cleanliness     : averages.cleanliness,
+            location        : averages.location,
+            service         : averages.service,
+        }))
+    }
+}
@@ -0,0 +1,184 @@
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use crate::{
+    events::{self, DomainEvent},
+    models::{room_blocks, rooms},
+    schemas::room_blocks::*,
+    services::{availability, traits::RoomBlockServiceTrait},
+    error::ApiError,
+};
+
+#[derive(Clone)]
+pub struct RoomBlockService {
+    db  : DatabaseConnection
+}
+
+impl RoomBlockService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    fn validate(req: &RoomBlockSchemaIn) -> Result<(), ApiError> {
+        if req.end_date < req.start_date {
+            return Err(ApiError::Validation("end_date must not be before start_date".to_string()));
+        }
+
+        if req.reason.trim().is_empty() {
+            return Err(ApiError::Validation("reason must not be empty".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Locks the room row so the block and concurrent bookings of the room are
+    /// serialized, then rejects the block when a holding booking stays any of its nights
+    async fn ensure_no_bookings(
+        txn         : &DatabaseTransaction
+        , room_id   : Uuid
+        , req       : &RoomBlockSchemaIn
+    ) -> Result<(), ApiError> {
+        let room = rooms::Entity::find_by_id(room_id)
+            .lock_exclusive()
+            .one(txn)
+            .await?
+            .ok_or_else(|| ApiError::RoomNotFound(room_id.to_string()))?;
+
+        let tz = availability::hotel_timezone(txn, room.hotel_id).await?;
+
+        if !availability::bookings_during(txn, room_id, req.start_date, req.end_date, tz).await?.is_empty() {
+            return Err(ApiError::RoomUnavailable(room_id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn to_schema(b: room_blocks::Model) -> RoomBlockSchemaOut {
+        RoomBlockSchemaOut {
+            id              : b.id
+            , room_id       : b.room_id
+            , kind          : b.kind
+            , start_date    : b.start_date
+            , end_date      : b.end_date
+            , reason        : b.reason
+            , created_at    : b.created_at
+            , updated_at    : b.updated_at
+        }
+    }
+}
+
+#[async_trait]
+impl RoomBlockServiceTrait for RoomBlockService {
+    async fn list_blocks(&self, room_id: Uuid) -> Result<Option<Vec<RoomBlockSchemaOut>>, ApiError> {
+        if rooms::Entity::find_by_id(room_id).one(&self.db).await?.is_none() {
+            return Ok(None);
+        }
+
+        let res = room_blocks::Entity::find()
+            .filter(room_blocks::Column::RoomId.eq(room_id))
+            .order_by_asc(room_blocks::Column::StartDate)
+            .all(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(Some(res.into_iter().map(Self::to_schema).collect()))
+    }
+
+    async fn get_block(&self, room_id: Uuid, id: Uuid) -> Result<Option<RoomBlockSchemaOut>, ApiError> {
+        let res = room_blocks::Entity::find_by_id(id)
+            .filter(room_blocks::Column::RoomId.eq(room_id))
+            .one(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(res.map(Self::to_schema))
+    }
+
+    async fn create_block(
+        &self
+        , room_id   : Uuid
+        , req       : RoomBlockSchemaIn
+    ) -> Result<RoomBlockSchemaOut, ApiError> {
+        Self::validate(&req)?;
+
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        let txn = self.db.begin().await?;
+
+        Self::ensure_no_bookings(&txn, room_id, &req).await?;
+
+        let block = room_blocks::ActiveModel {
+            id              : Set(Uuid::new_v4())
+            , room_id       : Set(room_id)
+            , kind          : Set(req.kind)
+            , start_date    : Set(req.start_date)
+            , end_date      : Set(req.end_date)
+            , reason        : Set(req.reason.trim().to_string())
+            , created_at    : Set(now)
+            , updated_at    : Set(None)
+        };
+
+        let block = Self::to_schema(block.insert(&txn).await.map_err(ApiError::Database)?);
+
+        events::record(&txn, DomainEvent::RoomBlockCreated(block.clone())).await?;
+        txn.commit().await?;
+
+        Ok(block)
+    }
+
+    async fn update_block(
+        &self
+        , room_id   : Uuid
+        , id        : Uuid
+        , req       : RoomBlockSchemaIn
+    ) -> Result<Option<RoomBlockSchemaOut>, ApiError> {
+        Self::validate(&req)?;
+
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        let txn = self.db.begin().await?;
+
+        let block = match room_blocks::Entity::find_by_id(id)
+            .filter(room_blocks::Column::RoomId.eq(room_id))
+            .one(&txn)
+            .await
+            .map_err(ApiError::Database)? {
+                Some(b) => b,
+                None    => return Ok(None),
+            };
+
+        Self::ensure_no_bookings(&txn, room_id, &req).await?;
+
+        let mut block: room_blocks::ActiveModel = block.into();
+
+        block.kind          = Set(req.kind);
+        block.start_date    = Set(req.start_date);
+        block.end_date      = Set(req.end_date);
+        block.reason        = Set(req.reason.trim().to_string());
+        block.updated_at    = Set(Some(now));
+
+        let block = Self::to_schema(block.update(&txn).await.map_err(ApiError::Database)?);
+
+        events::record(&txn, DomainEvent::RoomBlockUpdated(block.clone())).await?;
+        txn.commit().await?;
+
+        Ok(Some(block))
+    }
+
+    async fn delete_block(&self, room_id: Uuid, id: Uuid) -> Result<bool, ApiError> {
+        let txn = self.db.begin().await?;
+
+        let res = room_blocks::Entity::delete_many()
+            .filter(room_blocks::Column::Id.eq(id))
+            .filter(room_blocks::Column::RoomId.eq(room_id))
+            .exec(&txn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        if res.rows_affected > 0 {
+            events::record(&txn, DomainEvent::RoomBlockDeleted { id, room_id }).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(res.rows_affected > 0)
+    }
+}
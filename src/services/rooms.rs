@@ -1,10 +1,12 @@
+use std::collections::{hash_map::Entry, HashMap};
 use sea_orm::*;
 use uuid::Uuid;
 use chrono::{Utc, FixedOffset};
 use crate::{
-    models::rooms
+    events::{self, DomainEvent}
+    , models::{rooms, sea_orm_active_enums::HousekeepingStatus}
     , schemas::rooms::*
-    , services::traits::RoomServiceTrait
+    , services::{availability, media::MediaService, traits::RoomServiceTrait}
     , error::ApiError
 };
 
@@ -26,44 +28,55 @@ impl RoomService {
             .map_err(ApiError::Database)?
             .is_some())
     }
+
+    /// Where clause of the list and export endpoints
+    pub fn filter_condition(filter: &RoomFilter) -> Condition {
+        let mut condition = Condition::all();
+
+        if let Some(hotel_id) = filter.hotel_id {
+            condition = condition.add(rooms::Column::HotelId.eq(hotel_id));
+        }
+
+        if let Some(room_type) = &filter.room_type {
+            condition = condition.add(rooms::Column::RoomType.eq(room_type.as_str()));
+        }
+
+        if let Some(is_available) = filter.is_available {
+            condition = condition.add(rooms::Column::IsAvailable.eq(is_available));
+        }
+
+        condition
+    }
 }
 
 #[async_trait]
 impl RoomServiceTrait for RoomService {
-    async fn get_all_rooms(&self) -> Result<Vec<RoomSchemaOut>, ApiError> {
+    async fn get_all_rooms(&self, filter: RoomFilter) -> Result<Vec<RoomSchemaOut>, ApiError> {
         let res = rooms::Entity::find()
+            .filter(Self::filter_condition(&filter))
             .all(&self.db)
             .await
             .map_err(ApiError::Database)?;
 
-        Ok(res.into_iter().map(|h| RoomSchemaOut {
-            id              : h.id
-            , hotel_id      : h.hotel_id
-            , room_number   : h.room_number
-            , room_type     : h.room_type
-            , price_per_night   : h.price_per_night
-            , is_available      : h.is_available
-            , created_at        : h.created_at
-            , updated_at        : h.updated_at
-        }).collect())
+        let mut res = res.into_iter().map(RoomSchemaOut::from).collect::<Vec<_>>();
+        MediaService::attach_room_media(&self.db, &mut res).await?;
+
+        Ok(res)
     }
 
     async fn get_room(&self, id: Uuid) -> Result<Option<RoomSchemaOut>, ApiError> {
-        let res = rooms::Entity::find_by_id(id)
+        let Some(room) = rooms::Entity::find_by_id(id)
             .one(&self.db)
             .await
-            .map_err(ApiError::Database)?;
+            .map_err(ApiError::Database)? else {
+                return Ok(None);
+            };
 
-        Ok(res.map(|h| RoomSchemaOut {
-            id              : h.id
-            , hotel_id      : h.hotel_id
-            , room_number   : h.room_number
-            , room_type     : h.room_type
-            , price_per_night   : h.price_per_night
-            , is_available      : h.is_available
-            , created_at        : h.created_at
-            , updated_at        : h.updated_at
-        }))
+        let mut room = [RoomSchemaOut::from(room)];
+        MediaService::attach_room_media(&self.db, &mut room).await?;
+
+        let [room] = room;
+        Ok(Some(room))
     }
 
     async fn create_room(&self, req: RoomSchemaIn) -> Result<RoomSchemaOut, ApiError> {
@@ -81,24 +94,26 @@ impl RoomServiceTrait for RoomService {
             , room_type     : Set(req.room_type)
             , price_per_night   : Set(req.price_per_night)
             , is_available      : Set(req.is_available)
+            , description       : Set(req.description)
             , created_at        : Set(now)
             , updated_at        : Set(None)
+            , housekeeping_status       : Set(HousekeepingStatus::Clean)
+            , housekeeping_note         : Set(None)
+            , housekeeping_updated_at   : Set(None)
         };
 
-        let res = room.insert(&self.db)
+        let txn = self.db.begin().await?;
+
+        let res = room.insert(&txn)
             .await
             .map_err(ApiError::Database)?;
 
-        Ok(RoomSchemaOut {
-            id              : res.id
-            , hotel_id      : res.hotel_id
-            , room_number   : res.room_number
-            , room_type     : res.room_type
-            , price_per_night   : res.price_per_night
-            , is_available      : res.is_available
-            , created_at        : res.created_at
-            , updated_at        : res.updated_at
-        })
+        let room = RoomSchemaOut::from(res);
+
+        events::record(&txn, DomainEvent::RoomCreated(room.clone())).await?;
+        txn.commit().await?;
+
+        Ok(room)
     }
 
     async fn update_room(&self, id: Uuid, req: RoomSchemaIn) -> Result<Option<RoomSchemaOut>, ApiError> {
@@ -117,6 +132,7 @@ impl RoomServiceTrait for RoomService {
                 None => return Ok(None),
             };
         
+        let old_price = room.price_per_night;
         let mut room: rooms::ActiveModel = room.into();
 
         room.hotel_id           = Set(req.hotel_id);
@@ -124,30 +140,49 @@ impl RoomServiceTrait for RoomService {
         room.room_type          = Set(req.room_type);
         room.price_per_night    = Set(req.price_per_night);
         room.is_available       = Set(req.is_available);
+        room.description        = Set(req.description);
         room.updated_at         = Set(Some(now));
 
-        let updated = room.update(&self.db)
+        let txn = self.db.begin().await?;
+
+        let updated = room.update(&txn)
             .await
             .map_err(ApiError::Database)?;
 
-        Ok(Some(RoomSchemaOut {
-            id              : updated.id
-            , hotel_id      : updated.hotel_id
-            , room_number   : updated.room_number
-            , room_type     : updated.room_type
-            , price_per_night   : updated.price_per_night
-            , is_available      : updated.is_available
-            , created_at        : updated.created_at
-            , updated_at        : updated.updated_at
-        }))
+        let mut room = [RoomSchemaOut::from(updated)];
+        MediaService::attach_room_media(&txn, &mut room).await?;
+
+        let [room] = room;
+
+        events::record(&txn, DomainEvent::RoomUpdated(room.clone())).await?;
+
+        if room.price_per_night != old_price {
+            events::record(&txn, DomainEvent::RoomPriceChanged {
+                room_id     : room.id
+                , old_price
+                , new_price : room.price_per_night
+            }).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(Some(room))
     }
 
     async fn delete_room(&self, id: Uuid) -> Result<bool, ApiError> {
+        let txn = self.db.begin().await?;
+
         let res = rooms::Entity::delete_by_id(id)
-            .exec(&self.db)
+            .exec(&txn)
             .await
             .map_err(ApiError::Database)?;
 
+        if res.rows_affected > 0 {
+            events::record(&txn, DomainEvent::RoomDeleted { id }).await?;
+        }
+
+        txn.commit().await?;
+
         Ok(res.rows_affected > 0)
     }
 
@@ -163,15 +198,57 @@ impl RoomServiceTrait for RoomService {
             .await
             .map_err(ApiError::Database)?;
 
-        Ok(res.into_iter().map(|h| RoomSchemaOut {
-            id              : h.id
-            , hotel_id      : h.hotel_id
-            , room_number   : h.room_number
-            , room_type     : h.room_type
-            , price_per_night   : h.price_per_night
-            , is_available      : h.is_available
-            , created_at        : h.created_at
-            , updated_at        : h.updated_at
-        }).collect())
+        let mut res = res.into_iter().map(RoomSchemaOut::from).collect::<Vec<_>>();
+        MediaService::attach_room_media(&self.db, &mut res).await?;
+
+        Ok(res)
+    }
+    async fn search_available_rooms(&self, query: RoomAvailabilityQuery) -> Result<Vec<RoomSchemaOut>, ApiError> {
+        if query.check_out <= query.check_in {
+            return Err(ApiError::Validation("check_out must be after check_in".to_string()));
+        }
+
+        let occupied = availability::occupied_room_ids(&self.db, query.check_in, query.check_out).await?;
+
+        let mut rooms = rooms::Entity::find()
+            .filter(rooms::Column::IsAvailable.eq(true))
+            .filter(rooms::Column::Id.is_not_in(occupied));
+
+        if let Some(hotel_id) = query.hotel_id {
+            rooms = rooms.filter(rooms::Column::HotelId.eq(hotel_id));
+        }
+
+        if let Some(room_type) = query.room_type {
+            rooms = rooms.filter(rooms::Column::RoomType.eq(room_type));
+        }
+
+        let res = rooms
+            .order_by_asc(rooms::Column::PricePerNight)
+            .all(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        // Bookings not assigned a room yet still take one of their type, so
+        // drop the types whose inventory is sold out on any night
+        let mut sellable: HashMap<(Uuid, String), bool> = HashMap::new();
+
+        for room in &res {
+            if let Entry::Vacant(entry) = sellable.entry((room.hotel_id, room.room_type.clone())) {
+                let tz = availability::hotel_timezone(&self.db, room.hotel_id).await?;
+
+                entry.insert(availability::room_type_has_inventory(
+                    &self.db, room.hotel_id, &room.room_type, query.check_in, query.check_out, None, tz
+                ).await?);
+            }
+        }
+
+        let mut res = res.into_iter()
+            .filter(|r| sellable[&(r.hotel_id, r.room_type.clone())])
+            .map(RoomSchemaOut::from)
+            .collect::<Vec<_>>();
+
+        MediaService::attach_room_media(&self.db, &mut res).await?;
+
+        Ok(res)
     }
 }
\ No newline at end of file
@@ -0,0 +1,185 @@
+use sea_orm::*;
+use sea_orm::sea_query::LikeExpr;
+use uuid::Uuid;
+use crate::{
+    schemas::search::*,
+    services::traits::SearchServiceTrait,
+    error::ApiError,
+};
+
+const DEFAULT_PER_PAGE: u64 = 20;
+const MAX_PER_PAGE: u64 = 100;
+const MAX_QUERY_CHARS: usize = 200;
+
+/// `LIKE` pattern matching `text` anywhere, its `%`, `_` and `\` taken
+/// literally by escaping them with Postgres' default escape character
+pub fn contains_pattern(text: &str) -> LikeExpr {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+
+    LikeExpr::new(format!("%{}%", escaped))
+}
+
+/// Hotels matching on name, description or address and one hit per room type
+/// of a hotel matching on the type or a room description. The documents are
+/// the generated `search_vector` columns, `document` is the text snippets are
+/// cut from.
+const HITS_SQL: &str = r#"
+WITH query AS (
+    SELECT websearch_to_tsquery('english', $1) AS q
+),
+hotel_hits AS (
+    SELECT 'hotel' AS kind
+        , h.id AS hotel_id
+        , h.name AS hotel_name
+        , NULL::varchar AS room_type
+        , ts_rank(h.search_vector, query.q) AS rank
+        , concat_ws(' · ', h.name, h.description, h.address) AS document
+    FROM hotels h, query
+    WHERE h.search_vector @@ query.q
+),
+room_hits AS (
+    SELECT DISTINCT ON (r.hotel_id, r.room_type) 'room_type' AS kind
+        , r.hotel_id
+        , h.name AS hotel_name
+        , r.room_type::varchar AS room_type
+        , ts_rank(r.search_vector, query.q) AS rank
+        , concat_ws(' · ', r.room_type, r.description) AS document
+    FROM rooms r
+    JOIN hotels h ON h.id = r.hotel_id, query
+    WHERE r.search_vector @@ query.q
+    ORDER BY r.hotel_id, r.room_type, rank DESC
+),
+hits AS (
+    SELECT * FROM hotel_hits
+    UNION ALL
+    SELECT * FROM room_hits
+)
+"#;
+
+/// Only the rows of the page get a headline, it is the costly part. The
+/// document is HTML escaped before being highlighted, so the snippet is safe
+/// markup whatever the hotels and rooms contain; the parser reads the entities
+/// as single tokens, never cutting or highlighting them.
+const PAGE_SQL: &str = r#"
+, page AS (
+    SELECT * FROM hits
+    ORDER BY rank DESC, hotel_name, hotel_id, room_type NULLS FIRST
+    LIMIT $2 OFFSET $3
+)
+SELECT page.kind
+    , page.hotel_id
+    , page.hotel_name
+    , page.room_type
+    , page.rank
+    , ts_headline('english'
+        , replace(replace(replace(replace(replace(page.document
+            , '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;'), '''', '&#39;')
+        , query.q
+        , 'StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MinWords=5, MaxWords=20, FragmentDelimiter=" … "') AS snippet
+FROM page, query
+ORDER BY page.rank DESC, page.hotel_name, page.hotel_id, page.room_type NULLS FIRST
+"#;
+
+const COUNT_SQL: &str = "SELECT count(*) AS total FROM hits";
+
+#[derive(Debug, FromQueryResult)]
+struct HitRow {
+    kind            : String
+    , hotel_id      : Uuid
+    , hotel_name    : String
+    , room_type     : Option<String>
+    , rank          : f32
+    , snippet       : String
+}
+
+#[derive(Debug, FromQueryResult)]
+struct CountRow {
+    total   : i64
+}
+
+#[derive(Clone)]
+pub struct SearchService {
+    db  : DatabaseConnection
+}
+
+impl SearchService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl SearchServiceTrait for SearchService {
+    async fn search(&self, query: SearchQuery) -> Result<SearchResults, ApiError> {
+        let q = query.q.trim().to_string();
+
+        if q.is_empty() {
+            return Err(ApiError::Validation("q must not be empty".to_string()));
+        }
+
+        if q.chars().count() > MAX_QUERY_CHARS {
+            return Err(ApiError::Validation(format!("q must be at most {} characters", MAX_QUERY_CHARS)));
+        }
+
+        let page = query.page.unwrap_or(1);
+        if page == 0 {
+            return Err(ApiError::Validation("page must be at least 1".to_string()));
+        }
+
+        let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE);
+        if !(1..=MAX_PER_PAGE).contains(&per_page) {
+            return Err(ApiError::Validation(format!("per_page must be between 1 and {}", MAX_PER_PAGE)));
+        }
+
+        let offset = (page - 1).checked_mul(per_page)
+            .and_then(|o| i64::try_from(o).ok())
+            .ok_or_else(|| ApiError::Validation("page is out of range".to_string()))?;
+
+        let total = CountRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres
+            , format!("{}{}", HITS_SQL, COUNT_SQL)
+            , [q.clone().into()]
+        ))
+        .one(&self.db)
+        .await?
+        .map_or(0, |r| r.total);
+
+        let rows = if offset < total {
+            HitRow::find_by_statement(Statement::from_sql_and_values(
+                DbBackend::Postgres
+                , format!("{}{}", HITS_SQL, PAGE_SQL)
+                , [q.clone().into(), (per_page as i64).into(), offset.into()]
+            ))
+            .all(&self.db)
+            .await?
+        } else {
+            Vec::new()
+        };
+
+        let results = rows
+            .into_iter()
+            .map(|r| SearchHit {
+                kind        : match r.kind.as_str() {
+                    "hotel" => SearchHitKind::Hotel,
+                    _       => SearchHitKind::RoomType,
+                },
+                hotel_id    : r.hotel_id,
+                hotel_name  : r.hotel_name,
+                room_type   : r.room_type,
+                rank        : r.rank,
+                snippet     : r.snippet,
+            })
+            .collect();
+
+        Ok(SearchResults {
+            query   : q,
+            page,
+            per_page,
+            total,
+            results,
+        })
+    }
+}
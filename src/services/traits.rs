@@ -1,35 +1,56 @@
 use sea_orm::DbErr;
 use uuid::Uuid;
-use crate::schemas::{rooms::*, hotels::*, guests::*, booking::*};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use crate::schemas::{rooms::*, room_blocks::*, housekeeping::*, hotels::*, guests::*, booking::*, reservations::*, waitlist::*, webhooks::*, notifications::*, external_calendars::*, exports::*, imports::*, reports::*, calendar::*, search::*, amenities::*, reviews::*, media::*, audit::*, privacy::*, loyalty::*, promotions::*};
+use crate::models::sea_orm_active_enums::HousekeepingStatus;
+use crate::services::exports::ExportStream;
+use crate::services::guards::AccessLevel;
 use crate::error::ApiError;
 
 
 #[async_trait]
 pub trait HotelServiceTrait {
-    async fn list_hotels(&self) -> Result<Vec<HotelSchemaOut>, DbErr>;
+    async fn list_hotels(&self, filter: HotelFilter, stay: Option<HotelStayFilter>) -> Result<Vec<HotelSchemaOut>, ApiError>;
     async fn get_hotel(&self, id: Uuid) -> Result<Option<HotelSchemaOut>, DbErr>;
-    async fn create_hotel(&self, hotel: HotelSchemaIn) -> Result<HotelSchemaOut, DbErr>;
-    async fn update_hotel(&self, id: Uuid, hotel: HotelSchemaIn) -> Result<Option<HotelSchemaOut>, DbErr>;
+    async fn create_hotel(&self, hotel: HotelSchemaIn) -> Result<HotelSchemaOut, ApiError>;
+    async fn update_hotel(&self, id: Uuid, hotel: HotelSchemaIn) -> Result<Option<HotelSchemaOut>, ApiError>;
+    async fn update_policies(&self, id: Uuid, policies: HotelPoliciesSchema) -> Result<Option<HotelSchemaOut>, ApiError>;
     async fn delete_hotel(&self, id: Uuid) -> Result<bool, DbErr>;
 }
 
+#[async_trait]
+pub trait AmenityServiceTrait {
+    async fn list_amenities(&self) -> Result<Vec<AmenitySchemaOut>, ApiError>;
+    async fn get_amenity(&self, id: Uuid) -> Result<Option<AmenitySchemaOut>, ApiError>;
+    async fn create_amenity(&self, amenity: AmenitySchemaIn) -> Result<AmenitySchemaOut, ApiError>;
+    async fn update_amenity(&self, id: Uuid, amenity: AmenitySchemaIn) -> Result<Option<AmenitySchemaOut>, ApiError>;
+    async fn delete_amenity(&self, id: Uuid) -> Result<bool, ApiError>;
+    async fn get_hotel_amenities(&self, hotel_id: Uuid) -> Result<Option<Vec<AmenitySchemaOut>>, ApiError>;
+    async fn set_hotel_amenities(&self, hotel_id: Uuid, amenities: AmenityCodesSchemaIn) -> Result<Option<Vec<AmenitySchemaOut>>, ApiError>;
+    async fn get_room_type_amenities(&self, hotel_id: Uuid, room_type: &str) -> Result<Option<RoomTypeAmenitiesSchemaOut>, ApiError>;
+    async fn set_room_type_amenities(&self, hotel_id: Uuid, room_type: &str, amenities: AmenityCodesSchemaIn) -> Result<Option<RoomTypeAmenitiesSchemaOut>, ApiError>;
+}
+
 #[async_trait]
 pub trait RoomServiceTrait {
-    async fn get_all_rooms(&self) -> Result<Vec<RoomSchemaOut>, ApiError>;
+    async fn get_all_rooms(&self, filter: RoomFilter) -> Result<Vec<RoomSchemaOut>, ApiError>;
     async fn get_room(&self, id: Uuid) -> Result<Option<RoomSchemaOut>, ApiError>;
     async fn create_room(&self, room: RoomSchemaIn) -> Result<RoomSchemaOut, ApiError>;
     async fn update_room(&self, id: Uuid, room: RoomSchemaIn) -> Result<Option<RoomSchemaOut>, ApiError>;
     async fn delete_room(&self, id: Uuid) -> Result<bool, ApiError>;
     async fn get_rooms_by_hotel(&self, hotel_id: Uuid) -> Result<Vec<RoomSchemaOut>, ApiError>;
+    async fn search_available_rooms(&self, query: RoomAvailabilityQuery) -> Result<Vec<RoomSchemaOut>, ApiError>;
 }
 
 
 #[async_trait]
 pub trait GuestServiceTrait {
-    async fn list_guests(&self) -> Result<Vec<GuestSchemaOut>, DbErr>;
-    async fn get_guest(&self, id: Uuid) -> Result<Option<GuestSchemaOut>, DbErr>;
-    async fn create_guest(&self, guest: GuestSchemaIn) -> Result<GuestSchemaOut, DbErr>;
-    async fn update_guest(&self, id: Uuid, guest: GuestSchemaIn) -> Result<Option<GuestSchemaOut>, DbErr>;
+    async fn list_guests(&self, filter: GuestFilter, access: AccessLevel) -> Result<Vec<GuestSchemaOut>, DbErr>;
+    async fn get_guest(&self, id: Uuid, access: AccessLevel) -> Result<Option<GuestSchemaOut>, DbErr>;
+    async fn create_guest(&self, guest: GuestSchemaIn, access: AccessLevel) -> Result<GuestSchemaOut, ApiError>;
+    async fn update_guest(&self, id: Uuid, guest: GuestSchemaIn, access: AccessLevel) -> Result<Option<GuestSchemaOut>, ApiError>;
+    async fn find_duplicates(&self, filter: GuestDuplicateFilter, access: AccessLevel) -> Result<Vec<GuestDuplicateSchemaOut>, ApiError>;
+    async fn merge_guests(&self, id: Uuid, merge: GuestMergeSchemaIn, access: AccessLevel) -> Result<Option<GuestSchemaOut>, ApiError>;
     async fn delete_guest(&self, id: Uuid) -> Result<bool, DbErr>;
 }
 
@@ -38,25 +59,36 @@ pub trait BookingServiceTrait {
     async fn create_booking(
         &self
         , booking    : BookingSchemaIn
-    ) -> Result<BookingSchemaOut, DbErr>;
+    ) -> Result<BookingSchemaOut, ApiError>;
     
     async fn get_booking(
         &self
         , id        : Uuid
     ) -> Result<Option<BookingSchemaOut>, DbErr>;
     
-    async fn list_bookings(&self) -> Result<Vec<BookingSchemaOut>, DbErr>;
+    async fn list_bookings(&self, filter: BookingFilter) -> Result<Vec<BookingSchemaOut>, DbErr>;
     
     async fn update_booking(
         &self
         , id        : Uuid
         , booking   : BookingSchemaIn
-    ) -> Result<Option<BookingSchemaOut>, DbErr>;
+    ) -> Result<Option<BookingSchemaOut>, ApiError>;
     
     async fn delete_booking(
         &self
         , id        : Uuid
     ) -> Result<bool, DbErr>;
+
+    async fn assign_room(
+        &self
+        , id        : Uuid
+        , room_id   : Option<Uuid>
+    ) -> Result<Option<BookingSchemaOut>, ApiError>;
+
+    async fn quote_booking(
+        &self
+        , quote     : BookingQuoteSchemaIn
+    ) -> Result<BookingQuoteSchemaOut, ApiError>;
     
     async fn get_guest_bookings(
         &self
@@ -67,4 +99,171 @@ pub trait BookingServiceTrait {
         &self
         , room_id   : Uuid
     ) -> Result<Vec<BookingSchemaOut>, DbErr>;
+
+    async fn get_room_bookings_ics(
+        &self
+        , room_id   : Uuid
+    ) -> Result<Option<String>, ApiError>;
+
+    async fn get_guest_bookings_ics(
+        &self
+        , guest_id  : Uuid
+    ) -> Result<Option<String>, ApiError>;
+
+    async fn complete_past_bookings(
+        &self
+        , now       : DateTime<FixedOffset>
+    ) -> Result<u64, DbErr>;
+
+    async fn expire_pending_bookings(
+        &self
+        , cutoff    : DateTime<FixedOffset>
+    ) -> Result<u64, DbErr>;
+}
+
+#[async_trait]
+pub trait ReservationServiceTrait {
+    async fn list_reservations(&self, guest_id: Option<Uuid>) -> Result<Vec<ReservationSchemaOut>, ApiError>;
+    async fn get_reservation(&self, id: Uuid) -> Result<Option<ReservationSchemaOut>, ApiError>;
+    async fn create_reservation(&self, reservation: ReservationSchemaIn) -> Result<ReservationSchemaOut, ApiError>;
+
+    async fn update_reservation(
+        &self
+        , id            : Uuid
+        , reservation   : ReservationUpdateSchemaIn
+    ) -> Result<Option<ReservationSchemaOut>, ApiError>;
+
+    async fn cancel_reservation(&self, id: Uuid) -> Result<Option<ReservationSchemaOut>, ApiError>;
+}
+
+#[async_trait]
+pub trait WaitlistServiceTrait {
+    async fn list_entries(&self, filter: WaitlistFilter) -> Result<Vec<WaitlistEntrySchemaOut>, ApiError>;
+    async fn get_entry(&self, id: Uuid) -> Result<Option<WaitlistEntrySchemaOut>, ApiError>;
+    async fn create_entry(&self, entry: WaitlistEntrySchemaIn) -> Result<WaitlistEntrySchemaOut, ApiError>;
+    async fn delete_entry(&self, id: Uuid) -> Result<bool, ApiError>;
+}
+
+#[async_trait]
+pub trait ReviewServiceTrait {
+    async fn list_reviews(&self, filter: ReviewFilter) -> Result<Vec<ReviewSchemaOut>, ApiError>;
+    async fn get_review(&self, id: Uuid) -> Result<Option<ReviewSchemaOut>, ApiError>;
+    async fn create_review(&self, review: ReviewSchemaIn) -> Result<ReviewSchemaOut, ApiError>;
+    async fn update_review(&self, id: Uuid, review: ReviewUpdateSchemaIn) -> Result<Option<ReviewSchemaOut>, ApiError>;
+    async fn moderate_review(&self, id: Uuid, moderation: ReviewModerationSchemaIn) -> Result<Option<ReviewSchemaOut>, ApiError>;
+    async fn delete_review(&self, id: Uuid) -> Result<bool, ApiError>;
+    async fn get_hotel_reviews(&self, hotel_id: Uuid) -> Result<Option<Vec<ReviewSchemaOut>>, ApiError>;
+    async fn get_hotel_review_summary(&self, hotel_id: Uuid) -> Result<Option<ReviewSummary>, ApiError>;
+}
+
+#[async_trait]
+pub trait MediaServiceTrait {
+    async fn list_media(&self, owner: MediaOwner) -> Result<Option<Vec<MediaSchemaOut>>, ApiError>;
+    async fn get_media(&self, id: Uuid) -> Result<Option<MediaSchemaOut>, ApiError>;
+    async fn get_media_file(&self, id: Uuid, thumbnail: bool) -> Result<Option<MediaFile>, ApiError>;
+    async fn upload_media(&self, owner: MediaOwner, bytes: Vec<u8>, caption: Option<String>) -> Result<Option<MediaSchemaOut>, ApiError>;
+    async fn update_media(&self, id: Uuid, media: MediaUpdateSchemaIn) -> Result<Option<MediaSchemaOut>, ApiError>;
+    async fn set_cover(&self, id: Uuid) -> Result<Option<MediaSchemaOut>, ApiError>;
+    async fn reorder_media(&self, owner: MediaOwner, order: MediaOrderSchemaIn) -> Result<Option<Vec<MediaSchemaOut>>, ApiError>;
+    async fn delete_media(&self, id: Uuid) -> Result<bool, ApiError>;
+}
+
+#[async_trait]
+pub trait PrivacyServiceTrait {
+    async fn export_guest_data(&self, guest_id: Uuid) -> Result<Option<GuestDataExport>, ApiError>;
+    async fn anonymize_guest(&self, guest_id: Uuid) -> Result<Option<GuestSchemaOut>, ApiError>;
+}
+
+#[async_trait]
+pub trait AuditServiceTrait {
+    async fn list_entries(&self, filter: AuditFilter) -> Result<Vec<AuditEntrySchemaOut>, ApiError>;
+}
+
+#[async_trait]
+pub trait LoyaltyServiceTrait {
+    async fn get_account(&self, guest_id: Uuid) -> Result<Option<LoyaltyAccountSchemaOut>, DbErr>;
+}
+
+#[async_trait]
+pub trait PromotionServiceTrait {
+    async fn list_promotions(&self, filter: PromotionFilter) -> Result<Vec<PromotionSchemaOut>, ApiError>;
+    async fn get_promotion(&self, id: Uuid) -> Result<Option<PromotionSchemaOut>, ApiError>;
+    async fn create_promotion(&self, promotion: PromotionSchemaIn) -> Result<PromotionSchemaOut, ApiError>;
+    async fn update_promotion(&self, id: Uuid, promotion: PromotionSchemaIn) -> Result<Option<PromotionSchemaOut>, ApiError>;
+    async fn delete_promotion(&self, id: Uuid) -> Result<bool, ApiError>;
+}
+
+#[async_trait]
+pub trait WebhookServiceTrait {
+    async fn list_subscriptions(&self) -> Result<Vec<WebhookSubscriptionSchemaOut>, ApiError>;
+    async fn get_subscription(&self, id: Uuid) -> Result<Option<WebhookSubscriptionSchemaOut>, ApiError>;
+    async fn create_subscription(&self, subscription: WebhookSubscriptionSchemaIn) -> Result<WebhookSubscriptionSchemaOut, ApiError>;
+    async fn update_subscription(&self, id: Uuid, subscription: WebhookSubscriptionSchemaIn) -> Result<Option<WebhookSubscriptionSchemaOut>, ApiError>;
+    async fn delete_subscription(&self, id: Uuid) -> Result<bool, ApiError>;
+    async fn list_deliveries(&self, subscription_id: Uuid) -> Result<Option<Vec<WebhookDeliverySchemaOut>>, ApiError>;
+}
+
+#[async_trait]
+pub trait NotificationServiceTrait {
+    async fn get_booking_notifications(&self, booking_id: Uuid) -> Result<Vec<NotificationSchemaOut>, ApiError>;
+}
+
+#[async_trait]
+pub trait RoomBlockServiceTrait {
+    async fn list_blocks(&self, room_id: Uuid) -> Result<Option<Vec<RoomBlockSchemaOut>>, ApiError>;
+    async fn get_block(&self, room_id: Uuid, id: Uuid) -> Result<Option<RoomBlockSchemaOut>, ApiError>;
+    async fn create_block(&self, room_id: Uuid, block: RoomBlockSchemaIn) -> Result<RoomBlockSchemaOut, ApiError>;
+    async fn update_block(&self, room_id: Uuid, id: Uuid, block: RoomBlockSchemaIn) -> Result<Option<RoomBlockSchemaOut>, ApiError>;
+    async fn delete_block(&self, room_id: Uuid, id: Uuid) -> Result<bool, ApiError>;
+}
+
+#[async_trait]
+pub trait HousekeepingServiceTrait {
+    async fn hotel_tasks(&self, hotel_id: Uuid, status: Option<HousekeepingStatus>) -> Result<Option<Vec<HousekeepingTask>>, ApiError>;
+    async fn update_status(&self, room_id: Uuid, housekeeping: HousekeepingSchemaIn) -> Result<Option<HousekeepingSchemaOut>, ApiError>;
+}
+
+#[async_trait]
+pub trait ExternalCalendarServiceTrait {
+    async fn list_calendars(&self, room_id: Uuid) -> Result<Vec<ExternalCalendarSchemaOut>, ApiError>;
+    async fn create_calendar(&self, room_id: Uuid, calendar: ExternalCalendarSchemaIn) -> Result<ExternalCalendarSchemaOut, ApiError>;
+    async fn delete_calendar(&self, room_id: Uuid, id: Uuid) -> Result<bool, ApiError>;
+    async fn sync_calendar(&self, room_id: Uuid, id: Uuid) -> Result<Option<ExternalCalendarSchemaOut>, ApiError>;
+    async fn upload_calendar(&self, room_id: Uuid, id: Uuid, ics: String) -> Result<Option<ExternalCalendarSchemaOut>, ApiError>;
+    async fn list_blocks(&self, room_id: Uuid, id: Uuid) -> Result<Option<Vec<ExternalBlockSchemaOut>>, ApiError>;
+}
+
+#[async_trait]
+pub trait ImportServiceTrait {
+    async fn import_rooms(&self, csv: &str, dry_run: bool) -> Result<ImportReport, ApiError>;
+    async fn import_hotels(&self, csv: &str, dry_run: bool) -> Result<ImportReport, ApiError>;
+}
+
+#[async_trait]
+pub trait ReportServiceTrait {
+    async fn occupancy_report(
+        &self
+        , hotel_id      : Uuid
+        , from          : NaiveDate
+        , to            : NaiveDate
+        , granularity   : Granularity
+    ) -> Result<Option<OccupancyReport>, ApiError>;
+}
+
+#[async_trait]
+pub trait CalendarServiceTrait {
+    async fn hotel_calendar(&self, hotel_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Option<CalendarGrid>, ApiError>;
+}
+
+#[async_trait]
+pub trait SearchServiceTrait {
+    async fn search(&self, query: SearchQuery) -> Result<SearchResults, ApiError>;
+}
+
+#[async_trait]
+pub trait ExportServiceTrait {
+    async fn export_bookings(&self, filter: BookingFilter, format: ExportFormat) -> Result<ExportStream, ApiError>;
+    async fn export_rooms(&self, filter: RoomFilter, format: ExportFormat) -> Result<ExportStream, ApiError>;
+    async fn export_guests(&self, filter: GuestFilter, format: ExportFormat) -> Result<ExportStream, ApiError>;
+    async fn export_hotels(&self, filter: HotelFilter, format: ExportFormat) -> Result<ExportStream, ApiError>;
 }
\ No newline at end of file
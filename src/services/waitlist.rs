@@ -0,0 +1,220 @@
+use sea_orm::*;
+use uuid::Uuid;
+use chrono::{Utc, FixedOffset};
+use crate::{
+    events::{self, DomainEvent, EventHandler},
+    models::{guests, hotels, rooms, waitlist_entries, sea_orm_active_enums::WaitlistStatus},
+    schemas::{booking::BookingSchemaOut, waitlist::*},
+    services::{availability, traits::WaitlistServiceTrait},
+    error::ApiError,
+};
+
+#[derive(Clone)]
+pub struct WaitlistService {
+    db  : DatabaseConnection
+}
+
+impl WaitlistService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Offers the room freed by a cancelled booking to the oldest waiting entry
+    /// of the same hotel and room type whose stay overlaps the cancelled one and
+    /// can now be booked. A booking is offered at most once, so a redelivered
+    /// event does not offer it to a second guest.
+    pub async fn offer_freed_room<C: ConnectionTrait>(conn: &C, booking: &BookingSchemaOut) -> Result<(), DbErr> {
+        let already_offered = waitlist_entries::Entity::find()
+            .filter(waitlist_entries::Column::OfferedBookingId.eq(booking.id))
+            .one(conn)
+            .await?
+            .is_some();
+
+        if already_offered {
+            return Ok(());
+        }
+
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let candidates = waitlist_entries::Entity::find()
+            .filter(waitlist_entries::Column::HotelId.eq(booking.hotel_id))
+            .filter(waitlist_entries::Column::RoomType.eq(booking.room_type.as_str()))
+            .filter(waitlist_entries::Column::Status.eq(WaitlistStatus::Waiting))
+            .filter(waitlist_entries::Column::CheckInDate.lt(booking.check_out_date))
+            .filter(waitlist_entries::Column::CheckOutDate.gt(booking.check_in_date))
+            .filter(waitlist_entries::Column::CheckInDate.gt(now))
+            .order_by_asc(waitlist_entries::Column::CreatedAt)
+            .all(conn)
+            .await?;
+
+        let tz = availability::hotel_timezone(conn, booking.hotel_id).await?;
+
+        for entry in candidates {
+            let (check_in, check_out) = (entry.check_in_date, entry.check_out_date);
+
+            if !availability::room_type_has_inventory(conn, entry.hotel_id, &entry.room_type, check_in, check_out, None, tz).await? {
+                continue;
+            }
+
+            // Name the cancelled booking's room when it is free for the whole stay
+            let offered_room_id = match booking.room_id {
+                Some(room_id) if availability::room_is_free(conn, room_id, check_in, check_out, None, tz).await? => Some(room_id),
+                _ => None,
+            };
+
+            let mut entry: waitlist_entries::ActiveModel = entry.into();
+
+            entry.status                = Set(WaitlistStatus::Offered);
+            entry.offered_room_id       = Set(offered_room_id);
+            entry.offered_booking_id    = Set(Some(booking.id));
+            entry.offered_at            = Set(Some(now));
+            entry.updated_at            = Set(Some(now));
+
+            let entry = Self::to_schema(entry.update(conn).await?);
+
+            events::record(conn, DomainEvent::WaitlistOffered(entry)).await?;
+            break;
+        }
+
+        Ok(())
+    }
+
+    pub fn to_schema(w: waitlist_entries::Model) -> WaitlistEntrySchemaOut {
+        WaitlistEntrySchemaOut {
+            id                      : w.id
+            , guest_id              : w.guest_id
+            , hotel_id              : w.hotel_id
+            , room_type             : w.room_type
+            , check_in_date         : w.check_in_date
+            , check_out_date        : w.check_out_date
+            , status                : w.status
+            , offered_room_id       : w.offered_room_id
+            , offered_booking_id    : w.offered_booking_id
+            , offered_at            : w.offered_at
+            , created_at            : w.created_at
+            , updated_at            : w.updated_at
+        }
+    }
+}
+
+/// Offers rooms freed by cancelled bookings to waitlisted guests
+pub struct WaitlistEventHandler;
+
+#[async_trait]
+impl EventHandler for WaitlistEventHandler {
+    fn name(&self) -> &'static str {
+        "waitlist"
+    }
+
+    async fn handle(&self, txn: &DatabaseTransaction, event: &DomainEvent) -> Result<(), DbErr> {
+        match event {
+            DomainEvent::BookingCancelled(booking)  => WaitlistService::offer_freed_room(txn, booking).await,
+            _                                       => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl WaitlistServiceTrait for WaitlistService {
+    async fn list_entries(&self, filter: WaitlistFilter) -> Result<Vec<WaitlistEntrySchemaOut>, ApiError> {
+        let mut entries = waitlist_entries::Entity::find();
+
+        if let Some(hotel_id) = filter.hotel_id {
+            entries = entries.filter(waitlist_entries::Column::HotelId.eq(hotel_id));
+        }
+
+        if let Some(guest_id) = filter.guest_id {
+            entries = entries.filter(waitlist_entries::Column::GuestId.eq(guest_id));
+        }
+
+        if let Some(status) = filter.status {
+            entries = entries.filter(waitlist_entries::Column::Status.eq(status));
+        }
+
+        let res = entries
+            .order_by_asc(waitlist_entries::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        Ok(res.into_iter().map(Self::to_schema).collect())
+    }
+
+    async fn get_entry(&self, id: Uuid) -> Result<Option<WaitlistEntrySchemaOut>, ApiError> {
+        let res = waitlist_entries::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?;
+
+        Ok(res.map(Self::to_schema))
+    }
+
+    async fn create_entry(&self, req: WaitlistEntrySchemaIn) -> Result<WaitlistEntrySchemaOut, ApiError> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        if req.check_out_date <= req.check_in_date {
+            return Err(ApiError::Validation("check_out_date must be after check_in_date".to_string()));
+        }
+
+        if req.check_in_date <= now {
+            return Err(ApiError::Validation("check_in_date must be in the future".to_string()));
+        }
+
+        if hotels::Entity::find_by_id(req.hotel_id).one(&self.db).await?.is_none() {
+            return Err(ApiError::HotelNotFound(req.hotel_id.to_string()));
+        }
+
+        if guests::Entity::find_by_id(req.guest_id).one(&self.db).await?.is_none() {
+            return Err(ApiError::Validation(format!("Guest {} does not exist", req.guest_id)));
+        }
+
+        let has_room_type = rooms::Entity::find()
+            .filter(rooms::Column::HotelId.eq(req.hotel_id))
+            .filter(rooms::Column::RoomType.eq(req.room_type.as_str()))
+            .one(&self.db)
+            .await?
+            .is_some();
+
+        if !has_room_type {
+            return Err(ApiError::Validation(format!("The hotel has no {} rooms", req.room_type)));
+        }
+
+        let entry = waitlist_entries::ActiveModel {
+            id                      : Set(Uuid::new_v4())
+            , guest_id              : Set(req.guest_id)
+            , hotel_id              : Set(req.hotel_id)
+            , room_type             : Set(req.room_type)
+            , check_in_date         : Set(req.check_in_date)
+            , check_out_date        : Set(req.check_out_date)
+            , status                : Set(WaitlistStatus::Waiting)
+            , offered_room_id       : Set(None)
+            , offered_booking_id    : Set(None)
+            , offered_at            : Set(None)
+            , created_at            : Set(now)
+            , updated_at            : Set(None)
+        };
+
+        let txn = self.db.begin().await?;
+
+        let entry = Self::to_schema(entry.insert(&txn).await?);
+
+        events::record(&txn, DomainEvent::WaitlistEntryCreated(entry.clone())).await?;
+        txn.commit().await?;
+
+        Ok(entry)
+    }
+
+    async fn delete_entry(&self, id: Uuid) -> Result<bool, ApiError> {
+        let txn = self.db.begin().await?;
+
+        let res = waitlist_entries::Entity::delete_by_id(id)
+            .exec(&txn)
+            .await?;
+
+        if res.rows_affected > 0 {
+            events::record(&txn, DomainEvent::WaitlistEntryDeleted { id }).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(res.rows_affected > 0)
+    }
+}
@@ -0,0 +1,314 @@
+use sea_orm::*;
+use serde::Serialize;
+use uuid::Uuid;
+use chrono::{DateTime, Utc, FixedOffset};
+use crate::{
+    events::{DomainEvent, EventHandler},
+    models::{webhook_deliveries, webhook_subscriptions, sea_orm_active_enums::WebhookDeliveryStatus},
+    schemas::webhooks::*,
+    services::traits::WebhookServiceTrait,
+    error::ApiError,
+};
+
+#[derive(Clone)]
+pub struct WebhookService {
+    db  : DatabaseConnection
+}
+
+impl WebhookService {
+    pub fn new(db   : DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Queues a delivery of `event` for every active subscription listening to it.
+    /// Takes any connection so callers can enqueue inside their own transaction.
+    pub async fn enqueue<C, T>(
+        conn    : &C
+        , event : WebhookEvent
+        , data  : &T
+    ) -> Result<u64, DbErr>
+    where
+        C: ConnectionTrait,
+        T: Serialize + Sync,
+    {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let subscriptions = webhook_subscriptions::Entity::find()
+            .filter(webhook_subscriptions::Column::IsActive.eq(true))
+            .all(conn)
+            .await?
+            .into_iter()
+            .filter(|s| s.events.iter().any(|e| e == event.as_str()))
+            .collect::<Vec<_>>();
+
+        if subscriptions.is_empty() {
+            return Ok(0);
+        }
+
+        let data = serde_json::to_value(data)
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        let deliveries = subscriptions.iter().map(|s| {
+            let id = Uuid::new_v4();
+
+            webhook_deliveries::ActiveModel {
+                id                  : Set(id)
+                , subscription_id   : Set(s.id)
+                , event_type        : Set(event.as_str().to_string())
+                , payload           : Set(serde_json::json!({
+                    "id"            : id
+                    , "event"       : event
+                    , "occurred_at" : now
+                    , "data"        : data
+                }))
+                , status            : Set(WebhookDeliveryStatus::Pending)
+                , attempts          : Set(0)
+                , response_status   : Set(None)
+                , last_error        : Set(None)
+                , next_attempt_at   : Set(now)
+                , delivered_at      : Set(None)
+                , created_at        : Set(now)
+            }
+        });
+
+        let count = subscriptions.len() as u64;
+        webhook_deliveries::Entity::insert_many(deliveries)
+            .exec(conn)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Pending deliveries due at `now`, paired with their subscription
+    pub async fn due_deliveries(
+        &self
+        , now   : DateTime<FixedOffset>
+        , limit : u64
+    ) -> Result<Vec<(webhook_deliveries::Model, Option<webhook_subscriptions::Model>)>, DbErr> {
+        webhook_deliveries::Entity::find()
+            .find_also_related(webhook_subscriptions::Entity)
+            .filter(webhook_deliveries::Column::Status.eq(WebhookDeliveryStatus::Pending))
+            .filter(webhook_deliveries::Column::NextAttemptAt.lte(now))
+            .order_by_asc(webhook_deliveries::Column::NextAttemptAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn mark_delivered(
+        &self
+        , delivery          : webhook_deliveries::Model
+        , response_status   : i32
+    ) -> Result<(), DbErr> {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        let attempts = delivery.attempts + 1;
+
+        let mut delivery: webhook_deliveries::ActiveModel = delivery.into();
+
+        delivery.status             = Set(WebhookDeliveryStatus::Delivered);
+        delivery.attempts           = Set(attempts);
+        delivery.response_status    = Set(Some(response_status));
+        delivery.last_error         = Set(None);
+        delivery.delivered_at       = Set(Some(now));
+
+        delivery.update(&self.db).await?;
+        Ok(())
+    }
+
+    /// Records a failed attempt, scheduling the next one at `retry_at` or
+    /// giving up when `retry_at` is `None`
+    pub async fn mark_failed(
+        &self
+        , delivery          : webhook_deliveries::Model
+        , response_status   : Option<i32>
+        , error             : String
+        , retry_at          : Option<DateTime<FixedOffset>>
+    ) -> Result<(), DbErr> {
+        let attempts = delivery.attempts + 1;
+
+        let mut delivery: webhook_deliveries::ActiveModel = delivery.into();
+
+        delivery.attempts           = Set(attempts);
+        delivery.response_status    = Set(response_status);
+        delivery.last_error         = Set(Some(error));
+
+        match retry_at {
+            Some(at)    => delivery.next_attempt_at = Set(at),
+            None        => delivery.status = Set(WebhookDeliveryStatus::Failed),
+        }
+
+        delivery.update(&self.db).await?;
+        Ok(())
+    }
+
+    fn validate(req: &WebhookSubscriptionSchemaIn) -> Result<(), ApiError> {
+        if !(req.url.starts_with("http://") || req.url.starts_with("https://")) {
+            return Err(ApiError::Validation("url must start with http:// or https://".to_string()));
+        }
+
+        if req.events.is_empty() {
+            return Err(ApiError::Validation("events must not be empty".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// The subscription without its secret
+    fn to_schema(s: webhook_subscriptions::Model) -> WebhookSubscriptionSchemaOut {
+        WebhookSubscriptionSchemaOut {
+            id              : s.id
+            , url           : s.url
+            , secret        : None
+            , events        : s.events.iter().filter_map(|e| WebhookEvent::parse(e)).collect()
+            , is_active     : s.is_active
+            , created_at    : s.created_at
+            , updated_at    : s.updated_at
+        }
+    }
+}
+
+/// Turns booking lifecycle events from the outbox into webhook deliveries
+pub struct WebhookEventHandler;
+
+#[async_trait]
+impl EventHandler for WebhookEventHandler {
+    fn name(&self) -> &'static str {
+        "webhooks"
+    }
+
+    async fn handle(&self, txn: &DatabaseTransaction, event: &DomainEvent) -> Result<(), DbErr> {
+        let (event, booking) = match event {
+            DomainEvent::BookingCreated(b)      => (WebhookEvent::BookingCreated, b),
+            DomainEvent::BookingConfirmed(b)    => (WebhookEvent::BookingConfirmed, b),
+            DomainEvent::BookingCancelled(b)    => (WebhookEvent::BookingCancelled, b),
+            _                                   => return Ok(()),
+        };
+
+        WebhookService::enqueue(txn, event, booking).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WebhookServiceTrait for WebhookService {
+    async fn list_subscriptions(&self) -> Result<Vec<WebhookSubscriptionSchemaOut>, ApiError> {
+        let res = webhook_subscriptions::Entity::find()
+            .all(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(res.into_iter().map(Self::to_schema).collect())
+    }
+
+    async fn get_subscription(&self, id: Uuid) -> Result<Option<WebhookSubscriptionSchemaOut>, ApiError> {
+        let res = webhook_subscriptions::Entity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(res.map(Self::to_schema))
+    }
+
+    async fn create_subscription(
+        &self
+        , req   : WebhookSubscriptionSchemaIn
+    ) -> Result<WebhookSubscriptionSchemaOut, ApiError> {
+        Self::validate(&req)?;
+
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let subscription = webhook_subscriptions::ActiveModel {
+            id              : Set(Uuid::new_v4())
+            , url           : Set(req.url)
+            , secret        : Set(req.secret.unwrap_or_else(|| format!("whsec_{}", Uuid::new_v4().simple())))
+            , events        : Set(req.events.iter().map(|e| e.as_str().to_string()).collect())
+            , is_active     : Set(req.is_active)
+            , created_at    : Set(now)
+            , updated_at    : Set(None)
+        };
+
+        let res = subscription.insert(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        // The secret is shown this once, for the receiver to verify signatures
+        let secret = res.secret.clone();
+
+        Ok(WebhookSubscriptionSchemaOut { secret: Some(secret), ..Self::to_schema(res) })
+    }
+
+    async fn update_subscription(
+        &self
+        , id    : Uuid
+        , req   : WebhookSubscriptionSchemaIn
+    ) -> Result<Option<WebhookSubscriptionSchemaOut>, ApiError> {
+        Self::validate(&req)?;
+
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+        let subscription = match webhook_subscriptions::Entity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(ApiError::Database)? {
+                Some(s) => s,
+                None    => return Ok(None),
+            };
+
+        let mut subscription: webhook_subscriptions::ActiveModel = subscription.into();
+
+        subscription.url        = Set(req.url);
+        subscription.events     = Set(req.events.iter().map(|e| e.as_str().to_string()).collect());
+        subscription.is_active  = Set(req.is_active);
+        subscription.updated_at = Set(Some(now));
+
+        // Keep the existing secret unless a new one is supplied
+        if let Some(secret) = req.secret {
+            subscription.secret = Set(secret);
+        }
+
+        let updated = subscription.update(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(Some(Self::to_schema(updated)))
+    }
+
+    async fn delete_subscription(&self, id: Uuid) -> Result<bool, ApiError> {
+        let res = webhook_subscriptions::Entity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(res.rows_affected > 0)
+    }
+
+    async fn list_deliveries(
+        &self
+        , subscription_id   : Uuid
+    ) -> Result<Option<Vec<WebhookDeliverySchemaOut>>, ApiError> {
+        if self.get_subscription(subscription_id).await?.is_none() {
+            return Ok(None);
+        }
+
+        let res = webhook_deliveries::Entity::find()
+            .filter(webhook_deliveries::Column::SubscriptionId.eq(subscription_id))
+            .order_by_desc(webhook_deliveries::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(Some(res.into_iter().map(|d| WebhookDeliverySchemaOut {
+            id                  : d.id
+            , subscription_id   : d.subscription_id
+            , event_type        : d.event_type
+            , payload           : d.payload
+            , status            : d.status
+            , attempts          : d.attempts
+            , response_status   : d.response_status
+            , last_error        : d.last_error
+            , next_attempt_at   : d.next_attempt_at
+            , delivered_at      : d.delivered_at
+            , created_at        : d.created_at
+        }).collect()))
+    }
+}
@@ -0,0 +1,73 @@
+use std::{io, path::{Component, Path, PathBuf}};
+use rocket::async_trait;
+use tokio::fs;
+use uuid::Uuid;
+use crate::storage::MediaStorage;
+
+/// Keeps media files in a directory of the local filesystem, keys being
+/// paths relative to it
+pub struct LocalStorage {
+    root    : PathBuf
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Path of `key`, refusing keys that would escape the root directory
+    fn path(&self, key: &str) -> io::Result<PathBuf> {
+        let relative = Path::new(key);
+
+        if key.is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid storage key {}", key)));
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl MediaStorage for LocalStorage {
+    async fn put(&self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        let path = self.path(key)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // Write aside then rename, so readers never see a partial file
+        let partial = path.with_extension(format!("{}.part", Uuid::new_v4()));
+
+        fs::write(&partial, bytes).await?;
+
+        if let Err(err) = fs::rename(&partial, &path).await {
+            let _ = fs::remove_file(&partial).await;
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.path(key)?).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(key)?).await {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> io::Result<()> {
+        match fs::remove_dir_all(self.path(prefix)?).await {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use std::io;
+use rocket::async_trait;
+
+pub mod local;
+
+pub use local::LocalStorage;
+
+/// Where uploaded media files are kept, addressed by `/` separated keys
+#[async_trait]
+pub trait MediaStorage: Send + Sync {
+    async fn put(&self, key: &str, bytes: &[u8]) -> io::Result<()>;
+
+    /// Contents of the file at `key`, if there is one
+    async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>>;
+
+    /// Removes the file at `key`, a missing file is not an error
+    async fn delete(&self, key: &str) -> io::Result<()>;
+
+    /// Removes every file whose key starts with `prefix/`
+    async fn delete_prefix(&self, prefix: &str) -> io::Result<()>;
+}